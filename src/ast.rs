@@ -11,6 +11,7 @@ use crate::ast_types;
 use crate::ast_types::AbstractArgument;
 use crate::ast_types::DataType;
 use crate::ast_types::FieldId;
+use crate::compile_error::SourceLocation;
 use crate::type_checker::Typing;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -41,6 +42,9 @@ impl<T: Clone> Method<T> {
         Fn {
             signature: fn_signature,
             body: self.body,
+            type_parameters: vec![],
+            declared_type_parameters: vec![],
+            trait_bounds: vec![],
         }
     }
 }
@@ -72,12 +76,38 @@ pub(crate) struct Fn<T> {
     pub signature: FnSignature,
     // TODO: Should probably be a BlockStmt<T> instead of Vec<Stmt>
     pub body: RoutineBody<T>,
+
+    /// The type parameters of a generic function, like `T` in `fn sum<T>(v: &Vec<T>) -> T`.
+    /// A generic function is compiled once for each list of concrete types it is called with.
+    pub type_parameters: Vec<String>,
+
+    /// All type parameters in declaration order, which explicit type arguments like in
+    /// `apply::<u64, _>(f, x)` are matched against. The parameters bounded by a function
+    /// trait, like `F` in `fn apply<T, F: Fn(T) -> T>(f: F, x: T) -> T`, are `None`, as
+    /// they are given by the functions passed to the function arguments.
+    pub declared_type_parameters: Vec<Option<String>>,
+
+    /// The traits that the type parameters must implement, like `(T, PartialOrd)` for
+    /// `T: PartialOrd`, which are checked for the concrete types of each instantiation
+    pub trait_bounds: Vec<(String, String)>,
 }
 
 impl<T> Fn<T> {
     pub fn get_tasm_label(&self) -> String {
         self.signature.name.replace("::", "_assoc_funciton___of___")
     }
+
+    /// Return true if the function is generic or takes functions as arguments. Such a
+    /// function is compiled once for each list of concrete types and functions it is called
+    /// with.
+    pub fn is_instantiated_per_call(&self) -> bool {
+        !self.type_parameters.is_empty()
+            || self
+                .signature
+                .args
+                .iter()
+                .any(|arg| matches!(arg, AbstractArgument::FunctionArgument(_)))
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -138,17 +168,27 @@ pub(crate) enum ArgEvaluationOrder {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) enum Stmt<T> {
     Let(LetStmt<T>),
+    DestructuringLet(DestructuringLetStmt<T>),
     Assign(AssignStmt<T>),
     Return(Option<Expr<T>>),
     FnCall(FnCall<T>),
     MethodCall(MethodCall<T>),
     While(WhileStmt<T>),
+    For(ForStmt<T>),
+    Loop(LoopStmt<T>),
+    Break(Option<String>),
+    Continue(Option<String>),
     If(IfStmt<T>),
     Block(BlockStmt<T>),
     Assert(AssertStmt<T>),
     Panic(PanicStmt),
     FnDeclaration(Fn<T>),
     Match(MatchStmt<T>),
+    Static(StaticStmt<T>),
+
+    /// The location in the source code of the statement that follows. Does nothing, but
+    /// allows errors found after grafting to be reported at the statement they are found in.
+    Location(SourceLocation),
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -159,14 +199,69 @@ pub(crate) struct MatchStmt<T> {
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct MatchArm<T> {
-    pub match_condition: MatchCondition,
+    pub match_condition: MatchCondition<T>,
+
+    /// The condition of a guard, like `x > 10` in `Some(x) if x > 10 => { ... }`. Only
+    /// exists until type checking, where the match is lowered to matches without guards.
+    pub guard: Option<Expr<T>>,
     pub body: BlockStmt<T>,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-pub(crate) enum MatchCondition {
+pub(crate) enum MatchCondition<T> {
     CatchAll,
     EnumVariant(EnumVariantSelector),
+
+    /// A pattern on a value that is not an enum, or a nested pattern on an enum. Only exists
+    /// until type checking, where the match is lowered to `if` statements and to matches on
+    /// enum variants without nested patterns.
+    Pattern(Box<Pattern<T>>),
+}
+
+/// A pattern that is lowered by the type checker, like `0`, `1 | 2`, `3..=9`, `(a, true)`, or
+/// `Some(Ok(x))`
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) enum Pattern<T> {
+    /// `_`
+    Wildcard,
+
+    /// `a` or `mut a`
+    Binding(PatternMatchedBinding),
+
+    /// `0` or `true`
+    Literal(Expr<T>),
+
+    /// `3..=9` or `3..10`
+    Range(RangePattern<T>),
+
+    /// `1 | 2`
+    Or(Vec<Pattern<T>>),
+
+    /// `(a, true)`
+    Tuple(Vec<Pattern<T>>),
+
+    /// `Some(Ok(x))` or `Foo::Bar { a: 0, .. }`
+    EnumVariant(EnumVariantPattern<T>),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct EnumVariantPattern<T> {
+    pub type_name: Option<String>,
+    pub variant_name: String,
+
+    /// The patterns of the variant's data, in declaration order
+    pub fields: Vec<Pattern<T>>,
+
+    /// The field names and patterns of a variant with named fields. Fields that are not
+    /// mentioned are matched by `_`.
+    pub named_fields: Vec<(String, Pattern<T>)>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct RangePattern<T> {
+    pub start: Expr<T>,
+    pub end: Expr<T>,
+    pub inclusive: bool,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -179,6 +274,10 @@ pub(crate) struct EnumVariantSelector {
 
     // `baz`
     pub data_bindings: Vec<PatternMatchedBinding>,
+
+    /// Field names and bindings of a variant with named fields, like `Bar::Foo { a: b, c, .. }`.
+    /// Converted to `data_bindings` by the type checker, which knows the order of the fields.
+    pub named_data_bindings: Vec<(String, PatternMatchedBinding)>,
 }
 
 impl EnumVariantSelector {
@@ -224,6 +323,42 @@ pub(crate) struct PanicStmt;
 pub(crate) struct WhileStmt<T> {
     pub condition: Expr<T>,
     pub block: BlockStmt<T>,
+    pub label: Option<String>,
+}
+
+/// An unconditional loop, `loop { ... }`, that can only be exited through `break`
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct LoopStmt<T> {
+    pub block: BlockStmt<T>,
+    pub label: Option<String>,
+}
+
+/// A `for` loop. Only exists until type checking, where it is lowered to a block
+/// containing a `while` loop, as the lowering requires knowledge of the types involved.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct ForStmt<T> {
+    /// `None` if the loop variable is `_`
+    pub loop_variable: Option<PatternMatchedBinding>,
+    pub iterable: ForIterable<T>,
+    pub block: BlockStmt<T>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) enum ForIterable<T> {
+    /// `a..b`, `a..=b`, `(a..b).rev()`, or `(a..=b).rev()`
+    Range(RangeExpr<T>),
+
+    /// `v`, `&v`, `v.iter()`, or `v.into_iter()` where `v` is a list or a boxed array
+    Collection(Expr<T>),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct RangeExpr<T> {
+    pub start: Expr<T>,
+    pub end: Expr<T>,
+    pub inclusive: bool,
+    pub reversed: bool,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -241,9 +376,13 @@ pub(crate) struct BlockStmt<T> {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) enum ExprLit<T> {
     Bool(bool),
+    U8(u8),
+    U16(u16),
     U32(u32),
     U64(u64),
     U128(u128),
+    I32(i32),
+    I64(i64),
     Bfe(BFieldElement),
     Xfe(XFieldElement),
     Digest(Digest),
@@ -255,9 +394,13 @@ impl<T> ExprLit<T> {
     pub fn label_friendly_name(&self) -> String {
         match self {
             ExprLit::Bool(b) => b.to_string(),
+            ExprLit::U8(val) => val.to_string(),
+            ExprLit::U16(val) => val.to_string(),
             ExprLit::U32(u32) => u32.to_string(),
             ExprLit::U64(val) => val.to_string(),
             ExprLit::U128(val) => val.to_string(),
+            ExprLit::I32(val) => val.to_string().replace('-', "minus"),
+            ExprLit::I64(val) => val.to_string().replace('-', "minus"),
             ExprLit::Bfe(val) => val.to_string(),
             ExprLit::Xfe(val) => val.to_string(),
             ExprLit::Digest(val) => val.to_string(),
@@ -271,9 +414,13 @@ impl<T> Display for ExprLit<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = match self {
             ExprLit::Bool(b) => b.to_string(),
+            ExprLit::U8(val) => val.to_string(),
+            ExprLit::U16(val) => val.to_string(),
             ExprLit::U32(u32) => u32.to_string(),
             ExprLit::U64(val) => val.to_string(),
             ExprLit::U128(val) => val.to_string(),
+            ExprLit::I32(val) => val.to_string(),
+            ExprLit::I64(val) => val.to_string(),
             ExprLit::Bfe(val) => val.to_string(),
             ExprLit::Xfe(val) => val.to_string(),
             ExprLit::Digest(val) => val.to_string(),
@@ -312,9 +459,14 @@ impl<T> BFieldCodec for ExprLit<T> {
     fn encode(&self) -> Vec<BFieldElement> {
         match self {
             ExprLit::Bool(value) => vec![BFieldElement::new(*value as u64)],
+            ExprLit::U8(value) => vec![BFieldElement::new(*value as u64)],
+            ExprLit::U16(value) => vec![BFieldElement::new(*value as u64)],
             ExprLit::U32(value) => value.encode(),
             ExprLit::U64(value) => value.encode(),
             ExprLit::U128(val) => val.encode(),
+            // Signed integers are encoded as their two's complement bit pattern
+            ExprLit::I32(value) => (*value as u32).encode(),
+            ExprLit::I64(value) => (*value as u64).encode(),
             ExprLit::Bfe(value) => value.encode(),
             ExprLit::Xfe(value) => value.encode(),
             ExprLit::Digest(value) => value.encode(),
@@ -379,9 +531,11 @@ pub(crate) enum Expr<T> {
     Binop(Box<Expr<T>>, BinOp, Box<Expr<T>>, T),
     Unary(UnaryOp, Box<Expr<T>>, T),
     If(ExprIf<T>),
+    Match(MatchExpr<T>),
     Cast(Box<Expr<T>>, DataType),
     ReturningBlock(Box<ReturningBlock<T>>),
     Struct(StructExpr<T>),
+    Closure(Closure<T>),
     // Index(Box<Expr<T>>, Box<Expr<T>>), // a_expr[i_expr]    (a + 5)[3]
     // TODO: VM-specific intrinsics (hash, absorb, squeeze, etc.)
 }
@@ -425,11 +579,13 @@ impl<T> Expr<T> {
             Expr::Binop(_, binop, _, _) => format!("binop_{binop:?}"),
             Expr::Unary(unaryop, _, _) => format!("unaryop_{}", unaryop.label_friendly_name()),
             Expr::If(_) => "if_else".to_owned(),
+            Expr::Match(_) => "match".to_owned(),
             Expr::Cast(_, dt) => format!("cast_{}", dt.label_friendly_name()),
             Expr::ReturningBlock(_) => "returning_block".to_owned(),
             Expr::Struct(struct_expr) => {
                 format!("struct_expr_{}", struct_expr.label_friendly_name())
             }
+            Expr::Closure(_) => "closure".to_owned(),
             Expr::EnumDeclaration(enum_init) => {
                 format!("enum_init_{}", enum_init.label_friendly_name())
             }
@@ -455,9 +611,11 @@ impl<T> Display for Expr<T> {
             }) => format!("{}.{method_name}", args[0]),
             Expr::Binop(_, binop, _, _) => format!("binop_{binop:?}"),
             Expr::If(_) => "if_else".to_owned(),
+            Expr::Match(_) => "match".to_owned(),
             Expr::Cast(_, dt) => format!("cast_{dt}"),
             Expr::Unary(unaryop, _, _) => format!("unaryop_{unaryop:?}"),
             Expr::ReturningBlock(_) => "returning_block".to_owned(),
+            Expr::Closure(_) => "closure".to_owned(),
             Expr::Struct(struct_expr) => {
                 format!(
                     "struct_expression_for_{}",
@@ -513,6 +671,19 @@ impl<T> StructExpr<T> {
     }
 }
 
+/// A closure, like `|x| x * two`. Only exists until type checking, where it is lifted to a
+/// function that takes the values it captures as arguments before its own arguments.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct Closure<T> {
+    /// The names of the arguments, and their types if annotated, like `x: u64` in
+    /// `|x: u64| x + 1`
+    pub args: Vec<(String, Option<DataType>)>,
+
+    /// The type of the result, if annotated, like `u64` in `|x| -> u64 { x + 1 }`
+    pub output: Option<DataType>,
+    pub body: Box<Expr<T>>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct ExprIf<T> {
     pub condition: Box<Expr<T>>,
@@ -520,6 +691,20 @@ pub(crate) struct ExprIf<T> {
     pub else_branch: Box<ReturningBlock<T>>,
 }
 
+/// A `match` whose arms evaluate to a value, like `match x { A => 1, B(y) => y }`
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct MatchExpr<T> {
+    pub match_expression: Box<Expr<T>>,
+    pub arms: Vec<MatchExprArm<T>>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct MatchExprArm<T> {
+    pub match_condition: MatchCondition<T>,
+    pub guard: Option<Expr<T>>,
+    pub body: ReturningBlock<T>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct ReturningBlock<T> {
     pub stmts: Vec<Stmt<T>>,
@@ -616,19 +801,74 @@ pub(crate) struct AssignStmt<T> {
 pub(crate) struct LetStmt<T> {
     pub var_name: String,
     pub mutable: bool,
+
+    /// The declared type of the binding. `None` if the type is not annotated, in which case
+    /// it is inferred by the type checker.
+    pub data_type: Option<DataType>,
+    pub expr: Expr<T>,
+}
+
+/// A `static` item, like `static TABLE: [u64; 4] = [1, 2, 4, 8];`. Declared in the body of
+/// the entrypoint, where its value is stored in static memory at program start. All
+/// functions of the program read it from there.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct StaticStmt<T> {
+    pub name: String,
     pub data_type: DataType,
     pub expr: Expr<T>,
 }
 
+/// A `let` statement that destructures a value into several bindings, like
+/// `let (q, r): (u64, u64) = div(a, b);` or `let Point { x, y } = p;`
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct DestructuringLetStmt<T> {
+    pub pattern: DestructuringPattern,
+
+    /// The declared type of the value. `None` if the type is not annotated, in which case it
+    /// is inferred by the type checker.
+    pub data_type: Option<DataType>,
+    pub expr: Expr<T>,
+}
+
+/// An irrefutable pattern in a destructuring `let` statement
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) enum DestructuringPattern {
+    /// `_`
+    Wildcard,
+
+    /// `a` or `mut a`
+    Binding(PatternMatchedBinding),
+
+    /// `(a, _)`
+    Tuple(Vec<DestructuringPattern>),
+
+    /// `Pair(a, b)` or `Point { x, y: (b, c), .. }`. Fields that are not mentioned are matched
+    /// by `_`.
+    Struct {
+        struct_type: DataType,
+        fields: Vec<(FieldId, DestructuringPattern)>,
+    },
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct FnCall<T> {
     pub name: String,
     pub args: Vec<Expr<T>>,
-    pub type_parameter: Option<DataType>,
+
+    /// The explicit type arguments, like `u64` and `_` in `apply::<u64, _>(f, x)`, where
+    /// `_` is `None`
+    pub type_arguments: Vec<Option<DataType>>,
     pub arg_evaluation_order: ArgEvaluationOrder,
     pub annot: T,
 }
 
+impl<T> FnCall<T> {
+    /// The type argument of a call to a library function, which takes at most one
+    pub fn type_parameter(&self) -> Option<DataType> {
+        self.type_arguments.iter().flatten().next().cloned()
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct MethodCall<T> {
     pub method_name: String,
@@ -29,9 +29,13 @@ pub mod tuple;
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum DataType {
     Bool,
+    U8,
+    U16,
     U32,
     U64,
     U128,
+    I32,
+    I64,
     Bfe,
     Xfe,
     Digest,
@@ -53,7 +57,9 @@ impl DataType {
             DataType::Struct(StructType {
                 name: _,
                 is_copy: _,
+                is_partial_eq: _,
                 variant,
+                type_arguments: _,
             }) => match variant {
                 StructVariant::TupleStruct(ts) => ts.to_owned(),
                 StructVariant::NamedFields(_) => {
@@ -70,9 +76,13 @@ impl DataType {
     pub fn is_copy(&self) -> bool {
         match self {
             DataType::Bool => true,
+            DataType::U8 => true,
+            DataType::U16 => true,
             DataType::U32 => true,
             DataType::U64 => true,
             DataType::U128 => true,
+            DataType::I32 => true,
+            DataType::I64 => true,
             DataType::Bfe => true,
             DataType::Xfe => true,
             DataType::Digest => true,
@@ -88,14 +98,53 @@ impl DataType {
         }
     }
 
+    /// Return true if values of this type can be compared with `==` and `!=`
+    /// without calling a user-defined method.
+    pub fn is_partial_eq(&self) -> bool {
+        match self {
+            DataType::Bool => true,
+            DataType::U8 => true,
+            DataType::U16 => true,
+            DataType::U32 => true,
+            DataType::U64 => true,
+            DataType::U128 => true,
+            DataType::I32 => true,
+            DataType::I64 => true,
+            DataType::Bfe => true,
+            DataType::Xfe => true,
+            DataType::Digest => true,
+            DataType::Tuple(tuple) => tuple.into_iter().all(|x| x.is_partial_eq()),
+            DataType::Array(array_type) => array_type.element_type.is_partial_eq(),
+            DataType::List(element_type, _) => element_type.is_partial_eq(),
+            DataType::VoidPointer => false,
+            DataType::Function(_) => false,
+            DataType::Struct(struct_type) => {
+                struct_type.is_partial_eq && struct_type.field_types().all(|x| x.is_partial_eq())
+            }
+            DataType::Enum(enum_type) => {
+                enum_type.is_partial_eq
+                    && enum_type
+                        .variants
+                        .iter()
+                        .all(|(_, data_type)| data_type.is_partial_eq())
+            }
+            DataType::Unresolved(_) => false,
+            DataType::Boxed(inner_type) => inner_type.is_partial_eq(),
+        }
+    }
+
     /// Use this if the type is used to make labels in the TASM code
     pub fn label_friendly_name(&self) -> String {
         use DataType::*;
         match self {
             Bool => "bool".to_string(),
+            U8 => "u8".to_string(),
+            U16 => "u16".to_string(),
             U32 => "u32".to_string(),
             U64 => "u64".to_string(),
             U128 => "u128".to_string(),
+            I32 => "i32".to_string(),
+            I64 => "i64".to_string(),
             Bfe => "BField".to_string(),
             Xfe => "XField".to_string(),
             Digest => "Digest".to_string(),
@@ -115,20 +164,34 @@ impl DataType {
                 let output = fn_type.output.label_friendly_name();
                 format!("function_from_L{}R__to_L{}R", input, output)
             }
-            Struct(struct_type) => struct_type.name.to_owned(),
-            Enum(enum_type) => match enum_type.type_parameter.as_ref() {
-                // Use type parameter here to differentiate between
-                // methods for `Result<BFE>` and `Result<XFE>`.
-                Some(type_param) => {
-                    format!("{}___{}", enum_type.name, type_param.label_friendly_name())
-                }
-                None => enum_type.name.to_owned(),
-            },
+            Struct(struct_type) => {
+                Self::generic_label_friendly_name(&struct_type.name, &struct_type.type_arguments)
+            }
+            // Use type arguments here to differentiate between
+            // methods for `Result<BFE>` and `Result<XFE>`.
+            Enum(enum_type) => {
+                Self::generic_label_friendly_name(&enum_type.name, &enum_type.type_arguments)
+            }
             Unresolved(name) => name.to_string(),
             Boxed(ty) => format!("boxed_L{}R", ty.label_friendly_name()),
         }
     }
 
+    /// The label friendly name of a custom type, which includes the type arguments of
+    /// the instances of generic types
+    fn generic_label_friendly_name(name: &str, type_arguments: &[DataType]) -> String {
+        match type_arguments.is_empty() {
+            true => name.to_owned(),
+            false => format!(
+                "{name}___{}",
+                type_arguments
+                    .iter()
+                    .map(|type_argument| type_argument.label_friendly_name())
+                    .join("___")
+            ),
+        }
+    }
+
     pub fn from_tasm_lib_datatype(
         tasm_lib_type: tasm_lib::data_type::DataType,
         list_type: ListType,
@@ -158,38 +221,35 @@ impl DataType {
     }
 
     /// What type is returned when type is accessed with a field of name `field_name`?
-    pub fn field_access_returned_type(&self, field_id: &FieldId) -> Self {
-        match &self {
-            DataType::Boxed(inner_type) => match &**inner_type {
-                DataType::Struct(struct_type) => {
-                    let field_type = struct_type.get_field_type(field_id);
-                    if field_type.is_copy() {
-                        field_type
-                    } else {
-                        DataType::Boxed(Box::new(field_type))
-                    }
-                }
-                // TODO: We probably also want to allow field access to `Reference` types here
-                DataType::Tuple(tuple) => {
-                    let tuple_index: usize = field_id.try_into().expect("Tuple must be accessed with a tuple index");
-                    let field_type = tuple.fields[tuple_index].clone();
-                    if field_type.is_copy() {
-                        field_type
-                    } else {
-                        DataType::Boxed(Box::new(field_type))
-                    }
-                },
-                _ => panic!("Field getter can only operate on type of struct or pointer to struct. Attempted to access field `{field_id}` on type `{self}`")
-            },
-            DataType::Struct(struct_type) => {
-                struct_type.get_field_type(field_id)
-            },
-            DataType::Tuple(tuple) => {
-                let tuple_index: usize = field_id.try_into().expect("Tuple must be accessed with a tuple index");
-                tuple.fields[tuple_index].clone()
-            },
-            _ => panic!("Field getter can only operate on type of struct or pointer to struct. Attempted to access field `{field_id}` on type `{self}`")
+    pub fn field_access_returned_type(&self, field_id: &FieldId) -> anyhow::Result<Self> {
+        let tuple_field_type = |tuple: &Tuple| {
+            usize::try_from(field_id)
+                .ok()
+                .and_then(|tuple_index| tuple.fields.get(tuple_index))
+                .cloned()
+        };
+        let field_type = match &self {
+            DataType::Boxed(inner_type) => {
+                let field_type = match &**inner_type {
+                    DataType::Struct(struct_type) => Some(struct_type.get_field_type(field_id)?),
+                    // TODO: We probably also want to allow field access to `Reference` types here
+                    DataType::Tuple(tuple) => tuple_field_type(tuple),
+                    _ => None,
+                };
+                field_type.map(|field_type| match field_type.is_copy() {
+                    true => field_type,
+                    false => DataType::Boxed(Box::new(field_type)),
+                })
             }
+            DataType::Struct(struct_type) => Some(struct_type.get_field_type(field_id)?),
+            DataType::Tuple(tuple) => tuple_field_type(tuple),
+            _ => None,
+        };
+
+        match field_type {
+            Some(field_type) => Ok(field_type),
+            None => bail!("no field `{field_id}` on type `{self}`"),
+        }
     }
 
     // TODO: Consider getting rid of this method
@@ -214,9 +274,13 @@ impl DataType {
     pub fn bfield_codec_length(&self) -> Option<usize> {
         match self {
             DataType::Bool => Some(1),
+            DataType::U8 => Some(1),
+            DataType::U16 => Some(1),
             DataType::U32 => Some(1),
             DataType::U64 => Some(2),
             DataType::U128 => Some(4),
+            DataType::I32 => Some(1),
+            DataType::I64 => Some(2),
             DataType::Bfe => Some(1),
             DataType::Xfe => Some(3),
             DataType::Digest => Some(5),
@@ -279,9 +343,13 @@ impl DataType {
     pub fn stack_size(&self) -> usize {
         match self {
             Self::Bool => 1,
+            Self::U8 => 1,
+            Self::U16 => 1,
             Self::U32 => 1,
             Self::U64 => 2,
             Self::U128 => 4,
+            Self::I32 => 1,
+            Self::I64 => 2,
             Self::Bfe => 1,
             Self::Xfe => 3,
             Self::Digest => 5,
@@ -331,9 +399,15 @@ impl TryFrom<DataType> for tasm_lib::data_type::DataType {
     fn try_from(value: DataType) -> Result<Self, Self::Error> {
         match value {
             DataType::Bool => Ok(tasm_lib::data_type::DataType::Bool),
+            DataType::U8 | DataType::U16 => Err(format!(
+                "Small integer type {value} not yet supported by tasm-lib"
+            )),
             DataType::U32 => Ok(tasm_lib::data_type::DataType::U32),
             DataType::U64 => Ok(tasm_lib::data_type::DataType::U64),
             DataType::U128 => Ok(tasm_lib::data_type::DataType::U128),
+            DataType::I32 | DataType::I64 => Err(format!(
+                "Signed integer type {value} not yet supported by tasm-lib"
+            )),
             DataType::Bfe => Ok(tasm_lib::data_type::DataType::Bfe),
             DataType::Xfe => Ok(tasm_lib::data_type::DataType::Xfe),
             DataType::Digest => Ok(tasm_lib::data_type::DataType::Digest),
@@ -381,12 +455,16 @@ impl FromStr for DataType {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "bool" => Ok(DataType::Bool),
+            "u8" => Ok(DataType::U8),
+            "u16" => Ok(DataType::U16),
             "u32" => Ok(DataType::U32),
 
             // `usize` is just an alias for `u32` in this compiler
             "usize" => Ok(DataType::U32),
             "u64" => Ok(DataType::U64),
             "u128" => Ok(DataType::U128),
+            "i32" => Ok(DataType::I32),
+            "i64" => Ok(DataType::I64),
             "BFieldElement" => Ok(DataType::Bfe),
             "XFieldElement" => Ok(DataType::Xfe),
             "Digest" => Ok(DataType::Digest),
@@ -400,9 +478,13 @@ impl Display for DataType {
         use DataType::*;
         let str = match self {
             Bool => "bool".to_string(),
+            U8 => "u8".to_string(),
+            U16 => "u16".to_string(),
             U32 => "u32".to_string(),
             U64 => "u64".to_string(),
             U128 => "u128".to_string(),
+            I32 => "i32".to_string(),
+            I64 => "i64".to_string(),
             Bfe => "BFieldElement".to_string(),
             Xfe => "XFieldElement".to_string(),
             Digest => "Digest".to_string(),
@@ -415,10 +497,14 @@ impl Display for DataType {
                 let output = fn_type.output.to_string();
                 format!("Function: {input} -> {output}")
             }
-            Struct(struct_type) => struct_type.name.to_owned(),
-            Enum(enum_type) => match &enum_type.type_parameter {
-                Some(type_param) => format!("{}<{}>", enum_type.name, type_param),
-                None => enum_type.name.to_owned(),
+            Struct(struct_type) => struct_type.to_string(),
+            Enum(enum_type) => match enum_type.type_arguments.is_empty() {
+                true => enum_type.name.to_owned(),
+                false => format!(
+                    "{}<{}>",
+                    enum_type.name,
+                    enum_type.type_arguments.iter().join(", ")
+                ),
             },
             Unresolved(name) => name.to_string(),
             Boxed(ty) => format!("Boxed<{ty}>"),
@@ -433,7 +519,9 @@ impl From<FnSignature> for DataType {
 
         for inp in value.args.iter() {
             let input = match inp {
-                AbstractArgument::FunctionArgument(_) => todo!(),
+                AbstractArgument::FunctionArgument(abs_fn) => {
+                    DataType::Function(Box::new(abs_fn.function_type.to_owned()))
+                }
                 AbstractArgument::ValueArgument(abs_val) => abs_val.data_type.to_owned(),
             };
             input_args.push(input);
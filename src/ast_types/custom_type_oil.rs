@@ -81,6 +81,15 @@ impl CustomTypeOil {
         }
     }
 
+    /// Return true if the type is an instance of a generic struct or enum, like
+    /// `Pair<u32>`. The instances of a generic type share its name.
+    pub(crate) fn is_generic_instance(&self) -> bool {
+        match self {
+            CustomTypeOil::Struct(s) => !s.type_arguments.is_empty(),
+            CustomTypeOil::Enum(e) => !e.type_arguments.is_empty(),
+        }
+    }
+
     pub(crate) fn is_prelude(&self) -> bool {
         match self {
             CustomTypeOil::Struct(_) => false,
@@ -6,12 +6,17 @@ use super::DataType;
 pub struct EnumType {
     pub name: String,
     pub is_copy: bool,
+    pub is_partial_eq: bool,
     pub variants: Vec<(String, DataType)>,
+
+    /// The names of the fields of variants declared with named fields, like `Foo { a: u32 }`.
+    /// The data of these variants is stored like that of tuple-like variants.
+    pub variant_field_names: Vec<(String, Vec<String>)>,
     pub is_prelude: bool,
 
-    // Use `type_parameter` to create differentiate between function labels
+    // Use `type_arguments` to create differentiate between function labels
     // for e.g. `Result<T>` and `Result<S>` types.
-    pub type_parameter: Option<DataType>,
+    pub type_arguments: Vec<DataType>,
 }
 
 impl From<&EnumType> for DataType {
@@ -55,6 +60,14 @@ impl EnumType {
         );
     }
 
+    /// Return the names of the fields of a variant, if it was declared with named fields
+    pub(crate) fn variant_field_names(&self, variant_name: &str) -> Option<&[String]> {
+        self.variant_field_names
+            .iter()
+            .find(|(name, _)| name == variant_name)
+            .map(|(_, field_names)| field_names.as_slice())
+    }
+
     /// Return the "discriminant" of an enum variant, an integer showing
     /// what variant the enum type takes.
     pub(crate) fn variant_discriminant(&self, variant_name: &str) -> usize {
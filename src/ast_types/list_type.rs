@@ -1,4 +1,6 @@
 use std::fmt::Display;
+use std::str::FromStr;
+
 use tasm_lib::traits::basic_snippet::BasicSnippet;
 
 use super::DataType;
@@ -89,3 +91,15 @@ impl Display for ListType {
         )
     }
 }
+
+impl FromStr for ListType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "safe" => Ok(Self::Safe),
+            "unsafe" => Ok(Self::Unsafe),
+            _ => anyhow::bail!("Unknown list type \"{s}\". Expected \"safe\" or \"unsafe\"."),
+        }
+    }
+}
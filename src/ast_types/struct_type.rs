@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use anyhow::bail;
 use itertools::Itertools;
 
 use crate::libraries::LibraryFunction;
@@ -47,7 +48,12 @@ pub enum StructVariant {
 pub struct StructType {
     pub name: String,
     pub is_copy: bool,
+    pub is_partial_eq: bool,
     pub variant: StructVariant,
+
+    // Use `type_arguments` to differentiate between function labels for the
+    // instances of a generic struct, e.g. `Pair<u32>` and `Pair<u64>`.
+    pub type_arguments: Vec<DataType>,
 }
 
 impl From<StructType> for DataType {
@@ -64,7 +70,15 @@ impl From<&StructType> for DataType {
 
 impl Display for StructType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.name)
+        match self.type_arguments.is_empty() {
+            true => write!(f, "{}", self.name),
+            false => write!(
+                f,
+                "{}<{}>",
+                self.name,
+                self.type_arguments.iter().join(", ")
+            ),
+        }
     }
 }
 
@@ -79,30 +93,22 @@ impl StructType {
         tuple.constructor(&self.name, DataType::Struct(self.to_owned()))
     }
 
-    pub(crate) fn get_field_type(&self, field_id: &FieldId) -> DataType {
-        let res = match &self.variant {
-            StructVariant::TupleStruct(ts) => match field_id {
-                FieldId::NamedField(_) => {
-                    panic!("Cannot access tuple struct with named field '{field_id}'.")
-                }
-
-                FieldId::UnnamedField(tuple_index) => {
-                    ts.fields.get(*tuple_index).map(|x| x.to_owned())
-                }
-            },
-            StructVariant::NamedFields(nfs) => match field_id {
-                FieldId::NamedField(field_name) => nfs
-                    .fields
-                    .iter()
-                    .find(|&field| field.0 == *field_name)
-                    .map(|x| x.1.to_owned()),
-                FieldId::UnnamedField(tuple_index) => panic!("Cannot access struct with named fields with a tuple index. Got tuple index '{tuple_index}'.")
-            },
+    pub(crate) fn get_field_type(&self, field_id: &FieldId) -> anyhow::Result<DataType> {
+        let res = match (&self.variant, field_id) {
+            (StructVariant::TupleStruct(ts), FieldId::UnnamedField(tuple_index)) => {
+                ts.fields.get(*tuple_index)
+            }
+            (StructVariant::NamedFields(nfs), FieldId::NamedField(field_name)) => nfs
+                .fields
+                .iter()
+                .find(|&field| field.0 == *field_name)
+                .map(|x| &x.1),
+            _ => None,
         };
 
         match res {
-            Some(dtype) => dtype,
-            None => panic!("Struct {self} has no field '{field_id}'"),
+            Some(dtype) => Ok(dtype.to_owned()),
+            None => bail!("no field `{field_id}` on type `{self}`"),
         }
     }
 
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+
+use itertools::Itertools;
+use proc_macro2::Span;
+
+/// The kinds of errors the compiler can report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A source file could not be read or parsed as Rust
+    SyntaxError,
+
+    /// A valid Rust construct that is not supported by this compiler
+    UnsupportedSyntax,
+
+    /// A `let` binding without an explicit type, whose type cannot be inferred
+    MissingTypeAnnotation,
+
+    /// A type that is neither primitive nor a custom type
+    UnknownType,
+
+    /// A `let` binding without an initial value
+    MissingInitializer,
+
+    /// A module declared with `mod foo;` or imported through `use`, for which no readable
+    /// file exists
+    ModuleNotFound,
+
+    /// The entrypoint is not declared in the root module
+    MissingEntrypoint,
+
+    /// Two functions with the same name at module level
+    DuplicateFunction,
+
+    /// Two arguments with the same name in one function signature
+    DuplicateArgument,
+
+    /// Function arguments that do not fit on the accessible part of the stack
+    ArgumentsTooLarge,
+
+    /// A function whose body does not end with a `return` statement
+    MissingReturn,
+
+    /// A `break` or `continue` outside of a loop, or targeting an undeclared label
+    InvalidLoopExit,
+
+    /// An assignment to a binding that was not declared as `mut`
+    ImmutableAssignment,
+
+    /// An expression whose type does not match the expected type
+    TypeError,
+
+    /// Two custom types, traits, constants, or `static` items with the same name in the
+    /// modules of a program, or two methods with the same name for one type
+    DuplicateDefinition,
+}
+
+impl ErrorCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorCode::SyntaxError => "E0001",
+            ErrorCode::UnsupportedSyntax => "E0002",
+            ErrorCode::MissingTypeAnnotation => "E0003",
+            ErrorCode::UnknownType => "E0004",
+            ErrorCode::MissingInitializer => "E0005",
+            ErrorCode::ModuleNotFound => "E0006",
+            ErrorCode::MissingEntrypoint => "E0007",
+            ErrorCode::DuplicateFunction => "E0008",
+            ErrorCode::DuplicateArgument => "E0009",
+            ErrorCode::ArgumentsTooLarge => "E0010",
+            ErrorCode::MissingReturn => "E0011",
+            ErrorCode::InvalidLoopExit => "E0012",
+            ErrorCode::ImmutableAssignment => "E0013",
+            ErrorCode::TypeError => "E0014",
+            ErrorCode::DuplicateDefinition => "E0015",
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// A position in a source file. Lines and columns are 1-indexed.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceLocation {
+    pub file_path: Option<String>,
+    pub line: usize,
+    pub column: usize,
+
+    /// The number of characters to underline, starting at `column`
+    pub length: usize,
+}
+
+impl SourceLocation {
+    pub fn from_span(span: Span) -> Self {
+        let start = span.start();
+        let end = span.end();
+        let length = if start.line == end.line && end.column > start.column {
+            end.column - start.column
+        } else {
+            1
+        };
+
+        Self {
+            file_path: None,
+            line: start.line,
+            column: start.column + 1,
+            length,
+        }
+    }
+
+    fn source_line(&self) -> Option<String> {
+        let file_path = self.file_path.as_ref()?;
+        let content = fs::read_to_string(file_path).ok()?;
+        content.lines().nth(self.line - 1).map(str::to_owned)
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file_path = self.file_path.as_deref().unwrap_or("<unknown>");
+        write!(f, "{file_path}:{}:{}", self.line, self.column)
+    }
+}
+
+/// An error in the program being compiled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub location: Option<SourceLocation>,
+
+    /// The name of the function in which the error was found. Used to locate errors that
+    /// are found after the source code has been grafted, as the grafted AST carries no spans.
+    pub(crate) function: Option<String>,
+}
+
+impl CompileError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            location: None,
+            function: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        let file_path = self.location.and_then(|location| location.file_path);
+        self.location = Some(SourceLocation {
+            file_path,
+            ..SourceLocation::from_span(span)
+        });
+        self
+    }
+
+    /// Set the file of the error's location, unless it is already set
+    pub fn in_file(mut self, file_path: Option<&str>) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.file_path = location
+                .file_path
+                .take()
+                .or_else(|| file_path.map(str::to_owned));
+        }
+        self
+    }
+
+    /// Set the location of the error, unless it is already set
+    pub(crate) fn at_location(mut self, location: Option<&SourceLocation>) -> Self {
+        self.location = self.location.or_else(|| location.cloned());
+        self
+    }
+
+    /// Set the function in which the error was found, unless it is already set
+    pub(crate) fn in_function(mut self, function_name: &str) -> Self {
+        self.function = self.function.or_else(|| Some(function_name.to_owned()));
+        self
+    }
+
+    /// Locate an error without a location at the declaration of the function in which it
+    /// was found.
+    pub(crate) fn locate_in_function(
+        mut self,
+        function_locations: &HashMap<String, SourceLocation>,
+    ) -> Self {
+        if self.location.is_none() {
+            self.location = self
+                .function
+                .as_ref()
+                .and_then(|function_name| function_locations.get(function_name))
+                .cloned();
+        }
+        self
+    }
+
+    /// Render the error like `rustc` does, including the line of source code the error
+    /// refers to, if that line can be read.
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!("error[{}]: {}", self.code, self.message)];
+        let gutter = self
+            .location
+            .as_ref()
+            .map(|location| " ".repeat(location.line.to_string().len()))
+            .unwrap_or_default();
+
+        if let Some(location) = &self.location {
+            lines.push(format!("{gutter}--> {location}"));
+            if let Some(source_line) = location.source_line() {
+                let indentation = " ".repeat(location.column - 1);
+                let underline = "^".repeat(location.length);
+                lines.push(format!("{gutter} |"));
+                lines.push(format!("{} | {source_line}", location.line));
+                lines.push(format!("{gutter} | {indentation}{underline}"));
+            }
+        }
+
+        if let Some(function_name) = &self.function {
+            lines.push(format!("{gutter} = note: in function `{function_name}`"));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// All errors found while compiling a program
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub errors: Vec<CompileError>,
+}
+
+impl Diagnostics {
+    /// Return `Ok` if no errors were found
+    pub(crate) fn check(errors: Vec<CompileError>) -> Result<(), Self> {
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(Self::sorted(errors)),
+        }
+    }
+
+    /// Order errors by their location, as they are reported in the order in which they are
+    /// found, not in the order in which they appear in the source code
+    fn sorted(mut errors: Vec<CompileError>) -> Self {
+        errors.sort_by(|a, b| a.location.cmp(&b.location));
+        Self { errors }
+    }
+
+    /// Locate all errors without a location at the declaration of the function in which
+    /// they were found
+    pub(crate) fn locate_in_functions(
+        self,
+        function_locations: &HashMap<String, SourceLocation>,
+    ) -> Self {
+        let errors = self
+            .errors
+            .into_iter()
+            .map(|error| error.locate_in_function(function_locations))
+            .collect();
+        Self::sorted(errors)
+    }
+
+    pub fn render(&self) -> String {
+        let error_count = match self.errors.len() {
+            1 => "previous error".to_owned(),
+            n => format!("{n} previous errors"),
+        };
+        let rendered_errors = self.errors.iter().map(|error| error.render()).join("\n\n");
+
+        format!("{rendered_errors}\n\nerror: aborting due to {error_count}")
+    }
+}
+
+impl From<CompileError> for Diagnostics {
+    fn from(error: CompileError) -> Self {
+        Self {
+            errors: vec![error],
+        }
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(
+        file_path: Option<String>,
+        line: usize,
+        column: usize,
+        length: usize,
+    ) -> CompileError {
+        CompileError {
+            location: Some(SourceLocation {
+                file_path,
+                line,
+                column,
+                length,
+            }),
+            ..CompileError::new(ErrorCode::TypeError, "mismatched types")
+        }
+    }
+
+    #[test]
+    fn render_source_line_with_underline_test() {
+        let source_file = std::env::temp_dir().join(format!(
+            "tasm_lang_render_source_line_{}.rs",
+            std::process::id()
+        ));
+        let source = (1..=12)
+            .map(|i| format!("    let a{i}: u32 = {i};"))
+            .join("\n");
+        fs::write(&source_file, source).unwrap();
+
+        let file_path = source_file.to_str().unwrap().to_owned();
+        let rendered = error_at(Some(file_path.clone()), 10, 9, 3).render();
+        fs::remove_file(&source_file).unwrap();
+
+        // The gutter is as wide as the number of the line
+        let expected = format!(
+            "error[E0014]: mismatched types\n  \
+             --> {file_path}:10:9\n   \
+             |\n\
+             10 |     let a10: u32 = 10;\n   \
+             |         ^^^"
+        );
+        assert_eq!(expected, rendered);
+    }
+
+    #[test]
+    fn render_without_source_line_if_file_cannot_be_read_test() {
+        let rendered = error_at(Some("missing/file.rs".to_owned()), 7, 5, 2).render();
+        assert_eq!(
+            "error[E0014]: mismatched types\n --> missing/file.rs:7:5",
+            rendered
+        );
+
+        let rendered = error_at(None, 123, 1, 1).in_function("main").render();
+        assert_eq!(
+            "error[E0014]: mismatched types\n   --> <unknown>:123:1\n    = note: in function `main`",
+            rendered
+        );
+    }
+
+    #[test]
+    fn render_number_of_errors_test() {
+        let error = CompileError::new(ErrorCode::UnsupportedSyntax, "unsupported");
+        let diagnostics = Diagnostics::from(error.clone());
+        assert_eq!(
+            "error[E0002]: unsupported\n\nerror: aborting due to previous error",
+            diagnostics.render()
+        );
+
+        let diagnostics = Diagnostics {
+            errors: vec![error.clone(), error],
+        };
+        assert!(diagnostics
+            .render()
+            .ends_with("unsupported\n\nerror: aborting due to 2 previous errors"));
+    }
+}
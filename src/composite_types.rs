@@ -19,6 +19,10 @@ pub(crate) struct TypeContext {
     pub(crate) composite_type: ast_types::CustomTypeOil,
     pub(crate) methods: Vec<ast::Method<Typing>>,
     pub(crate) associated_functions: Vec<ast::Fn<Typing>>,
+
+    /// The traits implemented by `impl Trait for Type` blocks, whose methods are among
+    /// `methods` and `associated_functions`
+    pub(crate) traits: Vec<String>,
 }
 
 impl From<CustomTypeOil> for TypeContext {
@@ -27,6 +31,7 @@ impl From<CustomTypeOil> for TypeContext {
             composite_type: value,
             methods: Default::default(),
             associated_functions: Default::default(),
+            traits: Default::default(),
         }
     }
 }
@@ -86,6 +91,10 @@ pub(crate) struct CompositeTypes {
     by_name: HashMap<String, Vec<usize>>,
     by_type: HashMap<ast_types::DataType, usize>,
     composite_types: Vec<TypeContext>,
+
+    /// The concrete types of the type parameters of the generic function that is being
+    /// instantiated
+    type_arguments: HashMap<String, ast_types::DataType>,
 }
 
 impl IntoIterator for CompositeTypes {
@@ -211,6 +220,21 @@ impl CompositeTypes {
         self.composite_types.push(tyctx);
     }
 
+    /// Return a copy in which the given type parameters resolve to concrete types
+    pub(crate) fn with_type_arguments(
+        &self,
+        type_arguments: HashMap<String, ast_types::DataType>,
+    ) -> Self {
+        Self {
+            type_arguments,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn get_type_argument(&self, type_parameter: &str) -> Option<&ast_types::DataType> {
+        self.type_arguments.get(type_parameter)
+    }
+
     /// Return a type context that must be uniquely identified by its name.
     /// Otherwise this function panics.
     pub(crate) fn get_unique_by_name(&self, type_name: &str) -> TypeContext {
@@ -226,6 +250,20 @@ impl CompositeTypes {
         }
     }
 
+    /// Return the type contexts of all types with the given name. The instances of a
+    /// generic type share its name.
+    pub(crate) fn get_by_name(&self, type_name: &str) -> Vec<&TypeContext> {
+        self.by_name
+            .get(type_name)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .map(|index| &self.composite_types[*index])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Return a mutable pointer to a type context that must be uniquely identified
     /// by its name. Otherwise this function panics.
     pub(crate) fn get_mut_unique_by_name(&mut self, type_name: &str) -> &mut TypeContext {
@@ -262,11 +300,13 @@ impl CompositeTypes {
         variant_name: &str,
         expected_type: &ast_types::DataType,
     ) -> Option<EnumType> {
-        let expected_type = expected_type.as_enum_type();
+        let ast_types::DataType::Enum(expected_type) = expected_type else {
+            return None;
+        };
         let preludes = self.preludes();
         let mut ret = None;
         for prelude in preludes {
-            if prelude.has_variant_of_name(variant_name) && prelude == expected_type {
+            if prelude.has_variant_of_name(variant_name) && prelude == **expected_type {
                 ret = Some(prelude);
             }
         }
@@ -274,24 +314,18 @@ impl CompositeTypes {
         ret
     }
 
-    pub(crate) fn methods_mut(&mut self) -> std::vec::IntoIter<&mut ast::Method<Typing>> {
-        self.composite_types
-            .iter_mut()
-            .flat_map(|x| x.methods.iter_mut())
-            .collect_vec()
-            .into_iter()
-    }
-
-    pub(crate) fn associated_functions_mut(&mut self) -> std::vec::IntoIter<&mut ast::Fn<Typing>> {
-        self.composite_types
-            .iter_mut()
-            .flat_map(|x| x.associated_functions.iter_mut())
-            .collect_vec()
-            .into_iter()
+    pub(crate) fn type_contexts_mut(&mut self) -> std::slice::IterMut<'_, TypeContext> {
+        self.composite_types.iter_mut()
     }
 
-    pub(crate) fn get_associated_function_signature(&self, name: &str) -> Option<ast::FnSignature> {
-        self.get_associated_function(name).map(|x| x.signature)
+    pub(crate) fn get_associated_function_signature(
+        &self,
+        name: &str,
+        args: &[ast::Expr<Typing>],
+        output_type: Option<&ast_types::DataType>,
+    ) -> Option<ast::FnSignature> {
+        self.get_associated_function(name, args, output_type)
+            .map(|x| x.signature)
     }
 
     pub(crate) fn get_all_constructor_signatures(&self) -> HashMap<String, Vec<ast::FnSignature>> {
@@ -355,32 +389,16 @@ impl CompositeTypes {
         }
     }
 
-    /* Debugging */
-    pub(crate) fn all_method_names(&self) -> String {
-        self.composite_types
-            .iter()
-            .map(|tyctx| {
-                let method_names_of_type = tyctx.methods.iter().join(", ");
-                format!(
-                    "{}:\n    {method_names_of_type}",
-                    tyctx.composite_type.name()
-                )
-            })
-            .join("\n\n")
-    }
-
-    pub(crate) fn all_composite_type_names(&self) -> String {
-        self.composite_types
-            .iter()
-            .map(|tyctx| {
-                let as_dt: ast_types::DataType = tyctx.composite_type.clone().into();
-                format!("{as_dt}")
-            })
-            .join("\n\n")
-    }
-
     /********** Shared Methods **********/
-    pub(crate) fn get_associated_function(&self, name: &str) -> Option<ast::Fn<Typing>> {
+    /// Return the associated function called with `name`. If multiple instances of a generic
+    /// type have an associated function of that name, the one matching the types of the
+    /// arguments and the output is returned.
+    pub(crate) fn get_associated_function(
+        &self,
+        name: &str,
+        args: &[ast::Expr<Typing>],
+        output_type: Option<&ast_types::DataType>,
+    ) -> Option<ast::Fn<Typing>> {
         // Associated functions be called with `<Type>::<function_name>`, where `function_name`
         // must be lower-cased.
         let split_name = name.split("::").collect_vec();
@@ -391,18 +409,33 @@ impl CompositeTypes {
         let type_name = split_name[0];
         let fname = split_name[1];
 
-        let ty_ctx = if let Some(indices) = self.by_name.get(type_name) {
-            assert!(
-                indices.len().is_one(),
-                "Multiple composite types with name {} found",
-                type_name
-            );
-            &self.composite_types[indices[0]]
-        } else {
-            return None;
+        let candidates = self
+            .get_by_name(type_name)
+            .into_iter()
+            .filter_map(|ty_ctx| Some((ty_ctx, ty_ctx.get_associated_function(fname)?)))
+            .collect_vec();
+        let (ty_ctx, afunc) = match candidates[..] {
+            [] => return None,
+            [candidate] => candidate,
+            _ => {
+                let arg_types = args.iter().map(|arg| arg.get_type()).collect_vec();
+                candidates.iter().copied().find(|(_, afunc)| {
+                    afunc.signature.matches(&arg_types)
+                        && output_type.map_or(true, |output| afunc.signature.output == *output)
+                })?
+            }
         };
 
-        ty_ctx.get_associated_function(fname).map(|x| x.to_owned())
+        // The instances of a generic type share its associated functions, so their labels
+        // are prefixed with the instance
+        let mut afunc = afunc.to_owned();
+        if ty_ctx.composite_type.is_generic_instance() {
+            let type_label =
+                ast_types::DataType::from(ty_ctx.composite_type.clone()).label_friendly_name();
+            afunc.signature.name = format!("{type_label}_{fname}");
+        }
+
+        Some(afunc)
     }
 
     /// Return all enums that are included in `prelude`, meaning that
@@ -431,7 +464,7 @@ impl CompositeTypes {
         ast::FnCall {
             name,
             args,
-            type_parameter: _,
+            type_arguments: _,
             arg_evaluation_order: _,
             annot,
         }: &ast::FnCall<type_checker::Typing>,
@@ -481,6 +514,7 @@ impl CompositeTypes {
                                 .all(|(constructor_abstr_arg, actual_arg)| {
                                     constructor_abstr_arg == actual_arg.get_type()
                                 })
+                                && return_type == e.to_owned().into()
                             {
                                 return Some((
                                     self.composite_types[*candidate].composite_type.to_owned(),
@@ -502,6 +536,7 @@ impl CompositeTypes {
                             if s.field_ids_and_types()
                                 .zip(args.iter())
                                 .all(|((_, aa), ca)| *aa == ca.get_type())
+                                && return_type == s.to_owned().into()
                             {
                                 return Some((
                                     self.composite_types[*candidate].composite_type.to_owned(),
@@ -1,6 +1,9 @@
+use itertools::Itertools;
+
 use crate::ast::*;
 use crate::ast_types;
 use crate::ast_types::AbstractArgument;
+use crate::ast_types::AbstractFunctionArg;
 use crate::ast_types::AbstractValueArg;
 use crate::ast_types::CustomTypeOil;
 use crate::ast_types::DataType;
@@ -54,13 +57,13 @@ impl CustomTypeResolution for Expr<Typing> {
             Expr::FnCall(FnCall {
                 name: _,
                 args,
-                type_parameter,
+                type_arguments,
                 arg_evaluation_order: _,
                 annot: _,
             }) => {
                 args.iter_mut()
                     .for_each(|x| x.resolve_custom_types(composite_types));
-                if let Some(typa) = type_parameter.as_mut() {
+                for typa in type_arguments.iter_mut().flatten() {
                     typa.resolve_custom_types(composite_types)
                 }
             }
@@ -74,6 +77,19 @@ impl CustomTypeResolution for Expr<Typing> {
                 then_branch.resolve_custom_types(composite_types);
                 else_branch.resolve_custom_types(composite_types);
             }
+            Expr::Match(MatchExpr {
+                match_expression,
+                arms,
+            }) => {
+                match_expression.resolve_custom_types(composite_types);
+                for arm in arms.iter_mut() {
+                    arm.match_condition.resolve_custom_types(composite_types);
+                    if let Some(guard) = &mut arm.guard {
+                        guard.resolve_custom_types(composite_types);
+                    }
+                    arm.body.resolve_custom_types(composite_types);
+                }
+            }
             Expr::Cast(expr, target_type) => {
                 expr.resolve_custom_types(composite_types);
                 target_type.resolve_custom_types(composite_types);
@@ -85,20 +101,115 @@ impl CustomTypeResolution for Expr<Typing> {
                 rhs.resolve_custom_types(composite_types);
             }
             Expr::Struct(struct_expr) => {
-                struct_expr
-                    .struct_type
-                    .resolve_custom_types(composite_types);
                 for (_field_name, value) in struct_expr.field_names_and_values.iter_mut() {
                     value.resolve_custom_types(composite_types);
                 }
+                // An invalid enum variant is reported by the type checker
+                match enum_variant_constructor_call(struct_expr, composite_types) {
+                    Ok(Some(constructor_call)) => *self = Expr::FnCall(constructor_call),
+                    Ok(None) => struct_expr
+                        .struct_type
+                        .resolve_custom_types(composite_types),
+                    Err(_) => (),
+                }
             }
             Expr::EnumDeclaration(enum_decl) => {
                 enum_decl.enum_type.resolve_custom_types(composite_types);
             }
+            Expr::Closure(Closure { args, output, body }) => {
+                args.iter_mut()
+                    .filter_map(|(_name, data_type)| data_type.as_mut())
+                    .for_each(|x| x.resolve_custom_types(composite_types));
+                if let Some(output) = output.as_mut() {
+                    output.resolve_custom_types(composite_types);
+                }
+                body.resolve_custom_types(composite_types);
+            }
         }
     }
 }
 
+/// Return the call to the constructor of an enum variant with named fields, if the struct
+/// expression is such a variant, like `Foo::Bar { a: 1, b: 2 }`. The arguments of the call
+/// are the field values in the order in which the fields were declared. Returns an error if
+/// the expression names a variant of an enum that does not exist or that has other fields.
+pub(crate) fn enum_variant_constructor_call(
+    struct_expr: &StructExpr<Typing>,
+    composite_types: &CompositeTypes,
+) -> Result<Option<FnCall<Typing>>, String> {
+    let DataType::Unresolved(name) = &struct_expr.struct_type else {
+        return Ok(None);
+    };
+    let Some((type_name, variant_name)) = name.split_once("::") else {
+        return Ok(None);
+    };
+
+    // The field names are the same for all instances of a generic enum
+    let Some(type_context) = composite_types.get_by_name(type_name).first().copied() else {
+        return Err(format!("cannot find enum `{type_name}`"));
+    };
+    let CustomTypeOil::Enum(enum_type) = &type_context.composite_type else {
+        return Err(format!("expected enum, found struct `{type_name}`"));
+    };
+    if !enum_type.has_variant_of_name(variant_name) {
+        return Err(format!(
+            "no variant named `{variant_name}` in enum `{type_name}`"
+        ));
+    }
+    let Some(field_names) = enum_type.variant_field_names(variant_name) else {
+        return Err(format!("variant `{name}` has no named fields"));
+    };
+
+    let given_field_names = struct_expr
+        .field_names_and_values
+        .iter()
+        .map(|(field_name, _)| field_name)
+        .collect_vec();
+    if let Some(unknown_field_name) = given_field_names
+        .iter()
+        .find(|field_name| !field_names.contains(field_name))
+    {
+        return Err(format!(
+            "variant `{name}` has no field named `{unknown_field_name}`"
+        ));
+    }
+    if let Some(repeated_field_name) = given_field_names.iter().duplicates().next() {
+        return Err(format!(
+            "field `{repeated_field_name}` specified more than once"
+        ));
+    }
+    let missing_field_names = field_names
+        .iter()
+        .filter(|field_name| !given_field_names.contains(field_name))
+        .map(|field_name| format!("`{field_name}`"))
+        .collect_vec();
+    if !missing_field_names.is_empty() {
+        return Err(format!(
+            "missing fields {} in initializer of `{name}`",
+            missing_field_names.join(", ")
+        ));
+    }
+
+    let args = field_names
+        .iter()
+        .filter_map(|field_name| {
+            struct_expr
+                .field_names_and_values
+                .iter()
+                .find(|(value_field_name, _)| value_field_name == field_name)
+                .map(|(_, value)| value.to_owned())
+        })
+        .collect();
+
+    Ok(Some(FnCall {
+        name: name.to_owned(),
+        args,
+        type_arguments: vec![],
+        arg_evaluation_order: Default::default(),
+        annot: Default::default(),
+    }))
+}
+
 impl CustomTypeResolution for IndexExpr<Typing> {
     fn resolve_custom_types(&mut self, composite_types: &CompositeTypes) {
         match self {
@@ -127,7 +238,20 @@ impl CustomTypeResolution for Stmt<Typing> {
             Stmt::Let(LetStmt {
                 data_type, expr, ..
             }) => {
-                data_type.resolve_custom_types(composite_types);
+                if let Some(data_type) = data_type {
+                    data_type.resolve_custom_types(composite_types);
+                }
+                expr.resolve_custom_types(composite_types);
+            }
+            Stmt::DestructuringLet(DestructuringLetStmt {
+                pattern,
+                data_type,
+                expr,
+            }) => {
+                pattern.resolve_custom_types(composite_types);
+                if let Some(data_type) = data_type {
+                    data_type.resolve_custom_types(composite_types);
+                }
                 expr.resolve_custom_types(composite_types);
             }
             Stmt::Assign(AssignStmt { identifier, expr }) => {
@@ -141,12 +265,12 @@ impl CustomTypeResolution for Stmt<Typing> {
             }
             Stmt::FnCall(FnCall {
                 args,
-                type_parameter,
+                type_arguments,
                 ..
             }) => {
                 args.iter_mut()
                     .for_each(|x| x.resolve_custom_types(composite_types));
-                if let Some(typa) = type_parameter.as_mut() {
+                for typa in type_arguments.iter_mut().flatten() {
                     typa.resolve_custom_types(composite_types)
                 }
             }
@@ -154,13 +278,33 @@ impl CustomTypeResolution for Stmt<Typing> {
                 args.iter_mut()
                     .for_each(|x| x.resolve_custom_types(composite_types));
             }
-            Stmt::While(WhileStmt { condition, block }) => {
+            Stmt::While(WhileStmt {
+                condition, block, ..
+            }) => {
                 condition.resolve_custom_types(composite_types);
                 block
                     .stmts
                     .iter_mut()
                     .for_each(|x| x.resolve_custom_types(composite_types));
             }
+            Stmt::Loop(LoopStmt { block, .. }) => {
+                block.resolve_custom_types(composite_types);
+            }
+            Stmt::Break(_) | Stmt::Continue(_) | Stmt::Location(_) => (),
+            Stmt::For(ForStmt {
+                iterable, block, ..
+            }) => {
+                match iterable {
+                    ForIterable::Range(RangeExpr { start, end, .. }) => {
+                        start.resolve_custom_types(composite_types);
+                        end.resolve_custom_types(composite_types);
+                    }
+                    ForIterable::Collection(collection) => {
+                        collection.resolve_custom_types(composite_types)
+                    }
+                }
+                block.resolve_custom_types(composite_types);
+            }
             Stmt::If(IfStmt {
                 condition,
                 then_branch,
@@ -183,7 +327,14 @@ impl CustomTypeResolution for Stmt<Typing> {
                 expression.resolve_custom_types(composite_types)
             }
             Stmt::Panic(_) => (),
-            Stmt::FnDeclaration(Fn { signature, body }) => {
+            // Generic functions are resolved when they are instantiated, since their type
+            // parameters are only known then
+            Stmt::FnDeclaration(Fn {
+                type_parameters, ..
+            }) if !type_parameters.is_empty() => (),
+            Stmt::FnDeclaration(Fn {
+                signature, body, ..
+            }) => {
                 signature.resolve_custom_types(composite_types);
                 match body {
                     RoutineBody::Ast(stmts) => {
@@ -202,6 +353,12 @@ impl CustomTypeResolution for Stmt<Typing> {
                 arms.iter_mut()
                     .for_each(|x| x.resolve_custom_types(composite_types));
             }
+            Stmt::Static(StaticStmt {
+                data_type, expr, ..
+            }) => {
+                data_type.resolve_custom_types(composite_types);
+                expr.resolve_custom_types(composite_types);
+            }
         }
     }
 }
@@ -214,10 +371,66 @@ pub(crate) fn resolve_custom_types(function: &mut Fn<Typing>, custom_types: &mut
 
 impl CustomTypeResolution for MatchArm<Typing> {
     fn resolve_custom_types(&mut self, composite_types: &CompositeTypes) {
+        self.match_condition.resolve_custom_types(composite_types);
+        if let Some(guard) = &mut self.guard {
+            guard.resolve_custom_types(composite_types);
+        }
         self.body.resolve_custom_types(composite_types);
     }
 }
 
+impl CustomTypeResolution for MatchCondition<Typing> {
+    fn resolve_custom_types(&mut self, composite_types: &CompositeTypes) {
+        if let MatchCondition::Pattern(pattern) = self {
+            pattern.resolve_custom_types(composite_types);
+        }
+    }
+}
+
+impl CustomTypeResolution for Pattern<Typing> {
+    fn resolve_custom_types(&mut self, composite_types: &CompositeTypes) {
+        match self {
+            Pattern::Wildcard | Pattern::Binding(_) => (),
+            Pattern::Literal(literal) => literal.resolve_custom_types(composite_types),
+            Pattern::Range(RangePattern { start, end, .. }) => {
+                start.resolve_custom_types(composite_types);
+                end.resolve_custom_types(composite_types);
+            }
+            Pattern::Or(patterns) | Pattern::Tuple(patterns) => patterns
+                .iter_mut()
+                .for_each(|x| x.resolve_custom_types(composite_types)),
+            Pattern::EnumVariant(EnumVariantPattern {
+                fields,
+                named_fields,
+                ..
+            }) => fields
+                .iter_mut()
+                .chain(named_fields.iter_mut().map(|(_, pattern)| pattern))
+                .for_each(|x| x.resolve_custom_types(composite_types)),
+        }
+    }
+}
+
+impl CustomTypeResolution for DestructuringPattern {
+    fn resolve_custom_types(&mut self, composite_types: &CompositeTypes) {
+        match self {
+            DestructuringPattern::Wildcard | DestructuringPattern::Binding(_) => (),
+            DestructuringPattern::Tuple(patterns) => patterns
+                .iter_mut()
+                .for_each(|x| x.resolve_custom_types(composite_types)),
+            DestructuringPattern::Struct {
+                struct_type,
+                fields,
+            } => {
+                struct_type.resolve_custom_types(composite_types);
+                fields
+                    .iter_mut()
+                    .for_each(|(_, x)| x.resolve_custom_types(composite_types));
+            }
+        }
+    }
+}
+
 impl CustomTypeResolution for BlockStmt<Typing> {
     fn resolve_custom_types(&mut self, composite_types: &CompositeTypes) {
         self.stmts
@@ -270,9 +483,13 @@ impl DataType {
                 .any(|(_name, dtype)| dtype.is_unresolved()),
             DataType::Array(array_type) => array_type.element_type.is_unresolved(),
             DataType::Bool => false,
+            DataType::U8 => false,
+            DataType::U16 => false,
             DataType::U32 => false,
             DataType::U64 => false,
             DataType::U128 => false,
+            DataType::I32 => false,
+            DataType::I64 => false,
             DataType::Bfe => false,
             DataType::Xfe => false,
             DataType::Digest => false,
@@ -298,6 +515,17 @@ impl CustomTypeResolution for DataType {
         use DataType::*;
         match self {
             Unresolved(unresolved_type) => {
+                if let Some(type_argument) = composite_types.get_type_argument(unresolved_type) {
+                    *self = type_argument.to_owned();
+                    return;
+                }
+
+                // The instances of a generic type share its name, like in `Pair { a, b }`.
+                // Which instance is meant is inferred by the type checker.
+                if composite_types.get_by_name(unresolved_type).len() > 1 {
+                    return;
+                }
+
                 let resolved = composite_types
                     .get_unique_by_name(unresolved_type)
                     .composite_type;
@@ -346,9 +574,13 @@ impl CustomTypeResolution for DataType {
             }
 
             Bool => (),
+            U8 => (),
+            U16 => (),
             U32 => (),
             U64 => (),
             U128 => (),
+            I32 => (),
+            I64 => (),
             Bfe => (),
             Xfe => (),
             Digest => (),
@@ -362,7 +594,15 @@ impl CustomTypeResolution for FnSignature {
         self.output.resolve_custom_types(composite_types);
         for input in self.args.iter_mut() {
             match input {
-                AbstractArgument::FunctionArgument(_fn_arg) => (),
+                AbstractArgument::FunctionArgument(AbstractFunctionArg {
+                    abstract_name: _,
+                    function_type,
+                }) => {
+                    function_type
+                        .input_argument
+                        .resolve_custom_types(composite_types);
+                    function_type.output.resolve_custom_types(composite_types);
+                }
                 AbstractArgument::ValueArgument(AbstractValueArg {
                     name: _,
                     data_type,
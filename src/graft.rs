@@ -1,29 +1,109 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::str::FromStr;
 
 use itertools::Itertools;
-use num::One;
 use num::Zero;
 use syn::parse_quote;
+use syn::spanned::Spanned;
 use syn::ExprMacro;
 use syn::PathArguments;
+use triton_vm::twenty_first::shared_math::x_field_element::XFieldElement;
+use triton_vm::BFieldElement;
 
 use crate::ast;
 use crate::ast::ReturningBlock;
 use crate::ast::Stmt;
 use crate::ast_types;
 use crate::ast_types::DataType;
+use crate::compile_error::CompileError;
+use crate::compile_error::Diagnostics;
+use crate::compile_error::ErrorCode;
+use crate::compile_error::SourceLocation;
 use crate::composite_types::CompositeTypes;
+use crate::composite_types::TypeContext;
 use crate::libraries::Library;
 use crate::type_checker;
 
 pub type Annotation = type_checker::Typing;
 
+const SELF_TYPE: &str = "Self";
+
+/// The traits of the standard library that type parameters can be bounded by. The primitive
+/// types implement the traits of the operators they support.
+pub(crate) const STANDARD_TRAITS: [&str; 18] = [
+    "Copy",
+    "Clone",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Add",
+    "Sub",
+    "Mul",
+    "Div",
+    "Rem",
+    "Neg",
+    "Not",
+    "BitAnd",
+    "BitOr",
+    "BitXor",
+    "Shl",
+    "Shr",
+];
+
 #[derive(Debug)]
 pub(crate) struct Graft<'a> {
     pub list_type: ast_types::ListType,
     pub libraries: &'a [Box<dyn Library + 'a>],
     pub(crate) imported_custom_types: CompositeTypes,
+
+    /// The errors found while grafting. Grafting continues after an error, such that all
+    /// errors of a program are reported at once.
+    pub(crate) diagnostics: Vec<CompileError>,
+
+    /// The file containing the code that is currently being grafted, if known
+    pub(crate) file_path: Option<String>,
+
+    /// The files in which the functions, custom types, methods, and associated functions of
+    /// a program consisting of multiple files are declared. Methods and associated functions
+    /// are identified as `<Type>::<name>`.
+    pub(crate) source_files: HashMap<String, String>,
+
+    /// The locations of the declarations of all grafted functions, methods, and associated
+    /// functions, identified like in `source_files`.
+    pub(crate) function_locations: HashMap<String, SourceLocation>,
+
+    /// The traits declared in the program and the traits implemented by `impl Trait for
+    /// Type` blocks, along with the names of the types implementing them
+    pub(crate) trait_implementations: HashMap<String, Vec<String>>,
+
+    /// The generic structs and enums of the program, along with their methods and associated
+    /// functions. A generic type is grafted once for each list of type arguments it is used
+    /// with.
+    generic_types: HashMap<String, (CustomTypeRust, Vec<syn::ImplItemMethod>)>,
+
+    /// The instances of generic types grafted so far, identified by the name of the generic
+    /// type and the type arguments. `None` while the instance is being grafted.
+    generic_type_instances: HashMap<(String, Vec<DataType>), Option<DataType>>,
+
+    /// The concrete types of the type parameters of the generic type that is being
+    /// instantiated
+    type_arguments: HashMap<String, DataType>,
+
+    /// The type parameters of the generic function that is being grafted
+    fn_type_parameters: Vec<String>,
+
+    /// The type parameters of the function that is being grafted that stand for the types of
+    /// its function arguments, like `F` in `fn apply<F: Fn(u64) -> u64>(f: F, x: u64) -> u64`
+    function_type_parameters: HashMap<String, ast_types::FunctionType>,
+
+    /// The types and values of the `const` items of the program, by their paths, like `N`, or
+    /// `Point::N` for an associated constant. Constants are inlined where they are used.
+    constants: HashMap<String, (syn::Type, syn::Expr)>,
+
+    /// The `static` items of the program, which are declared in the body of the entrypoint
+    statics: Vec<syn::ItemStatic>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +112,15 @@ pub enum CustomTypeRust {
     Enum(syn::ItemEnum),
 }
 
+impl CustomTypeRust {
+    fn generics(&self) -> &syn::Generics {
+        match self {
+            CustomTypeRust::Struct(struct_item) => &struct_item.generics,
+            CustomTypeRust::Enum(enum_item) => &enum_item.generics,
+        }
+    }
+}
+
 macro_rules! get_standard_setup {
     ($list_type:expr, $graft_config:ident, $libraries:ident) => {
         let library_config = crate::libraries::LibraryConfig {
@@ -48,23 +137,294 @@ impl<'a> Graft<'a> {
             list_type,
             libraries,
             imported_custom_types: Default::default(),
+            diagnostics: vec![],
+            file_path: None,
+            source_files: HashMap::default(),
+            function_locations: HashMap::default(),
+            trait_implementations: HashMap::default(),
+            generic_types: HashMap::default(),
+            generic_type_instances: HashMap::default(),
+            type_arguments: HashMap::default(),
+            fn_type_parameters: vec![],
+            function_type_parameters: HashMap::default(),
+            constants: HashMap::default(),
+            statics: vec![],
         }
     }
 
-    fn is_copy(attrs: &[syn::Attribute]) -> bool {
-        match attrs.len() {
-            0 => false,
-            1 => attrs[0].tokens.to_string().contains("Copy"),
-            _ => panic!("Can only handle one line of attributes for now."),
+    /// Report an error in the code being grafted
+    pub(crate) fn report(
+        &mut self,
+        code: ErrorCode,
+        source: &impl Spanned,
+        message: impl Into<String>,
+    ) {
+        let error = CompileError::new(code, message)
+            .with_span(source.span())
+            .in_file(self.file_path.as_deref());
+        self.diagnostics.push(error);
+    }
+
+    /// Return all errors found while grafting, if any
+    pub(crate) fn take_diagnostics(&mut self) -> Result<(), Diagnostics> {
+        Diagnostics::check(std::mem::take(&mut self.diagnostics))
+    }
+
+    /// Continue grafting in the file in which `item_name` is declared, if it is known
+    fn enter_item(&mut self, item_name: &str) {
+        if let Some(file_path) = self.source_files.get(item_name) {
+            self.file_path = Some(file_path.to_owned());
         }
     }
 
+    fn add_function_location(&mut self, function_name: String, ident: &syn::Ident) {
+        let location = SourceLocation {
+            file_path: self.file_path.clone(),
+            ..SourceLocation::from_span(ident.span())
+        };
+        self.function_locations
+            .entry(function_name)
+            .or_insert(location);
+    }
+
+    /// Return the traits that the `derive` attributes of an item derive, like `Copy` in
+    /// `#[derive(Clone, Copy)]`. Other attributes are ignored.
+    fn derived_traits(&mut self, attrs: &[syn::Attribute]) -> Vec<syn::Path> {
+        let mut derived_traits = vec![];
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("derive")) {
+            let Ok(syn::Meta::List(derive)) = attr.parse_meta() else {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    attr,
+                    "expected a list of traits, like `#[derive(Clone, Copy)]`",
+                );
+                continue;
+            };
+            for derived in derive.nested {
+                match derived {
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) => derived_traits.push(path),
+                    other => self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        &other,
+                        "expected the name of a trait to derive",
+                    ),
+                }
+            }
+        }
+
+        derived_traits
+    }
+
+    /// Declare the `const` and `static` items of a program, including the associated constants
+    /// of `impl` blocks, such that the code grafted afterwards can use them. Must be called
+    /// before anything else is grafted.
+    pub(crate) fn declare_constants_and_statics<'b>(
+        &mut self,
+        items: impl IntoIterator<Item = &'b syn::Item>,
+    ) {
+        for item in items {
+            match item {
+                syn::Item::Const(item_const) => {
+                    self.constants.insert(
+                        item_const.ident.to_string(),
+                        (*item_const.ty.clone(), *item_const.expr.clone()),
+                    );
+                }
+                syn::Item::Static(item_static) => {
+                    if item_static.mutability.is_some() {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            item_static,
+                            "only immutable `static` items are supported",
+                        );
+                        continue;
+                    }
+                    self.statics.push(item_static.to_owned());
+                }
+                // The associated constants of generic types are not supported
+                syn::Item::Impl(item_impl) if !item_impl.generics.params.is_empty() => {
+                    let outer_file_path = self.file_path.clone();
+                    if let syn::Type::Path(type_path) = item_impl.self_ty.as_ref() {
+                        self.enter_item(&Self::path_to_ident(&type_path.path));
+                    }
+                    for impl_item in &item_impl.items {
+                        if let syn::ImplItem::Const(impl_const) = impl_item {
+                            self.report(
+                                ErrorCode::UnsupportedSyntax,
+                                impl_const,
+                                "associated constants of generic types are not supported",
+                            );
+                        }
+                    }
+                    self.file_path = outer_file_path;
+                }
+                syn::Item::Impl(item_impl) => {
+                    let associated_constants = item_impl
+                        .items
+                        .iter()
+                        .filter_map(|impl_item| match impl_item {
+                            syn::ImplItem::Const(impl_const) => Some(impl_const),
+                            _ => None,
+                        })
+                        .collect_vec();
+                    if associated_constants.is_empty() {
+                        continue;
+                    }
+                    let type_name = self.syn_type_to_ast_type(&item_impl.self_ty).to_string();
+                    for impl_const in associated_constants {
+                        self.constants.insert(
+                            format!("{type_name}::{}", impl_const.ident),
+                            (impl_const.ty.clone(), impl_const.expr.clone()),
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Return the value of the constant with the given path, if there is one. The integer
+    /// literals without a suffix in the value get the declared type of the constant.
+    fn graft_constant(&mut self, path: &str) -> Option<ast::Expr<Annotation>> {
+        let (rust_type, rust_value) = self.constants.get(path)?.to_owned();
+        let data_type = self.syn_type_to_ast_type(&rust_type);
+        let mut value = self.graft_expr(&rust_value);
+        Self::type_integer_literals(&mut value, &data_type);
+
+        Some(value)
+    }
+
+    /// Give the integer literals without a suffix in a value of a known type that type, like
+    /// the elements of `[1, 2, 3]` of type `[u64; 3]`, or the operands of `1 << 5` of type
+    /// `u32`. Literals that do not fit the type are left for the type checker to report.
+    fn type_integer_literals(value: &mut ast::Expr<Annotation>, data_type: &DataType) {
+        match value {
+            ast::Expr::Lit(ast::ExprLit::GenericNum(n, _)) => {
+                let typed_literal = match data_type {
+                    DataType::U8 => u8::try_from(*n).ok().map(ast::ExprLit::U8),
+                    DataType::U16 => u16::try_from(*n).ok().map(ast::ExprLit::U16),
+                    DataType::U32 => u32::try_from(*n).ok().map(ast::ExprLit::U32),
+                    DataType::U64 => u64::try_from(*n).ok().map(ast::ExprLit::U64),
+                    DataType::U128 => Some(ast::ExprLit::U128(*n)),
+                    DataType::I32 => i32::try_from(*n).ok().map(ast::ExprLit::I32),
+                    DataType::I64 => i64::try_from(*n).ok().map(ast::ExprLit::I64),
+                    DataType::Bfe if *n <= BFieldElement::MAX as u128 => {
+                        Some(ast::ExprLit::Bfe(BFieldElement::new(*n as u64)))
+                    }
+                    DataType::Xfe if *n <= BFieldElement::MAX as u128 => Some(ast::ExprLit::Xfe(
+                        XFieldElement::new_const(BFieldElement::new(*n as u64)),
+                    )),
+                    _ => None,
+                };
+                if let Some(typed_literal) = typed_literal {
+                    *value = ast::Expr::Lit(typed_literal);
+                }
+            }
+            ast::Expr::Array(ast::ArrayExpression::ElementsSpecified(elements), _) => {
+                let DataType::Array(array_type) = data_type else {
+                    return;
+                };
+                for element in elements.iter_mut() {
+                    Self::type_integer_literals(element, &array_type.element_type);
+                }
+            }
+            ast::Expr::Tuple(elements) => {
+                let DataType::Tuple(tuple) = data_type else {
+                    return;
+                };
+                for (element, element_type) in elements.iter_mut().zip(tuple.fields.iter()) {
+                    Self::type_integer_literals(element, element_type);
+                }
+            }
+            ast::Expr::Binop(lhs, binop, rhs, _) => {
+                use ast::BinOp::*;
+                match binop {
+                    Add | BitAnd | BitOr | BitXor | Div | Mul | Rem | Sub => {
+                        Self::type_integer_literals(lhs, data_type);
+                        Self::type_integer_literals(rhs, data_type);
+                    }
+                    Shl | Shr => Self::type_integer_literals(lhs, data_type),
+                    And | Or | Eq | Neq | Lt | Gt => (),
+                }
+            }
+            ast::Expr::Unary(ast::UnaryOp::Neg, inner, _) => {
+                Self::type_integer_literals(inner, data_type)
+            }
+            _ => (),
+        }
+    }
+
+    /// Return the value of an integer expression built from literals, like `1 << 4` after its
+    /// constants have been replaced by their values, or `None` if the value is not known at
+    /// compile time or does not fit a `u128`.
+    fn evaluate_integer_expr(expr: &ast::Expr<Annotation>) -> Option<u128> {
+        match expr {
+            ast::Expr::Lit(literal) => match literal {
+                ast::ExprLit::U8(n) => Some((*n).into()),
+                ast::ExprLit::U16(n) => Some((*n).into()),
+                ast::ExprLit::U32(n) => Some((*n).into()),
+                ast::ExprLit::U64(n) => Some((*n).into()),
+                ast::ExprLit::U128(n) => Some(*n),
+                ast::ExprLit::I32(n) => u128::try_from(*n).ok(),
+                ast::ExprLit::I64(n) => u128::try_from(*n).ok(),
+                ast::ExprLit::GenericNum(n, _) => Some(*n),
+                _ => None,
+            },
+            ast::Expr::Binop(lhs, binop, rhs, _) => {
+                use ast::BinOp::*;
+                let lhs = Self::evaluate_integer_expr(lhs)?;
+                let rhs = Self::evaluate_integer_expr(rhs)?;
+                match binop {
+                    Add => lhs.checked_add(rhs),
+                    Sub => lhs.checked_sub(rhs),
+                    Mul => lhs.checked_mul(rhs),
+                    Div => lhs.checked_div(rhs),
+                    Rem => lhs.checked_rem(rhs),
+                    Shl => lhs.checked_shl(rhs.try_into().ok()?),
+                    Shr => lhs.checked_shr(rhs.try_into().ok()?),
+                    BitAnd => Some(lhs & rhs),
+                    BitOr => Some(lhs | rhs),
+                    BitXor => Some(lhs ^ rhs),
+                    And | Or | Eq | Neq | Lt | Gt => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Graft the `static` items of the program to declarations in the body of the entrypoint
+    fn graft_statics(&mut self) -> Vec<Stmt<Annotation>> {
+        let mut declarations = vec![];
+        for item_static in std::mem::take(&mut self.statics) {
+            let name = item_static.ident.to_string();
+            self.enter_item(&name);
+            let data_type = self.syn_type_to_ast_type(&item_static.ty);
+            let mut expr = self.graft_expr(&item_static.expr);
+            Self::type_integer_literals(&mut expr, &data_type);
+            declarations.push(Stmt::Static(ast::StaticStmt {
+                name,
+                data_type,
+                expr,
+            }));
+        }
+
+        declarations
+    }
+
     pub(crate) fn graft_custom_types_methods_and_associated_functions(
         &mut self,
         structs_and_methods: HashMap<String, (CustomTypeRust, Vec<syn::ImplItemMethod>)>,
     ) -> CompositeTypes {
         let mut composite_types = CompositeTypes::default();
 
+        // Generic types are grafted when they are used, since their type arguments are only
+        // known then
+        let (generic_types, structs_and_methods): (HashMap<_, _>, HashMap<_, _>) =
+            structs_and_methods
+                .into_iter()
+                .partition(|(_, (custom_type, _))| !custom_type.generics().params.is_empty());
+        self.generic_types = generic_types;
+
         // Handle composite data structures
         let structs = structs_and_methods
             .clone()
@@ -72,66 +432,8 @@ impl<'a> Graft<'a> {
             .map(|(_, (syn_struct, _methods))| syn_struct)
             .collect_vec();
         for struct_ in structs {
-            match struct_ {
-                CustomTypeRust::Enum(enum_item) => {
-                    let syn::ItemEnum {
-                        attrs,
-                        vis: _,
-                        enum_token: _,
-                        ident,
-                        generics: _,
-                        brace_token: _,
-                        variants,
-                    } = enum_item;
-                    let name = ident.to_string();
-
-                    let is_copy = Self::is_copy(&attrs);
-
-                    let variants = self.graft_enum_variants(variants.into_iter().collect_vec());
-                    let enum_type = ast_types::EnumType {
-                        name: name.clone(),
-                        is_copy,
-                        variants,
-                        is_prelude: false,
-                        type_parameter: None,
-                    };
-
-                    composite_types.add_custom_type(ast_types::CustomTypeOil::Enum(enum_type));
-                }
-                CustomTypeRust::Struct(struct_item) => {
-                    let syn::ItemStruct {
-                        attrs,
-                        vis: _,
-                        struct_token: _,
-                        ident,
-                        generics: _,
-                        fields,
-                        semi_token: _,
-                    } = struct_item;
-                    let name = ident.to_string();
-
-                    let is_copy = Self::is_copy(&attrs);
-
-                    // Rust structs come in three forms: with named fields, tuple structs, and
-                    // unit structs. We don't yet support unit structs, so we can assume that
-                    // the struct has at least *one* field.
-                    let struct_type = match fields.iter().next().unwrap().ident {
-                        Some(_) => ast_types::StructVariant::NamedFields(
-                            self.graft_struct_with_named_fields(&name, fields),
-                        ),
-                        None => {
-                            ast_types::StructVariant::TupleStruct(self.graft_tuple_struct(fields))
-                        }
-                    };
-                    let struct_type = ast_types::StructType {
-                        is_copy,
-                        variant: struct_type,
-                        name: name.clone(),
-                    };
-
-                    composite_types.add_custom_type(ast_types::CustomTypeOil::Struct(struct_type));
-                }
-            }
+            let custom_type = self.graft_custom_type(struct_, vec![]);
+            composite_types.add_custom_type(custom_type);
         }
 
         // Handle methods and associated functions
@@ -142,25 +444,291 @@ impl<'a> Graft<'a> {
             .collect_vec();
         for (type_name, assoc_function) in struct_names_and_associated_functions {
             let type_ctx = composite_types.get_mut_unique_by_name(&type_name);
-            let as_dt: ast_types::DataType = type_ctx.composite_type.clone().into();
-            for syn_method in assoc_function {
-                if syn_method.sig.receiver().is_some() {
-                    let oil_method = self.graft_method(&syn_method, &as_dt);
-                    type_ctx.add_method(oil_method);
-                } else {
-                    // Associated function is *not* a method, i.e., does not take a `self` argument
-                    let new_fun = self.graft_associated_function(&syn_method);
-                    type_ctx.add_associated_function(new_fun);
-                }
-            }
+            type_ctx.traits = self.implemented_traits(&type_name);
+            self.graft_methods_and_associated_functions(&type_name, type_ctx, &assoc_function);
         }
 
         composite_types
     }
 
+    /// Graft the declaration of a struct or an enum. The type arguments are set for the
+    /// instances of generic types.
+    fn graft_custom_type(
+        &mut self,
+        custom_type: CustomTypeRust,
+        type_arguments: Vec<DataType>,
+    ) -> ast_types::CustomTypeOil {
+        match custom_type {
+            CustomTypeRust::Enum(enum_item) => {
+                let syn::ItemEnum {
+                    attrs,
+                    vis: _,
+                    enum_token: _,
+                    ident,
+                    generics: _,
+                    brace_token: _,
+                    variants,
+                } = enum_item;
+                let name = ident.to_string();
+                self.enter_item(&name);
+
+                let derived_traits = self.derived_traits(&attrs);
+                let is_copy = derived_traits.iter().any(|path| path.is_ident("Copy"));
+                let is_partial_eq = derived_traits.iter().any(|path| path.is_ident("PartialEq"));
+
+                let variant_field_names = Self::enum_variant_field_names(&variants);
+                let variants = self.graft_enum_variants(variants.into_iter().collect_vec());
+                let enum_type = ast_types::EnumType {
+                    name,
+                    is_copy,
+                    is_partial_eq,
+                    variants,
+                    variant_field_names,
+                    is_prelude: false,
+                    type_arguments,
+                };
+
+                ast_types::CustomTypeOil::Enum(enum_type)
+            }
+            CustomTypeRust::Struct(struct_item) => {
+                let syn::ItemStruct {
+                    attrs,
+                    vis: _,
+                    struct_token: _,
+                    ident,
+                    generics: _,
+                    fields,
+                    semi_token: _,
+                } = struct_item;
+                let name = ident.to_string();
+                self.enter_item(&name);
+
+                let derived_traits = self.derived_traits(&attrs);
+                let is_copy = derived_traits.iter().any(|path| path.is_ident("Copy"));
+                let is_partial_eq = derived_traits.iter().any(|path| path.is_ident("PartialEq"));
+
+                // Rust structs come in three forms: with named fields, tuple structs, and
+                // unit structs. We don't yet support unit structs, so we can assume that
+                // the struct has at least *one* field.
+                let struct_type = match fields.iter().next().unwrap().ident {
+                    Some(_) => ast_types::StructVariant::NamedFields(
+                        self.graft_struct_with_named_fields(&name, fields),
+                    ),
+                    None => ast_types::StructVariant::TupleStruct(self.graft_tuple_struct(fields)),
+                };
+                let struct_type = ast_types::StructType {
+                    is_copy,
+                    is_partial_eq,
+                    variant: struct_type,
+                    name,
+                    type_arguments,
+                };
+
+                ast_types::CustomTypeOil::Struct(struct_type)
+            }
+        }
+    }
+
+    fn graft_methods_and_associated_functions(
+        &mut self,
+        type_name: &str,
+        type_ctx: &mut TypeContext,
+        assoc_function: &[syn::ImplItemMethod],
+    ) {
+        let as_dt: ast_types::DataType = type_ctx.composite_type.clone().into();
+
+        // `Self` refers to the type whose methods are grafted
+        let outer_self_type = self
+            .type_arguments
+            .insert(SELF_TYPE.to_owned(), as_dt.clone());
+        for syn_method in assoc_function {
+            let function_name = format!("{type_name}::{}", syn_method.sig.ident);
+            self.enter_item(&function_name);
+            self.add_function_location(function_name, &syn_method.sig.ident);
+            if syn_method.sig.receiver().is_some() {
+                let oil_method = self.graft_method(syn_method, &as_dt);
+                type_ctx.add_method(oil_method);
+            } else {
+                // Associated function is *not* a method, i.e., does not take a `self` argument
+                let new_fun = self.graft_associated_function(syn_method);
+                type_ctx.add_associated_function(new_fun);
+            }
+        }
+        match outer_self_type {
+            Some(outer_self_type) => self
+                .type_arguments
+                .insert(SELF_TYPE.to_owned(), outer_self_type),
+            None => self.type_arguments.remove(SELF_TYPE),
+        };
+    }
+
+    /// Return the traits implemented for the type `type_name` by `impl Trait for Type` blocks
+    fn implemented_traits(&self, type_name: &str) -> Vec<String> {
+        self.trait_implementations
+            .iter()
+            .filter(|(_, implementors)| implementors.iter().any(|name| name == type_name))
+            .map(|(trait_name, _)| trait_name.to_owned())
+            .sorted()
+            .collect_vec()
+    }
+
+    /// Replace `Self` in a path like `Self::new` or `Self::Variant` by the name of the type
+    /// whose methods are grafted
+    fn resolve_self_in_path(&self, name: String) -> String {
+        let self_type_name = match self.type_arguments.get(SELF_TYPE) {
+            Some(DataType::Struct(struct_type)) => &struct_type.name,
+            Some(DataType::Enum(enum_type)) => &enum_type.name,
+            _ => return name,
+        };
+        match name.strip_prefix(SELF_TYPE) {
+            Some(rest) if rest.is_empty() || rest.starts_with("::") => {
+                format!("{self_type_name}{rest}")
+            }
+            _ => name,
+        }
+    }
+
+    /// Return the instance of a generic struct or enum for the type arguments of the type
+    /// path `Name<A, B>`. Each instance is grafted once, along with its methods and
+    /// associated functions, with its type parameters replaced by the type arguments.
+    ///
+    /// As the type arguments must be concrete when grafting, recursive generic types like
+    /// `enum Tree<T> { Leaf(T), Node(Box<Tree<T>>, Box<Tree<T>>) }` are rejected, as are
+    /// instances in generic functions of the type parameters of the function, like
+    /// `Pair<T>` in `fn first<T>(pair: Pair<T>) -> T`.
+    fn instantiate_generic_type(&mut self, rust_type_path: &syn::TypePath) -> DataType {
+        let path_segment = &rust_type_path.path.segments[0];
+        let type_name = path_segment.ident.to_string();
+        let Some((custom_type, impl_items)) = self.generic_types.get(&type_name).cloned() else {
+            self.report(
+                ErrorCode::UnknownType,
+                rust_type_path,
+                format!("cannot find generic type `{type_name}`"),
+            );
+            return DataType::unit();
+        };
+
+        let PathArguments::AngleBracketed(generic_arguments) = &path_segment.arguments else {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                &path_segment.arguments,
+                "type arguments must be given in angle brackets",
+            );
+            return DataType::unit();
+        };
+        let mut type_arguments = vec![];
+        for generic_argument in generic_arguments.args.iter() {
+            let syn::GenericArgument::Type(type_argument) = generic_argument else {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    generic_argument,
+                    "only types are supported as type arguments",
+                );
+                return DataType::unit();
+            };
+            type_arguments.push(self.syn_type_to_ast_type(type_argument));
+        }
+
+        let type_parameters = custom_type
+            .generics()
+            .type_params()
+            .map(|type_param| type_param.ident.to_string())
+            .collect_vec();
+        if type_parameters.len() != type_arguments.len() {
+            self.report(
+                ErrorCode::TypeError,
+                rust_type_path,
+                format!(
+                    "`{type_name}` takes {} type arguments but {} were supplied",
+                    type_parameters.len(),
+                    type_arguments.len()
+                ),
+            );
+            return DataType::unit();
+        }
+        if type_arguments
+            .iter()
+            .any(|type_argument| self.contains_fn_type_parameter(type_argument))
+        {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                rust_type_path,
+                "generic types cannot be instantiated with the type parameters of a generic function",
+            );
+            return DataType::unit();
+        }
+
+        let instance_id = (type_name.clone(), type_arguments.clone());
+        match self.generic_type_instances.get(&instance_id) {
+            Some(Some(instance)) => return instance.to_owned(),
+            Some(None) => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    rust_type_path,
+                    format!("recursive generic type `{type_name}` is not supported"),
+                );
+                return DataType::unit();
+            }
+            None => (),
+        }
+        self.generic_type_instances
+            .insert(instance_id.clone(), None);
+
+        let outer_file_path = self.file_path.clone();
+        let outer_fn_type_parameters = std::mem::take(&mut self.fn_type_parameters);
+        let outer_type_arguments = std::mem::replace(
+            &mut self.type_arguments,
+            type_parameters
+                .into_iter()
+                .zip(type_arguments.clone())
+                .collect(),
+        );
+
+        // The instance is known before its methods are grafted, as they may refer to it
+        let composite_type = self.graft_custom_type(custom_type, type_arguments);
+        let instance: DataType = composite_type.clone().into();
+        self.generic_type_instances
+            .insert(instance_id, Some(instance.clone()));
+        let mut type_ctx: TypeContext = composite_type.into();
+        type_ctx.traits = self.implemented_traits(&type_name);
+        self.graft_methods_and_associated_functions(&type_name, &mut type_ctx, &impl_items);
+        self.imported_custom_types.add_type_context_if_new(type_ctx);
+
+        self.type_arguments = outer_type_arguments;
+        self.fn_type_parameters = outer_fn_type_parameters;
+        self.file_path = outer_file_path;
+
+        instance
+    }
+
+    fn contains_fn_type_parameter(&self, data_type: &DataType) -> bool {
+        match data_type {
+            DataType::Unresolved(name) => self.fn_type_parameters.contains(name),
+            DataType::List(inner, _) | DataType::Boxed(inner) => {
+                self.contains_fn_type_parameter(inner)
+            }
+            DataType::Array(array_type) => {
+                self.contains_fn_type_parameter(&array_type.element_type)
+            }
+            DataType::Tuple(tuple) => tuple
+                .fields
+                .iter()
+                .any(|field| self.contains_fn_type_parameter(field)),
+            _ => false,
+        }
+    }
+
     fn graft_enum_variants(&mut self, variants: Vec<syn::Variant>) -> Vec<(String, DataType)> {
         let mut grafted_variants = vec![];
         for variant in variants {
+            // Variants are identified by their position, which casts to integers rely on
+            if let Some((_, discriminant)) = &variant.discriminant {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    discriminant,
+                    "explicit enum discriminants are not supported",
+                );
+            }
             let variant_name = variant.ident.to_string();
             let field_type = self.graft_tuple_struct(variant.fields);
             grafted_variants.push((variant_name, DataType::Tuple(field_type)));
@@ -169,6 +737,25 @@ impl<'a> Graft<'a> {
         grafted_variants
     }
 
+    fn enum_variant_field_names(
+        variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    ) -> Vec<(String, Vec<String>)> {
+        variants
+            .iter()
+            .filter_map(|variant| match &variant.fields {
+                syn::Fields::Named(fields) => Some((
+                    variant.ident.to_string(),
+                    fields
+                        .named
+                        .iter()
+                        .map(|field| field.ident.as_ref().unwrap().to_string())
+                        .collect(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn graft_tuple_struct(&mut self, fields: syn::Fields) -> ast_types::Tuple {
         let mut ast_fields: Vec<DataType> = vec![];
         for field in fields {
@@ -230,7 +817,16 @@ impl<'a> Graft<'a> {
                 mutable: mutability.is_some(),
             }
         } else {
-            panic!("Expected receiver as 1st abstract argument to method {method_name}");
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                &receiver,
+                "only `self`, `mut self`, `&self` and `&mut self` are supported as receivers",
+            );
+            ast_types::AbstractValueArg {
+                name: "self".to_string(),
+                data_type: custom_type.to_owned(),
+                mutable: false,
+            }
         };
         let other_args = method
             .sig
@@ -239,11 +835,11 @@ impl<'a> Graft<'a> {
             .skip(1)
             .map(|x| self.graft_fn_arg(x))
             .collect_vec();
-        let all_args = [vec![receiver], other_args].concat();
-        let all_args = all_args
-            .into_iter()
-            .map(ast_types::AbstractArgument::ValueArgument)
-            .collect_vec();
+        let all_args = [
+            vec![ast_types::AbstractArgument::ValueArgument(receiver)],
+            other_args,
+        ]
+        .concat();
 
         let output = self.graft_return_type(&method.sig.output);
         let signature = ast::FnSignature {
@@ -252,12 +848,7 @@ impl<'a> Graft<'a> {
             output,
             arg_evaluation_order: Default::default(),
         };
-        let body = method
-            .block
-            .stmts
-            .iter()
-            .map(|x| self.graft_stmt(x))
-            .collect_vec();
+        let body = self.graft_stmts(&method.block.stmts);
 
         ast::Method {
             signature,
@@ -278,17 +869,8 @@ impl<'a> Graft<'a> {
             .iter()
             .map(|x| self.graft_fn_arg(x))
             .collect_vec();
-        let args = args
-            .into_iter()
-            .map(ast_types::AbstractArgument::ValueArgument)
-            .collect_vec();
         let output = self.graft_return_type(&input.sig.output);
-        let body = input
-            .block
-            .stmts
-            .iter()
-            .map(|x| self.graft_stmt(x))
-            .collect_vec();
+        let body = self.graft_stmts(&input.block.stmts);
 
         ast::Fn {
             signature: ast::FnSignature {
@@ -298,28 +880,43 @@ impl<'a> Graft<'a> {
                 arg_evaluation_order: Default::default(),
             },
             body: ast::RoutineBody::Ast(body),
+            type_parameters: vec![],
+            declared_type_parameters: vec![],
+            trait_bounds: vec![],
         }
     }
 
     pub(crate) fn graft_fn_decl(&mut self, input: &syn::ItemFn) -> ast::Fn<Annotation> {
         let function_name = input.sig.ident.to_string();
+        self.add_function_location(function_name.clone(), &input.sig.ident);
+        let function_type_parameters = self.graft_function_type_parameters(&input.sig.generics);
+        let declared_type_parameters = self
+            .graft_type_parameters(&input.sig.generics)
+            .into_iter()
+            .map(|type_parameter| {
+                (!function_type_parameters.contains_key(&type_parameter)).then_some(type_parameter)
+            })
+            .collect_vec();
+        let type_parameters = declared_type_parameters
+            .iter()
+            .flatten()
+            .cloned()
+            .collect_vec();
+        let trait_bounds = self.graft_trait_bounds(&input.sig.generics, &type_parameters);
+        let outer_fn_type_parameters =
+            std::mem::replace(&mut self.fn_type_parameters, type_parameters.clone());
+        let outer_function_type_parameters =
+            std::mem::replace(&mut self.function_type_parameters, function_type_parameters);
         let args = input
             .sig
             .inputs
             .iter()
             .map(|x| self.graft_fn_arg(x))
             .collect_vec();
-        let args = args
-            .into_iter()
-            .map(ast_types::AbstractArgument::ValueArgument)
-            .collect_vec();
         let output = self.graft_return_type(&input.sig.output);
-        let body = input
-            .block
-            .stmts
-            .iter()
-            .map(|x| self.graft_stmt(x))
-            .collect_vec();
+        let body = self.graft_stmts(&input.block.stmts);
+        self.fn_type_parameters = outer_fn_type_parameters;
+        self.function_type_parameters = outer_function_type_parameters;
 
         ast::Fn {
             body: ast::RoutineBody::Ast(body),
@@ -329,19 +926,239 @@ impl<'a> Graft<'a> {
                 output,
                 arg_evaluation_order: Default::default(),
             },
+            type_parameters,
+            declared_type_parameters,
+            trait_bounds,
+        }
+    }
+
+    /// Return the names of the type parameters of a generic function
+    fn graft_type_parameters(&mut self, generics: &syn::Generics) -> Vec<String> {
+        let mut type_parameters = vec![];
+        for generic_param in generics.params.iter() {
+            match generic_param {
+                syn::GenericParam::Type(type_param) => {
+                    type_parameters.push(type_param.ident.to_string())
+                }
+                syn::GenericParam::Lifetime(_) => self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    generic_param,
+                    "lifetime parameters are not supported",
+                ),
+                syn::GenericParam::Const(_) => self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    generic_param,
+                    "const parameters are not supported",
+                ),
+            }
         }
+
+        type_parameters
+    }
+
+    /// Return the type parameters of a function that are bounded by `Fn`, `FnMut`, or `FnOnce`,
+    /// either where they are declared or in a `where` clause, along with the function type
+    /// they stand for. Since closures do not mutate what they capture, the three traits are
+    /// not distinguished.
+    fn graft_function_type_parameters(
+        &mut self,
+        generics: &syn::Generics,
+    ) -> HashMap<String, ast_types::FunctionType> {
+        let mut function_type_parameters = HashMap::default();
+        for (type_parameter, bounds) in Self::bounded_type_parameters(generics) {
+            if let Some(function_trait_arguments) = Self::function_trait_arguments(bounds) {
+                let function_type = self.graft_function_type(
+                    function_trait_arguments.inputs.iter(),
+                    &function_trait_arguments.output,
+                );
+                function_type_parameters.insert(type_parameter, function_type);
+            }
+        }
+
+        function_type_parameters
+    }
+
+    /// Return the traits that the type parameters of a generic function are bounded by,
+    /// either where they are declared or in a `where` clause, like `(T, PartialOrd)` for
+    /// `T: PartialOrd`. The bounds are checked when the function is instantiated, so only the
+    /// traits declared in the program and the traits of the standard library that the
+    /// compiler knows the implementations of can be used.
+    fn graft_trait_bounds(
+        &mut self,
+        generics: &syn::Generics,
+        type_parameters: &[String],
+    ) -> Vec<(String, String)> {
+        let mut trait_bounds = vec![];
+        for (type_parameter, bounds) in Self::bounded_type_parameters(generics) {
+            if !type_parameters.contains(&type_parameter) {
+                continue;
+            }
+            for bound in bounds {
+                let syn::TypeParamBound::Trait(trait_bound) = bound else {
+                    continue;
+                };
+                if let syn::TraitBoundModifier::Maybe(_) = trait_bound.modifier {
+                    continue;
+                }
+                let trait_name = trait_bound.path.segments.last().unwrap().ident.to_string();
+                let is_known_trait = STANDARD_TRAITS.contains(&trait_name.as_str())
+                    || self.trait_implementations.contains_key(&trait_name);
+                if !is_known_trait {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        &trait_bound.path,
+                        format!("trait bound `{trait_name}` is not supported"),
+                    );
+                    continue;
+                }
+                trait_bounds.push((type_parameter.clone(), trait_name));
+            }
+        }
+
+        trait_bounds
+    }
+
+    /// Return the type parameters of a list of generic parameters along with their trait
+    /// bounds, which are given where they are declared or in a `where` clause
+    fn bounded_type_parameters(
+        generics: &syn::Generics,
+    ) -> Vec<(
+        String,
+        &syn::punctuated::Punctuated<syn::TypeParamBound, syn::token::Add>,
+    )> {
+        let mut bounded_type_parameters = vec![];
+        for generic_param in generics.params.iter() {
+            if let syn::GenericParam::Type(type_param) = generic_param {
+                bounded_type_parameters.push((type_param.ident.to_string(), &type_param.bounds));
+            }
+        }
+        for predicate in generics
+            .where_clause
+            .iter()
+            .flat_map(|x| x.predicates.iter())
+        {
+            let syn::WherePredicate::Type(predicate_type) = predicate else {
+                continue;
+            };
+            if let syn::Type::Path(type_path) = &predicate_type.bounded_ty {
+                if let Some(ident) = type_path.path.get_ident() {
+                    bounded_type_parameters.push((ident.to_string(), &predicate_type.bounds));
+                }
+            }
+        }
+
+        bounded_type_parameters
+    }
+
+    /// Return the arguments of the `Fn`, `FnMut`, or `FnOnce` bound among a list of trait
+    /// bounds, like `(u64) -> u64` in `Fn(u64) -> u64`
+    fn function_trait_arguments<'b>(
+        bounds: impl IntoIterator<Item = &'b syn::TypeParamBound>,
+    ) -> Option<&'b syn::ParenthesizedGenericArguments> {
+        bounds.into_iter().find_map(|bound| {
+            let syn::TypeParamBound::Trait(trait_bound) = bound else {
+                return None;
+            };
+            let segment = trait_bound.path.segments.last()?;
+            if !matches!(
+                segment.ident.to_string().as_str(),
+                "Fn" | "FnMut" | "FnOnce"
+            ) {
+                return None;
+            }
+            match &segment.arguments {
+                PathArguments::Parenthesized(arguments) => Some(arguments),
+                _ => None,
+            }
+        })
+    }
+
+    /// Graft the type of a function from the types of its arguments and of its result.
+    /// Functions with several arguments take them as a tuple.
+    fn graft_function_type<'b>(
+        &mut self,
+        inputs: impl IntoIterator<Item = &'b syn::Type>,
+        output: &syn::ReturnType,
+    ) -> ast_types::FunctionType {
+        let mut input_types = inputs
+            .into_iter()
+            .map(|input| self.syn_type_to_ast_type(input))
+            .collect_vec();
+        let input_argument = match input_types.len() {
+            1 => input_types.remove(0),
+            _ => DataType::Tuple(input_types.into()),
+        };
+
+        ast_types::FunctionType {
+            input_argument,
+            output: self.graft_return_type(output),
+        }
+    }
+
+    /// Graft the entrypoint along with the free functions declared next to it at module
+    /// level. The free functions become local function declarations of the entrypoint,
+    /// such that they are type-checked and compiled as subroutines of the entrypoint. The
+    /// `static` items of the program are declared at the start of the body of the entrypoint.
+    pub(crate) fn graft_fn_decl_with_free_functions(
+        &mut self,
+        entrypoint: &syn::ItemFn,
+        free_functions: &[syn::ItemFn],
+    ) -> ast::Fn<Annotation> {
+        self.enter_item(&entrypoint.sig.ident.to_string());
+        let mut function = self.graft_fn_decl(entrypoint);
+
+        let mut function_names = HashSet::from([entrypoint.sig.ident.to_string()]);
+        let mut free_function_declarations = vec![];
+        for free_function in free_functions {
+            if free_function.sig.ident == entrypoint.sig.ident {
+                continue;
+            }
+
+            let function_name = free_function.sig.ident.to_string();
+            self.enter_item(&function_name);
+            if !function_names.insert(function_name.clone()) {
+                self.report(
+                    ErrorCode::DuplicateFunction,
+                    &free_function.sig.ident,
+                    format!("the function `{function_name}` is defined multiple times"),
+                );
+                continue;
+            }
+            free_function_declarations.push(Stmt::FnDeclaration(self.graft_fn_decl(free_function)));
+        }
+
+        // The values of the `static` items are stored before anything else is executed
+        let mut declarations = self.graft_statics();
+        declarations.extend(free_function_declarations);
+
+        let ast::RoutineBody::Ast(body) = &mut function.body else {
+            unreachable!("Grafted function must have a body")
+        };
+        body.splice(0..0, declarations);
+
+        function
     }
 
     fn rust_type_path_to_data_type(
         &mut self,
         rust_type_path: &syn::TypePath,
     ) -> ast_types::DataType {
-        assert_eq!(
-            1,
-            rust_type_path.path.segments.len(),
-            "Length other than one not supported"
-        );
+        if rust_type_path.path.segments.len() != 1 {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                rust_type_path,
+                "paths to types are not supported, import the type instead",
+            );
+            return DataType::unit();
+        }
         let rust_type_as_string = rust_type_path.path.segments[0].ident.to_string();
+        if let Some(type_argument) = self.type_arguments.get(&rust_type_as_string) {
+            return type_argument.to_owned();
+        }
+        if let Some(function_type) = self.function_type_parameters.get(&rust_type_as_string) {
+            return DataType::Function(Box::new(function_type.to_owned()));
+        }
+
         let primitive_type_parse_result = rust_type_as_string.parse::<ast_types::DataType>();
 
         if let Ok(data_type) = primitive_type_parse_result {
@@ -368,67 +1185,79 @@ impl<'a> Graft<'a> {
             return self.rust_option_type_to_data_type(&rust_type_path.path.segments[0].arguments);
         }
 
+        if !rust_type_path.path.segments[0].arguments.is_empty() {
+            return self.instantiate_generic_type(rust_type_path);
+        }
+
         // We only allow the user to use types that are capitalized
         if rust_type_as_string
             .chars()
             .next()
             .map_or(false, |c| c.is_uppercase())
         {
-            // Note that `Unresolved` handles `Self` as well.
-            // The `custom_type_resolver` translates `Self` to its
-            // associated type.
+            // `Self` was replaced by its type above, when grafting methods and
+            // associated functions.
             ast_types::DataType::Unresolved(rust_type_as_string)
         } else {
-            panic!("Does not know type {rust_type_as_string}");
+            self.report(
+                ErrorCode::UnknownType,
+                rust_type_path,
+                format!("cannot find type `{rust_type_as_string}`"),
+            );
+            DataType::unit()
         }
     }
 
     fn rust_vec_to_data_type(&mut self, path_args: &syn::PathArguments) -> ast_types::DataType {
-        match path_args {
-            syn::PathArguments::AngleBracketed(ab) => {
-                assert_eq!(1, ab.args.len(), "Must be Vec<T> for *one* generic T.");
-                match &ab.args[0] {
-                    syn::GenericArgument::Type(element_type) => ast_types::DataType::List(
-                        Box::new(self.syn_type_to_ast_type(element_type)),
-                        self.list_type,
-                    ),
-                    other => panic!("Unsupported type {other:#?}"),
-                }
+        let Some(element_type) = self.single_type_argument("Vec", path_args) else {
+            return DataType::unit();
+        };
+
+        ast_types::DataType::List(
+            Box::new(self.syn_type_to_ast_type(element_type)),
+            self.list_type,
+        )
+    }
+
+    /// Return the type argument `T` of a generic type like `Vec<T>`, or report an error if
+    /// the type does not have exactly one type argument.
+    fn single_type_argument<'b>(
+        &mut self,
+        type_name: &str,
+        path_args: &'b syn::PathArguments,
+    ) -> Option<&'b syn::Type> {
+        let type_arguments = match path_args {
+            syn::PathArguments::AngleBracketed(generics) => generics.args.iter().collect_vec(),
+            _ => vec![],
+        };
+        match type_arguments[..] {
+            [syn::GenericArgument::Type(type_argument)] => Some(type_argument),
+            _ => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    path_args,
+                    format!("`{type_name}` must have exactly one type argument"),
+                );
+                None
             }
-            other => panic!("Unsupported type {other:#?}"),
         }
     }
 
     fn rust_box_to_data_type(&mut self, path_args: &syn::PathArguments) -> ast_types::DataType {
-        let inner_type = if let syn::PathArguments::AngleBracketed(ab) = &path_args {
-            assert_eq!(1, ab.args.len(), "Must be Box<T> for *one* generic T.");
-            if let syn::GenericArgument::Type(inner) = &ab.args[0] {
-                self.syn_type_to_ast_type(inner)
-            } else {
-                panic!("Unsupported type parameter for Box<T> {:#?}", ab.args[0])
-            }
-        } else {
-            panic!("Box must be followed by its type parameter `<T>`");
+        let Some(inner_type) = self.single_type_argument("Box", path_args) else {
+            return DataType::unit();
         };
+        let inner_type = self.syn_type_to_ast_type(inner_type);
 
         ast_types::DataType::Boxed(Box::new(inner_type))
     }
 
     fn rust_option_type_to_data_type(&mut self, path_args: &PathArguments) -> DataType {
         use crate::libraries;
-        let PathArguments::AngleBracketed(generics) = path_args else {
-            panic!("Unsupported path argument {path_args:#?}");
-        };
-        assert!(
-            generics.args.len().is_one(),
-            "`Option` must have exactly one generic"
-        );
-
-        let some_type_arg = &generics.args[0];
-        let syn::GenericArgument::Type(ok_type) = some_type_arg else {
-            panic!("Unsupported type {some_type_arg:#?}");
+        let Some(some_type) = self.single_type_argument("Option", path_args) else {
+            return DataType::unit();
         };
-        let some_type = self.syn_type_to_ast_type(ok_type);
+        let some_type = self.syn_type_to_ast_type(some_type);
 
         let resolved_type = libraries::core::option_type::option_type(some_type);
         self.imported_custom_types
@@ -438,14 +1267,19 @@ impl<'a> Graft<'a> {
 
     fn rust_result_type_to_data_type(&mut self, path_args: &PathArguments) -> DataType {
         use crate::libraries;
-        let PathArguments::AngleBracketed(generics) = path_args else {
-            panic!("Unsupported path argument {path_args:#?}");
+        let type_arguments = match path_args {
+            PathArguments::AngleBracketed(generics) => generics.args.iter().collect_vec(),
+            _ => vec![],
         };
-        assert_eq!(2, generics.args.len(), "`Result` must have two generics");
-
-        let ok_type_arg = &generics.args[0];
-        let syn::GenericArgument::Type(ok_type) = ok_type_arg else {
-            panic!("Unsupported type {ok_type_arg:#?}");
+        let [syn::GenericArgument::Type(ok_type), syn::GenericArgument::Type(_)] =
+            type_arguments[..]
+        else {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                path_args,
+                "`Result` must have exactly two type arguments",
+            );
+            return DataType::unit();
         };
         let ok_type = self.syn_type_to_ast_type(ok_type);
 
@@ -478,7 +1312,14 @@ impl<'a> Graft<'a> {
                     // Structs that are not copy must be Boxed for reference arguments to work
                     ast_types::DataType::Boxed(Box::new(inner_type))
                 }
-                _ => todo!(),
+                other => {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        &other,
+                        "references are only supported to named types",
+                    );
+                    DataType::unit()
+                }
             },
             syn::Type::Array(syn::TypeArray {
                 bracket_token: _,
@@ -487,15 +1328,17 @@ impl<'a> Graft<'a> {
                 len,
             }) => {
                 let element_type = self.syn_type_to_ast_type(elem);
-                let length = if let syn::Expr::Lit(expr_lit) = len {
-                    let grafted_lit = self.graft_lit(&expr_lit.lit);
-                    match grafted_lit {
-                        ast::ExprLit::U32(length) => length,
-                        ast::ExprLit::GenericNum(length, _) => length as u32,
-                        other => panic!("Bad type for length indication of array. Got {other:#?}"),
+                let grafted_length = self.graft_expr(len);
+                let length = match Self::evaluate_integer_expr(&grafted_length) {
+                    Some(length) if length <= u32::MAX as u128 => length as u32,
+                    _ => {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            len,
+                            "the length of an array must be an integer literal or a constant",
+                        );
+                        0
                     }
-                } else {
-                    panic!("Bad type for length indication of array. Got {len:#?}");
                 };
 
                 ast_types::DataType::Array(ast_types::ArrayType {
@@ -503,7 +1346,38 @@ impl<'a> Graft<'a> {
                     length: length.try_into().unwrap(),
                 })
             }
-            other_type => panic!("Unsupported {other_type:#?}"),
+            syn::Type::BareFn(bare_fn) => {
+                let function_type = self.graft_function_type(
+                    bare_fn.inputs.iter().map(|input| &input.ty),
+                    &bare_fn.output,
+                );
+                DataType::Function(Box::new(function_type))
+            }
+            syn::Type::ImplTrait(impl_trait) => {
+                match Self::function_trait_arguments(&impl_trait.bounds) {
+                    Some(arguments) => {
+                        let function_type =
+                            self.graft_function_type(arguments.inputs.iter(), &arguments.output);
+                        DataType::Function(Box::new(function_type))
+                    }
+                    None => {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            impl_trait,
+                            "only `impl Fn(...) -> ...` is supported as `impl` type",
+                        );
+                        DataType::unit()
+                    }
+                }
+            }
+            other_type => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other_type,
+                    "this kind of type is not supported",
+                );
+                DataType::unit()
+            }
         }
     }
 
@@ -520,17 +1394,26 @@ impl<'a> Graft<'a> {
                 ident: _,
                 subpat: _,
             }) => mutability.is_some(),
-            other_type => panic!("Unsupported {other_type:#?}"),
+            other_pattern => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    &other_pattern,
+                    "only identifiers are supported as patterns here",
+                );
+                false
+            }
         };
         let ast_type = self.syn_type_to_ast_type(rust_type_path.ty.as_ref());
 
         (ast_type, mutable)
     }
 
+    /// Return the name bound by a pattern. Patterns other than identifiers are reported by
+    /// [`Self::pat_type_to_data_type_and_mutability`].
     fn pat_to_name(pat: &syn::Pat) -> String {
         match pat {
             syn::Pat::Ident(ident) => ident.ident.to_string(),
-            other => panic!("unsupported: {other:?}"),
+            _ => String::default(),
         }
     }
 
@@ -540,7 +1423,9 @@ impl<'a> Graft<'a> {
         identifiers.join("::")
     }
 
-    fn graft_fn_arg(&mut self, rust_fn_arg: &syn::FnArg) -> ast_types::AbstractValueArg {
+    /// Graft an argument of a function. Arguments of a function type, like `f: fn(u64) -> u64`
+    /// or `f: impl Fn(u64) -> u64`, become function arguments.
+    fn graft_fn_arg(&mut self, rust_fn_arg: &syn::FnArg) -> ast_types::AbstractArgument {
         match rust_fn_arg {
             syn::FnArg::Typed(pat_type) => {
                 let name = Self::pat_to_name(&pat_type.pat);
@@ -559,13 +1444,36 @@ impl<'a> Graft<'a> {
                     mutable = mutable || mutability.is_some();
                 }
 
-                ast_types::AbstractValueArg {
-                    name,
-                    data_type,
-                    mutable,
+                match data_type {
+                    DataType::Function(function_type) => {
+                        ast_types::AbstractArgument::FunctionArgument(
+                            ast_types::AbstractFunctionArg {
+                                abstract_name: name,
+                                function_type: *function_type,
+                            },
+                        )
+                    }
+                    data_type => {
+                        ast_types::AbstractArgument::ValueArgument(ast_types::AbstractValueArg {
+                            name,
+                            data_type,
+                            mutable,
+                        })
+                    }
                 }
             }
-            other => panic!("unsupported: {other:?}"),
+            syn::FnArg::Receiver(receiver) => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    receiver,
+                    "`self` is only allowed as the first argument of a method",
+                );
+                ast_types::AbstractArgument::ValueArgument(ast_types::AbstractValueArg {
+                    name: "self".to_owned(),
+                    data_type: DataType::unit(),
+                    mutable: false,
+                })
+            }
         }
     }
 
@@ -582,38 +1490,71 @@ impl<'a> Graft<'a> {
 
                     ast_types::DataType::Tuple(output_elements.into())
                 }
-                _ => panic!("unsupported: {path:?}"),
+                other => {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        other,
+                        "this kind of return type is not supported",
+                    );
+                    DataType::unit()
+                }
             },
             syn::ReturnType::Default => ast_types::DataType::Tuple(vec![].into()),
         }
     }
 
-    /// Return type argument found in path
-    pub(crate) fn path_to_type_parameter(
+    /// Return the type arguments of a path, like `u64` and `_` in `apply::<u64, _>`, where
+    /// `_` is `None`
+    pub(crate) fn path_to_type_arguments(
         &mut self,
         path: &syn::Path,
-    ) -> Option<ast_types::DataType> {
-        let mut type_parameter: Option<ast_types::DataType> = None;
+    ) -> Vec<Option<ast_types::DataType>> {
+        let mut type_arguments = vec![];
         for segment in path.segments.iter() {
             if segment.arguments == PathArguments::None {
                 continue;
             }
             let PathArguments::AngleBracketed(abgas) = &segment.arguments else {
-                panic!("unsupported PathArguments: {path:#?}");
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    &segment.arguments,
+                    "only angle-bracketed type arguments are supported",
+                );
+                continue;
             };
             for generic_arg in abgas.args.iter() {
                 let syn::GenericArgument::Type(generic_type) = generic_arg else {
-                    panic!("unsupported GenericArgument: {generic_arg:#?}");
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        generic_arg,
+                        "only types are supported as generic arguments",
+                    );
+                    continue;
                 };
                 if let syn::Type::Infer(_) = generic_type {
+                    type_arguments.push(None);
                     continue;
                 }
-                if type_parameter.is_some() {
-                    panic!("only one type parameter supported, got {generic_type:?}");
-                }
-                type_parameter = Some(self.syn_type_to_ast_type(generic_type));
+                type_arguments.push(Some(self.syn_type_to_ast_type(generic_type)));
             }
         }
+        type_arguments
+    }
+
+    /// Return the type argument of a path to a library function, which takes at most one
+    pub(crate) fn path_to_type_parameter(
+        &mut self,
+        path: &syn::Path,
+    ) -> Option<ast_types::DataType> {
+        let mut type_arguments = self.path_to_type_arguments(path).into_iter().flatten();
+        let type_parameter = type_arguments.next();
+        if type_arguments.next().is_some() {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                path,
+                "only one type parameter is supported",
+            );
+        }
         type_parameter
     }
 
@@ -626,17 +1567,25 @@ impl<'a> Graft<'a> {
             args,
         }: &syn::ExprCall,
     ) -> ast::Expr<Annotation> {
-        let (full_name, function_type_parameter) = match func.as_ref() {
+        let (full_name, path) = match func.as_ref() {
             syn::Expr::Path(path) => (
-                Graft::path_to_ident(&path.path),
-                self.path_to_type_parameter(&path.path),
+                self.resolve_self_in_path(Graft::path_to_ident(&path.path)),
+                &path.path,
             ),
-            other => panic!("unsupported: {other:?}"),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "only named functions can be called",
+                );
+                return ast::Expr::Tuple(vec![]);
+            }
         };
 
         // Check if grafting should be handled by a library
         for lib in self.libraries.iter() {
             if let Some(fn_name) = lib.get_graft_function_name(&full_name) {
+                let function_type_parameter = self.path_to_type_parameter(path);
                 return lib
                     .graft_function(self, &fn_name, args, function_type_parameter)
                     .unwrap();
@@ -645,6 +1594,7 @@ impl<'a> Graft<'a> {
 
         // Grafting was not handled by a library. Treat function call as a regular
         // function that is hopefully in scope
+        let type_arguments = self.path_to_type_arguments(path);
         let args = args.iter().map(|x| self.graft_expr(x)).collect_vec();
         let annot = Default::default();
 
@@ -652,7 +1602,7 @@ impl<'a> Graft<'a> {
             name: full_name,
             args,
             annot,
-            type_parameter: function_type_parameter,
+            type_arguments,
             arg_evaluation_order: Default::default(),
         })
     }
@@ -665,11 +1615,13 @@ impl<'a> Graft<'a> {
         let base_ident = match base_expression {
             ast::Expr::Var(ident) => ident,
             _ => {
-                panic!(
-                    "Left-hand-side of tuple operator must be a declared variable. \
-                     Declare more bindings if needed. \
-                     Failed to parse expression: {base_expression} as an identifier"
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    base,
+                    "fields can only be accessed on declared variables, \
+                     declare more bindings if needed",
                 );
+                ast::Identifier::String(String::default(), Default::default())
             }
         };
 
@@ -718,7 +1670,7 @@ impl<'a> Graft<'a> {
         right: &syn::Expr,
     ) -> ast::Expr<Annotation> {
         let left = self.graft_expr(left);
-        let ast_binop: ast::BinOp = Self::graft_eq_binop(op);
+        let ast_binop: ast::BinOp = self.graft_eq_binop(op);
         let right = self.graft_expr(right);
 
         ast::Expr::Binop(
@@ -768,7 +1720,7 @@ impl<'a> Graft<'a> {
                         Default::default(),
                     )
                 } else {
-                    let ast_binop = Self::graft_binop(bin_expr.op);
+                    let ast_binop = self.graft_binop(&bin_expr.op);
                     ast::Expr::Binop(
                         Box::new(left),
                         ast_binop,
@@ -779,12 +1731,20 @@ impl<'a> Graft<'a> {
             }
             syn::Expr::Path(path) => {
                 let path = &path.path;
-                let ident: String = Self::path_to_ident(path);
+                let ident: String = self.resolve_self_in_path(Self::path_to_ident(path));
 
                 // TODO: Maybe not so elegant to handle this here...
                 // Should be handled on a different level
                 // TODO: Put this into `unsigned` library
-                if ident == "u32::MAX" {
+                if ident == "u8::MAX" {
+                    ast::Expr::Lit(ast::ExprLit::U8(u8::MAX))
+                } else if ident == "u8::BITS" {
+                    ast::Expr::Lit(ast::ExprLit::U32(u8::BITS))
+                } else if ident == "u16::MAX" {
+                    ast::Expr::Lit(ast::ExprLit::U16(u16::MAX))
+                } else if ident == "u16::BITS" {
+                    ast::Expr::Lit(ast::ExprLit::U32(u16::BITS))
+                } else if ident == "u32::MAX" {
                     ast::Expr::Lit(ast::ExprLit::U32(u32::MAX))
                 } else if ident == "u32::BITS" {
                     ast::Expr::Lit(ast::ExprLit::U32(u32::BITS))
@@ -796,17 +1756,33 @@ impl<'a> Graft<'a> {
                     ast::Expr::Lit(ast::ExprLit::U128(u128::MAX))
                 } else if ident == "u128::BITS" {
                     ast::Expr::Lit(ast::ExprLit::U32(u128::BITS))
+                } else if ident == "i32::MIN" {
+                    ast::Expr::Lit(ast::ExprLit::I32(i32::MIN))
+                } else if ident == "i32::MAX" {
+                    ast::Expr::Lit(ast::ExprLit::I32(i32::MAX))
+                } else if ident == "i32::BITS" {
+                    ast::Expr::Lit(ast::ExprLit::U32(i32::BITS))
+                } else if ident == "i64::MIN" {
+                    ast::Expr::Lit(ast::ExprLit::I64(i64::MIN))
+                } else if ident == "i64::MAX" {
+                    ast::Expr::Lit(ast::ExprLit::I64(i64::MAX))
+                } else if ident == "i64::BITS" {
+                    ast::Expr::Lit(ast::ExprLit::U32(i64::BITS))
+                } else if let Some(constant) = self.graft_constant(&ident) {
+                    constant
                 } else {
                     // if string name contains `::`, and we don't know the type,
                     // we assume this refers to an enum variant, without
                     // contained data: 'Foo::Bar'.
                     let enum_init = ident.split("::").collect_vec();
                     if enum_init.len() > 1 && ast_types::DataType::from_str(enum_init[0]).is_err() {
-                        assert_eq!(
-                            2,
-                            enum_init.len(),
-                            "Expected enum initialization to only contain one instance of '::'"
-                        );
+                        if enum_init.len() != 2 {
+                            self.report(
+                                ErrorCode::UnsupportedSyntax,
+                                path,
+                                "expected enum variant of the form `<Type>::<VariantName>`",
+                            );
+                        }
                         // This is the initialization of a value of an enum type -- without
                         // associated data.
                         ast::Expr::EnumDeclaration({
@@ -840,20 +1816,31 @@ impl<'a> Graft<'a> {
                 let mut oil_fields = vec![];
                 for field in fields.iter() {
                     let oil_expr = self.graft_expr(&field.expr);
-                    let field_name = if let syn::Member::Named(ident) = &field.member {
-                        ident.to_string()
-                    } else {
-                        panic!("Field must be named in struct declaration")
+                    let field_name = match &field.member {
+                        syn::Member::Named(ident) => ident.to_string(),
+                        syn::Member::Unnamed(index) => {
+                            self.report(
+                                ErrorCode::UnsupportedSyntax,
+                                index,
+                                "fields must be named in struct expressions",
+                            );
+                            index.index.to_string()
+                        }
                     };
                     oil_fields.push((field_name, oil_expr));
                 }
 
-                // Get name of struct from declaration
-                assert!(
-                    path.segments.len().is_one(),
-                    "Struct declaration must have path segment of length 1 for now"
-                );
-                let name_of_struct = path.segments[0].ident.to_string();
+                // Get name of struct from declaration. Variants of enums with named fields,
+                // like `Foo::Bar { a: 1 }`, are resolved to their constructors later, when
+                // the order of the fields is known.
+                if path.segments.len() > 2 {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        path,
+                        "paths to types are not supported, import the type instead",
+                    );
+                }
+                let name_of_struct = self.resolve_self_in_path(Graft::path_to_ident(path));
 
                 ast::Expr::Struct(ast::StructExpr {
                     struct_type: ast_types::DataType::Unresolved(name_of_struct),
@@ -867,44 +1854,13 @@ impl<'a> Graft<'a> {
             syn::Expr::Call(call_exp) => self.graft_call_exp(call_exp),
             syn::Expr::Paren(paren_exp) => self.graft_expr(&paren_exp.expr),
             syn::Expr::If(expr_if) => {
-                let condition = self.graft_expr(&expr_if.cond);
-                let if_branch = &expr_if.then_branch.stmts;
-                let then_branch = match if_branch.last() {
-                    Some(syn::Stmt::Expr(last_expr)) => {
-                        let then_branch_statements = if_branch[0..if_branch.len() - 1]
-                            .iter()
-                            .map(|x| self.graft_stmt(x))
-                            .collect_vec();
-                        let then_branch_last_expr = self.graft_expr(last_expr);
-                        ReturningBlock {
-                            stmts: then_branch_statements,
-                            return_expr: then_branch_last_expr,
-                        }
-                    }
-                    _ => panic!("unsupported: {if_branch:#?}"),
-                };
+                if let syn::Expr::Let(expr_let) = expr_if.cond.as_ref() {
+                    return self.graft_if_let_expr(expr_if, expr_let);
+                }
 
-                let else_branch = &expr_if.else_branch.as_ref().unwrap().1;
-                let else_branch = match else_branch.as_ref() {
-                    syn::Expr::Block(block) => {
-                        let else_branch = &block.block.stmts;
-                        match else_branch.last() {
-                            Some(syn::Stmt::Expr(last_expr)) => {
-                                let else_branch_statements = else_branch[0..else_branch.len() - 1]
-                                    .iter()
-                                    .map(|x| self.graft_stmt(x))
-                                    .collect_vec();
-                                let else_branch_last_expr = self.graft_expr(last_expr);
-                                ReturningBlock {
-                                    stmts: else_branch_statements,
-                                    return_expr: else_branch_last_expr,
-                                }
-                            }
-                            _ => panic!("unsupported: {if_branch:#?}"),
-                        }
-                    }
-                    other => panic!("unsupported: {other:?}"),
-                };
+                let condition = self.graft_expr(&expr_if.cond);
+                let then_branch = self.graft_returning_block(&expr_if.then_branch);
+                let else_branch = self.graft_else_returning_block(expr_if);
 
                 ast::Expr::If(ast::ExprIf {
                     condition: Box::new(condition),
@@ -912,6 +1868,7 @@ impl<'a> Graft<'a> {
                     else_branch: Box::new(else_branch),
                 })
             }
+            syn::Expr::Match(expr_match) => self.graft_match_expr(expr_match),
             syn::Expr::MethodCall(method_call_expr) => self.graft_method_call(method_call_expr),
             syn::Expr::Field(field_expr) => ast::Expr::Var(self.graft_field_expression(field_expr)),
             syn::Expr::Index(index_expr) => {
@@ -926,14 +1883,35 @@ impl<'a> Graft<'a> {
                     expr => ast::IndexExpr::Dynamic(expr),
                 };
 
-                if let ast::Expr::Var(identifier) = expr {
-                    ast::Expr::Var(ast::Identifier::Index(
+                match (expr, index) {
+                    (ast::Expr::Var(identifier), index) => ast::Expr::Var(ast::Identifier::Index(
                         Box::new(identifier),
                         Box::new(index),
                         Default::default(),
-                    ))
-                } else {
-                    panic!("unsupported index expression: {index_expr:#?}");
+                    )),
+                    // An element of a constant array is inlined like the array, like in
+                    // `TABLE[2]` where `TABLE` is a `const` item
+                    (
+                        ast::Expr::Array(ast::ArrayExpression::ElementsSpecified(mut elements), _),
+                        ast::IndexExpr::Static(index),
+                    ) if index < elements.len() => elements.swap_remove(index),
+                    (ast::Expr::Array(..), _) => {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            index_expr,
+                            "arrays that are not bound to a variable can only be indexed into \
+                             with constant indices, declare tables as `static` items instead",
+                        );
+                        ast::Expr::Tuple(vec![])
+                    }
+                    _ => {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            &index_expr.expr,
+                            "only declared variables can be indexed into",
+                        );
+                        ast::Expr::Tuple(vec![])
+                    }
                 }
             }
             syn::Expr::Cast(syn::ExprCast {
@@ -974,20 +1952,7 @@ impl<'a> Graft<'a> {
                 attrs: _,
                 label: _,
                 block,
-            }) => {
-                let (returning_expr, stmts) = block.stmts.split_last().unwrap();
-
-                // Last line must be an expression, cannot be a binding or anything else.
-                let return_expr = match returning_expr {
-                    syn::Stmt::Local(_) => panic!(),
-                    syn::Stmt::Item(_) => panic!(),
-                    syn::Stmt::Expr(expr) => self.graft_expr(expr),
-                    syn::Stmt::Semi(_, _) => panic!(),
-                };
-
-                let stmts = stmts.iter().map(|x| self.graft_stmt(x)).collect();
-                ast::Expr::ReturningBlock(Box::new(ast::ReturningBlock { stmts, return_expr }))
-            }
+            }) => ast::Expr::ReturningBlock(Box::new(self.graft_returning_block(block))),
             syn::Expr::Array(syn::ExprArray {
                 attrs: _,
                 bracket_token: _,
@@ -1012,7 +1977,163 @@ impl<'a> Graft<'a> {
                     associated_type: Default::default(),
                 })
             }
-            other => panic!("unsupported: {other:?}"),
+            syn::Expr::Closure(closure) => ast::Expr::Closure(self.graft_closure(closure)),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "this kind of expression is not supported",
+                );
+                ast::Expr::Tuple(vec![])
+            }
+        }
+    }
+
+    /// Graft a closure, like `|x| x * two` or `move |x: u64| -> u64 { x * two }`. Closures
+    /// always capture copies of the values they use, so `move` makes no difference.
+    fn graft_closure(&mut self, closure: &syn::ExprClosure) -> ast::Closure<Annotation> {
+        if let Some(asyncness) = &closure.asyncness {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                asyncness,
+                "async closures are not supported",
+            );
+        }
+
+        let mut args = vec![];
+        for input in closure.inputs.iter() {
+            match input {
+                syn::Pat::Ident(pat_ident) => args.push((pat_ident.ident.to_string(), None)),
+                syn::Pat::Type(pat_type) => {
+                    let (data_type, _mutable) = self.pat_type_to_data_type_and_mutability(pat_type);
+                    args.push((Self::pat_to_name(&pat_type.pat), Some(data_type)));
+                }
+                other => self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "only identifiers are supported as closure arguments",
+                ),
+            }
+        }
+        let output = match &closure.output {
+            syn::ReturnType::Default => None,
+            output => Some(self.graft_return_type(output)),
+        };
+
+        ast::Closure {
+            args,
+            output,
+            body: Box::new(self.graft_expr(&closure.body)),
+        }
+    }
+
+    /// Handle blocks that evaluate to the expression on their last line
+    fn graft_returning_block(&mut self, block: &syn::Block) -> ReturningBlock<Annotation> {
+        let Some((syn::Stmt::Expr(returning_expr), stmts)) = block.stmts.split_last() else {
+            self.report(
+                ErrorCode::UnsupportedSyntax,
+                block,
+                "block must end with the expression it evaluates to",
+            );
+            return Self::placeholder_block();
+        };
+
+        let stmts = self.graft_stmts(stmts);
+        let return_expr = self.graft_expr(returning_expr);
+        ReturningBlock { stmts, return_expr }
+    }
+
+    /// Handle the `else` branch of an `if` expression, which must be present
+    fn graft_else_returning_block(&mut self, expr_if: &syn::ExprIf) -> ReturningBlock<Annotation> {
+        match expr_if.else_branch.as_ref() {
+            Some((_, else_branch)) => match else_branch.as_ref() {
+                syn::Expr::Block(block) => self.graft_returning_block(&block.block),
+                other => {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        other,
+                        "`else if` is not supported in `if` expressions",
+                    );
+                    Self::placeholder_block()
+                }
+            },
+            None => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    expr_if,
+                    "`if` expressions must have an `else` branch",
+                );
+                Self::placeholder_block()
+            }
+        }
+    }
+
+    /// Handle `if let` expressions, which are grafted as `match` expressions whose catch-all
+    /// arm evaluates the `else` branch
+    fn graft_if_let_expr(
+        &mut self,
+        expr_if: &syn::ExprIf,
+        syn::ExprLet { pat, expr, .. }: &syn::ExprLet,
+    ) -> ast::Expr<Annotation> {
+        let match_expression = self.graft_expr(expr);
+        let match_condition = self.graft_match_condition(pat);
+        let then_branch = self.graft_returning_block(&expr_if.then_branch);
+        let else_branch = self.graft_else_returning_block(expr_if);
+
+        ast::Expr::Match(ast::MatchExpr {
+            match_expression: Box::new(match_expression),
+            arms: vec![
+                ast::MatchExprArm {
+                    match_condition,
+                    guard: None,
+                    body: then_branch,
+                },
+                ast::MatchExprArm {
+                    match_condition: ast::MatchCondition::CatchAll,
+                    guard: None,
+                    body: else_branch,
+                },
+            ],
+        })
+    }
+
+    /// Handle `match` expressions, whose arms evaluate to a value
+    fn graft_match_expr(
+        &mut self,
+        syn::ExprMatch { expr, arms, .. }: &syn::ExprMatch,
+    ) -> ast::Expr<Annotation> {
+        let match_expression = self.graft_expr(expr);
+        let arms = arms
+            .iter()
+            .map(|arm| {
+                let body = match arm.body.as_ref() {
+                    syn::Expr::Block(syn::ExprBlock { block, .. }) => {
+                        self.graft_returning_block(block)
+                    }
+                    body => ReturningBlock {
+                        stmts: vec![],
+                        return_expr: self.graft_expr(body),
+                    },
+                };
+                ast::MatchExprArm {
+                    match_condition: self.graft_match_condition(&arm.pat),
+                    guard: arm.guard.as_ref().map(|(_, guard)| self.graft_expr(guard)),
+                    body,
+                }
+            })
+            .collect_vec();
+
+        ast::Expr::Match(ast::MatchExpr {
+            match_expression: Box::new(match_expression),
+            arms,
+        })
+    }
+
+    /// The block that code which could not be grafted is replaced with
+    fn placeholder_block() -> ReturningBlock<Annotation> {
+        ReturningBlock {
+            stmts: vec![],
+            return_expr: ast::Expr::Tuple(vec![]),
         }
     }
 
@@ -1025,6 +2146,18 @@ impl<'a> Graft<'a> {
                 let int_lit_str = int_lit.token().to_string();
 
                 // Despite its name `base10_parse` can handle hex. Don't ask me why.
+                if let Some(_int_lit_stripped) = int_lit_str.strip_suffix("u8") {
+                    if let Ok(int_u8) = int_lit.base10_parse::<u8>() {
+                        return ast::ExprLit::U8(int_u8);
+                    }
+                }
+
+                if let Some(_int_lit_stripped) = int_lit_str.strip_suffix("u16") {
+                    if let Ok(int_u16) = int_lit.base10_parse::<u16>() {
+                        return ast::ExprLit::U16(int_u16);
+                    }
+                }
+
                 if let Some(_int_lit_stripped) = int_lit_str.strip_suffix("u32") {
                     if let Ok(int_u32) = int_lit.base10_parse::<u32>() {
                         return ast::ExprLit::U32(int_u32);
@@ -1050,17 +2183,41 @@ impl<'a> Graft<'a> {
                     }
                 }
 
+                if let Some(_int_lit_stripped) = int_lit_str.strip_suffix("i32") {
+                    if let Ok(int_i32) = int_lit.base10_parse::<i32>() {
+                        return ast::ExprLit::I32(int_i32);
+                    }
+                }
+
+                if let Some(_int_lit_stripped) = int_lit_str.strip_suffix("i64") {
+                    if let Ok(int_i64) = int_lit.base10_parse::<i64>() {
+                        return ast::ExprLit::I64(int_i64);
+                    }
+                }
+
                 if let Ok(int_u128) = int_lit.base10_parse::<u128>() {
                     return ast::ExprLit::GenericNum(int_u128, Default::default());
                 }
 
-                panic!("unsupported integer literal: {int_lit_str}");
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    int_lit,
+                    format!("unsupported integer literal `{int_lit_str}`"),
+                );
+                ast::ExprLit::GenericNum(0, Default::default())
+            }
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "this kind of literal is not supported",
+                );
+                ast::ExprLit::GenericNum(0, Default::default())
             }
-            other => panic!("unsupported: {other:?}"),
         }
     }
 
-    fn graft_binop(rust_binop: syn::BinOp) -> ast::BinOp {
+    fn graft_binop(&mut self, rust_binop: &syn::BinOp) -> ast::BinOp {
         match rust_binop {
             syn::BinOp::Add(_) => ast::BinOp::Add,
             syn::BinOp::And(_) => ast::BinOp::And,
@@ -1078,11 +2235,18 @@ impl<'a> Graft<'a> {
             syn::BinOp::Shl(_) => ast::BinOp::Shl,
             syn::BinOp::Shr(_) => ast::BinOp::Shr,
             syn::BinOp::Sub(_) => ast::BinOp::Sub,
-            other => panic!("unsupported: {other:?}"),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "this binary operator is not supported",
+                );
+                ast::BinOp::Add
+            }
         }
     }
 
-    fn graft_eq_binop(rust_eq_binop: &syn::BinOp) -> ast::BinOp {
+    fn graft_eq_binop(&mut self, rust_eq_binop: &syn::BinOp) -> ast::BinOp {
         match rust_eq_binop {
             syn::BinOp::AddEq(_) => ast::BinOp::Add,
             syn::BinOp::SubEq(_) => ast::BinOp::Sub,
@@ -1093,10 +2257,31 @@ impl<'a> Graft<'a> {
             syn::BinOp::BitAndEq(_) => ast::BinOp::BitAnd,
             syn::BinOp::ShlEq(_) => ast::BinOp::Shl,
             syn::BinOp::ShrEq(_) => ast::BinOp::Shr,
-            other => panic!("unsupported for eq binop: {other:?}"),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "this compound assignment operator is not supported",
+                );
+                ast::BinOp::Add
+            }
         }
     }
 
+    /// Graft a list of statements, each preceded by its location in the source code
+    fn graft_stmts(&mut self, rust_stmts: &[syn::Stmt]) -> Vec<Stmt<Annotation>> {
+        rust_stmts
+            .iter()
+            .flat_map(|rust_stmt| {
+                let location = SourceLocation {
+                    file_path: self.file_path.clone(),
+                    ..SourceLocation::from_span(rust_stmt.span())
+                };
+                [Stmt::Location(location), self.graft_stmt(rust_stmt)]
+            })
+            .collect()
+    }
+
     pub fn graft_stmt(&mut self, rust_stmt: &syn::Stmt) -> Stmt<Annotation> {
         match rust_stmt {
             syn::Stmt::Local(local) => self.graft_local_stmt(local),
@@ -1106,29 +2291,33 @@ impl<'a> Graft<'a> {
         }
     }
 
-    /// Handle declarations, i.e. `let a: u32 = 200;`
+    /// Handle declarations, i.e. `let a: u32 = 200;` and `let a = 200;`
     fn graft_local_stmt(&mut self, local: &syn::Local) -> Stmt<Annotation> {
-        let (ident, data_type, mutable): (String, DataType, bool) = match &local.pat {
-            syn::Pat::Type(pat_type) => {
-                let (dt, mutable): (DataType, bool) =
-                    self.pat_type_to_data_type_and_mutability(pat_type);
-                let ident: String = Graft::pat_to_name(&pat_type.pat);
-
-                (ident, dt, mutable)
+        let (pat, data_type): (&syn::Pat, Option<DataType>) = match &local.pat {
+            syn::Pat::Type(syn::PatType { pat, ty, .. }) => {
+                (pat.as_ref(), Some(self.syn_type_to_ast_type(ty)))
             }
-            syn::Pat::Ident(d) => {
-                // This would indicate that the explicit type is missing
-                let ident = d.ident.to_string();
-                panic!("Missing explicit type in declaration of '{ident}'");
-            }
-            other => panic!("unsupported: {other:?}"),
+            // The type is inferred by the type checker
+            pat => (pat, None),
+        };
+        let (ident, mutable): (String, bool) = match pat {
+            syn::Pat::Ident(syn::PatIdent {
+                ident,
+                mutability,
+                subpat: None,
+                ..
+            }) => (ident.to_string(), mutability.is_some()),
+            pat => return self.graft_destructuring_let_stmt(local, pat, data_type),
         };
 
-        let init = local
-            .init
-            .as_ref()
-            .unwrap_or_else(|| panic!("must initialize \"{ident}\""));
-        let init_expr = init.1.as_ref();
+        let Some((_, init_expr)) = local.init.as_ref() else {
+            self.report(
+                ErrorCode::MissingInitializer,
+                local,
+                format!("`{ident}` must be initialized where it is declared"),
+            );
+            return Stmt::Panic(ast::PanicStmt);
+        };
         let ast_expt = self.graft_expr(init_expr);
         let let_stmt = ast::LetStmt {
             var_name: ident,
@@ -1139,45 +2328,163 @@ impl<'a> Graft<'a> {
         Stmt::Let(let_stmt)
     }
 
+    /// Handle `let` statements that destructure the value into several bindings, like
+    /// `let (q, r): (u64, u64) = div(a, b);`
+    fn graft_destructuring_let_stmt(
+        &mut self,
+        local: &syn::Local,
+        pat: &syn::Pat,
+        data_type: Option<DataType>,
+    ) -> Stmt<Annotation> {
+        let pattern = self.graft_destructuring_pattern(pat);
+        let Some((_, init_expr)) = local.init.as_ref() else {
+            self.report(
+                ErrorCode::MissingInitializer,
+                local,
+                "destructured values must be initialized where they are declared",
+            );
+            return Stmt::Panic(ast::PanicStmt);
+        };
+
+        Stmt::DestructuringLet(ast::DestructuringLetStmt {
+            pattern,
+            data_type,
+            expr: self.graft_expr(init_expr),
+        })
+    }
+
+    /// Handle the pattern of a destructuring `let` statement
+    fn graft_destructuring_pattern(&mut self, pat: &syn::Pat) -> ast::DestructuringPattern {
+        match pat {
+            syn::Pat::Wild(_) => ast::DestructuringPattern::Wildcard,
+            syn::Pat::Ident(syn::PatIdent {
+                ident,
+                mutability,
+                by_ref: None,
+                subpat: None,
+                ..
+            }) => ast::DestructuringPattern::Binding(ast::PatternMatchedBinding {
+                name: ident.to_string(),
+                mutable: mutability.is_some(),
+            }),
+            syn::Pat::Tuple(syn::PatTuple { elems, .. }) => ast::DestructuringPattern::Tuple(
+                elems
+                    .iter()
+                    .map(|elem| self.graft_destructuring_pattern(elem))
+                    .collect(),
+            ),
+            syn::Pat::TupleStruct(syn::PatTupleStruct { path, pat, .. }) => {
+                ast::DestructuringPattern::Struct {
+                    struct_type: ast_types::DataType::Unresolved(Graft::path_to_ident(path)),
+                    fields: pat
+                        .elems
+                        .iter()
+                        .enumerate()
+                        .map(|(i, elem)| {
+                            let field_id = ast_types::FieldId::UnnamedField(i);
+                            (field_id, self.graft_destructuring_pattern(elem))
+                        })
+                        .collect(),
+                }
+            }
+            syn::Pat::Struct(syn::PatStruct { path, fields, .. }) => {
+                ast::DestructuringPattern::Struct {
+                    struct_type: ast_types::DataType::Unresolved(Graft::path_to_ident(path)),
+                    fields: fields
+                        .iter()
+                        .map(|syn::FieldPat { member, pat, .. }| {
+                            let field_id = match member {
+                                syn::Member::Named(field_name) => {
+                                    ast_types::FieldId::NamedField(field_name.to_string())
+                                }
+                                syn::Member::Unnamed(index) => {
+                                    ast_types::FieldId::UnnamedField(index.index as usize)
+                                }
+                            };
+                            (field_id, self.graft_destructuring_pattern(pat))
+                        })
+                        .collect(),
+                }
+            }
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "only identifiers, `_`, tuples and structs are supported as patterns in \
+                     `let` statements",
+                );
+                ast::DestructuringPattern::Wildcard
+            }
+        }
+    }
+
     /// Handle expressions
     fn graft_expr_stmt(&mut self, expr: &syn::Expr) -> Stmt<Annotation> {
         match expr {
             syn::Expr::While(while_stmt) => {
+                if let syn::Expr::Let(expr_let) = while_stmt.cond.as_ref() {
+                    return self.graft_while_let(while_stmt, expr_let);
+                }
+
                 let expr_while = while_stmt;
                 let while_condition = self.graft_expr(&expr_while.cond);
-                let while_stmts: Vec<Stmt<Annotation>> = while_stmt
-                    .body
-                    .stmts
-                    .iter()
-                    .map(|x| self.graft_stmt(x))
-                    .collect_vec();
+                let while_stmts: Vec<Stmt<Annotation>> = self.graft_stmts(&while_stmt.body.stmts);
 
                 let while_stmt = ast::WhileStmt {
                     condition: while_condition,
                     block: ast::BlockStmt { stmts: while_stmts },
+                    label: Self::graft_loop_label(&while_stmt.label),
                 };
                 Stmt::While(while_stmt)
             }
+            syn::Expr::Loop(syn::ExprLoop { label, body, .. }) => {
+                let stmts = self.graft_stmts(&body.stmts);
+                Stmt::Loop(ast::LoopStmt {
+                    block: ast::BlockStmt { stmts },
+                    label: Self::graft_loop_label(label),
+                })
+            }
+            syn::Expr::ForLoop(syn::ExprForLoop {
+                label,
+                pat,
+                expr,
+                body,
+                ..
+            }) => {
+                let loop_variable = match pat {
+                    syn::Pat::Ident(ident) => Some(ast::PatternMatchedBinding {
+                        name: ident.ident.to_string(),
+                        mutable: ident.mutability.is_some(),
+                    }),
+                    syn::Pat::Wild(_) => None,
+                    other => {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            other,
+                            "only identifiers and `_` are supported as patterns in for-loops",
+                        );
+                        None
+                    }
+                };
+                let iterable = self.graft_for_loop_iterable(expr);
+                let stmts = self.graft_stmts(&body.stmts);
+
+                Stmt::For(ast::ForStmt {
+                    loop_variable,
+                    iterable,
+                    block: ast::BlockStmt { stmts },
+                    label: Self::graft_loop_label(label),
+                })
+            }
             syn::Expr::If(if_expr) => {
+                if let syn::Expr::Let(expr_let) = if_expr.cond.as_ref() {
+                    return self.graft_if_let_stmt(if_expr, expr_let);
+                }
+
                 let if_condition = self.graft_expr(&if_expr.cond);
-                let then_stmts: Vec<Stmt<Annotation>> = if_expr
-                    .then_branch
-                    .stmts
-                    .iter()
-                    .map(|x| self.graft_stmt(x))
-                    .collect_vec();
-                let else_stmts: Vec<Stmt<Annotation>> = match if_expr.else_branch.as_ref() {
-                    Some(else_stmts) => match else_stmts.1.as_ref() {
-                        syn::Expr::Block(block) => block
-                            .block
-                            .stmts
-                            .iter()
-                            .map(|x| self.graft_stmt(x))
-                            .collect(),
-                        other => panic!("unsupported: {other:?}"),
-                    },
-                    None => vec![],
-                };
+                let then_stmts: Vec<Stmt<Annotation>> =
+                    self.graft_stmts(&if_expr.then_branch.stmts);
+                let else_stmts = self.graft_else_stmts(if_expr);
 
                 let if_stmt = ast::IfStmt {
                     condition: if_condition,
@@ -1191,14 +2498,411 @@ impl<'a> Graft<'a> {
                 label: _label,
                 block,
             }) => {
-                let stmts: Vec<Stmt<Annotation>> =
-                    block.stmts.iter().map(|x| self.graft_stmt(x)).collect_vec();
+                let stmts: Vec<Stmt<Annotation>> = self.graft_stmts(&block.stmts);
                 Stmt::Block(ast::BlockStmt { stmts })
             }
-            other => panic!(
-                "unsupported expression. make sure to end statements by semi-colon \
-                     and to explicitly 'return':\n{other:?}"
+            syn::Expr::Break(_) | syn::Expr::Continue(_) => self.graft_semi_stmt(expr),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "unsupported expression, make sure to end statements by semi-colon \
+                     and to explicitly `return`",
+                );
+                Stmt::Panic(ast::PanicStmt)
+            }
+        }
+    }
+
+    /// Handle the `else` branch of an `if` statement, which may be absent
+    fn graft_else_stmts(&mut self, if_expr: &syn::ExprIf) -> Vec<Stmt<Annotation>> {
+        match if_expr.else_branch.as_ref() {
+            Some(else_stmts) => match else_stmts.1.as_ref() {
+                syn::Expr::Block(block) => self.graft_stmts(&block.block.stmts),
+                other => {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        other,
+                        "`else if` is not supported, nest the `if` in an `else` block",
+                    );
+                    vec![]
+                }
+            },
+            None => vec![],
+        }
+    }
+
+    /// Handle `if let` statements, which are grafted as match statements whose catch-all arm
+    /// executes the `else` branch
+    fn graft_if_let_stmt(
+        &mut self,
+        if_expr: &syn::ExprIf,
+        syn::ExprLet { pat, expr, .. }: &syn::ExprLet,
+    ) -> Stmt<Annotation> {
+        let match_expression = self.graft_expr(expr);
+        let match_condition = self.graft_match_condition(pat);
+        let then_stmts = self.graft_stmts(&if_expr.then_branch.stmts);
+        let else_stmts = self.graft_else_stmts(if_expr);
+
+        Stmt::Match(ast::MatchStmt {
+            match_expression,
+            arms: vec![
+                ast::MatchArm {
+                    match_condition,
+                    guard: None,
+                    body: ast::BlockStmt { stmts: then_stmts },
+                },
+                ast::MatchArm {
+                    match_condition: ast::MatchCondition::CatchAll,
+                    guard: None,
+                    body: ast::BlockStmt { stmts: else_stmts },
+                },
+            ],
+        })
+    }
+
+    /// Handle `while let` loops, which are grafted as loops around a match statement that
+    /// breaks out of the loop when the pattern does not match, like
+    /// `loop { match <expr> { <pattern> => { ... } _ => break, } }`.
+    fn graft_while_let(
+        &mut self,
+        while_stmt: &syn::ExprWhile,
+        syn::ExprLet { pat, expr, .. }: &syn::ExprLet,
+    ) -> Stmt<Annotation> {
+        let match_expression = self.graft_expr(expr);
+        let match_condition = self.graft_match_condition(pat);
+        let stmts = self.graft_stmts(&while_stmt.body.stmts);
+
+        // A catch-all arm after a pattern that always matches would not be the last arm that
+        // can match, so it is left out
+        let always_matches = match pat {
+            syn::Pat::Wild(_) => true,
+            syn::Pat::Ident(syn::PatIdent {
+                ident,
+                subpat: None,
+                ..
+            }) => !ident.to_string().starts_with(char::is_uppercase),
+            _ => false,
+        };
+        let mut arms = vec![ast::MatchArm {
+            match_condition,
+            guard: None,
+            body: ast::BlockStmt { stmts },
+        }];
+        if !always_matches {
+            arms.push(ast::MatchArm {
+                match_condition: ast::MatchCondition::CatchAll,
+                guard: None,
+                body: ast::BlockStmt {
+                    stmts: vec![Stmt::Break(None)],
+                },
+            });
+        }
+
+        let block = ast::BlockStmt {
+            stmts: vec![Stmt::Match(ast::MatchStmt {
+                match_expression,
+                arms,
+            })],
+        };
+        Stmt::Loop(ast::LoopStmt {
+            block,
+            label: Self::graft_loop_label(&while_stmt.label),
+        })
+    }
+
+    /// Handle loop labels, like `'outer` in `'outer: loop { ... }`
+    fn graft_loop_label(label: &Option<syn::Label>) -> Option<String> {
+        label.as_ref().map(|label| label.name.ident.to_string())
+    }
+
+    /// Handle the expression that a `for` loop iterates over
+    fn graft_for_loop_iterable(&mut self, expr: &syn::Expr) -> ast::ForIterable<Annotation> {
+        match expr {
+            syn::Expr::Paren(syn::ExprParen { expr, .. }) => self.graft_for_loop_iterable(expr),
+            syn::Expr::Range(syn::ExprRange {
+                from, limits, to, ..
+            }) => {
+                let (Some(from), Some(to)) = (from, to) else {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        expr,
+                        "ranges in for-loops must have both a start and an end",
+                    );
+                    return ast::ForIterable::Collection(ast::Expr::Tuple(vec![]));
+                };
+                let range = ast::RangeExpr {
+                    start: self.graft_expr(from),
+                    end: self.graft_expr(to),
+                    inclusive: matches!(limits, syn::RangeLimits::Closed(_)),
+                    reversed: false,
+                };
+                ast::ForIterable::Range(range)
+            }
+            syn::Expr::MethodCall(syn::ExprMethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            }) if args.is_empty() => match method.to_string().as_str() {
+                "rev" => match self.graft_for_loop_iterable(receiver) {
+                    ast::ForIterable::Range(range) => ast::ForIterable::Range(ast::RangeExpr {
+                        reversed: !range.reversed,
+                        ..range
+                    }),
+                    ast::ForIterable::Collection(collection) => {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            expr,
+                            "reversed iteration is only supported for ranges",
+                        );
+                        ast::ForIterable::Collection(collection)
+                    }
+                },
+                "iter" | "into_iter" => ast::ForIterable::Collection(self.graft_expr(receiver)),
+                _ => ast::ForIterable::Collection(self.graft_expr(expr)),
+            },
+            _ => ast::ForIterable::Collection(self.graft_expr(expr)),
+        }
+    }
+
+    /// Handle the pattern of a match arm
+    fn graft_match_condition(&mut self, pat: &syn::Pat) -> ast::MatchCondition<Annotation> {
+        match pat {
+            syn::Pat::Ident(syn::PatIdent {
+                mutability: Some(_),
+                subpat: None,
+                ..
+            }) => ast::MatchCondition::Pattern(Box::new(self.graft_pattern(pat))),
+            syn::Pat::Ident(ident) => {
+                // A single identifier is either a variant of an enum in prelude, like `None`,
+                // or a binding. The type checker tells the two apart.
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&ident.ident.to_string(), pat);
+                ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+                    type_name,
+                    variant_name,
+                    data_bindings: vec![],
+                    named_data_bindings: vec![],
+                })
+            }
+            syn::Pat::Path(syn::PatPath { path, .. }) => {
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&Graft::path_to_ident(path), pat);
+                ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+                    type_name,
+                    variant_name,
+                    data_bindings: vec![],
+                    named_data_bindings: vec![],
+                })
+            }
+            syn::Pat::TupleStruct(_) | syn::Pat::Struct(_)
+                if !Self::is_flat_enum_variant_pattern(pat) =>
+            {
+                ast::MatchCondition::Pattern(Box::new(self.graft_pattern(pat)))
+            }
+            syn::Pat::TupleStruct(syn::PatTupleStruct { pat, path, .. }) => {
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&Graft::path_to_ident(path), path);
+
+                let data_bindings = pat
+                    .elems
+                    .iter()
+                    .filter_map(|pat_elem| match pat_elem {
+                        syn::Pat::Ident(ident) => Some(ast::PatternMatchedBinding {
+                            mutable: ident.mutability.is_some(),
+                            name: ident.ident.to_string(),
+                        }),
+                        _ => None,
+                    })
+                    .collect();
+
+                ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+                    type_name,
+                    variant_name,
+                    data_bindings,
+                    named_data_bindings: vec![],
+                })
+            }
+            syn::Pat::Struct(syn::PatStruct { path, fields, .. }) => {
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&Graft::path_to_ident(path), path);
+
+                let mut named_data_bindings = vec![];
+                for syn::FieldPat { member, pat, .. } in fields.iter() {
+                    let syn::Member::Named(field_name) = member else {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            member,
+                            "fields must be named in struct patterns",
+                        );
+                        continue;
+                    };
+                    if let syn::Pat::Ident(ident) = pat.as_ref() {
+                        named_data_bindings.push((
+                            field_name.to_string(),
+                            ast::PatternMatchedBinding {
+                                mutable: ident.mutability.is_some(),
+                                name: ident.ident.to_string(),
+                            },
+                        ));
+                    }
+                }
+
+                ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+                    type_name,
+                    variant_name,
+                    data_bindings: vec![],
+                    named_data_bindings,
+                })
+            }
+            syn::Pat::Wild(_) => ast::MatchCondition::CatchAll,
+            _ => ast::MatchCondition::Pattern(Box::new(self.graft_pattern(pat))),
+        }
+    }
+
+    /// Return true if the data of an enum variant in a pattern is only bound to identifiers,
+    /// like in `Foo::Bar(a, b)` and `Foo::Baz { a, b: c, .. }`, or ignored as a whole, like in
+    /// `Foo::Bar(_)`. Other patterns on enum variants, like `Some(Ok(x))`, are nested patterns,
+    /// which the type checker lowers.
+    fn is_flat_enum_variant_pattern(pat: &syn::Pat) -> bool {
+        // Identifiers starting with an uppercase letter are variants, like `None`, not bindings
+        let is_binding = |pat: &syn::Pat| match pat {
+            syn::Pat::Ident(syn::PatIdent {
+                ident,
+                subpat: None,
+                ..
+            }) => !ident.to_string().starts_with(char::is_uppercase),
+            _ => false,
+        };
+        let is_wildcard = |pat: &syn::Pat| matches!(pat, syn::Pat::Wild(_));
+
+        match pat {
+            syn::Pat::TupleStruct(syn::PatTupleStruct { pat, .. }) => match pat.elems.len() {
+                1 => is_binding(&pat.elems[0]) || is_wildcard(&pat.elems[0]),
+                _ => pat.elems.iter().all(is_binding),
+            },
+            syn::Pat::Struct(syn::PatStruct { fields, .. }) => fields.iter().all(|field| {
+                !matches!(field.member, syn::Member::Named(_))
+                    || is_binding(&field.pat)
+                    || is_wildcard(&field.pat)
+            }),
+            _ => true,
+        }
+    }
+
+    /// Split the name of an enum variant in a match arm into the name of the type and the
+    /// name of the variant. Enums that are in prelude can be matched with only the variant
+    /// name, like `None` instead of `Option::None`.
+    fn graft_enum_variant_name(
+        &mut self,
+        enum_case: &str,
+        spanned: &impl Spanned,
+    ) -> (Option<String>, String) {
+        let enum_case_split = enum_case.split("::").collect_vec();
+        match enum_case_split.len() {
+            1 => (None, enum_case_split[0].to_owned()),
+            2 => (
+                Some(enum_case_split[0].to_owned()),
+                enum_case_split[1].to_owned(),
             ),
+            _ => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    spanned,
+                    format!(
+                        "expected `<Type>::<VariantName>` or `<VariantName>` for enum match \
+                         case, got `{enum_case}`"
+                    ),
+                );
+                (None, enum_case.to_owned())
+            }
+        }
+    }
+
+    /// Handle a pattern that the type checker lowers, like a pattern on a value that is not an
+    /// enum or a nested pattern on an enum
+    fn graft_pattern(&mut self, pat: &syn::Pat) -> ast::Pattern<Annotation> {
+        match pat {
+            syn::Pat::Wild(_) => ast::Pattern::Wildcard,
+            syn::Pat::Ident(syn::PatIdent {
+                ident,
+                mutability,
+                by_ref: None,
+                subpat: None,
+                ..
+            }) => ast::Pattern::Binding(ast::PatternMatchedBinding {
+                name: ident.to_string(),
+                mutable: mutability.is_some(),
+            }),
+            syn::Pat::Lit(syn::PatLit { expr, .. }) => ast::Pattern::Literal(self.graft_expr(expr)),
+            syn::Pat::Range(syn::PatRange { lo, limits, hi, .. }) => {
+                ast::Pattern::Range(ast::RangePattern {
+                    start: self.graft_expr(lo),
+                    end: self.graft_expr(hi),
+                    inclusive: matches!(limits, syn::RangeLimits::Closed(_)),
+                })
+            }
+            syn::Pat::Or(syn::PatOr { cases, .. }) => {
+                ast::Pattern::Or(cases.iter().map(|case| self.graft_pattern(case)).collect())
+            }
+            syn::Pat::Tuple(syn::PatTuple { elems, .. }) => {
+                ast::Pattern::Tuple(elems.iter().map(|elem| self.graft_pattern(elem)).collect())
+            }
+            syn::Pat::Path(syn::PatPath { path, .. }) => {
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&Graft::path_to_ident(path), path);
+                ast::Pattern::EnumVariant(ast::EnumVariantPattern {
+                    type_name,
+                    variant_name,
+                    fields: vec![],
+                    named_fields: vec![],
+                })
+            }
+            syn::Pat::TupleStruct(syn::PatTupleStruct { path, pat, .. }) => {
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&Graft::path_to_ident(path), path);
+                ast::Pattern::EnumVariant(ast::EnumVariantPattern {
+                    type_name,
+                    variant_name,
+                    fields: pat
+                        .elems
+                        .iter()
+                        .map(|elem| self.graft_pattern(elem))
+                        .collect(),
+                    named_fields: vec![],
+                })
+            }
+            syn::Pat::Struct(syn::PatStruct { path, fields, .. }) => {
+                let (type_name, variant_name) =
+                    self.graft_enum_variant_name(&Graft::path_to_ident(path), path);
+                let mut named_fields = vec![];
+                for syn::FieldPat { member, pat, .. } in fields.iter() {
+                    let syn::Member::Named(field_name) = member else {
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            member,
+                            "fields must be named in struct patterns",
+                        );
+                        continue;
+                    };
+                    named_fields.push((field_name.to_string(), self.graft_pattern(pat)));
+                }
+                ast::Pattern::EnumVariant(ast::EnumVariantPattern {
+                    type_name,
+                    variant_name,
+                    fields: vec![],
+                    named_fields,
+                })
+            }
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "this kind of pattern is not supported in match arms",
+                );
+                ast::Pattern::Wildcard
+            }
         }
     }
 
@@ -1218,6 +2922,8 @@ impl<'a> Graft<'a> {
 
                 match ast_fn_call {
                     ast::Expr::FnCall(fncall) => Stmt::FnCall(fncall),
+                    // The call could not be grafted, and an error has been reported
+                    ast::Expr::Tuple(_) => Stmt::Panic(ast::PanicStmt),
                     _ => panic!("function call as a statement cannot be a literal"),
                 }
             }
@@ -1232,11 +2938,12 @@ impl<'a> Graft<'a> {
                 let left_ident = match left_expr {
                     ast::Expr::Var(ident) => ident,
                     _ => {
-                        panic!(
-                            "Left-hand-side of tuple operator must be a declared variable. \
-                                 Declare more bindings if needed. Failed to parse expression: \
-                                 {left_expr} as an identifier"
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            left,
+                            "left-hand side of assignment must be a declared variable",
                         );
+                        return Stmt::Panic(ast::PanicStmt);
                     }
                 };
                 let right_expr = self.graft_expr(right);
@@ -1258,11 +2965,12 @@ impl<'a> Graft<'a> {
                 let identifier = match identifier_expr {
                     ast::Expr::Var(ident) => ident,
                     _ => {
-                        panic!(
-                            "Left-hand-side of tuple operator must be a declared variable. \
-                                 Declare more bindings if needed. Failed to parse expression: \
-                                 {identifier_expr} as an identifier"
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            left,
+                            "left-hand side of assignment must be a declared variable",
                         );
+                        return Stmt::Panic(ast::PanicStmt);
                     }
                 };
                 let assign_expr = self.graft_binop_eq_expr(left, op, right);
@@ -1281,6 +2989,19 @@ impl<'a> Graft<'a> {
                 }
             }
             syn::Expr::Macro(expr_macro) => self.graft_expr_macro(expr_macro),
+            syn::Expr::Break(syn::ExprBreak { label, expr, .. }) => {
+                if let Some(expr) = expr {
+                    self.report(
+                        ErrorCode::UnsupportedSyntax,
+                        expr,
+                        "`break` with a value is not supported",
+                    );
+                }
+                Stmt::Break(label.as_ref().map(|label| label.ident.to_string()))
+            }
+            syn::Expr::Continue(syn::ExprContinue { label, .. }) => {
+                Stmt::Continue(label.as_ref().map(|label| label.ident.to_string()))
+            }
             syn::Expr::Match(syn::ExprMatch {
                 attrs: _,
                 match_token: _,
@@ -1294,7 +3015,7 @@ impl<'a> Graft<'a> {
                     let syn::Arm {
                         attrs: _,
                         pat,
-                        guard: _,
+                        guard,
                         fat_arrow_token: _,
                         body,
                         comma: _,
@@ -1305,120 +3026,19 @@ impl<'a> Graft<'a> {
                     let arm_body = if let Stmt::Block(block_stmt) = arm_body {
                         block_stmt
                     } else {
-                        panic!("Expected block statement for match-arm's body")
+                        self.report(
+                            ErrorCode::UnsupportedSyntax,
+                            body,
+                            "the body of a match arm must be a block",
+                        );
+                        ast::BlockStmt { stmts: vec![] }
                     };
 
-                    let match_condition = match pat {
-                        syn::Pat::Box(_) => todo!(),
-                        syn::Pat::Lit(_) => todo!(),
-                        syn::Pat::Macro(_) => todo!(),
-                        syn::Pat::Or(_) => todo!(),
-                        syn::Pat::Ident(ident) => {
-                            // Enums that are in prelude can be matched with only the variant
-                            // name, like `None` instead of `Result::None`
-                            let enum_case = ident.ident.to_string();
-                            let enum_case_split = enum_case.split("::").collect_vec();
-                            let (type_name, variant_name) = match enum_case_split.len() {
-                                1 => (None, enum_case_split[0].to_owned()),
-                                2 => (
-                                    Some(enum_case_split[0].to_owned()),
-                                    enum_case_split[1].to_owned(),
-                                ),
-                                _ => panic!(
-                                    "Expected `<Type>::<VariantName>` or `VariantName` \
-                                         for enum match case. Got: `{enum_case}`"
-                                ),
-                            };
-
-                            ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
-                                data_bindings: Vec::default(),
-                                type_name,
-                                variant_name,
-                            })
-                        }
-                        syn::Pat::Path(syn::PatPath { path, .. }) => {
-                            // Enums that are in prelude can be matched with only the variant
-                            // name, like `None` instead of `Result::None`
-                            let enum_case = Graft::path_to_ident(path);
-                            let enum_case_split = enum_case.split("::").collect_vec();
-                            let (type_name, variant_name) = match enum_case_split.len() {
-                                1 => (None, enum_case_split[0].to_owned()),
-                                2 => (
-                                    Some(enum_case_split[0].to_owned()),
-                                    enum_case_split[1].to_owned(),
-                                ),
-                                _ => panic!(
-                                    "Expected `<Type>::<VariantName>` or `VariantName`\
-                                         for enum match case. Got: `{enum_case}`"
-                                ),
-                            };
-
-                            ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
-                                data_bindings: Vec::default(),
-                                type_name,
-                                variant_name,
-                            })
-                        }
-                        syn::Pat::Range(_) => todo!(),
-                        syn::Pat::Reference(_) => todo!(),
-                        syn::Pat::Rest(_) => todo!(),
-                        syn::Pat::Slice(_) => todo!(),
-                        syn::Pat::Struct(_) => todo!(),
-                        syn::Pat::Tuple(_) => todo!(),
-                        syn::Pat::TupleStruct(syn::PatTupleStruct { pat, path, .. }) => {
-                            // Enums that are in prelude can be matched with only the variant
-                            // name, like `None` instead of `Result::None`
-                            let enum_case = Graft::path_to_ident(path);
-                            let enum_case_split = enum_case.split("::").collect_vec();
-                            let (type_name, variant_name) = match enum_case_split.len() {
-                                1 => (None, enum_case_split[0].to_owned()),
-                                2 => (
-                                    Some(enum_case_split[0].to_owned()),
-                                    enum_case_split[1].to_owned(),
-                                ),
-                                _ => panic!(
-                                    "Expected `<Type>::<VariantName>` or `<VariantName>` \
-                                         for enum match case. Got: `{enum_case}`"
-                                ),
-                            };
-
-                            let mut data_bindings = vec![];
-                            for pat_elem in pat.elems.iter() {
-                                match pat_elem {
-                                    syn::Pat::Ident(ident) => {
-                                        data_bindings.push(ast::PatternMatchedBinding {
-                                            mutable: ident.mutability.is_some(),
-                                            name: ident.ident.to_string(),
-                                        });
-                                    }
-                                    syn::Pat::Wild(_) => {
-                                        assert!(
-                                            pat.elems.len().is_one(),
-                                            "For now, wildcard binding must be only binding"
-                                        );
-                                    }
-                                    other => {
-                                        panic!("unsupported binding for match-arm: {other:?}")
-                                    }
-                                }
-                            }
-
-                            ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
-                                type_name,
-                                variant_name,
-                                data_bindings,
-                            })
-                        }
-                        syn::Pat::Type(_) => todo!(),
-                        syn::Pat::Verbatim(_) => todo!(),
-                        syn::Pat::Wild(syn::PatWild {
-                            attrs: _,
-                            underscore_token: _,
-                        }) => ast::MatchCondition::CatchAll,
-                        _ => todo!(),
-                    };
+                    let match_condition = self.graft_match_condition(pat);
+                    let guard = guard.as_ref().map(|(_, guard)| self.graft_expr(guard));
                     match_arms.push(ast::MatchArm {
                         match_condition,
+                        guard,
                         body: arm_body,
                     });
                 }
@@ -1428,7 +3048,14 @@ impl<'a> Graft<'a> {
                     match_expression,
                 })
             }
-            other => panic!("unsupported: {other:#?}"),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "this kind of statement is not supported",
+                );
+                Stmt::Panic(ast::PanicStmt)
+            }
         }
     }
 
@@ -1437,7 +3064,14 @@ impl<'a> Graft<'a> {
         match ident.as_str() {
             "panic" => self.graft_panic_macro(),
             "assert" => self.graft_assert_macro(expr_macro),
-            _ => panic!("unsupported macro: {ident}"),
+            _ => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    &expr_macro.mac.path,
+                    format!("unsupported macro `{ident}!`"),
+                );
+                Stmt::Panic(ast::PanicStmt)
+            }
         }
     }
 
@@ -1460,7 +3094,14 @@ impl<'a> Graft<'a> {
     fn graft_item_stmt(&mut self, item: &syn::Item) -> Stmt<Annotation> {
         match item {
             syn::Item::Fn(item_fn) => Stmt::FnDeclaration(self.graft_fn_decl(item_fn)),
-            other => panic!("unsupported: {other:#?}"),
+            other => {
+                self.report(
+                    ErrorCode::UnsupportedSyntax,
+                    other,
+                    "only functions can be declared inside of functions",
+                );
+                Stmt::Panic(ast::PanicStmt)
+            }
         }
     }
 }
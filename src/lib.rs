@@ -1,17 +1,22 @@
 use std::collections::HashMap;
-use std::fs;
+use std::collections::HashSet;
 
 use itertools::Itertools;
 use syn::ImplItemMethod;
 use syn::Item;
 use syn::ItemEnum;
+use syn::ItemFn;
 use syn::ItemImpl;
 use syn::ItemStruct;
-use syn::UseTree;
+use syn::ItemTrait;
 use triton_vm::instruction::LabelledInstruction;
 
 use ast_types::ListType;
+use compile_error::CompileError;
+use compile_error::Diagnostics;
+use compile_error::ErrorCode;
 use graft::CustomTypeRust;
+use module_resolution::Module;
 
 use crate::custom_type_resolver::resolve_custom_types;
 use crate::graft::Graft;
@@ -21,12 +26,14 @@ use crate::type_checker::annotate_fn_outer;
 pub mod ast;
 pub mod ast_types;
 pub mod cfg;
+pub mod compile_error;
 mod compiled_tasm;
 #[macro_use]
 pub mod graft;
 mod composite_types;
 pub mod custom_type_resolver;
 pub mod libraries;
+mod module_resolution;
 pub mod ssa;
 mod subroutine;
 pub mod tasm_code_generator;
@@ -40,60 +47,243 @@ pub(crate) type StructsAndMethodsRustAst = HashMap<String, (CustomTypeRust, Vec<
 /// Like [`StructsAndMethodsRustAst`] but with an Option type for the type declaration.
 type MaybeStructsAndMethodsRustAst = HashMap<String, (Option<CustomTypeRust>, Vec<ImplItemMethod>)>;
 
-pub(crate) fn extract_types_and_function(
-    parsed_file: &syn::File,
-) -> (StructsAndMethodsRustAst, Vec<String>) {
+/// Extract the custom types and their methods, and the free functions from the modules of a
+/// program. Types may have methods declared in other modules than the type itself.
+fn extract_types_and_functions_from_modules(
+    modules: &[Module],
+) -> Result<(StructsAndMethodsRustAst, Vec<ItemFn>), Diagnostics> {
     get_standard_setup!(ListType::Unsafe, graft_config, _lib);
     let mut types: MaybeStructsAndMethodsRustAst = HashMap::default();
-    let mut dependencies = vec![];
+    let mut traits = HashMap::default();
+    let mut trait_impls = vec![];
+    let mut free_functions = vec![];
 
-    for item in &parsed_file.items {
-        handle_item(&mut graft_config, &mut types, &mut dependencies, item);
+    for module in modules {
+        for item in &module.file.items {
+            match item {
+                Item::Trait(item_trait) => {
+                    _ = traits.insert(item_trait.ident.to_string(), item_trait)
+                }
+                Item::Impl(item_impl) if item_impl.trait_.is_some() => {
+                    trait_impls.push((item_impl, &module.file_path))
+                }
+                _ => handle_item(&mut graft_config, &mut types, &mut free_functions, item),
+            }
+        }
+    }
+
+    // Trait implementations are handled once all traits are known, as a trait may be declared
+    // after its implementations
+    for (item_impl, file_path) in trait_impls {
+        graft_config.file_path = Some(file_path.to_owned());
+        extract_methods_from_trait_impl_block(&mut graft_config, &mut types, &traits, item_impl);
     }
+    graft_config.take_diagnostics()?;
 
     let types = unwrap_custom_rust_type(types);
-    (types, dependencies)
+    Ok((types, free_functions))
 }
 
 fn handle_item(
     graft_config: &mut Graft,
     types: &mut MaybeStructsAndMethodsRustAst,
-    dependencies: &mut Vec<String>,
+    free_functions: &mut Vec<ItemFn>,
     item: &Item,
 ) {
     match item {
+        Item::Fn(item_fn) if !is_test_function(item_fn) => free_functions.push(item_fn.to_owned()),
         Item::Impl(item_impl) => extract_methods_from_impl_block(graft_config, types, item_impl),
         Item::Struct(item_struct) => add_struct_declaration(types, item_struct),
         Item::Enum(item_enum) => add_custom_enum_declaration(types, item_enum),
-        Item::Use(syn::ItemUse { tree, .. }) => {
-            maybe_add_dependency_super_module_from_use_tree(dependencies, tree)
-        }
-
         _ => (),
     }
 }
 
+fn is_test_function(item_fn: &ItemFn) -> bool {
+    item_fn
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("test") || attr.path.is_ident("proptest"))
+}
+
 fn extract_methods_from_impl_block(
     graft_config: &mut Graft,
     types: &mut MaybeStructsAndMethodsRustAst,
     item_impl: &ItemImpl,
 ) {
-    let data_type = graft_config.syn_type_to_ast_type(&item_impl.self_ty);
-    let type_name = data_type.to_string();
+    // The type parameters of the `impl` block of a generic type are only known once the type
+    // is instantiated
+    let type_name = match item_impl.self_ty.as_ref() {
+        syn::Type::Path(type_path) if !item_impl.generics.params.is_empty() => {
+            Graft::path_to_ident(&type_path.path)
+        }
+        self_ty => graft_config.syn_type_to_ast_type(self_ty).to_string(),
+    };
 
     for impl_item in &item_impl.items {
         let syn::ImplItem::Method(struct_method) = impl_item else {
             continue;
         };
 
+        // Methods are identified by their names only, so the methods of two implementations
+        // of a trait with different type arguments, like `Add<A>` and `Add<B>`, would clash
+        let method_name = &struct_method.sig.ident;
         let custom_type_entry = types.get_mut(&type_name);
         match custom_type_entry {
+            Some(value)
+                if value
+                    .1
+                    .iter()
+                    .any(|method| method.sig.ident == *method_name) =>
+            {
+                graft_config.report(
+                    ErrorCode::DuplicateDefinition,
+                    method_name,
+                    format!("duplicate definitions with name `{method_name}` for `{type_name}`"),
+                );
+            }
             Some(value) => value.1.push(struct_method.to_owned()),
             None => _ = types.insert(type_name.clone(), (None, vec![struct_method.to_owned()])),
         };
     }
 }
 
+/// Extract the methods of an `impl Trait for Type` block along with the default methods of
+/// the trait that the block does not override. The methods become methods of the type, so
+/// calls to them are resolved statically. Of the traits that are not declared in the program,
+/// only the operator traits the language can overload are supported.
+fn extract_methods_from_trait_impl_block(
+    graft_config: &mut Graft,
+    types: &mut MaybeStructsAndMethodsRustAst,
+    traits: &HashMap<String, &ItemTrait>,
+    item_impl: &ItemImpl,
+) {
+    let (_, trait_path, _) = item_impl.trait_.as_ref().unwrap();
+    let trait_name = trait_path.segments.last().unwrap().ident.to_string();
+    let Some(item_trait) = traits.get(&trait_name) else {
+        if !is_operator_trait(&Graft::path_to_ident(trait_path)) {
+            graft_config.report(
+                ErrorCode::UnsupportedSyntax,
+                trait_path,
+                format!(
+                    "trait `{trait_name}` is neither declared in the program nor an operator trait"
+                ),
+            );
+            return;
+        }
+        let item_impl = resolve_operator_trait_impl(&trait_name, item_impl);
+        extract_methods_from_impl_block(graft_config, types, &item_impl);
+        return;
+    };
+
+    let mut item_impl = item_impl.to_owned();
+    let implemented_methods = item_impl
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            syn::ImplItem::Method(method) => Some(method.sig.ident.clone()),
+            _ => None,
+        })
+        .collect_vec();
+    for trait_item in &item_trait.items {
+        let syn::TraitItem::Method(trait_method) = trait_item else {
+            continue;
+        };
+        if implemented_methods.contains(&trait_method.sig.ident) {
+            continue;
+        }
+        let Some(default_body) = &trait_method.default else {
+            graft_config.report(
+                ErrorCode::TypeError,
+                trait_path,
+                format!(
+                    "not all trait items implemented, missing: `{}` of trait `{trait_name}`",
+                    trait_method.sig.ident
+                ),
+            );
+            continue;
+        };
+        item_impl.items.push(syn::ImplItem::Method(ImplItemMethod {
+            attrs: trait_method.attrs.clone(),
+            vis: syn::Visibility::Inherited,
+            defaultness: None,
+            sig: trait_method.sig.clone(),
+            block: default_body.clone(),
+        }));
+    }
+
+    extract_methods_from_impl_block(graft_config, types, &item_impl);
+}
+
+/// The operator traits that custom types can implement, along with the module of the
+/// standard library declaring them
+const OPERATOR_TRAITS: [(&str, &str); 5] = [
+    ("ops", "Add"),
+    ("ops", "Sub"),
+    ("ops", "Mul"),
+    ("ops", "Neg"),
+    ("cmp", "PartialEq"),
+];
+
+/// Return true if the path, like `Add` or `std::ops::Add`, names an operator trait that
+/// custom types can implement
+fn is_operator_trait(trait_path: &str) -> bool {
+    OPERATOR_TRAITS.iter().any(|(module, name)| {
+        trait_path == *name
+            || trait_path == format!("std::{module}::{name}")
+            || trait_path == format!("core::{module}::{name}")
+    })
+}
+
+/// Prepare an implementation of an operator trait like `Add` or `PartialEq` such that its
+/// methods can be called for the operators of the language. `Self::Output` is replaced by the
+/// type the block declares as `Output`. The operands of `==` and `!=` are passed by value, so
+/// the reference parameters of the methods of `PartialEq` become value parameters.
+fn resolve_operator_trait_impl(trait_name: &str, item_impl: &ItemImpl) -> ItemImpl {
+    let mut item_impl = item_impl.to_owned();
+    let output_type = item_impl
+        .items
+        .iter()
+        .find_map(|impl_item| match impl_item {
+            syn::ImplItem::Type(impl_type) if impl_type.ident == "Output" => {
+                Some(impl_type.ty.clone())
+            }
+            _ => None,
+        });
+
+    for impl_item in item_impl.items.iter_mut() {
+        let syn::ImplItem::Method(method) = impl_item else {
+            continue;
+        };
+
+        if let (syn::ReturnType::Type(_, return_type), Some(output_type)) =
+            (&mut method.sig.output, &output_type)
+        {
+            if matches!(return_type.as_ref(), syn::Type::Path(type_path)
+                if Graft::path_to_ident(&type_path.path) == "Self::Output")
+            {
+                **return_type = output_type.clone();
+            }
+        }
+
+        if trait_name != "PartialEq" {
+            continue;
+        }
+        for input in method.sig.inputs.iter_mut() {
+            match input {
+                syn::FnArg::Receiver(receiver) => receiver.reference = None,
+                syn::FnArg::Typed(pat_type) => {
+                    if let syn::Type::Reference(reference) = pat_type.ty.as_ref() {
+                        pat_type.ty = reference.elem.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    item_impl
+}
+
 fn add_struct_declaration(
     custom_types: &mut MaybeStructsAndMethodsRustAst,
     item_struct: &ItemStruct,
@@ -118,25 +308,6 @@ fn add_custom_enum_declaration(
     };
 }
 
-/// Handle imports of the form `use super::<module>::*;`
-fn maybe_add_dependency_super_module_from_use_tree(dependencies: &mut Vec<String>, tree: &UseTree) {
-    let UseTree::Path(use_path) = tree else {
-        return;
-    };
-    if use_path.ident != "super" {
-        return;
-    };
-    let UseTree::Path(use_path) = use_path.tree.as_ref() else {
-        return;
-    };
-    let UseTree::Glob(_) = *use_path.tree else {
-        return;
-    };
-
-    let module_name = use_path.ident.to_string();
-    dependencies.push(module_name);
-}
-
 /// After parsing all code, each method must have a struct. Then, we can unwrap the Option type.
 fn unwrap_custom_rust_type(
     custom_types: MaybeStructsAndMethodsRustAst,
@@ -152,58 +323,206 @@ fn unwrap_custom_rust_type(
         .collect()
 }
 
-/// Limitation: this function cannot handle imports yet.
-fn parse_function_and_types(file_path: &str) -> (syn::ItemFn, StructsAndMethodsRustAst) {
-    let content = fs::read_to_string(file_path).expect("Unable to read file {path}");
-    let parsed_file: syn::File = syn::parse_str(&content).expect("Unable to parse rust code");
-    let entrypoint = extract_entrypoint(&parsed_file, "main");
-    let (custom_types, dependencies) = extract_types_and_function(&parsed_file);
+/// Map the names of the free functions, `static` items, custom types, methods, and associated
+/// functions of a program to the files in which they are declared. Methods and associated
+/// functions are named `<Type>::<name>`.
+fn declaration_files(modules: &[Module]) -> HashMap<String, String> {
+    let mut declaration_files = HashMap::default();
+    for module in modules {
+        for item in &module.file.items {
+            let names = match item {
+                Item::Fn(item_fn) => vec![item_fn.sig.ident.to_string()],
+                Item::Struct(item_struct) => vec![item_struct.ident.to_string()],
+                Item::Enum(item_enum) => vec![item_enum.ident.to_string()],
+                Item::Static(item_static) => vec![item_static.ident.to_string()],
+                Item::Impl(ItemImpl { self_ty, items, .. }) => {
+                    let syn::Type::Path(type_path) = self_ty.as_ref() else {
+                        continue;
+                    };
+                    let type_name = Graft::path_to_ident(&type_path.path);
+                    items
+                        .iter()
+                        .filter_map(|impl_item| match impl_item {
+                            syn::ImplItem::Method(method) => {
+                                Some(format!("{type_name}::{}", method.sig.ident))
+                            }
+                            _ => None,
+                        })
+                        .collect()
+                }
+                _ => vec![],
+            };
+            for name in names {
+                declaration_files.insert(name, module.file_path.clone());
+            }
+        }
+    }
+
+    declaration_files
+}
+
+/// Map the traits declared in a program, and the traits implemented by its `impl Trait for
+/// Type` blocks, to the names of the types implementing them
+fn trait_implementations(modules: &[Module]) -> HashMap<String, Vec<String>> {
+    let mut trait_implementations: HashMap<String, Vec<String>> = HashMap::default();
+    for item in modules.iter().flat_map(|module| &module.file.items) {
+        match item {
+            Item::Trait(item_trait) => {
+                trait_implementations
+                    .entry(item_trait.ident.to_string())
+                    .or_default();
+            }
+            Item::Impl(ItemImpl {
+                trait_: Some((_, trait_path, _)),
+                self_ty,
+                ..
+            }) => {
+                let syn::Type::Path(type_path) = self_ty.as_ref() else {
+                    continue;
+                };
+                let trait_name = trait_path.segments.last().unwrap().ident.to_string();
+                trait_implementations
+                    .entry(trait_name)
+                    .or_default()
+                    .push(Graft::path_to_ident(&type_path.path));
+            }
+            _ => (),
+        }
+    }
+
+    trait_implementations
+}
 
-    assert!(
-        dependencies.is_empty(),
-        "Cannot handle dependencies here yet. See the OZK testing framework for a solution."
-    );
+/// Parse the program whose root module is the file at `file_path`. The entrypoint must be
+/// declared in the root module, or in one of its inline modules, like `test::foo`. Free
+/// functions, custom types, methods, and associated functions are collected from all modules
+/// of the program.
+fn parse_function_and_types(
+    modules: &[Module],
+    entrypoint: &str,
+) -> Result<(ItemFn, Vec<ItemFn>, StructsAndMethodsRustAst), Diagnostics> {
+    let entrypoint = extract_entrypoint(&modules[0], entrypoint)?;
+    let (custom_types, free_functions) = extract_types_and_functions_from_modules(modules)?;
 
-    (entrypoint, custom_types)
+    Ok((entrypoint, free_functions, custom_types))
 }
 
-pub(crate) fn compile_to_instructions(
+/// Compile the function `entrypoint` found in the file at `file_path`, along with the
+/// free functions, custom types, methods, and associated functions declared in the
+/// modules of the program. Modules are resolved through `mod foo;` declarations and
+/// through imports like `use crate::foo::Bar;` and `use super::*;`.
+///
+/// All errors found in the program are returned as [`Diagnostics`], which can be rendered
+/// like `rustc` renders its errors.
+pub fn compile_to_instructions(
     file_path: &str,
+    entrypoint: &str,
     list_type: ListType,
-) -> Vec<LabelledInstruction> {
+) -> Result<Vec<LabelledInstruction>, Diagnostics> {
     get_standard_setup!(list_type, graft_config, libraries);
 
-    let (rust_main_ast, rust_struct_asts) = parse_function_and_types(file_path);
+    let modules = module_resolution::parse_modules(file_path, entrypoint)?;
+    check_unique_declarations(&modules)?;
+    let (rust_main_ast, rust_free_function_asts, rust_struct_asts) =
+        parse_function_and_types(&modules, entrypoint)?;
 
-    let mut oil_ast = graft_config.graft_fn_decl(&rust_main_ast);
+    graft_config.file_path = Some(file_path.to_owned());
+    graft_config.source_files = declaration_files(&modules);
+    graft_config.trait_implementations = trait_implementations(&modules);
+    graft_config
+        .declare_constants_and_statics(modules.iter().flat_map(|module| &module.file.items));
     let mut composite_types =
         graft_config.graft_custom_types_methods_and_associated_functions(rust_struct_asts);
+    let mut oil_ast =
+        graft_config.graft_fn_decl_with_free_functions(&rust_main_ast, &rust_free_function_asts);
+    graft_config.take_diagnostics()?;
     composite_types.checked_merge(graft_config.imported_custom_types);
 
     resolve_custom_types(&mut oil_ast, &mut composite_types);
-    annotate_fn_outer(&mut oil_ast, &mut composite_types, &libraries);
+    annotate_fn_outer(&mut oil_ast, &mut composite_types, &libraries)
+        .map_err(|diagnostics| diagnostics.locate_in_functions(&graft_config.function_locations))?;
 
     let tasm = compile_function(&oil_ast, &libraries, &composite_types);
     // TODO: Add optimizations step here
 
-    tasm.compose()
+    Ok(tasm.compose())
 }
 
-pub fn compile_to_string(file_path: &str, list_type: ListType) -> String {
-    compile_to_instructions(file_path, list_type)
-        .into_iter()
-        .join("\n")
+pub fn compile_to_string(
+    file_path: &str,
+    entrypoint: &str,
+    list_type: ListType,
+) -> Result<String, Diagnostics> {
+    let instructions = compile_to_instructions(file_path, entrypoint, list_type)?;
+    Ok(instructions.into_iter().join("\n"))
 }
 
-fn extract_entrypoint(parsed_file: &syn::File, entrypoint: &str) -> syn::ItemFn {
-    for item in &parsed_file.items {
-        let Item::Fn(func) = item else {
-            continue;
-        };
-        if func.sig.ident == entrypoint {
-            return func.to_owned();
+/// Find the entrypoint in the root module. An entrypoint like `test::foo` is found in the
+/// inline module `test` of the root module.
+fn extract_entrypoint(root_module: &Module, entrypoint: &str) -> Result<ItemFn, CompileError> {
+    let missing_entrypoint = || {
+        CompileError::new(
+            ErrorCode::MissingEntrypoint,
+            format!(
+                "cannot find entrypoint `{entrypoint}` in `{}`",
+                root_module.file_path
+            ),
+        )
+    };
+
+    let mut items = &root_module.file.items;
+    for module_name in module_resolution::entrypoint_module_path(entrypoint) {
+        items = items
+            .iter()
+            .find_map(|item| match item {
+                Item::Mod(syn::ItemMod {
+                    ident,
+                    content: Some((_, module_items)),
+                    ..
+                }) if *ident == module_name => Some(module_items),
+                _ => None,
+            })
+            .ok_or_else(missing_entrypoint)?;
+    }
+
+    let function_name = entrypoint.rsplit("::").next().unwrap_or(entrypoint);
+    items
+        .iter()
+        .find_map(|item| match item {
+            Item::Fn(func) if func.sig.ident == function_name => Some(func.to_owned()),
+            _ => None,
+        })
+        .ok_or_else(missing_entrypoint)
+}
+
+/// Report the custom types, traits, constants, and `static` items that are declared in more
+/// than one module of a program. The modules of a program share one namespace, in which such
+/// items would silently replace each other. Free functions are checked when grafted.
+fn check_unique_declarations(modules: &[Module]) -> Result<(), Diagnostics> {
+    let mut type_names = HashSet::new();
+    let mut value_names = HashSet::new();
+    let mut errors = vec![];
+    for module in modules {
+        for item in &module.file.items {
+            let (names, ident) = match item {
+                Item::Struct(item_struct) => (&mut type_names, &item_struct.ident),
+                Item::Enum(item_enum) => (&mut type_names, &item_enum.ident),
+                Item::Trait(item_trait) => (&mut type_names, &item_trait.ident),
+                Item::Const(item_const) => (&mut value_names, &item_const.ident),
+                Item::Static(item_static) => (&mut value_names, &item_static.ident),
+                _ => continue,
+            };
+            if !names.insert(ident.to_string()) {
+                let error = CompileError::new(
+                    ErrorCode::DuplicateDefinition,
+                    format!("the name `{ident}` is defined multiple times"),
+                )
+                .with_span(ident.span())
+                .in_file(Some(&module.file_path));
+                errors.push(error);
+            }
         }
     }
 
-    panic!("Failed to locate entrypoint {entrypoint}");
+    Diagnostics::check(errors)
 }
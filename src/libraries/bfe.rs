@@ -261,7 +261,7 @@ fn graft_bfe_primitive_root(
             ast::Expr::FnCall(ast::FnCall {
                 name: FUNCTION_ROOT_FULL_NAME.to_string(),
                 args: vec![init_arg.to_owned()],
-                type_parameter: None,
+                type_arguments: vec![],
                 arg_evaluation_order: Default::default(),
                 annot: Default::default(),
             })
@@ -308,7 +308,7 @@ fn graft_bfe_new(
                 ast::Expr::FnCall(ast::FnCall {
                     name: "BFieldElement::new".to_string(),
                     args: vec![init_arg.to_owned()],
-                    type_parameter: None,
+                    type_arguments: vec![],
                     arg_evaluation_order: bfe_new_function.signature.arg_evaluation_order,
                     annot: Default::default(),
                 })
@@ -320,7 +320,7 @@ fn graft_bfe_new(
             ast::Expr::FnCall(ast::FnCall {
                 name: "BFieldElement::new".to_string(),
                 args: vec![init_arg.to_owned()],
-                type_parameter: None,
+                type_arguments: vec![],
                 arg_evaluation_order: bfe_new_function.signature.arg_evaluation_order,
                 annot: Default::default(),
             })
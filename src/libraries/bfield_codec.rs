@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use tasm_lib::memory::dyn_malloc;
 use triton_vm::instruction::LabelledInstruction;
 use triton_vm::triton_asm;
@@ -202,14 +204,16 @@ impl Library for BFieldCodecLib {
             );
             let return_type = split_fn_name[0].to_owned();
 
-            // TODO: This is not very elegant! Can only handle `Vec<T>` and declared structs.
+            // TODO: This is not very elegant! Can only handle `Vec<T>`, primitive types like
+            // `u8`, and declared structs.
             let mem_pointer_declared_type = if return_type == "Vec" {
                 match function_type_parameter {
                     Some(t) => ast_types::DataType::List(Box::new(t), list_type),
                     None => panic!("Expected type parameter for Vec<T> in `decode` function"),
                 }
             } else {
-                ast_types::DataType::Unresolved(return_type)
+                ast_types::DataType::from_str(&return_type)
+                    .unwrap_or(ast_types::DataType::Unresolved(return_type))
             };
 
             let decode_arg = match args.len() {
@@ -226,7 +230,7 @@ impl Library for BFieldCodecLib {
                         ast::Expr::FnCall(ast::FnCall {
                             name,
                             args: load_function_args,
-                            type_parameter: _,
+                            type_arguments: _,
                             arg_evaluation_order: _,
                             annot: _,
                         }) => {
@@ -11,13 +11,15 @@ use crate::type_checker::Typing;
 pub(crate) fn option_type(payload_type: DataType) -> crate::composite_types::TypeContext {
     let enum_type = ast_types::EnumType {
         is_copy: payload_type.is_copy(),
+        is_partial_eq: true,
         name: "Option".to_owned(),
         variants: vec![
             ("None".to_owned(), DataType::unit()),
             ("Some".to_owned(), payload_type.clone()),
         ],
+        variant_field_names: vec![],
         is_prelude: true,
-        type_parameter: Some(payload_type.clone()),
+        type_arguments: vec![payload_type.clone()],
     };
     let is_some_method = option_is_some_method(&enum_type);
     let is_none_method = option_is_none_method(&enum_type);
@@ -27,6 +29,7 @@ pub(crate) fn option_type(payload_type: DataType) -> crate::composite_types::Typ
         composite_type: enum_type.into(),
         methods: vec![is_some_method, is_none_method, unwrap_method],
         associated_functions: vec![],
+        traits: vec![],
     }
 }
 
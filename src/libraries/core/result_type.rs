@@ -11,6 +11,7 @@ use crate::type_checker::Typing;
 pub(crate) fn result_type(ok_type: DataType) -> crate::composite_types::TypeContext {
     let enum_type = ast_types::EnumType {
         is_copy: ok_type.is_copy(),
+        is_partial_eq: true,
         name: "Result".to_owned(),
         variants: vec![
             (
@@ -19,8 +20,9 @@ pub(crate) fn result_type(ok_type: DataType) -> crate::composite_types::TypeCont
             ),
             ("Ok".to_owned(), ok_type.clone()),
         ],
+        variant_field_names: vec![],
         is_prelude: true,
-        type_parameter: Some(ok_type.clone()),
+        type_arguments: vec![ok_type.clone()],
     };
     let is_ok_method = result_is_ok_method(&enum_type);
     let is_err_method = result_is_err_method(&enum_type);
@@ -30,6 +32,7 @@ pub(crate) fn result_type(ok_type: DataType) -> crate::composite_types::TypeCont
         composite_type: enum_type.into(),
         methods: vec![is_ok_method, is_err_method, unwrap_method],
         associated_functions: vec![],
+        traits: vec![],
     }
 }
 
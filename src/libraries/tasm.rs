@@ -4,11 +4,15 @@ use crate::ast;
 use crate::ast_types;
 use crate::graft::Graft;
 use crate::tasm_code_generator::CompilerState;
+use crate::type_checker::is_small_unsigned_integer_type;
 
 use super::tasm_lib_snippet_to_fn_signature;
 use super::Library;
+use super::LibraryFunction;
 
 const TASM_LIB_INDICATOR: &str = "tasm::";
+const READ_STDIN_PREFIX: &str = "tasm_io_read_stdin___";
+const WRITE_TO_STDOUT_PREFIX: &str = "tasm_io_write_to_stdout___";
 
 #[derive(Clone, Debug)]
 pub struct TasmLibrary {
@@ -52,6 +56,10 @@ impl Library for TasmLibrary {
     ) -> ast::FnSignature {
         // Note that this function expects `fn_name` to be stripped of `tasm::`
         // Maybe that behavior should be changed though?
+        if let Some(small_integer_io) = get_small_unsigned_integer_io_function(stripped_name) {
+            return small_integer_io.signature;
+        }
+
         let snippet = tasm_lib::exported_snippets::name_to_snippet(stripped_name);
 
         tasm_lib_snippet_to_fn_signature(self.list_type, snippet)
@@ -77,6 +85,10 @@ impl Library for TasmLibrary {
         // TODO:
         // Note that this function expects `fn_name` to be stripped of `tasm::`
         // Maybe that behavior should be changed though?
+        if let Some(small_integer_io) = get_small_unsigned_integer_io_function(stripped_name) {
+            return small_integer_io.body;
+        }
+
         let snippet = tasm_lib::exported_snippets::name_to_snippet(stripped_name);
         let entrypoint = snippet.entrypoint();
         state.import_snippet(snippet);
@@ -106,3 +118,50 @@ impl Library for TasmLibrary {
         None
     }
 }
+
+/// tasm-lib has no I/O snippets for the small unsigned integer types `u8` and `u16`, so
+/// `tasm_io_read_stdin___u8` and friends are generated here. Values read from standard
+/// input are range checked.
+fn get_small_unsigned_integer_io_function(stripped_name: &str) -> Option<LibraryFunction> {
+    let (is_read, type_name) =
+        if let Some(type_name) = stripped_name.strip_prefix(READ_STDIN_PREFIX) {
+            (true, type_name)
+        } else if let Some(type_name) = stripped_name.strip_prefix(WRITE_TO_STDOUT_PREFIX) {
+            (false, type_name)
+        } else {
+            return None;
+        };
+
+    let data_type: ast_types::DataType = type_name.parse().ok()?;
+    if !is_small_unsigned_integer_type(&data_type) {
+        return None;
+    }
+
+    let (args, output, body) = if is_read {
+        let body = triton_asm!(
+            read_io 1
+            {&data_type.small_unsigned_integer_range_check_code()}
+        );
+        (vec![], data_type, body)
+    } else {
+        let arg = ast_types::AbstractArgument::ValueArgument(ast_types::AbstractValueArg {
+            name: "value".to_owned(),
+            data_type,
+            mutable: false,
+        });
+        (
+            vec![arg],
+            ast_types::DataType::unit(),
+            triton_asm!(write_io 1),
+        )
+    };
+
+    let signature = ast::FnSignature {
+        name: stripped_name.to_owned(),
+        args,
+        output,
+        arg_evaluation_order: Default::default(),
+    };
+
+    Some(LibraryFunction { signature, body })
+}
@@ -1,15 +1,19 @@
 use tasm_lib::traits::basic_snippet::BasicSnippet;
+use triton_vm::instruction::LabelledInstruction;
 use triton_vm::triton_asm;
 
 use crate::ast;
 use crate::ast_types;
 use crate::graft::Graft;
+use crate::libraries::core::option_type::option_type;
 use crate::tasm_code_generator::CompilerState;
 use crate::type_checker::is_u32_based_type;
 
 use super::Library;
 use super::LibraryFunction;
 
+mod limb_arithmetic;
+
 #[derive(Clone, Debug)]
 pub struct UnsignedIntegersLib {
     pub list_type: ast_types::ListType,
@@ -21,6 +25,21 @@ const POW_METHOD: &str = "pow";
 const OVERFLOWING_ADD_METHOD: &str = "overflowing_add";
 const OVERFLOWING_SUB_METHOD: &str = "overflowing_sub";
 const WRAPPING_SUB_METHOD: &str = "wrapping_sub";
+const CHECKED_ADD_METHOD: &str = "checked_add";
+const CHECKED_SUB_METHOD: &str = "checked_sub";
+const CHECKED_MUL_METHOD: &str = "checked_mul";
+const CHECKED_DIV_METHOD: &str = "checked_div";
+const WRAPPING_ADD_METHOD: &str = "wrapping_add";
+const WRAPPING_MUL_METHOD: &str = "wrapping_mul";
+const SATURATING_ADD_METHOD: &str = "saturating_add";
+const SATURATING_SUB_METHOD: &str = "saturating_sub";
+const SATURATING_MUL_METHOD: &str = "saturating_mul";
+const MIN_METHOD: &str = "min";
+const MAX_METHOD: &str = "max";
+const TRAILING_ZEROS_METHOD: &str = "trailing_zeros";
+const IS_POWER_OF_TWO_METHOD: &str = "is_power_of_two";
+const NEXT_POWER_OF_TWO_METHOD: &str = "next_power_of_two";
+const ILOG2_METHOD: &str = "ilog2";
 
 impl Library for UnsignedIntegersLib {
     fn get_function_name(&self, _full_name: &str) -> Option<String> {
@@ -41,6 +60,21 @@ impl Library for UnsignedIntegersLib {
                     | OVERFLOWING_ADD_METHOD
                     | OVERFLOWING_SUB_METHOD
                     | WRAPPING_SUB_METHOD
+                    | CHECKED_ADD_METHOD
+                    | CHECKED_SUB_METHOD
+                    | CHECKED_MUL_METHOD
+                    | CHECKED_DIV_METHOD
+                    | WRAPPING_ADD_METHOD
+                    | WRAPPING_MUL_METHOD
+                    | SATURATING_ADD_METHOD
+                    | SATURATING_SUB_METHOD
+                    | SATURATING_MUL_METHOD
+                    | MIN_METHOD
+                    | MAX_METHOD
+                    | TRAILING_ZEROS_METHOD
+                    | IS_POWER_OF_TWO_METHOD
+                    | NEXT_POWER_OF_TWO_METHOD
+                    | ILOG2_METHOD
             )
         {
             return Some(method_name.to_owned());
@@ -64,6 +98,10 @@ impl Library for UnsignedIntegersLib {
             return get_count_ones_u32_method().signature;
         }
 
+        if is_generated_method(method_name, receiver_type) {
+            return generated_method_signature(method_name, receiver_type);
+        }
+
         let snippet = name_to_tasm_lib_snippet(method_name, receiver_type)
             .unwrap_or_else(|| panic!("Unknown function name {method_name}"));
 
@@ -121,6 +159,10 @@ impl Library for UnsignedIntegersLib {
             return get_count_ones_u32_method().body;
         }
 
+        if is_generated_method(method_name, receiver_type) {
+            return generated_method_code(method_name, receiver_type, state);
+        }
+
         let snippet = name_to_tasm_lib_snippet(method_name, receiver_type)
             .unwrap_or_else(|| panic!("Unknown function name {method_name}"));
         let entrypoint = snippet.entrypoint();
@@ -182,6 +224,231 @@ fn get_count_ones_u32_method() -> LibraryFunction {
     }
 }
 
+/// Return true if the method is compiled to code generated by the compiler, rather than to
+/// a call to a tasm-lib snippet
+fn is_generated_method(method_name: &str, receiver_type: &ast_types::DataType) -> bool {
+    match method_name {
+        WRAPPING_SUB_METHOD | WRAPPING_MUL_METHOD | ILOG2_METHOD => {
+            ast_types::DataType::U64 != *receiver_type
+        }
+        CHECKED_ADD_METHOD
+        | CHECKED_SUB_METHOD
+        | CHECKED_MUL_METHOD
+        | CHECKED_DIV_METHOD
+        | WRAPPING_ADD_METHOD
+        | SATURATING_ADD_METHOD
+        | SATURATING_SUB_METHOD
+        | SATURATING_MUL_METHOD
+        | MIN_METHOD
+        | MAX_METHOD
+        | TRAILING_ZEROS_METHOD
+        | IS_POWER_OF_TWO_METHOD
+        | NEXT_POWER_OF_TWO_METHOD => true,
+        _ => false,
+    }
+}
+
+fn generated_method_signature(
+    method_name: &str,
+    receiver_type: &ast_types::DataType,
+) -> ast::FnSignature {
+    let value_argument = |name: &str| {
+        ast_types::AbstractArgument::ValueArgument(ast_types::AbstractValueArg {
+            name: name.to_owned(),
+            data_type: receiver_type.clone(),
+            mutable: false,
+        })
+    };
+
+    let args = match method_name {
+        TRAILING_ZEROS_METHOD
+        | IS_POWER_OF_TWO_METHOD
+        | NEXT_POWER_OF_TWO_METHOD
+        | ILOG2_METHOD => vec![value_argument("self")],
+        _ => vec![value_argument("self"), value_argument("rhs")],
+    };
+
+    let output = match method_name {
+        CHECKED_ADD_METHOD | CHECKED_SUB_METHOD | CHECKED_MUL_METHOD | CHECKED_DIV_METHOD => {
+            let option = option_type(receiver_type.clone());
+            ast_types::DataType::Enum(Box::new(option.composite_type.try_into().unwrap()))
+        }
+        TRAILING_ZEROS_METHOD | ILOG2_METHOD => ast_types::DataType::U32,
+        IS_POWER_OF_TWO_METHOD => ast_types::DataType::Bool,
+        _ => receiver_type.clone(),
+    };
+
+    ast::FnSignature {
+        name: method_name.to_owned(),
+        args,
+        output,
+        arg_evaluation_order: Default::default(),
+    }
+}
+
+/// Return the code for calling the subroutine that implements the method for the receiver
+/// type, which is imported if it isn't already
+fn generated_method_code(
+    method_name: &str,
+    receiver_type: &ast_types::DataType,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    let limb_count = receiver_type.stack_size();
+    let body = match method_name {
+        CHECKED_ADD_METHOD => [
+            overflowing_add_code(receiver_type, state),
+            limb_arithmetic::overflow_to_option_code(limb_count),
+        ]
+        .concat(),
+        CHECKED_SUB_METHOD => [
+            overflowing_sub_code(receiver_type, state),
+            limb_arithmetic::overflow_to_option_code(limb_count),
+        ]
+        .concat(),
+        CHECKED_MUL_METHOD => [
+            overflowing_mul_code(receiver_type, state),
+            limb_arithmetic::overflow_to_option_code(limb_count),
+        ]
+        .concat(),
+        CHECKED_DIV_METHOD => {
+            let div_code = match receiver_type {
+                ast_types::DataType::U32 => triton_asm!(swap 1 div_mod pop 1),
+                ast_types::DataType::U64 => {
+                    let div_mod_u64 = state.import_snippet(Box::new(
+                        tasm_lib::arithmetic::u64::div_mod_u64::DivModU64,
+                    ));
+                    triton_asm!(call { div_mod_u64 } pop 2)
+                }
+                _ => limb_arithmetic::long_division_code(
+                    limb_count,
+                    &format!("tasm_langs_div_{receiver_type}"),
+                    state,
+                ),
+            };
+            limb_arithmetic::checked_div_code(limb_count, div_code)
+        }
+        WRAPPING_ADD_METHOD => [
+            overflowing_add_code(receiver_type, state),
+            triton_asm!(pop 1),
+        ]
+        .concat(),
+        WRAPPING_SUB_METHOD => [
+            overflowing_sub_code(receiver_type, state),
+            triton_asm!(pop 1),
+        ]
+        .concat(),
+        WRAPPING_MUL_METHOD => [
+            overflowing_mul_code(receiver_type, state),
+            triton_asm!(pop 1),
+        ]
+        .concat(),
+        SATURATING_ADD_METHOD => [
+            overflowing_add_code(receiver_type, state),
+            limb_arithmetic::replace_limbs_if_flag_code(limb_count, u32::MAX),
+            triton_asm!(pop 1),
+        ]
+        .concat(),
+        SATURATING_SUB_METHOD => [
+            overflowing_sub_code(receiver_type, state),
+            limb_arithmetic::replace_limbs_if_flag_code(limb_count, 0),
+            triton_asm!(pop 1),
+        ]
+        .concat(),
+        SATURATING_MUL_METHOD => [
+            overflowing_mul_code(receiver_type, state),
+            limb_arithmetic::replace_limbs_if_flag_code(limb_count, u32::MAX),
+            triton_asm!(pop 1),
+        ]
+        .concat(),
+        MIN_METHOD => limb_arithmetic::min_code(limb_count),
+        MAX_METHOD => limb_arithmetic::max_code(limb_count),
+        TRAILING_ZEROS_METHOD => limb_arithmetic::trailing_zeros_code(limb_count),
+        IS_POWER_OF_TWO_METHOD => limb_arithmetic::is_power_of_two_code(limb_count),
+        NEXT_POWER_OF_TWO_METHOD => limb_arithmetic::next_power_of_two_code(limb_count),
+        ILOG2_METHOD => limb_arithmetic::ilog2_code(limb_count),
+        _ => unreachable!("`{method_name}` is not a generated method"),
+    };
+
+    let subroutine_label = format!("tasm_langs_{method_name}_{receiver_type}");
+    if !state.contains_subroutine(&subroutine_label) {
+        let subroutine = triton_asm!(
+            {subroutine_label}:
+                {&body}
+                return
+        );
+        state.add_library_function(subroutine.try_into().unwrap());
+    }
+
+    triton_asm!(call { subroutine_label })
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs + rhs mod 2^bits] carry
+fn overflowing_add_code(
+    receiver_type: &ast_types::DataType,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    match receiver_type {
+        ast_types::DataType::U64 => {
+            let overflowing_add = state.import_snippet(Box::new(
+                tasm_lib::arithmetic::u64::overflowing_add_u64::OverflowingAdd,
+            ));
+            triton_asm!(call { overflowing_add })
+        }
+        _ => limb_arithmetic::overflowing_add_code(receiver_type.stack_size()),
+    }
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs - rhs mod 2^bits] borrow
+fn overflowing_sub_code(
+    receiver_type: &ast_types::DataType,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    match receiver_type {
+        ast_types::DataType::U64 => {
+            let overflowing_sub = state.import_snippet(Box::new(
+                tasm_lib::arithmetic::u64::overflowing_sub_u64::OverflowingSub,
+            ));
+            triton_asm!(call { overflowing_sub })
+        }
+        _ => limb_arithmetic::overflowing_sub_code(receiver_type.stack_size()),
+    }
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs * rhs mod 2^bits] overflow
+fn overflowing_mul_code(
+    receiver_type: &ast_types::DataType,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    match receiver_type {
+        ast_types::DataType::U64 => {
+            let mul_to_u128 = state.import_snippet(Box::new(
+                tasm_lib::arithmetic::u64::mul_two_u64s_to_u128_u64::MulTwoU64sToU128,
+            ));
+
+            // The product overflows iff either of its two most significant limbs is non-zero
+            triton_asm!(
+                call { mul_to_u128 }
+                            // _ prod_3 prod_2 prod_1 prod_0
+                swap 3      // _ prod_0 prod_2 prod_1 prod_3
+                swap 1      // _ prod_0 prod_2 prod_3 prod_1
+                swap 2      // _ prod_0 prod_1 prod_3 prod_2
+                add
+                push 0
+                eq
+                push 0
+                eq          // _ prod_0 prod_1 overflow
+                swap 1      // _ prod_0 overflow prod_1
+                swap 2      // _ prod_1 overflow prod_0
+                swap 1      // _ prod_1 prod_0 overflow
+            )
+        }
+        _ => limb_arithmetic::overflowing_mul_code(receiver_type.stack_size()),
+    }
+}
+
 /// Map list-function or method name to the TASM lib snippet type
 fn name_to_tasm_lib_snippet(
     public_name: &str,
@@ -225,6 +492,18 @@ fn name_to_tasm_lib_snippet(
             )),
             _ => panic!("Dont know `{public_name}` for {receiver_type}"),
         },
+        WRAPPING_MUL_METHOD => match receiver_type {
+            ast_types::DataType::U64 => Some(Box::new(
+                tasm_lib::arithmetic::u64::wrapping_mul_u64::WrappingMulU64,
+            )),
+            _ => panic!("Dont know `{public_name}` for {receiver_type}"),
+        },
+        ILOG2_METHOD => match receiver_type {
+            ast_types::DataType::U64 => Some(Box::new(
+                tasm_lib::arithmetic::u64::log_2_floor_u64::Log2FloorU64,
+            )),
+            _ => panic!("Dont know `{public_name}` for {receiver_type}"),
+        },
         _ => None,
     }
 }
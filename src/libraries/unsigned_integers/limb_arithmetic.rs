@@ -0,0 +1,651 @@
+//! Code generators for arithmetic on unsigned integers of any number of `U32` limbs. A value
+//! is stored with its most significant limb deepest in the stack, so limb `i` of a value on
+//! top of the stack is found at depth `i`.
+
+use triton_vm::instruction::LabelledInstruction;
+use triton_vm::triton_asm;
+
+use crate::tasm_code_generator::pop_n;
+use crate::tasm_code_generator::CompilerState;
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs + rhs mod 2^(32 * limb_count)] carry
+pub(super) fn overflowing_add_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    let mut code = triton_asm!();
+    for i in 0..limb_count {
+        if i > 0 {
+            // _ [lhs_limbs] [sum_limbs] [rhs_limbs] carry
+            code.extend(triton_asm!(add));
+        }
+
+        // `lhs_i` is found at depth `limb_count`, and is replaced by the limb of the sum
+        code.extend(triton_asm!(
+            dup { limb_count }
+            add
+            split
+            swap { limb_count + 1 }
+            pop 1
+        ));
+    }
+
+    code
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs - rhs mod 2^(32 * limb_count)] borrow
+pub(super) fn overflowing_sub_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    let mut code = triton_asm!();
+    for i in 0..limb_count {
+        if i == 0 {
+            code.extend(triton_asm!(
+                // _ [lhs_limbs] [rhs_limbs]
+                push -1
+                mul
+                push {1u64 << 32}
+                add
+            ));
+        } else {
+            code.extend(triton_asm!(
+                // _ [lhs_limbs] [diff_limbs] [rhs_limbs] no_borrow
+                push {u32::MAX}
+                add
+                swap 1
+                push -1
+                mul
+                add
+            ));
+        }
+
+        // _ [lhs_limbs] [diff_limbs] [rhs_limbs] (2^32 - 1 - rhs_i + no_borrow)
+        code.extend(triton_asm!(
+            dup { limb_count }
+            add
+            split
+            swap { limb_count + 1 }
+            pop 1
+        ));
+    }
+
+    [code, triton_asm!(push 0 eq)].concat()
+}
+
+/// The product of two limbs fits in the field, so it is accumulated column by column, like
+/// schoolbook multiplication. The result overflows if any product of limbs belongs to a
+/// column beyond the most significant one, or if a carry does.
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs * rhs mod 2^(32 * limb_count)] overflow
+pub(super) fn overflowing_mul_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    if limb_count == 1 {
+        return triton_asm!(
+            // _ lhs rhs
+            mul
+            split
+            swap 1
+            push 0
+            eq
+            push 0
+            eq
+        );
+    }
+
+    // _ [lhs] [rhs] [product_limbs] acc next_acc
+    let mut code = triton_asm!(push 0 push 0);
+    for column in 0..limb_count {
+        for lhs_limb in 0..=column {
+            let rhs_limb = column - lhs_limb;
+            code.extend(triton_asm!(
+                dup { limb_count + lhs_limb + column + 2 }
+                dup { rhs_limb + column + 3 }
+                mul
+                split
+                // _ [lhs] [rhs] [product_limbs] acc next_acc hi lo
+                swap 2
+                add
+                swap 2
+                add
+                swap 1
+            ));
+        }
+
+        code.extend(triton_asm!(
+            // _ [lhs] [rhs] [product_limbs] acc next_acc
+            swap 1
+            split
+            swap 2
+            add
+        ));
+        if column < limb_count - 1 {
+            code.extend(triton_asm!(push 0));
+        }
+    }
+
+    // _ [lhs] [rhs] product_limb_0 ... product_limb_{limb_count - 1} carry
+    for lhs_limb in 1..limb_count {
+        code.extend(triton_asm!(
+            dup { 2 * limb_count + lhs_limb + 1 }
+            push 0
+            eq
+            push 0
+            eq
+            dup { 2 * limb_count - lhs_limb + 2 }
+        ));
+        for rhs_limb in limb_count - lhs_limb + 1..limb_count {
+            code.extend(triton_asm!(
+                dup { rhs_limb + limb_count + 3 }
+                add
+            ));
+        }
+
+        // _ [lhs] [rhs] [product_limbs] carry (lhs_i != 0) (sum of rhs limbs in overflowing columns)
+        code.extend(triton_asm!(mul add));
+    }
+
+    code.extend(triton_asm!(push 0 eq push 0 eq));
+    [
+        code,
+        move_into_garbage_code(2 * limb_count, limb_count),
+        pop_n(2 * limb_count),
+    ]
+    .concat()
+}
+
+/// Move limbs that were computed least significant first into the place of the words below
+/// them, reversing their order.
+/// BEFORE: _ [garbage] limb_0 ... limb_{limb_count - 1} flag
+/// AFTER:  _ limb_{limb_count - 1} ... limb_0 flag [_; garbage_size]
+fn move_into_garbage_code(garbage_size: usize, limb_count: usize) -> Vec<LabelledInstruction> {
+    let mut code = triton_asm!(swap { garbage_size });
+    for limb in (0..limb_count).rev() {
+        code.extend(triton_asm!(
+            swap { limb_count - limb }
+            swap { limb + 1 + garbage_size }
+        ));
+    }
+
+    code
+}
+
+/// Replace each limb of a value with `replacement_limb` if the flag is set.
+/// BEFORE: _ [value] flag
+/// AFTER:  _ [flag ? replacement : value] flag
+pub(super) fn replace_limbs_if_flag_code(
+    limb_count: usize,
+    replacement_limb: u32,
+) -> Vec<LabelledInstruction> {
+    let mut code = triton_asm!();
+    for i in 0..limb_count {
+        code.extend(triton_asm!(
+            dup 0
+            dup { i + 2 }
+            push -1
+            mul
+            push { replacement_limb }
+            add
+            mul
+            // _ [value] flag (flag * (replacement_limb - value_i))
+            dup { i + 2 }
+            add
+            swap { i + 2 }
+            pop 1
+        ));
+    }
+
+    code
+}
+
+/// Convert the result of an overflowing operation to an `Option`, which is `None` on
+/// overflow. The data of `None` is zeroed, as it is for any other `None` value.
+/// BEFORE: _ [value] overflow
+/// AFTER:  _ [Option<value>]
+pub(super) fn overflow_to_option_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [
+        replace_limbs_if_flag_code(limb_count, 0),
+        triton_asm!(push 0 eq),
+    ]
+    .concat()
+}
+
+/// BEFORE: _ [lhs] [rhs] flag
+/// AFTER:  _ [flag ? lhs : rhs]
+fn select_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    let select_limb = triton_asm!(
+        dup { limb_count + 1 }
+        dup 2
+        push -1
+        mul
+        add
+        dup 1
+        mul
+        // _ [lhs_limbs] [selected_limbs] [rhs_limbs] flag (flag * (lhs_i - rhs_i))
+        swap 1
+        swap 2
+        add
+        swap { limb_count + 1 }
+        pop 1
+    );
+
+    [vec![select_limb; limb_count].concat(), triton_asm!(pop 1)].concat()
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [lhs] [rhs] (lhs < rhs)
+fn lt_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [
+        vec![triton_asm!(dup { 2 * limb_count - 1 }); 2 * limb_count].concat(),
+        overflowing_sub_code(limb_count),
+        triton_asm!(swap { limb_count }),
+        pop_n(limb_count),
+    ]
+    .concat()
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [min(lhs, rhs)]
+pub(super) fn min_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [lt_code(limb_count), select_code(limb_count)].concat()
+}
+
+/// BEFORE: _ [lhs] [rhs]
+/// AFTER:  _ [max(lhs, rhs)]
+pub(super) fn max_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [
+        lt_code(limb_count),
+        triton_asm!(push 0 eq),
+        select_code(limb_count),
+    ]
+    .concat()
+}
+
+/// Return the code for moving the top element `depth` positions down the stack
+fn bury_code(depth: usize) -> Vec<LabelledInstruction> {
+    (1..=depth)
+        .rev()
+        .flat_map(|i| triton_asm!(swap { i }))
+        .collect()
+}
+
+/// Return the code for moving the element at depth `depth` to the top of the stack
+fn unbury_code(depth: usize) -> Vec<LabelledInstruction> {
+    (1..=depth).flat_map(|i| triton_asm!(swap { i })).collect()
+}
+
+/// Return the code for `checked_div`. Division by zero is avoided by dividing by one
+/// instead, and the result is then discarded.
+/// BEFORE: _ [numerator] [divisor]
+/// AFTER:  _ [Option<numerator / divisor>]
+pub(super) fn checked_div_code(
+    limb_count: usize,
+    div_code: Vec<LabelledInstruction>,
+) -> Vec<LabelledInstruction> {
+    let mut code = triton_asm!(dup 0);
+    for i in 1..limb_count {
+        code.extend(triton_asm!(
+            dup { i + 1 }
+            add
+        ));
+    }
+
+    [
+        code,
+        triton_asm!(
+            push 0
+            eq
+            // _ [numerator] [divisor] (divisor == 0)
+            dup 0
+            swap 2
+            add
+            swap 1
+        ),
+        bury_code(2 * limb_count),
+        // _ (divisor == 0) [numerator] [divisor']
+        div_code,
+        unbury_code(limb_count),
+        overflow_to_option_code(limb_count),
+    ]
+    .concat()
+}
+
+/// Return the code for shifting a value one bit to the left, shifting in the carry on top
+/// of the stack. The limbs of the value are found at depths `depth`, `depth + 1`, ...
+/// BEFORE: _ [value] [_; depth - 1] carry
+/// AFTER:  _ [value << 1 | carry] [_; depth - 1] carry'
+fn shift_left_in_place_code(limb_count: usize, depth: usize) -> Vec<LabelledInstruction> {
+    (0..limb_count)
+        .flat_map(|i| {
+            triton_asm!(
+                dup { depth + i }
+                push 2
+                mul
+                add
+                split
+                swap { depth + i + 1 }
+                pop 1
+            )
+        })
+        .collect()
+}
+
+/// Return the code for calling a subroutine that divides two unsigned integers by binary
+/// long division, one bit of the quotient per iteration. The divisor must not be zero.
+/// BEFORE: _ [numerator] [divisor]
+/// AFTER:  _ [numerator / divisor]
+pub(super) fn long_division_code(
+    limb_count: usize,
+    subroutine_label: &str,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    if state.contains_subroutine(subroutine_label) {
+        return triton_asm!(call { subroutine_label });
+    }
+
+    // The numerator is shifted into the remainder while the quotient is shifted into the
+    // numerator. The shifted remainder can have one bit more than the divisor.
+    let mut loop_body = [
+        triton_asm!(push 0),
+        shift_left_in_place_code(limb_count, limb_count + 1),
+        shift_left_in_place_code(limb_count, 1),
+        // _ remaining [divisor] [numerator'] [remainder'] remainder_overflow
+        triton_asm!(push 1),
+    ]
+    .concat();
+
+    // Subtract the divisor from the remainder
+    for i in 0..limb_count {
+        loop_body.extend(triton_asm!(
+            push {u32::MAX}
+            add
+            dup { 2 * limb_count + i + 2 }
+            push -1
+            mul
+            add
+            dup { i + 2 }
+            add
+            split
+            swap { i + 3 }
+            pop 1
+        ));
+    }
+
+    // The divisor fits in the shifted remainder if either overflowed
+    loop_body.extend(triton_asm!(
+        add
+        // _ remaining [divisor] [numerator'] [remainder' - divisor] quotient_bit
+        dup 0
+        push 0
+        eq
+        push 0
+    ));
+
+    // Add the divisor back to the remainder if it didn't fit
+    for i in 0..limb_count {
+        loop_body.extend(triton_asm!(
+            dup { 2 * limb_count + i + 3 }
+            dup 2
+            mul
+            add
+            dup { i + 3 }
+            add
+            split
+            swap { i + 4 }
+            pop 1
+        ));
+    }
+
+    loop_body.extend(triton_asm!(
+        pop 2
+        // _ remaining [divisor] [numerator'] [remainder] quotient_bit
+        dup { limb_count + 1 }
+        add
+        swap { limb_count + 1 }
+        pop 1
+        dup { 3 * limb_count }
+        push -1
+        add
+        swap { 3 * limb_count + 1 }
+        pop 1
+    ));
+
+    let loop_label = format!("{subroutine_label}_loop");
+    state.add_library_function(
+        triton_asm!(
+            {loop_label}:
+                // _ remaining [divisor] [numerator] [remainder]
+                dup { 3 * limb_count }
+                push 0
+                eq
+                skiz
+                return
+
+                {&loop_body}
+                recurse
+        )
+        .try_into()
+        .unwrap(),
+    );
+
+    // Swap the operands
+    let mut swap_operands = triton_asm!(swap { limb_count });
+    for i in 1..limb_count {
+        swap_operands.extend(triton_asm!(
+            swap { i }
+            swap { limb_count + i }
+            swap { i }
+        ));
+    }
+
+    state.add_library_function(
+        triton_asm!(
+            {subroutine_label}:
+                // _ [numerator] [divisor]
+                {&swap_operands}
+                push { 32 * limb_count }
+                {&bury_code(2 * limb_count)}
+                {&triton_asm![push 0; limb_count]}
+                // _ (32 * limb_count) [divisor] [numerator] [0]
+                call {loop_label}
+                // _ 0 [divisor] [quotient] [remainder]
+
+                {&pop_n(limb_count)}
+                {&vec![triton_asm!(swap { limb_count + 1 } pop 1); limb_count].concat()}
+                pop 1
+                // _ [quotient]
+                return
+        )
+        .try_into()
+        .unwrap(),
+    );
+
+    triton_asm!(call { subroutine_label })
+}
+
+/// BEFORE: _ limb
+/// AFTER:  _ limb.trailing_zeros()
+fn trailing_zeros_u32_code() -> Vec<LabelledInstruction> {
+    // The trailing zeros are the ones of `!limb & (limb - 1)`
+    triton_asm!(
+        dup 0
+        push {u32::MAX}
+        add
+        split
+        swap 1
+        pop 1
+        swap 1
+        push {u32::MAX}
+        xor
+        and
+        pop_count
+    )
+}
+
+/// BEFORE: _ [value]
+/// AFTER:  _ value.trailing_zeros()
+pub(super) fn trailing_zeros_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    let mut code = triton_asm!(
+        dup 0
+        push 0
+        eq
+        swap 1
+        {&trailing_zeros_u32_code()}
+        swap 1
+    );
+    for _ in 1..limb_count {
+        code.extend(triton_asm!(
+            // _ [value_limbs] acc (all lower limbs are zero)
+            swap 2
+            dup 0
+            push 0
+            eq
+            swap 1
+            {&trailing_zeros_u32_code()}
+            dup 3
+            mul
+            // _ all_zero acc (limb == 0) (all_zero * limb.trailing_zeros())
+            swap 1
+            swap 2
+            add
+            swap 2
+            mul
+        ));
+    }
+
+    [code, triton_asm!(pop 1)].concat()
+}
+
+/// BEFORE: _ limb
+/// AFTER:  _ (32 - limb.leading_zeros())
+fn bit_length_u32_code() -> Vec<LabelledInstruction> {
+    // `log_2_floor` crashes on zero, so zero is replaced by one and corrected for
+    triton_asm!(
+        dup 0
+        push 0
+        eq
+        dup 0
+        swap 2
+        add
+        log_2_floor
+        push 1
+        add
+        swap 1
+        push -1
+        mul
+        add
+    )
+}
+
+/// BEFORE: _ [value]
+/// AFTER:  _ (32 * limb_count - value.leading_zeros())
+fn bit_length_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    let mut code = bit_length_u32_code();
+    for i in 1..limb_count {
+        code.extend(triton_asm!(
+            // _ [value_limbs] limb_i acc
+            swap 1
+            dup 0
+            push 0
+            eq
+            push 0
+            eq
+            swap 1
+            {&bit_length_u32_code()}
+            push { 32 * i }
+            add
+            // _ acc (limb_i != 0) (bit length if limb_i is the most significant non-zero limb)
+            dup 2
+            push -1
+            mul
+            add
+            mul
+            add
+        ));
+    }
+
+    code
+}
+
+/// Crash the VM if the value is zero, like Rust panics.
+/// BEFORE: _ [value]
+/// AFTER:  _ value.ilog2()
+pub(super) fn ilog2_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [
+        bit_length_code(limb_count),
+        triton_asm!(
+            dup 0
+            push 0
+            eq
+            push 0
+            eq
+            assert
+            push -1
+            add
+        ),
+    ]
+    .concat()
+}
+
+/// BEFORE: _ [value]
+/// AFTER:  _ value.count_ones()
+fn count_ones_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [
+        triton_asm!(pop_count),
+        vec![triton_asm!(swap 1 pop_count add); limb_count - 1].concat(),
+    ]
+    .concat()
+}
+
+/// BEFORE: _ [value]
+/// AFTER:  _ value.is_power_of_two()
+pub(super) fn is_power_of_two_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    [count_ones_code(limb_count), triton_asm!(push 1 eq)].concat()
+}
+
+/// Crash the VM if the result overflows, like Rust panics.
+/// BEFORE: _ [value]
+/// AFTER:  _ [value.next_power_of_two()]
+pub(super) fn next_power_of_two_code(limb_count: usize) -> Vec<LabelledInstruction> {
+    let mut code = [
+        vec![triton_asm!(dup { limb_count - 1 }); limb_count].concat(),
+        is_power_of_two_code(limb_count),
+        bury_code(limb_count),
+        bit_length_code(limb_count),
+        triton_asm!(
+            // _ value.is_power_of_two() bit_length
+            swap 1
+            push -1
+            mul
+            add
+            // _ exponent
+            push { 32 * limb_count }
+            dup 1
+            lt
+            assert
+        ),
+    ]
+    .concat();
+
+    if limb_count == 1 {
+        code.extend(triton_asm!(push 2 pow));
+        return code;
+    }
+
+    code.extend(triton_asm!(
+        push 32
+        swap 1
+        div_mod
+        push 2
+        pow
+        // _ (exponent / 32) 2^(exponent % 32)
+    ));
+    for i in (0..limb_count).rev() {
+        code.extend(triton_asm!(
+            dup 1
+            push { i }
+            eq
+            dup 1
+            mul
+            swap 2
+            swap 1
+        ));
+    }
+
+    [code, triton_asm!(pop 2)].concat()
+}
@@ -1,4 +1,3 @@
-use itertools::Itertools;
 use num::One;
 use tasm_lib::traits::basic_snippet::BasicSnippet;
 use triton_vm::triton_asm;
@@ -6,6 +5,7 @@ use triton_vm::triton_asm;
 use crate::ast;
 use crate::ast_types;
 use crate::ast_types::ListType;
+use crate::compile_error::ErrorCode;
 use crate::graft::Graft;
 use crate::tasm_code_generator::CompilerState;
 use crate::type_checker::GetType;
@@ -58,7 +58,8 @@ impl Library for VectorLib {
         };
 
         // Special-case on `map` as we need to dig into the type checker state to find the
-        // function signature.
+        // function signature. Closures and function arguments passed to `map` have been
+        // replaced by the functions they stand for by the type checker.
         if fn_name == "map" {
             return self.fn_signature_for_map(args, type_checker_state);
         }
@@ -178,108 +179,54 @@ impl Library for VectorLib {
         graft_config: &mut Graft,
         rust_method_call: &syn::ExprMethodCall,
     ) -> Option<ast::Expr<super::Annotation>> {
-        const POP_NAME: &str = "pop";
         const COLLECT_VEC_NAME: &str = "collect_vec";
-        const UNWRAP_NAME: &str = "unwrap";
         const INTO_ITER_NAME: &str = "into_iter";
         const MAP_NAME: &str = "map";
 
         let last_method_name = rust_method_call.method.to_string();
 
         match last_method_name.as_str() {
-            UNWRAP_NAME => {
-                // Handle `a.pop().unwrap();`
-                match rust_method_call.receiver.as_ref() {
-                    syn::Expr::MethodCall(rust_inner_method_call) => {
-                        let inner_method_call =
-                            graft_config.graft_method_call(rust_inner_method_call);
-                        let inner_method_call = match inner_method_call {
-                            ast::Expr::MethodCall(mc) => mc,
-                            _ => return None,
-                        };
-                        if inner_method_call.method_name != POP_NAME {
-                            return None;
-                        }
-
-                        let identifier = match &inner_method_call.args[0] {
-                            ast::Expr::Var(ident) => ident.to_owned(),
-                            // Maybe cover more cases here?
-                            _ => todo!(),
-                        };
-
-                        let mut args = vec![ast::Expr::Var(identifier)];
-                        args.append(
-                            &mut rust_inner_method_call
-                                .args
-                                .iter()
-                                .map(|x| graft_config.graft_expr(x))
-                                .collect_vec(),
-                        );
-                        Some(ast::Expr::MethodCall(ast::MethodCall {
-                            method_name: POP_NAME.to_owned(),
-                            args,
-                            annot: Default::default(),
-                            associated_type: None,
-                        }))
-                    }
-                    _ => None,
-                }
-            }
             COLLECT_VEC_NAME => {
-                match rust_method_call.receiver.as_ref() {
-                    syn::Expr::MethodCall(rust_inner_method_call) => {
-                        let inner_method_call =
-                            graft_config.graft_method_call(rust_inner_method_call);
-                        let inner_method_call = match inner_method_call {
-                            ast::Expr::MethodCall(mc) => mc,
-                            _ => return None,
-                        };
-                        if inner_method_call.method_name != MAP_NAME {
-                            return None;
-                        }
-
-                        let identifier = match rust_inner_method_call.receiver.as_ref() {
-                            syn::Expr::MethodCall(rust_inner_inner_method_call) => {
-                                let maybe_iter_name =
-                                    rust_inner_inner_method_call.method.to_string();
-                                if maybe_iter_name != INTO_ITER_NAME {
-                                    panic!("Only allowed syntax with `map` is `x.into_iter().map(<function_name>).collect_vec()")
-                                }
-
-                                let inner_inner_method_call =
-                                    graft_config.graft_method_call(rust_inner_inner_method_call);
-                                let inner_inner_method_call = match inner_inner_method_call {
-                                    ast::Expr::MethodCall(mc) => mc,
-                                    _ => return None,
-                                };
-
-                                match &inner_inner_method_call.args[0] {
-                                    ast::Expr::Var(ident) => ident.to_owned(),
-                                    // Maybe cover more cases here?
-                                    _ => todo!(),
-                                }
-                            }
-                            _ => todo!(),
-                        };
-
-                        let mut args = vec![ast::Expr::Var(identifier)];
-                        args.append(
-                            &mut rust_inner_method_call
-                                .args
-                                .iter()
-                                .map(|x| graft_config.graft_expr(x))
-                                .collect_vec(),
-                        );
+                // Handle `x.into_iter().map(f).collect_vec()`
+                let syn::Expr::MethodCall(rust_map_call) = rust_method_call.receiver.as_ref()
+                else {
+                    return None;
+                };
+                if rust_map_call.method != MAP_NAME {
+                    return None;
+                }
 
-                        Some(ast::Expr::MethodCall(ast::MethodCall {
-                            method_name: MAP_NAME.to_owned(),
-                            args,
-                            annot: Default::default(),
-                            associated_type: None,
-                        }))
+                let list = match rust_map_call.receiver.as_ref() {
+                    syn::Expr::MethodCall(rust_into_iter_call)
+                        if rust_into_iter_call.method == INTO_ITER_NAME =>
+                    {
+                        graft_config.graft_expr(&rust_into_iter_call.receiver)
                     }
-                    _ => todo!(),
-                }
+                    _ => {
+                        graft_config.report(
+                            ErrorCode::UnsupportedSyntax,
+                            rust_method_call,
+                            "only `x.into_iter().map(<function or closure>).collect_vec()` is \
+                             supported with `map`",
+                        );
+                        return Some(ast::Expr::Tuple(vec![]));
+                    }
+                };
+
+                let mut args = vec![list];
+                args.extend(
+                    rust_map_call
+                        .args
+                        .iter()
+                        .map(|x| graft_config.graft_expr(x)),
+                );
+
+                Some(ast::Expr::MethodCall(ast::MethodCall {
+                    method_name: MAP_NAME.to_owned(),
+                    args,
+                    annot: Default::default(),
+                    associated_type: None,
+                }))
             }
             _ => None,
         }
@@ -341,7 +288,10 @@ impl VectorLib {
         let inner_fn_signature = &inner_fn_signature[0];
         let inner_output = &inner_fn_signature.output;
         let inner_input = match &inner_fn_signature.args[0] {
-            ast_types::AbstractArgument::FunctionArgument(_) => todo!(),
+            // Rejected by the type checker, as the function must take the list elements
+            ast_types::AbstractArgument::FunctionArgument(_) => {
+                unreachable!("cannot map function {inner_fn_name} taking a function argument")
+            }
             ast_types::AbstractArgument::ValueArgument(value_arg) => value_arg.data_type.to_owned(),
         };
         let derived_inner_function_as_function_arg =
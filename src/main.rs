@@ -1,31 +1,443 @@
 use std::env;
-use std::fs::File;
-use std::io::Read;
+use std::fs;
 use std::process;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use itertools::Itertools;
+use triton_vm::twenty_first::shared_math::bfield_codec::BFieldCodec;
+use triton_vm::BFieldElement;
+use triton_vm::Claim;
+use triton_vm::NonDeterminism;
+use triton_vm::Program;
+use triton_vm::Proof;
+use triton_vm::PublicInput;
+use triton_vm::StarkParameters;
 
 use tasm_lang::ast_types::ListType;
+use tasm_lang::compile_error::Diagnostics;
+
+const USAGE: &str = "\
+Usage: tasm-lang <COMMAND> [OPTIONS]
+
+Commands:
+  compile <FILE>               Compile FILE and print the resulting Triton assembly
+  run <FILE>                   Compile FILE, execute it in Triton VM, and print the public output
+  prove <FILE> <PROOF_FILE>    Compile FILE, prove its execution, and write claim and proof to PROOF_FILE
+  verify <PROOF_FILE>          Verify a claim and proof previously written by `prove`
+
+Options:
+  --entrypoint <NAME>          Name of the function to compile [default: main]
+  --list-type <safe|unsafe>    List implementation used for `Vec<T>` [default: unsafe]
+  --public-input <FILE>        File containing the public input (stdin) of the program
+  --secret-input <FILE>        File containing the secret input (divine) of the program
+  -o, --output <FILE>          Write the output of `compile` to FILE instead of stdout
+  -h, --help                   Print this message
+
+Input files contain base field elements separated by whitespace or commas.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Compile {
+        source_file: String,
+    },
+    Run {
+        source_file: String,
+    },
+    Prove {
+        source_file: String,
+        proof_file: String,
+    },
+    Verify {
+        proof_file: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CliArgs {
+    command: Command,
+    entrypoint: String,
+    list_type: ListType,
+    public_input: Option<String>,
+    secret_input: Option<String>,
+    output: Option<String>,
+}
+
+impl CliArgs {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut entrypoint = "main".to_owned();
+        let mut list_type = ListType::Unsafe;
+        let mut public_input = None;
+        let mut secret_input = None;
+        let mut output = None;
+        let mut positionals = vec![];
+
+        while let Some(arg) = args.next() {
+            let mut option_value = |option: &str| {
+                args.next()
+                    .ok_or_else(|| anyhow!("Option `{option}` requires a value"))
+            };
+            match arg.as_str() {
+                "--entrypoint" => entrypoint = option_value(&arg)?,
+                "--list-type" => list_type = ListType::from_str(&option_value(&arg)?)?,
+                "--public-input" => public_input = Some(option_value(&arg)?),
+                "--secret-input" => secret_input = Some(option_value(&arg)?),
+                "-o" | "--output" => output = Some(option_value(&arg)?),
+                option if option.starts_with('-') => bail!("Unknown option `{option}`"),
+                _ => positionals.push(arg.clone()),
+            }
+        }
+
+        let command = match positionals.iter().map(String::as_str).collect_vec()[..] {
+            ["compile", source_file] => Command::Compile {
+                source_file: source_file.to_owned(),
+            },
+            ["run", source_file] => Command::Run {
+                source_file: source_file.to_owned(),
+            },
+            ["prove", source_file, proof_file] => Command::Prove {
+                source_file: source_file.to_owned(),
+                proof_file: proof_file.to_owned(),
+            },
+            ["verify", proof_file] => Command::Verify {
+                proof_file: proof_file.to_owned(),
+            },
+            [] => bail!("Missing command"),
+            [command, ..] => bail!("Invalid arguments for command `{command}`"),
+        };
+
+        Ok(Self {
+            command,
+            entrypoint,
+            list_type,
+            public_input,
+            secret_input,
+            output,
+        })
+    }
+
+    fn compile(&self, source_file: &str) -> Result<Program> {
+        let code =
+            tasm_lang::compile_to_instructions(source_file, &self.entrypoint, self.list_type)?;
+        Ok(Program::new(&code))
+    }
+
+    fn public_input(&self) -> Result<Vec<BFieldElement>> {
+        read_optional_input_file(self.public_input.as_deref())
+    }
+
+    fn secret_input(&self) -> Result<Vec<BFieldElement>> {
+        read_optional_input_file(self.secret_input.as_deref())
+    }
+}
+
+fn read_optional_input_file(file_path: Option<&str>) -> Result<Vec<BFieldElement>> {
+    let Some(file_path) = file_path else {
+        return Ok(vec![]);
+    };
+    let content =
+        fs::read_to_string(file_path).with_context(|| format!("Unable to read {file_path}"))?;
+    parse_field_elements(&content).with_context(|| format!("Malformed input file {file_path}"))
+}
+
+fn parse_field_elements(content: &str) -> Result<Vec<BFieldElement>> {
+    content
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let value = u64::from_str(token)
+                .with_context(|| format!("`{token}` is not an unsigned integer"))?;
+            if value >= BFieldElement::P {
+                bail!("`{token}` is not a canonical base field element");
+            }
+            Ok(BFieldElement::new(value))
+        })
+        .collect()
+}
+
+/// Uses the canonical representation of each element, such that the output can be read back
+/// by [`parse_field_elements`].
+fn format_field_elements(elements: &[BFieldElement]) -> String {
+    elements.iter().map(|element| element.value()).join(",")
+}
+
+fn compile(args: &CliArgs, source_file: &str) -> Result<()> {
+    let code = tasm_lang::compile_to_string(source_file, &args.entrypoint, args.list_type)?;
+    match &args.output {
+        Some(output_file) => fs::write(output_file, code)
+            .with_context(|| format!("Unable to write to {output_file}"))?,
+        None => println!("{code}"),
+    }
+
+    Ok(())
+}
+
+fn run(args: &CliArgs, source_file: &str) -> Result<()> {
+    let program = args.compile(source_file)?;
+    let public_input = PublicInput::new(args.public_input()?);
+    let non_determinism = NonDeterminism::new(args.secret_input()?);
+    let output = program.run(public_input, non_determinism)?;
+    println!("{}", format_field_elements(&output));
+
+    Ok(())
+}
+
+/// The proof file contains two lines: the encoded claim, followed by the encoded proof.
+fn prove(args: &CliArgs, source_file: &str, proof_file: &str) -> Result<()> {
+    let program = args.compile(source_file)?;
+    let public_input = args.public_input()?.iter().map(|x| x.value()).collect_vec();
+    let secret_input = args.secret_input()?.iter().map(|x| x.value()).collect_vec();
+    let non_determinism = NonDeterminism::new(secret_input);
+
+    let (_, claim, proof) = triton_vm::prove_program(&program, &public_input, &non_determinism)?;
+    let proof_file_content = format!(
+        "{}\n{}\n",
+        format_field_elements(&claim.encode()),
+        format_field_elements(&proof.encode()),
+    );
+    fs::write(proof_file, proof_file_content)
+        .with_context(|| format!("Unable to write to {proof_file}"))?;
+    println!("Public output: {}", format_field_elements(&claim.output));
+
+    Ok(())
+}
+
+fn verify(proof_file: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(proof_file).with_context(|| format!("Unable to read {proof_file}"))?;
+    let Some((claim, proof)) = content.lines().collect_tuple() else {
+        bail!("Proof file {proof_file} must contain exactly two lines: a claim and a proof");
+    };
+
+    let claim = *Claim::decode(&parse_field_elements(claim)?)?;
+    let proof = *Proof::decode(&parse_field_elements(proof)?)?;
+    if !triton_vm::verify(StarkParameters::default(), &claim, &proof) {
+        bail!("Proof is invalid");
+    }
+    println!("Proof is valid");
+
+    Ok(())
+}
 
 fn main() {
-    let mut args = env::args();
-    let _ = args.next(); // executable name
+    if env::args().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return;
+    }
 
-    let filename = match (args.next(), args.next()) {
-        (Some(filename), None) => filename,
-        _ => {
-            eprintln!("Usage: dump-syntax path/to/filename.rs");
+    let args = match CliArgs::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
             process::exit(1);
         }
     };
 
-    let mut file = File::open(&filename).expect("Unable to open file");
+    let result = match &args.command {
+        Command::Compile { source_file } => compile(&args, source_file),
+        Command::Run { source_file } => run(&args, source_file),
+        Command::Prove {
+            source_file,
+            proof_file,
+        } => prove(&args, source_file, proof_file),
+        Command::Verify { proof_file } => verify(proof_file),
+    };
+
+    if let Err(error) = result {
+        match error.downcast_ref::<Diagnostics>() {
+            Some(diagnostics) => eprintln!("{diagnostics}"),
+            None => eprintln!("Error: {error:#}"),
+        }
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut src = String::new();
-    file.read_to_string(&mut src).expect("Unable to read file");
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
 
-    // TODO: Allow this to be set by CLI args
-    let list_type = ListType::Unsafe;
+    fn parse_error(args: &[&str]) -> String {
+        parse(args).unwrap_err().to_string()
+    }
 
-    let output = tasm_lang::compile_to_string(&filename, list_type);
+    #[test]
+    fn parse_compile_with_default_options() {
+        let expected = CliArgs {
+            command: Command::Compile {
+                source_file: "program.rs".to_owned(),
+            },
+            entrypoint: "main".to_owned(),
+            list_type: ListType::Unsafe,
+            public_input: None,
+            secret_input: None,
+            output: None,
+        };
+        assert_eq!(expected, parse(&["compile", "program.rs"]).unwrap());
+    }
+
+    #[test]
+    fn parse_options_in_any_position() {
+        let args = parse(&[
+            "--entrypoint",
+            "verify_proof",
+            "run",
+            "--list-type",
+            "safe",
+            "program.rs",
+            "--public-input",
+            "stdin.txt",
+            "--secret-input",
+            "divine.txt",
+            "-o",
+            "out.tasm",
+        ])
+        .unwrap();
+
+        let expected = CliArgs {
+            command: Command::Run {
+                source_file: "program.rs".to_owned(),
+            },
+            entrypoint: "verify_proof".to_owned(),
+            list_type: ListType::Safe,
+            public_input: Some("stdin.txt".to_owned()),
+            secret_input: Some("divine.txt".to_owned()),
+            output: Some("out.tasm".to_owned()),
+        };
+        assert_eq!(expected, args);
+        assert_eq!(
+            Some("out.tasm".to_owned()),
+            parse(&["compile", "program.rs", "--output", "out.tasm"])
+                .unwrap()
+                .output
+        );
+    }
+
+    #[test]
+    fn parse_prove_and_verify() {
+        assert_eq!(
+            Command::Prove {
+                source_file: "program.rs".to_owned(),
+                proof_file: "program.proof".to_owned(),
+            },
+            parse(&["prove", "program.rs", "program.proof"])
+                .unwrap()
+                .command
+        );
+        assert_eq!(
+            Command::Verify {
+                proof_file: "program.proof".to_owned(),
+            },
+            parse(&["verify", "program.proof"]).unwrap().command
+        );
+    }
+
+    #[test]
+    fn parse_rejects_missing_command() {
+        assert_eq!("Missing command", parse_error(&[]));
+        assert_eq!("Missing command", parse_error(&["--entrypoint", "foo"]));
+    }
+
+    #[test]
+    fn parse_rejects_wrong_number_of_arguments() {
+        for args in [
+            &["compile"][..],
+            &["compile", "a.rs", "b.rs"],
+            &["prove", "program.rs"],
+            &["verify"],
+        ] {
+            let command = args[0];
+            assert_eq!(
+                format!("Invalid arguments for command `{command}`"),
+                parse_error(args)
+            );
+        }
+        assert_eq!(
+            "Invalid arguments for command `build`",
+            parse_error(&["build", "program.rs"])
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_option() {
+        assert_eq!(
+            "Unknown option `--verbose`",
+            parse_error(&["compile", "program.rs", "--verbose"])
+        );
+    }
+
+    #[test]
+    fn parse_rejects_option_without_value() {
+        assert_eq!(
+            "Option `--entrypoint` requires a value",
+            parse_error(&["compile", "program.rs", "--entrypoint"])
+        );
+        assert_eq!(
+            "Option `-o` requires a value",
+            parse_error(&["compile", "program.rs", "-o"])
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_list_type() {
+        assert!(parse(&["compile", "program.rs", "--list-type", "linked"]).is_err());
+    }
+
+    #[test]
+    fn parse_field_elements_separated_by_whitespace_and_commas() {
+        let expected = [1, 2, 3, 4, 5].map(BFieldElement::new).to_vec();
+        assert_eq!(
+            expected,
+            parse_field_elements(" 1,2, 3\n4\t,,5 \n").unwrap()
+        );
+        assert!(parse_field_elements("").unwrap().is_empty());
+        assert!(parse_field_elements(" \n, ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_largest_canonical_field_element() {
+        let largest = BFieldElement::P - 1;
+        assert_eq!(
+            vec![BFieldElement::new(largest)],
+            parse_field_elements(&largest.to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_field_elements_rejects_non_canonical_elements() {
+        let error = parse_field_elements(&BFieldElement::P.to_string()).unwrap_err();
+        assert_eq!(
+            format!(
+                "`{}` is not a canonical base field element",
+                BFieldElement::P
+            ),
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_field_elements_rejects_non_integers() {
+        for content in ["1 two 3", "-1", "1.5", "0x10"] {
+            let error = parse_field_elements(content).unwrap_err();
+            assert!(
+                error.to_string().ends_with("is not an unsigned integer"),
+                "unexpected error for {content:?}: {error}"
+            );
+        }
+        assert!(parse_field_elements(&u128::MAX.to_string()).is_err());
+    }
 
-    println!("{output}");
+    #[test]
+    fn format_and_parse_field_elements_round_trip() {
+        let elements = [0, 1, 42, BFieldElement::P - 1].map(BFieldElement::new);
+        let formatted = format_field_elements(&elements);
+        assert_eq!("0,1,42,18446744069414584320", formatted);
+        assert_eq!(elements.to_vec(), parse_field_elements(&formatted).unwrap());
+    }
 }
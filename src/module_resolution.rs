@@ -0,0 +1,531 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use syn::spanned::Spanned;
+use syn::Item;
+use syn::UseTree;
+
+use crate::compile_error::CompileError;
+use crate::compile_error::Diagnostics;
+use crate::compile_error::ErrorCode;
+
+/// A module of a program, along with the path of the file in which it is declared
+pub(crate) struct Module {
+    pub(crate) file_path: String,
+    pub(crate) file: syn::File,
+}
+
+/// Parse the file at `root_file_path` along with all modules it depends on, either through
+/// `mod foo;` declarations, or through imports like `use crate::foo::Bar;` and
+/// `use super::foo::*;`. Inline modules, `mod foo { ... }`, are returned as separate
+/// modules. Modules annotated with `#[cfg(test)]` are skipped, unless they contain the
+/// entrypoint, like `test` does for the entrypoint `test::foo`. The root module is returned
+/// first.
+pub(crate) fn parse_modules(
+    root_file_path: &str,
+    entrypoint: &str,
+) -> Result<Vec<Module>, Diagnostics> {
+    let root_file_path = PathBuf::from(root_file_path);
+    let root_directory = root_file_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let mut loader = ModuleLoader {
+        root_file_path: canonical_path(&root_file_path),
+        root_directory,
+        entrypoint_module_path: entrypoint_module_path(entrypoint),
+        loaded_files: HashSet::default(),
+        modules: vec![],
+        diagnostics: vec![],
+    };
+    if let Err(error) = loader.load_file(&root_file_path) {
+        let display_path = root_file_path.display();
+        let error = CompileError::new(
+            ErrorCode::SyntaxError,
+            format!("unable to read file `{display_path}`: {error}"),
+        );
+        loader.diagnostics.push(error);
+    }
+
+    Diagnostics::check(loader.diagnostics)?;
+    Ok(loader.modules)
+}
+
+struct ModuleLoader {
+    root_file_path: PathBuf,
+    root_directory: PathBuf,
+
+    /// The names of the inline modules of the root module that lead to the entrypoint
+    entrypoint_module_path: Vec<String>,
+
+    loaded_files: HashSet<PathBuf>,
+    modules: Vec<Module>,
+    diagnostics: Vec<CompileError>,
+}
+
+impl ModuleLoader {
+    /// Load the module in the file at `file_path` and the modules it depends on. An error
+    /// is returned if the file cannot be read, such that the caller can report it at the
+    /// item referring to the module.
+    fn load_file(&mut self, file_path: &Path) -> io::Result<()> {
+        if !self.loaded_files.insert(canonical_path(file_path)) {
+            return Ok(());
+        }
+
+        let display_path = file_path.display().to_string();
+        let content = fs::read_to_string(file_path)?;
+        let parsed_file: syn::File = match syn::parse_str(&content) {
+            Ok(parsed_file) => parsed_file,
+            Err(error) => {
+                self.diagnostics.push(
+                    CompileError::new(ErrorCode::SyntaxError, error.to_string())
+                        .with_span(error.span())
+                        .in_file(Some(&display_path)),
+                );
+                return Ok(());
+            }
+        };
+
+        let scope = self.file_module_scope(file_path);
+        let entrypoint_module_path = if canonical_path(file_path) == self.root_file_path {
+            self.entrypoint_module_path.clone()
+        } else {
+            vec![]
+        };
+        let items = parsed_file.items.clone();
+        self.modules.push(Module {
+            file_path: display_path,
+            file: parsed_file,
+        });
+        self.load_dependencies(&items, &scope, file_path, &entrypoint_module_path);
+
+        Ok(())
+    }
+
+    /// Load module `module_name` in the file at `module_file`, which is referred to by `item`
+    /// in the file at `file_path`. If the file cannot be read, the error is reported at `item`.
+    fn load_module_file(
+        &mut self,
+        module_name: &str,
+        module_file: &Path,
+        item: &impl Spanned,
+        file_path: &Path,
+    ) {
+        if let Err(error) = self.load_file(module_file) {
+            let module_file = module_file.display();
+            let error = CompileError::new(
+                ErrorCode::ModuleNotFound,
+                format!("unable to read file `{module_file}` for module `{module_name}`: {error}"),
+            )
+            .with_span(item.span())
+            .in_file(Some(&file_path.display().to_string()));
+            self.diagnostics.push(error);
+        }
+    }
+
+    /// Load the modules declared or imported in `items`, which are the items of a module
+    /// with the given scope. `entrypoint_module_path` holds the names of the inline modules
+    /// that lead from this module to the entrypoint, if the entrypoint is declared in one.
+    fn load_dependencies(
+        &mut self,
+        items: &[Item],
+        scope: &ModuleScope,
+        file_path: &Path,
+        entrypoint_module_path: &[String],
+    ) {
+        for item in items {
+            let contains_entrypoint = matches!(
+                entrypoint_module_path.first(),
+                Some(module_name) if item_mod_ident_is(item, module_name)
+            );
+            match item {
+                Item::Mod(item_mod) if is_cfg_test(&item_mod.attrs) && !contains_entrypoint => (),
+                Item::Mod(item_mod) => {
+                    let module_name = item_mod.ident.to_string();
+                    match &item_mod.content {
+                        Some((_, inline_items)) => {
+                            self.modules.push(Module {
+                                file_path: file_path.display().to_string(),
+                                file: syn::File {
+                                    shebang: None,
+                                    attrs: vec![],
+                                    items: inline_items.to_owned(),
+                                },
+                            });
+                            let inline_module_scope = ModuleScope {
+                                submodule_directories: scope
+                                    .submodule_directories
+                                    .iter()
+                                    .map(|directory| directory.join(&module_name))
+                                    .collect(),
+                                sibling_directories: scope.submodule_directories.clone(),
+                            };
+                            let entrypoint_module_path: &[String] = if contains_entrypoint {
+                                &entrypoint_module_path[1..]
+                            } else {
+                                &[]
+                            };
+                            self.load_dependencies(
+                                inline_items,
+                                &inline_module_scope,
+                                file_path,
+                                entrypoint_module_path,
+                            );
+                        }
+                        None => {
+                            let submodule_file = scope
+                                .submodule_directories
+                                .iter()
+                                .find_map(|directory| find_module_file(directory, &module_name));
+                            match submodule_file {
+                                Some(submodule_file) => self.load_module_file(
+                                    &module_name,
+                                    &submodule_file,
+                                    &item_mod.ident,
+                                    file_path,
+                                ),
+                                None => self.diagnostics.push(
+                                    CompileError::new(
+                                        ErrorCode::ModuleNotFound,
+                                        format!("file not found for module `{module_name}`"),
+                                    )
+                                    .with_span(item_mod.ident.span())
+                                    .in_file(Some(&file_path.display().to_string())),
+                                ),
+                            }
+                        }
+                    }
+                }
+                Item::Use(item_use) => {
+                    for use_path in use_tree_paths(&item_use.tree) {
+                        self.load_imported_modules(&use_path, scope, item_use, file_path);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Load the modules referenced by an import, like `foo` and `bar` in
+    /// `use crate::foo::bar::Baz;`. Paths that do not start with `crate`, `self`, or `super`,
+    /// as well as path segments that do not correspond to a file, are ignored, as those
+    /// refer to external crates, or to items.
+    fn load_imported_modules(
+        &mut self,
+        use_path: &[String],
+        scope: &ModuleScope,
+        item_use: &syn::ItemUse,
+        file_path: &Path,
+    ) {
+        let (directories, module_names) = match use_path.split_first() {
+            Some((first, rest)) if first == "crate" => (vec![self.root_directory.clone()], rest),
+            Some((first, rest)) if first == "self" => (scope.submodule_directories.clone(), rest),
+            Some((first, rest)) if first == "super" => (scope.sibling_directories.clone(), rest),
+            _ => return,
+        };
+
+        let mut directories = directories;
+        for module_name in module_names {
+            let Some((directory, module_file)) = directories.iter().find_map(|directory| {
+                find_module_file(directory, module_name).map(|file| (directory.clone(), file))
+            }) else {
+                return;
+            };
+            self.load_module_file(module_name, &module_file, item_use, file_path);
+            directories = vec![directory.join(module_name)];
+        }
+    }
+
+    /// Return the scope of the module defined in the file at `file_path`
+    fn file_module_scope(&self, file_path: &Path) -> ModuleScope {
+        let directory = file_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let parent_directory = directory
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let file_stem = file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if file_stem == "mod" {
+            return ModuleScope {
+                submodule_directories: vec![directory],
+                sibling_directories: vec![parent_directory],
+            };
+        }
+
+        if canonical_path(file_path) == self.root_file_path {
+            // The root file may be named anything, so its submodules are looked for both in
+            // the directory named after it and next to it.
+            return ModuleScope {
+                submodule_directories: vec![directory.join(&file_stem), directory.clone()],
+                sibling_directories: vec![directory],
+            };
+        }
+
+        ModuleScope {
+            submodule_directories: vec![directory.join(&file_stem)],
+            sibling_directories: vec![directory],
+        }
+    }
+}
+
+/// The directories in which the files of modules related to a module are located, in order
+/// of preference
+struct ModuleScope {
+    /// Directories containing the files of the module's submodules, `self::<name>`
+    submodule_directories: Vec<PathBuf>,
+
+    /// Directories containing the files of the module's siblings, `super::<name>`
+    sibling_directories: Vec<PathBuf>,
+}
+
+/// Find the file defining module `module_name` in `directory`, which is either
+/// `<module_name>.rs` or `<module_name>/mod.rs`.
+fn find_module_file(directory: &Path, module_name: &str) -> Option<PathBuf> {
+    [
+        directory.join(format!("{module_name}.rs")),
+        directory.join(module_name).join("mod.rs"),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())
+}
+
+/// Return all paths of an import. `use a::{b::C, d::*};` has the paths `a::b::C` and `a::d`.
+fn use_tree_paths(tree: &UseTree) -> Vec<Vec<String>> {
+    match tree {
+        UseTree::Path(use_path) => use_tree_paths(&use_path.tree)
+            .into_iter()
+            .map(|mut path| {
+                path.insert(0, use_path.ident.to_string());
+                path
+            })
+            .collect(),
+        UseTree::Name(use_name) => vec![vec![use_name.ident.to_string()]],
+        UseTree::Rename(use_rename) => vec![vec![use_rename.ident.to_string()]],
+        UseTree::Glob(_) => vec![vec![]],
+        UseTree::Group(use_group) => use_group.items.iter().flat_map(use_tree_paths).collect(),
+    }
+}
+
+/// Return the names of the modules in the path of an entrypoint like `test::foo`
+pub(crate) fn entrypoint_module_path(entrypoint: &str) -> Vec<String> {
+    let mut path = entrypoint.split("::").map(str::to_owned).collect_vec();
+    path.pop();
+    path
+}
+
+fn item_mod_ident_is(item: &Item, module_name: &str) -> bool {
+    matches!(item, Item::Mod(item_mod) if item_mod.ident == module_name)
+}
+
+fn is_cfg_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path.is_ident("cfg")
+            && attr
+                .parse_args::<syn::Ident>()
+                .is_ok_and(|ident| ident == "test")
+    })
+}
+
+fn canonical_path(file_path: &Path) -> PathBuf {
+    fs::canonicalize(file_path).unwrap_or_else(|_| file_path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    /// Write the files of a program to a new directory and return the path of the directory
+    fn write_program(name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "tasm_lang_module_resolution_{name}_{}",
+            std::process::id()
+        ));
+        for (file_name, content) in files {
+            let file_path = directory.join(file_name);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(file_path, content).unwrap();
+        }
+
+        directory
+    }
+
+    /// The paths of the files of the modules, relative to `directory`
+    fn module_files(modules: &[Module], directory: &Path) -> Vec<String> {
+        modules
+            .iter()
+            .map(|module| {
+                let file_path = Path::new(&module.file_path);
+                file_path
+                    .strip_prefix(directory)
+                    .unwrap()
+                    .display()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn loader(root_file_path: &str) -> ModuleLoader {
+        let root_file_path = PathBuf::from(root_file_path);
+        ModuleLoader {
+            root_directory: root_file_path.parent().unwrap().to_path_buf(),
+            root_file_path,
+            entrypoint_module_path: vec![],
+            loaded_files: HashSet::default(),
+            modules: vec![],
+            diagnostics: vec![],
+        }
+    }
+
+    #[test]
+    fn use_tree_paths_test() {
+        let item_use: syn::ItemUse = parse_quote!(
+            use a::{b::C, d::*, e as f};
+        );
+        let expected = vec![
+            vec!["a".to_owned(), "b".to_owned(), "C".to_owned()],
+            vec!["a".to_owned(), "d".to_owned()],
+            vec!["a".to_owned(), "e".to_owned()],
+        ];
+        assert_eq!(expected, use_tree_paths(&item_use.tree));
+
+        let item_use: syn::ItemUse = parse_quote!(
+            use super::*;
+        );
+        assert_eq!(
+            vec![vec!["super".to_owned()]],
+            use_tree_paths(&item_use.tree)
+        );
+    }
+
+    #[test]
+    fn file_module_scope_test() {
+        let loader = loader("/program/main.rs");
+        let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect_vec();
+
+        // The root file may be named anything
+        let scope = loader.file_module_scope(Path::new("/program/main.rs"));
+        assert_eq!(
+            paths(&["/program/main", "/program"]),
+            scope.submodule_directories
+        );
+        assert_eq!(paths(&["/program"]), scope.sibling_directories);
+
+        let scope = loader.file_module_scope(Path::new("/program/foo.rs"));
+        assert_eq!(paths(&["/program/foo"]), scope.submodule_directories);
+        assert_eq!(paths(&["/program"]), scope.sibling_directories);
+
+        let scope = loader.file_module_scope(Path::new("/program/foo/mod.rs"));
+        assert_eq!(paths(&["/program/foo"]), scope.submodule_directories);
+        assert_eq!(paths(&["/program"]), scope.sibling_directories);
+    }
+
+    #[test]
+    fn cfg_test_modules_are_skipped_unless_they_contain_the_entrypoint_test() {
+        let root_file = "\
+mod a;
+
+#[cfg(test)]
+mod b;
+
+#[cfg(test)]
+mod test {
+    use super::c::*;
+
+    fn foo() {}
+}
+
+fn main() {}
+";
+        let directory = write_program(
+            "cfg_test",
+            &[
+                ("main.rs", root_file.as_bytes()),
+                ("a.rs", b"fn a() {}"),
+                ("b.rs", b"fn b() {}"),
+                ("c.rs", b"fn c() {}"),
+            ],
+        );
+        let root_file_path = directory.join("main.rs").display().to_string();
+
+        let modules = parse_modules(&root_file_path, "main").unwrap();
+        assert_eq!(vec!["main.rs", "a.rs"], module_files(&modules, &directory));
+
+        let modules = parse_modules(&root_file_path, "test::foo").unwrap();
+        assert_eq!(
+            vec!["main.rs", "a.rs", "main.rs", "c.rs"],
+            module_files(&modules, &directory)
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn self_and_super_imports_are_relative_to_the_importing_module_test() {
+        let directory = write_program(
+            "self_and_super",
+            &[
+                ("main.rs", b"mod foo;\n\nfn main() {}\n"),
+                ("foo.rs", b"use self::bar::Bar;\nuse super::baz::*;\n"),
+                ("foo/bar.rs", b"struct Bar(u32);"),
+                ("baz.rs", b"fn baz() {}"),
+            ],
+        );
+        let root_file_path = directory.join("main.rs").display().to_string();
+
+        let modules = parse_modules(&root_file_path, "main").unwrap();
+        assert_eq!(
+            vec!["main.rs", "foo.rs", "foo/bar.rs", "baz.rs"],
+            module_files(&modules, &directory)
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn unreadable_module_files_are_reported_at_the_referring_item_test() {
+        let directory = write_program(
+            "unreadable",
+            &[
+                (
+                    "main.rs",
+                    b"mod foo;\nuse crate::bar::Bar;\n\nfn main() {}\n",
+                ),
+                ("foo.rs", b"\xff\xfe"),
+                ("bar.rs", b"\xff\xfe"),
+            ],
+        );
+        let root_file_path = directory.join("main.rs").display().to_string();
+
+        let diagnostics = parse_modules(&root_file_path, "main").err().unwrap();
+        let codes_and_lines = diagnostics
+            .errors
+            .iter()
+            .map(|error| (error.code, error.location.as_ref().unwrap().line))
+            .collect_vec();
+        assert_eq!(
+            vec![
+                (ErrorCode::ModuleNotFound, 1),
+                (ErrorCode::ModuleNotFound, 2)
+            ],
+            codes_and_lines
+        );
+        assert!(diagnostics.errors[0]
+            .message
+            .contains("for module `foo`: stream did not contain valid UTF-8"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
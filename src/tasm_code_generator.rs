@@ -1,6 +1,7 @@
 mod data_type;
 mod function_state;
 mod inner_function_tasm_code;
+mod loop_control;
 mod outer_function_tasm_code;
 mod stack;
 
@@ -114,6 +115,13 @@ pub struct GlobalCodeGeneratorState {
     static_allocations: HashMap<ValueIdentifier, (BFieldElement, ast_types::DataType)>,
     compiled_methods_and_afs: HashMap<String, InnerFunctionTasmCode>,
     library_snippets: HashMap<String, SubRoutine>,
+
+    /// The addresses of the values of the `static` items of the program, by their names
+    statics: HashMap<String, BFieldElement>,
+
+    /// The memory that the operands of `==` and `!=` are moved to, by the label friendly
+    /// names of their types
+    eq_scratch_areas: HashMap<String, (BFieldElement, BFieldElement)>,
 }
 
 impl GlobalCodeGeneratorState {
@@ -221,7 +229,11 @@ impl<'a> CompilerState<'a> {
         let mut fn_arg_spilling = vec![];
         for input_arg in input_arguments {
             match input_arg {
-                ast_types::AbstractArgument::FunctionArgument(_) => todo!(),
+                ast_types::AbstractArgument::FunctionArgument(_) => unreachable!(
+                    "functions taking function arguments must be compiled through their \
+                     instantiations, which take the values captured by the functions passed \
+                     to them instead"
+                ),
                 ast_types::AbstractArgument::ValueArgument(abstract_input) => {
                     let (fn_arg_addr, spill) =
                         self.new_value_identifier(FN_ARG_NAME_PREFIX, &abstract_input.data_type);
@@ -255,6 +267,10 @@ impl<'a> CompilerState<'a> {
         match identifier {
             ast::Identifier::String(_, _) => {
                 let var_name = identifier.binding_name();
+                if let Some(static_address) = self.global_compiler_state.statics.get(&var_name) {
+                    return ValueLocation::StaticMemoryAddress(*static_address);
+                }
+
                 let var_addr = self
                     .function_state
                     .var_addr
@@ -752,37 +768,28 @@ impl<'a> CompilerState<'a> {
         previous_stack: &VStack,
         previous_var_addr: &VarAddr,
     ) -> Vec<LabelledInstruction> {
-        // Clear stack, vstack, and var_addr of locally declared values for those that are on top of the stack
+        // Clear stack and vstack of the values that were put there inside of the code block.
+        // These are always on top of the values that were present when the block was entered.
+        // Whether a value is local is determined by its value identifier and not by the name
+        // of a binding to it, since a local binding may shadow a binding from the outside.
         let mut code = vec![];
-        loop {
-            let (addr, (dt, spilled)) = self.function_state.vstack.peek().unwrap().to_owned();
-            let binding_name = self
-                .function_state
-                .var_addr
+        while let Some((addr, (dt, _spilled))) = self.function_state.vstack.peek().cloned() {
+            let is_declared_outside_block = previous_stack
+                .inner
                 .iter()
-                .find(|(_var_name, ident)| **ident == addr)
-                //.unwrap_or_else(|| panic!("Cannot handle stack cleanup of unbound values"))
-                .map(|x| x.0.clone());
-            // .0
-            // .clone();
-            if binding_name.is_some()
-                && previous_var_addr.contains_key(&binding_name.clone().unwrap())
-                || binding_name.is_none()
-                    && previous_stack
-                        .inner
-                        .contains(&(addr, (dt.clone(), spilled)))
-            {
+                .any(|(previous_addr, _)| *previous_addr == addr);
+            if is_declared_outside_block {
                 break;
-            } else {
-                code.extend(pop_n(dt.stack_size()));
-                self.function_state.vstack.pop();
-                if let Some(binding) = binding_name {
-                    let removed = self.function_state.var_addr.remove(&binding);
-                    assert!(removed.is_some());
-                }
             }
+
+            code.extend(pop_n(dt.stack_size()));
+            self.function_state.vstack.pop();
         }
 
+        // Bindings declared inside of the block go out of scope, and the bindings they
+        // shadowed are visible again.
+        self.function_state.var_addr = previous_var_addr.clone();
+
         // Verify that ordering of previously declared bindings did not change from the execution of
         // this code block.
         self.verify_same_ordering_of_bindings(previous_stack, previous_var_addr);
@@ -1024,6 +1031,28 @@ pub(crate) fn compile_function(
 ) -> OuterFunctionTasmCode {
     let mut state =
         CompilerState::new(GlobalCodeGeneratorState::default(), libraries, custom_types);
+
+    // The values of the `static` items are stored in static memory, such that all functions
+    // can read them
+    if let RoutineBody::Ast(stmts) = &function.body {
+        for stmt in stmts {
+            let ast::Stmt::Static(ast::StaticStmt {
+                name, data_type, ..
+            }) = stmt
+            else {
+                continue;
+            };
+            let value_identifier = ValueIdentifier::from(format!("static_{}", name.to_lowercase()));
+            let address = state
+                .global_compiler_state
+                .allocate_for_value_id(&value_identifier, data_type);
+            state
+                .global_compiler_state
+                .statics
+                .insert(name.to_owned(), address);
+        }
+    }
+
     let compiled_function = compile_function_inner(
         function,
         &mut state.global_compiler_state,
@@ -1054,6 +1083,55 @@ fn compile_block_stmt(
     [block_body_code, restore_stack_code].concat()
 }
 
+/// Bind the parts of a destructured value to the bindings of a pattern by splitting the
+/// compiler's view of the value. The value itself is neither moved nor copied.
+fn bind_destructured_value(
+    pattern: &ast::DestructuringPattern,
+    value_identifier: ValueIdentifier,
+    data_type: &ast_types::DataType,
+    state: &mut CompilerState,
+) {
+    match pattern {
+        ast::DestructuringPattern::Wildcard => (),
+        ast::DestructuringPattern::Binding(binding) => {
+            state
+                .function_state
+                .var_addr
+                .insert(binding.name.to_owned(), value_identifier);
+        }
+        ast::DestructuringPattern::Tuple(elements) => {
+            let element_types = data_type.as_tuple_type().fields;
+            let element_identifiers = state.split_value(&value_identifier, element_types.clone());
+            for ((element, element_identifier), element_type) in elements
+                .iter()
+                .zip_eq(element_identifiers)
+                .zip_eq(element_types.iter())
+            {
+                bind_destructured_value(element, element_identifier, element_type, state);
+            }
+        }
+        ast::DestructuringPattern::Struct { fields, .. } => {
+            let ast_types::DataType::Struct(struct_type) = data_type else {
+                panic!("Only structs can be destructured by struct patterns. Got: {data_type}");
+            };
+            let (field_ids, field_types): (Vec<_>, Vec<_>) = struct_type
+                .field_ids_and_types()
+                .map(|(field_id, field_type)| (field_id, field_type.to_owned()))
+                .unzip();
+            let field_identifiers = state.split_value(&value_identifier, field_types.clone());
+            for (field_id, field_pattern) in fields.iter() {
+                let index = field_ids.iter().position(|x| x == field_id).unwrap();
+                bind_destructured_value(
+                    field_pattern,
+                    field_identifiers[index].clone(),
+                    &field_types[index],
+                    state,
+                );
+            }
+        }
+    }
+}
+
 /// Produce the code and handle the `vstack` for a statement. `env_fn_signature` is the
 /// function signature in which the statement is enclosed.
 fn compile_stmt(
@@ -1070,6 +1148,12 @@ fn compile_stmt(
             expr_code
         }
 
+        ast::Stmt::DestructuringLet(ast::DestructuringLetStmt { pattern, expr, .. }) => {
+            let (expr_addr, expr_code) = compile_expr(expr, "destructuring_let", state);
+            bind_destructured_value(pattern, expr_addr, &expr.get_type(), state);
+            expr_code
+        }
+
         ast::Stmt::Assign(ast::AssignStmt { identifier, expr }) => {
             // When overwriting a value, we ignore the value identifier of the new expression as
             // it's simply popped from the stack and the old value identifier is used.
@@ -1127,18 +1211,18 @@ fn compile_stmt(
         }
 
         ast::Stmt::Return(Some(ret_expr)) => {
-            // special-case on returning variable, without unnecessary dup-instructions
-            let expr_code = if let ast::Expr::Var(ast::Identifier::String(var_name, _known_type)) =
-                ret_expr
-            {
+            // special-case on returning variable, without unnecessary dup-instructions. `static`
+            // items are not bound on the stack, so they are returned like other expressions.
+            let returned_binding = match ret_expr {
+                ast::Expr::Var(ast::Identifier::String(var_name, _known_type)) => {
+                    state.function_state.var_addr.get(var_name).cloned()
+                }
+                _ => None,
+            };
+            let expr_code = if let Some(needle) = returned_binding {
                 // Case: Returning a bound variable
 
                 // Remove everything above returned value
-                let needle = state
-                    .function_state
-                    .var_addr
-                    .get(var_name)
-                    .expect("Returned value must exist in value/addr map");
                 let mut code = vec![];
 
                 // If the returned value is a spilled value, we pop everything and
@@ -1150,7 +1234,7 @@ fn compile_stmt(
                     // This loop pops values from the stack until the value we want to return.
                     // Then the below if-expression takes over when the value is found.
                     let (haystack, (dt, spilled)) = state.function_state.vstack.peek().unwrap();
-                    if *haystack == *needle {
+                    if *haystack == needle {
                         match spilled {
                             Some(spill_addr) => {
                                 // Value is spilled, so we load it from memory and clear that stack.
@@ -1195,7 +1279,14 @@ fn compile_stmt(
 
         ast::Stmt::MethodCall(method_call) => compile_method_call(method_call, state),
 
-        ast::Stmt::While(ast::WhileStmt { condition, block }) => {
+        ast::Stmt::While(while_stmt) if loop_control::contains_loop_exits(&while_stmt.block) => {
+            let lowered = loop_control::lower_while_stmt(while_stmt, state);
+            compile_stmt(&lowered, state)
+        }
+
+        ast::Stmt::While(ast::WhileStmt {
+            condition, block, ..
+        }) => {
             // The code generated here is a subroutine that contains the while loop code
             // and then just a call to this subroutine.
             let (cond_addr, cond_evaluation_code) =
@@ -1227,6 +1318,19 @@ fn compile_stmt(
             })
         }
 
+        ast::Stmt::Loop(loop_stmt) => {
+            let lowered = loop_control::lower_loop_stmt(loop_stmt, state);
+            compile_stmt(&lowered, state)
+        }
+
+        ast::Stmt::Break(_) | ast::Stmt::Continue(_) => {
+            unreachable!("`break` and `continue` must be lowered along with their enclosing loop")
+        }
+
+        ast::Stmt::For(_) => {
+            unreachable!("`for` loops must be lowered to `while` loops by the type checker")
+        }
+
         ast::Stmt::If(ast::IfStmt {
             condition,
             then_branch,
@@ -1292,6 +1396,10 @@ fn compile_stmt(
             )
         }
         ast::Stmt::Panic(_) => triton_asm! {push 0 hint panic = stack[0] assert},
+        ast::Stmt::Location(_) => vec![],
+        // Generic functions and functions taking function arguments are compiled through
+        // their instantiations
+        ast::Stmt::FnDeclaration(function) if function.is_instantiated_per_call() => vec![],
         ast::Stmt::FnDeclaration(function) => {
             let compiled_fn = compile_function_inner(
                 function,
@@ -1305,61 +1413,136 @@ fn compile_stmt(
 
             vec![]
         }
-        ast::Stmt::Match(match_stmt) => {
-            let vstack_init = state.function_state.vstack.clone();
-            let var_addr_init = state.function_state.var_addr.clone();
-            let (match_expr_id, match_expr_evaluation) =
-                compile_expr(&match_stmt.match_expression, "match-expr", state);
-            assert!(
-                !state.function_state.spill_required.contains(&match_expr_id),
-                "Cannot handle memory-spill of evaluated match expressions. But {match_expr_id} required memory spilling"
-            );
-
-            // stack: // _ [enum]
-            let match_stmt = match match_stmt.match_expression.get_type() {
-                ast_types::DataType::Enum(_) => {
-                    compile_match_stmt_stack_expr(match_stmt, state, &match_expr_id)
-                }
-                ast_types::DataType::Boxed(inner) => match *inner.to_owned() {
-                    ast_types::DataType::Enum(_) => {
-                        compile_match_stmt_boxed_expr(match_stmt, state, &match_expr_id)
-                    }
-                    _ => unreachable!(),
-                },
-                _ => unreachable!(),
-            };
+        ast::Stmt::Match(ast::MatchStmt {
+            arms,
+            match_expression,
+        }) => {
+            let arms = arms
+                .iter()
+                .map(|arm| (&arm.match_condition, MatchArmBody::Block(&arm.body)))
+                .collect_vec();
+            compile_match(match_expression, &arms, state)
+        }
+        ast::Stmt::Static(ast::StaticStmt {
+            name,
+            data_type,
+            expr,
+        }) => {
+            let (_expr_addr, expr_code) = compile_expr(expr, name, state);
+            let static_address = state.global_compiler_state.statics[name];
 
-            // Remove match-expression from stack
-            let restore_stack_code = state.restore_stack_code(&vstack_init, &var_addr_init);
+            // The value is moved to static memory
+            state.function_state.vstack.pop();
 
             triton_asm!(
-                // _
-                {&match_expr_evaluation}
-                // _ match_expr
+                {&expr_code}
+                {&move_top_stack_value_to_memory(Some(static_address), data_type.stack_size())}
+            )
+        }
+    }
+}
 
-                {&match_stmt}
-                // _ match_expr
+/// The body of an arm of a match statement or of a match expression
+enum MatchArmBody<'a> {
+    Block(&'a ast::BlockStmt<type_checker::Typing>),
 
-                {&restore_stack_code}
+    /// The body of an arm of a match expression. The value returned by the body is moved into
+    /// a placeholder on the stack, whose top element is at stack height `result_stack_height`.
+    Returning {
+        block: &'a ast::ReturningBlock<type_checker::Typing>,
+        result_stack_height: usize,
+    },
+}
 
-                // _
-            )
+/// Evaluate the matched-against value of a match statement or expression, execute the body of
+/// the arm it matches, and remove the matched-against value from the stack again.
+fn compile_match(
+    match_expression: &ast::Expr<type_checker::Typing>,
+    arms: &[(&ast::MatchCondition<type_checker::Typing>, MatchArmBody)],
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    let vstack_init = state.function_state.vstack.clone();
+    let var_addr_init = state.function_state.var_addr.clone();
+    let (match_expr_id, match_expr_evaluation) =
+        compile_expr(match_expression, "match-expr", state);
+    assert!(
+        !state.function_state.spill_required.contains(&match_expr_id),
+        "Cannot handle memory-spill of evaluated match expressions. But {match_expr_id} required memory spilling"
+    );
+
+    // stack: // _ [enum]
+    let match_code = match match_expression.get_type() {
+        ast_types::DataType::Enum(_) => {
+            compile_match_stmt_stack_expr(match_expression, arms, state, &match_expr_id)
         }
-    }
+        ast_types::DataType::Boxed(inner) => match *inner.to_owned() {
+            ast_types::DataType::Enum(_) => {
+                compile_match_stmt_boxed_expr(match_expression, arms, state, &match_expr_id)
+            }
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    // Remove match-expression from stack
+    let restore_stack_code = state.restore_stack_code(&vstack_init, &var_addr_init);
+
+    triton_asm!(
+        // _
+        {&match_expr_evaluation}
+        // _ match_expr
+
+        {&match_code}
+        // _ match_expr
+
+        {&restore_stack_code}
+
+        // _
+    )
+}
+
+/// Compile the body of a match arm. The body of an arm of a match expression leaves the stack
+/// unchanged, as its value is moved into the placeholder for the result of the expression.
+fn compile_match_arm_body(
+    body: &MatchArmBody,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    let (block, result_stack_height) = match body {
+        MatchArmBody::Block(block) => return compile_block_stmt(block, state),
+        MatchArmBody::Returning {
+            block,
+            result_stack_height,
+        } => (block, *result_stack_height),
+    };
+
+    let (_, body_code) = compile_returning_block_expr("match_arm", state, block);
+
+    // stack: _ [result_placeholder] ... [result]
+    // Moving the top element of the result into the placeholder and popping the placeholder's
+    // old element leaves the next element of the result at the same distance from its place.
+    let result_size = block.get_type().stack_size();
+    let distance_to_placeholder =
+        state.function_state.vstack.get_stack_height() + result_size - result_stack_height;
+    assert!(
+        distance_to_placeholder < SIZE_OF_ACCESSIBLE_STACK,
+        "Cannot handle match expressions whose result is placed deeper than the accessible stack"
+    );
+    let move_result_to_placeholder =
+        vec![triton_asm!(swap { distance_to_placeholder } pop 1); result_size].concat();
+
+    [body_code, move_result_to_placeholder].concat()
 }
 
-/// Compile a match-statement where the matched-against value lives in memory
+/// Compile a match statement or expression where the matched-against value lives in memory
 fn compile_match_stmt_boxed_expr(
-    ast::MatchStmt {
-        arms,
-        match_expression,
-    }: &ast::MatchStmt<type_checker::Typing>,
+    match_expression: &ast::Expr<type_checker::Typing>,
+    arms: &[(&ast::MatchCondition<type_checker::Typing>, MatchArmBody)],
     state: &mut CompilerState,
     match_expr_id: &ValueIdentifier,
 ) -> Vec<LabelledInstruction> {
     let contains_wildcard = arms
         .iter()
-        .any(|x| matches!(x.match_condition, ast::MatchCondition::CatchAll));
+        .any(|(match_condition, _)| matches!(match_condition, ast::MatchCondition::CatchAll));
 
     let mut match_code = vec![];
     let match_expr_discriminant = if contains_wildcard {
@@ -1387,13 +1570,13 @@ fn compile_match_stmt_boxed_expr(
     let outer_vstack = state.function_state.vstack.clone();
     let outer_bindings = state.function_state.var_addr.clone();
 
-    for (arm_counter, arm) in arms.iter().enumerate() {
+    for (arm_counter, (match_condition, arm_body)) in arms.iter().enumerate() {
         // At start of each loop-iternation, stack is:
         // stack: _ *match_expression <no_arm_taken>
 
         let arm_subroutine_label = format!("{match_expr_id}_body_{arm_counter}");
 
-        match &arm.match_condition {
+        match match_condition {
             ast::MatchCondition::EnumVariant(enum_variant_selector) => {
                 let arm_variant_discriminant = match_expression_enum_type
                     .variant_discriminant(&enum_variant_selector.variant_name);
@@ -1453,7 +1636,7 @@ fn compile_match_stmt_boxed_expr(
                         .insert(binding.name.to_owned(), new_binding_id);
                     });
 
-                let body_code = compile_block_stmt(&arm.body, state);
+                let body_code = compile_match_arm_body(arm_body, state);
 
                 let pop_local_bindings = pop_n(enum_variant_selector.data_bindings.len());
                 let subroutine_code = triton_asm!(
@@ -1489,7 +1672,7 @@ fn compile_match_stmt_boxed_expr(
                     push 0 // push 0 to make stack-cleanup code-path independent
                 ));
 
-                let body_code = compile_block_stmt(&arm.body, state);
+                let body_code = compile_match_arm_body(arm_body, state);
                 let subroutine_code = triton_asm!(
                     {arm_subroutine_label}:
                         {&body_code}
@@ -1500,6 +1683,11 @@ fn compile_match_stmt_boxed_expr(
                     .subroutines
                     .push(subroutine_code.try_into().unwrap());
             }
+            ast::MatchCondition::Pattern(_) => {
+                unreachable!(
+                    "matches on patterns must be lowered to `if` statements by the type checker"
+                )
+            }
         }
 
         // Restore stack view and bindings view for next loop-iteration
@@ -1516,18 +1704,16 @@ fn compile_match_stmt_boxed_expr(
     triton_asm!({ &match_code })
 }
 
-/// Compile a match-statement where the matched-against value lives on the stack
+/// Compile a match statement or expression where the matched-against value lives on the stack
 fn compile_match_stmt_stack_expr(
-    ast::MatchStmt {
-        arms,
-        match_expression,
-    }: &ast::MatchStmt<type_checker::Typing>,
+    match_expression: &ast::Expr<type_checker::Typing>,
+    arms: &[(&ast::MatchCondition<type_checker::Typing>, MatchArmBody)],
     state: &mut CompilerState,
     match_expr_id: &ValueIdentifier,
 ) -> Vec<LabelledInstruction> {
     let contains_wildcard = arms
         .iter()
-        .any(|x| matches!(x.match_condition, ast::MatchCondition::CatchAll));
+        .any(|(match_condition, _)| matches!(match_condition, ast::MatchCondition::CatchAll));
 
     let mut match_code = if contains_wildcard {
         // Indicate that no arm body has been executed yet. For wildcard arm-conditions.
@@ -1541,13 +1727,13 @@ fn compile_match_stmt_stack_expr(
     let outer_vstack = state.function_state.vstack.clone();
     let outer_bindings = state.function_state.var_addr.clone();
     let match_expr_discriminant = triton_asm!(dup {contains_wildcard as u32});
-    for (arm_counter, arm) in arms.iter().enumerate() {
+    for (arm_counter, (match_condition, arm_body)) in arms.iter().enumerate() {
         // At start of each loop-iteration, stack is:
         // stack: _ [expression_variant_data] expression_variant_discriminant <no_arm_taken>
 
         let arm_subroutine_label = format!("{match_expr_id}_body_{arm_counter}");
 
-        match &arm.match_condition {
+        match match_condition {
             ast::MatchCondition::EnumVariant(enum_variant_selector) => {
                 // We know that variant discriminant is on top
                 let arm_variant_discriminant = match_expression_enum_type
@@ -1597,7 +1783,7 @@ fn compile_match_stmt_stack_expr(
                             .insert(binding.name.to_owned(), new_id.clone());
                     });
 
-                let body_code = compile_block_stmt(&arm.body, state);
+                let body_code = compile_match_arm_body(arm_body, state);
 
                 // This arm-body changes the `arm_taken` bool but otherwise leaves the stack unchanged
                 let subroutine_code = triton_asm!(
@@ -1625,7 +1811,7 @@ fn compile_match_stmt_stack_expr(
                     push 0 // push 0 to make stack-cleanup code-path independent
                 ));
 
-                let body_code = compile_block_stmt(&arm.body, state);
+                let body_code = compile_match_arm_body(arm_body, state);
                 let subroutine_code = triton_asm!(
                     {arm_subroutine_label}:
                         {&body_code}
@@ -1637,6 +1823,11 @@ fn compile_match_stmt_stack_expr(
                     .push(subroutine_code.try_into().unwrap());
                 // stack: _  [expression_variant_data] expression_variant_discriminant <no_arm_taken>
             }
+            ast::MatchCondition::Pattern(_) => {
+                unreachable!(
+                    "matches on patterns must be lowered to `if` statements by the type checker"
+                )
+            }
         }
 
         // Restore stack view and bindings view for next loop-iteration
@@ -1678,7 +1869,7 @@ fn compile_fn_call(
         if let Some(fn_name) = lib.get_function_name(&fn_call.name) {
             call_fn_code.append(&mut lib.call_function(
                 &fn_name,
-                fn_call.type_parameter.to_owned(),
+                fn_call.type_parameter(),
                 &fn_call.args,
                 state,
             ));
@@ -1689,7 +1880,12 @@ fn compile_fn_call(
     // Associated functions are in scope in `ftable`, they must be called with `<Type>::<function_name>`,
     // where function_name must be lower-cased.
     if call_fn_code.is_empty() {
-        match state.composite_types.get_associated_function(&fn_call.name) {
+        let afunc = state.composite_types.get_associated_function(
+            &fn_call.name,
+            &fn_call.args,
+            Some(&fn_call.annot.get_type()),
+        );
+        match afunc {
             None => (),
             Some(afunc) => {
                 let function_label: String = afunc.get_tasm_label();
@@ -1827,6 +2023,38 @@ fn compile_method_call(
     [args_code.concat(), call_code].concat()
 }
 
+/// Compile the operands of a short-circuiting operator. The right-hand side is compiled into
+/// a subroutine that replaces the value of the left-hand side on top of the stack with the
+/// value of the right-hand side. Returns the name of this subroutine, and the code for the
+/// left-hand side.
+fn compile_short_circuit_operands(
+    lhs_expr: &ast::Expr<type_checker::Typing>,
+    rhs_expr: &ast::Expr<type_checker::Typing>,
+    subroutine_prefix: &str,
+    state: &mut CompilerState,
+) -> (String, Vec<LabelledInstruction>) {
+    let (_lhs_expr_addr, lhs_expr_code) = compile_expr(lhs_expr, "_binop_lhs", state);
+
+    // The left-hand side is popped before the right-hand side is evaluated.
+    state.function_state.vstack.pop();
+    let (_rhs_expr_addr, rhs_expr_code) = compile_expr(rhs_expr, "_binop_rhs", state);
+    state.function_state.vstack.pop();
+
+    let rhs_subroutine_name = state.unique_label(subroutine_prefix, None);
+    let rhs_subroutine = triton_asm!(
+        {rhs_subroutine_name}:
+            pop 1
+            {&rhs_expr_code}
+            return
+    );
+    state
+        .function_state
+        .subroutines
+        .push(rhs_subroutine.try_into().unwrap());
+
+    (rhs_subroutine_name, lhs_expr_code)
+}
+
 fn compile_expr(
     expr: &ast::Expr<type_checker::Typing>,
     _context: &str,
@@ -1839,6 +2067,14 @@ fn compile_expr(
                 triton_asm!(push {*value as u64})
             }
 
+            ast::ExprLit::U8(value) => {
+                triton_asm!(push {*value as u64})
+            }
+
+            ast::ExprLit::U16(value) => {
+                triton_asm!(push {*value as u64})
+            }
+
             ast::ExprLit::U32(value) => {
                 triton_asm!(push {*value as u64})
             }
@@ -1857,6 +2093,18 @@ fn compile_expr(
                 code
             }
 
+            ast::ExprLit::I32(value) => {
+                triton_asm!(push {*value as u32 as u64})
+            }
+
+            ast::ExprLit::I64(value) => {
+                let value = *value as u64;
+                triton_asm!(
+                    push {value >> 32}
+                    push {value & u32::MAX as u64}
+                )
+            }
+
             ast::ExprLit::U128(value) => {
                 let mut stack_serialized = value.encode();
                 stack_serialized.reverse();
@@ -2053,14 +2301,22 @@ fn compile_expr(
             let (_inner_expr_addr, inner_expr_code) = compile_expr(rhs_expr, "unop_operand", state);
             let code = match unaryop {
                 ast::UnaryOp::Neg => match rhs_type {
+                    ast_types::DataType::I32 | ast_types::DataType::I64 => {
+                        rhs_type.signed_integer_neg_code(state)
+                    }
                     ast_types::DataType::Bfe => triton_asm!(push -1 mul),
                     ast_types::DataType::Xfe => triton_asm!(push -1 xbmul),
                     _ => panic!("Unsupported negation of type {rhs_type}"),
                 },
                 ast::UnaryOp::Not => match rhs_type {
                     ast_types::DataType::Bool => triton_asm!(push 0 eq),
-                    ast_types::DataType::U32 => triton_asm!(push {u32::MAX as u64} xor),
-                    ast_types::DataType::U64 => triton_asm!(
+                    ast_types::DataType::U8 | ast_types::DataType::U16 => {
+                        triton_asm!(push {rhs_type.small_unsigned_integer_max()} xor)
+                    }
+                    ast_types::DataType::U32 | ast_types::DataType::I32 => {
+                        triton_asm!(push {u32::MAX as u64} xor)
+                    }
+                    ast_types::DataType::U64 | ast_types::DataType::I64 => triton_asm!(
                         swap 1
                         push {u32::MAX as u64}
                         xor
@@ -2103,6 +2359,10 @@ fn compile_expr(
                         compile_expr(rhs_expr, "_binop_rhs", state);
 
                     let add_code = match result_type {
+                        ast_types::DataType::U8 | ast_types::DataType::U16 => triton_asm!(
+                            add
+                            {&result_type.small_unsigned_integer_range_check_code()}
+                        ),
                         ast_types::DataType::U32 => {
                             // We use the safe, overflow-checking, add code as default
                             let safe_add_u32 = state.import_snippet(Box::new(
@@ -2126,6 +2386,9 @@ fn compile_expr(
 
                             triton_asm!(call { add_u128 })
                         }
+                        ast_types::DataType::I32 | ast_types::DataType::I64 => {
+                            result_type.signed_integer_add_code(state)
+                        }
                         ast_types::DataType::Bfe => triton_asm!(add),
                         ast_types::DataType::Xfe => triton_asm!(xxadd),
                         _ => panic!("Operator add is not supported for type {result_type}"),
@@ -2137,21 +2400,22 @@ fn compile_expr(
                     [lhs_expr_code, rhs_expr_code, add_code].concat()
                 }
                 ast::BinOp::And => {
-                    let (_lhs_expr_addr, lhs_expr_code) =
-                        compile_expr(lhs_expr, "_binop_lhs", state);
-
-                    let (_rhs_expr_addr, rhs_expr_code) =
-                        compile_expr(rhs_expr, "_binop_rhs", state);
-
-                    let and_code = match result_type {
-                        ast_types::DataType::Bool => triton_asm!(add push 2 eq),
-                        _ => panic!("Logical AND operator is not supported for {result_type}"),
-                    };
+                    assert!(
+                        result_type == ast_types::DataType::Bool,
+                        "Logical AND operator is not supported for {result_type}"
+                    );
 
-                    state.function_state.vstack.pop();
-                    state.function_state.vstack.pop();
+                    // The right-hand side is only evaluated if the left-hand side is true, in
+                    // which case the left-hand side is replaced by the right-hand side.
+                    let (rhs_subroutine_name, lhs_expr_code) =
+                        compile_short_circuit_operands(lhs_expr, rhs_expr, "and_rhs", state);
 
-                    [lhs_expr_code, rhs_expr_code, and_code].concat()
+                    triton_asm!(
+                        {&lhs_expr_code}
+                        dup 0
+                        skiz
+                        call {rhs_subroutine_name}
+                    )
                 }
 
                 ast::BinOp::BitAnd => {
@@ -2162,8 +2426,11 @@ fn compile_expr(
                         compile_expr(rhs_expr, "_binop_rhs", state);
 
                     let bitwise_and_code = match result_type {
-                        ast_types::DataType::U32 => triton_asm!(and),
-                        ast_types::DataType::U64 => {
+                        ast_types::DataType::U8
+                        | ast_types::DataType::U16
+                        | ast_types::DataType::U32
+                        | ast_types::DataType::I32 => triton_asm!(and),
+                        ast_types::DataType::U64 | ast_types::DataType::I64 => {
                             let and_u64 = state.import_snippet(Box::new(
                                 tasm_lib::arithmetic::u64::and_u64::AndU64,
                             ));
@@ -2187,8 +2454,8 @@ fn compile_expr(
 
                     use ast_types::DataType::*;
                     let xor_code = match result_type {
-                        U32 => triton_asm!(xor),
-                        U64 => triton_asm!(
+                        U8 | U16 | U32 | I32 => triton_asm!(xor),
+                        U64 | I64 => triton_asm!(
                             swap 3
                             xor
                             swap 2
@@ -2212,12 +2479,12 @@ fn compile_expr(
                     use ast_types::DataType::*;
 
                     let bitwise_or_code = match result_type {
-                        U32 => {
+                        U8 | U16 | U32 | I32 => {
                             let or_u32 =
                                 state.import_snippet(Box::new(tasm_lib::arithmetic::u32::or::Or));
                             triton_asm!(call { or_u32 })
                         }
-                        U64 => {
+                        U64 | I64 => {
                             let or_u64 = state
                                 .import_snippet(Box::new(tasm_lib::arithmetic::u64::or_u64::OrU64));
                             triton_asm!(call { or_u64 })
@@ -2234,7 +2501,7 @@ fn compile_expr(
                 ast::BinOp::Div => {
                     use ast_types::DataType::*;
                     match result_type {
-                        U32 => {
+                        U8 | U16 | U32 => {
                             // TODO: Consider evaluating in opposite order to save a clock-cycle by removing `swap1`
                             // below. This would change the "left-to-right" convention though.
                             let (_lhs_expr_addr, lhs_expr_code) =
@@ -2329,6 +2596,23 @@ fn compile_expr(
                                 xxmul
                             )
                         }
+                        I32 | I64 => {
+                            let (_lhs_expr_addr, lhs_expr_code) =
+                                compile_expr(lhs_expr, "_binop_lhs", state);
+                            let (_rhs_expr_addr, rhs_expr_code) =
+                                compile_expr(rhs_expr, "_binop_rhs", state);
+
+                            // Pop numerator and denominator
+                            state.function_state.vstack.pop();
+                            state.function_state.vstack.pop();
+
+                            let div_code = result_type.signed_integer_div_code(state);
+                            triton_asm!(
+                                {&lhs_expr_code}
+                                {&rhs_expr_code}
+                                {&div_code}
+                            )
+                        }
                         _ => panic!("Unsupported div for type {result_type}"),
                     }
                 }
@@ -2336,7 +2620,7 @@ fn compile_expr(
                 ast::BinOp::Rem => {
                     use ast_types::DataType::*;
                     match result_type {
-                        U32 => {
+                        U8 | U16 | U32 => {
                             // TODO: Consider evaluating in opposite order to save a clock-cycle by removing `swap1`
                             // below. This would change the "left-to-right" convention though.
                             let (_lhs_expr_addr, lhs_expr_code) =
@@ -2383,6 +2667,23 @@ fn compile_expr(
                                 pop 1
                             )
                         }
+                        I32 | I64 => {
+                            let (_lhs_expr_addr, lhs_expr_code) =
+                                compile_expr(lhs_expr, "_binop_lhs", state);
+                            let (_rhs_expr_addr, rhs_expr_code) =
+                                compile_expr(rhs_expr, "_binop_rhs", state);
+
+                            // Pop numerator and denominator
+                            state.function_state.vstack.pop();
+                            state.function_state.vstack.pop();
+
+                            let rem_code = result_type.signed_integer_rem_code(state);
+                            triton_asm!(
+                                {&lhs_expr_code}
+                                {&rhs_expr_code}
+                                {&rem_code}
+                            )
+                        }
                         _ => panic!("Unsupported remainder of type {lhs_type}"),
                     }
                 }
@@ -2415,7 +2716,7 @@ fn compile_expr(
                     state.function_state.vstack.pop();
 
                     match lhs_type {
-                        U32 => triton_asm!(
+                        U8 | U16 | U32 => triton_asm!(
                             {&lhs_expr_code}
                             {&rhs_expr_code}
                             swap 1
@@ -2437,6 +2738,28 @@ fn compile_expr(
                                 call {lt_u64}
                             )
                         }
+                        I32 => triton_asm!(
+                            {&lhs_expr_code}
+                            {&rhs_expr_code}
+                            {&lhs_type.flip_sign_bits_of_operands_code()}
+                            swap 1
+                            lt
+                        ),
+                        I64 => {
+                            let lt_u64 = state.import_snippet(Box::new(
+                                tasm_lib::arithmetic::u64::lt_u64::LtStandardU64,
+                            ));
+                            triton_asm!(
+                                {&lhs_expr_code}
+                                {&rhs_expr_code}
+                                {&lhs_type.flip_sign_bits_of_operands_code()}
+                                swap 3
+                                swap 1
+                                swap 3
+                                swap 2
+                                call {lt_u64}
+                            )
+                        }
                         _ => panic!("Unsupported < for type {lhs_type}"),
                     }
                 }
@@ -2453,7 +2776,7 @@ fn compile_expr(
                     state.function_state.vstack.pop();
 
                     match lhs_type {
-                        U32 => triton_asm!(
+                        U8 | U16 | U32 => triton_asm!(
                             {&lhs_expr_code}
                             {&rhs_expr_code}
                             lt
@@ -2470,6 +2793,23 @@ fn compile_expr(
                                 call {lt_u64}
                             )
                         }
+                        I32 => triton_asm!(
+                            {&lhs_expr_code}
+                            {&rhs_expr_code}
+                            {&lhs_type.flip_sign_bits_of_operands_code()}
+                            lt
+                        ),
+                        I64 => {
+                            let lt_u64 = state.import_snippet(Box::new(
+                                tasm_lib::arithmetic::u64::lt_u64::LtStandardU64,
+                            ));
+                            triton_asm!(
+                                {&lhs_expr_code}
+                                {&rhs_expr_code}
+                                {&lhs_type.flip_sign_bits_of_operands_code()}
+                                call {lt_u64}
+                            )
+                        }
                         _ => panic!("Unsupported < for type {lhs_type}"),
                     }
                 }
@@ -2487,6 +2827,12 @@ fn compile_expr(
                     state.function_state.vstack.pop();
 
                     match (&lhs_type, &rhs_type) {
+                        (U8, U8) | (U16, U16) => triton_asm!(
+                            {&lhs_expr_code}
+                            {&rhs_expr_code}
+                            mul
+                            {&lhs_type.small_unsigned_integer_range_check_code()}
+                        ),
                         (U32, U32) => {
                             let fn_name = state.import_snippet(Box::new(
                                 tasm_lib::arithmetic::u32::safemul::Safemul,
@@ -2520,6 +2866,14 @@ fn compile_expr(
                                 call {fn_name}
                             )
                         }
+                        (I32, I32) | (I64, I64) => {
+                            let mul_code = lhs_type.signed_integer_mul_code(state);
+                            triton_asm!(
+                                {&lhs_expr_code}
+                                {&rhs_expr_code}
+                                {&mul_code}
+                            )
+                        }
                         (Bfe, Bfe) => triton_asm!(
                             {&lhs_expr_code}
                             {&rhs_expr_code}
@@ -2560,23 +2914,18 @@ fn compile_expr(
                 }
 
                 ast::BinOp::Or => {
-                    let (_lhs_expr_addr, lhs_expr_code) =
-                        compile_expr(lhs_expr, "_binop_lhs", state);
-
-                    let (_rhs_expr_addr, rhs_expr_code) =
-                        compile_expr(rhs_expr, "_binop_rhs", state);
-
-                    state.function_state.vstack.pop();
-                    state.function_state.vstack.pop();
+                    // The right-hand side is only evaluated if the left-hand side is false, in
+                    // which case the left-hand side is replaced by the right-hand side.
+                    let (rhs_subroutine_name, lhs_expr_code) =
+                        compile_short_circuit_operands(lhs_expr, rhs_expr, "or_rhs", state);
 
                     triton_asm!(
                         {&lhs_expr_code}
-                        {&rhs_expr_code}
-                        add
-                        push 0
-                        eq
+                        dup 0
                         push 0
                         eq
+                        skiz
+                        call {rhs_subroutine_name}
                     )
                 }
 
@@ -2589,8 +2938,8 @@ fn compile_expr(
 
                     let lhs_type = lhs_expr.get_type();
                     let shl = match lhs_type {
-                        ast_types::DataType::U32 => state.import_snippet(Box::new(tasm_lib::arithmetic::u32::shiftleft::Shiftleft)),
-                        ast_types::DataType::U64 => state.import_snippet(Box::new(
+                        ast_types::DataType::U8 | ast_types::DataType::U16 | ast_types::DataType::U32 | ast_types::DataType::I32 => state.import_snippet(Box::new(tasm_lib::arithmetic::u32::shiftleft::Shiftleft)),
+                        ast_types::DataType::U64 | ast_types::DataType::I64 => state.import_snippet(Box::new(
                                     tasm_lib::arithmetic::u64::shift_left_u64::ShiftLeftU64,
                                 )),
                         ast_types::DataType::U128 => state.import_snippet(Box::new(
@@ -2602,10 +2951,18 @@ fn compile_expr(
                     state.function_state.vstack.pop();
                     state.function_state.vstack.pop();
 
+                    // Bits shifted out of a small unsigned integer are discarded
+                    let truncate_code = if type_checker::is_small_unsigned_integer_type(&lhs_type) {
+                        triton_asm!(push {lhs_type.small_unsigned_integer_max()} and)
+                    } else {
+                        triton_asm!()
+                    };
+
                     triton_asm!(
                         {&lhs_expr_code}
                         {&rhs_expr_code}
                         call {shl}
+                        {&truncate_code}
                     )
                 }
 
@@ -2619,14 +2976,31 @@ fn compile_expr(
                     let lhs_type = lhs_expr.get_type();
                     // TODO: add optimization where RHS is a literal. Also applies for `Binop::Shl`. We have code snippets
                     // for left/right shifting u128s with statically known bits.
-                    let shr = match lhs_type {
-                        ast_types::DataType::U32 => state.import_snippet(Box::new(tasm_lib::arithmetic::u32::shiftright::Shiftright)),
-                        ast_types::DataType::U64 => state.import_snippet(Box::new(
-                                    tasm_lib::arithmetic::u64::shift_right_u64::ShiftRightU64,
-                                )),
-                        ast_types::DataType::U128 => state.import_snippet(Box::new(
-                            tasm_lib::arithmetic::u128::shift_right_u128::ShiftRightU128,
-                        )),
+                    let shr_code = match lhs_type {
+                        ast_types::DataType::U8
+                        | ast_types::DataType::U16
+                        | ast_types::DataType::U32 => {
+                            let shr = state.import_snippet(Box::new(
+                                tasm_lib::arithmetic::u32::shiftright::Shiftright,
+                            ));
+                            triton_asm!(call { shr })
+                        }
+                        ast_types::DataType::U64 => {
+                            let shr = state.import_snippet(Box::new(
+                                tasm_lib::arithmetic::u64::shift_right_u64::ShiftRightU64,
+                            ));
+                            triton_asm!(call { shr })
+                        }
+                        ast_types::DataType::U128 => {
+                            let shr = state.import_snippet(Box::new(
+                                tasm_lib::arithmetic::u128::shift_right_u128::ShiftRightU128,
+                            ));
+                            triton_asm!(call { shr })
+                        }
+                        // Right-shifting a signed integer preserves its sign
+                        ast_types::DataType::I32 | ast_types::DataType::I64 => {
+                            lhs_type.signed_integer_shr_code(state)
+                        }
                         _ => panic!("Unsupported SHR of type {lhs_type}. Expression was `{lhs_expr}: >> {rhs_expr}`; types: `{lhs_type}`, {}.", rhs_expr.get_type()),
                     };
 
@@ -2636,7 +3010,7 @@ fn compile_expr(
                     triton_asm!(
                         {&lhs_expr_code}
                         {&rhs_expr_code}
-                        call {shr}
+                        {&shr_code}
                     )
                 }
 
@@ -2648,7 +3022,9 @@ fn compile_expr(
                         compile_expr(rhs_expr, "_binop_rhs", state);
 
                     let sub_code: Vec<LabelledInstruction> = match result_type {
-                        ast_types::DataType::U32 => {
+                        ast_types::DataType::U8
+                        | ast_types::DataType::U16
+                        | ast_types::DataType::U32 => {
                             // As standard, we use safe arithmetic that crashes on overflow
                             let safe_sub_u32 = state.import_snippet(Box::new(
                                 tasm_lib::arithmetic::u32::safesub::Safesub,
@@ -2702,6 +3078,9 @@ fn compile_expr(
                                 call {sub_u128}
                             )
                         }
+                        ast_types::DataType::I32 | ast_types::DataType::I64 => {
+                            result_type.signed_integer_sub_code(state)
+                        }
                         ast_types::DataType::Bfe => {
                             triton_asm!(
                                 push -1
@@ -2788,12 +3167,50 @@ fn compile_expr(
             code
         }
 
+        ast::Expr::Match(ast::MatchExpr {
+            match_expression,
+            arms,
+        }) => {
+            // Reserve space for the value of the match expression below the matched-against
+            // value. The body of the arm that is taken moves its value into this placeholder.
+            let (placeholder_id, spill) =
+                state.new_value_identifier("match_expr_result", &result_type);
+            assert!(
+                spill.is_none(),
+                "Cannot handle memory-spill of match expression results. But {placeholder_id} required memory spilling"
+            );
+            let result_stack_height = state.function_state.vstack.get_stack_height();
+            let placeholder_code = vec![triton_instr!(push 0); result_type.stack_size()];
+
+            let arms = arms
+                .iter()
+                .map(|arm| {
+                    let body = MatchArmBody::Returning {
+                        block: &arm.body,
+                        result_stack_height,
+                    };
+                    (&arm.match_condition, body)
+                })
+                .collect_vec();
+            let match_code = compile_match(match_expression, &arms, state);
+
+            // The placeholder now holds the value of the match expression, which is
+            // registered on the stack below.
+            state.function_state.vstack.pop();
+
+            [placeholder_code, match_code].concat()
+        }
+
         ast::Expr::ReturningBlock(ret_block) => {
             let (_, code) = compile_returning_block_expr(_context, state, ret_block);
 
             code
         }
 
+        ast::Expr::Closure(_) => {
+            unreachable!("closures must be lifted to functions by the type checker")
+        }
+
         ast::Expr::Cast(expr, _as_type) => {
             let previous_type = expr.get_type();
             let (_expr_addr, expr_code) = compile_expr(expr, "as", state);
@@ -2806,60 +3223,8 @@ fn compile_expr(
                 "Can't handle spilled values in casting yet"
             );
 
-            match (&previous_type, &result_type) {
-                (ast_types::DataType::U64, ast_types::DataType::U32) => {
-                    triton_asm!(
-                        {&expr_code}
-                        swap 1
-                        pop 1
-                    )
-                }
-                (ast_types::DataType::U32, ast_types::DataType::U64) => {
-                    triton_asm!({ &expr_code } push 0 swap 1)
-                }
-                (ast_types::DataType::U32, ast_types::DataType::U128) => {
-                    triton_asm!(
-                        {&expr_code}
-                        push 0
-                        push 0
-                        push 0
-                        swap 3
-                    )
-                }
-                (ast_types::DataType::U64, ast_types::DataType::U128) => {
-                    triton_asm!(
-                        {&expr_code}
-                        push 0
-                        push 0
-                        swap 3
-                        swap 1
-                        swap 2
-                    )
-                }
-                // Allow identity-casting since we might need this to make the types
-                // agree with code compiled by rustc.
-                (ast_types::DataType::U32, ast_types::DataType::U32) => expr_code,
-                (ast_types::DataType::U64, ast_types::DataType::U64) => expr_code,
-                (ast_types::DataType::Bool, ast_types::DataType::U64) => {
-                    triton_asm!(
-                        {&expr_code}
-                        push 0
-                        swap 1
-                    )
-                }
-                (ast_types::DataType::Bool, ast_types::DataType::U32) => expr_code,
-                (ast_types::DataType::Bool, ast_types::DataType::Bfe) => expr_code,
-                (ast_types::DataType::U128, ast_types::DataType::U64) => {
-                    triton_asm!(
-                    {&expr_code}
-                    swap 2
-                    pop 1
-                    swap 2
-                    pop 1
-                    )
-                }
-                _ => todo!("previous_type: {previous_type}; result_type: {result_type}"),
-            }
+            let cast_code = previous_type.cast_code(&result_type);
+            [expr_code, cast_code].concat()
         }
     };
 
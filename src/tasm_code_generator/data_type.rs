@@ -8,7 +8,11 @@ use crate::ast_types;
 use crate::tasm_code_generator::read_n_words_from_memory;
 use crate::tasm_code_generator::CompilerState;
 
+pub mod cast;
 pub mod enum_type;
+pub mod equality;
+pub mod signed_integer;
+pub mod small_unsigned_integer;
 pub mod struct_type;
 
 impl ast_types::DataType {
@@ -48,9 +52,13 @@ impl ast_types::DataType {
     ) -> Vec<LabelledInstruction> {
         match self {
             ast_types::DataType::Bool
+            | ast_types::DataType::U8
+            | ast_types::DataType::U16
             | ast_types::DataType::U32
             | ast_types::DataType::U64
             | ast_types::DataType::U128
+            | ast_types::DataType::I32
+            | ast_types::DataType::I64
             | ast_types::DataType::Bfe
             | ast_types::DataType::Xfe
             | ast_types::DataType::Digest
@@ -90,8 +98,8 @@ impl ast_types::DataType {
     pub(super) fn compile_eq_code(&self, state: &mut CompilerState) -> Vec<LabelledInstruction> {
         use ast_types::DataType::*;
         match self {
-            Bool | U32 | Bfe | VoidPointer => triton_asm!(eq),
-            U64 => triton_asm!(
+            Bool | U8 | U16 | U32 | I32 | Bfe | VoidPointer => triton_asm!(eq),
+            U64 | I64 => triton_asm!(
                 // _ a_hi a_lo b_hi b_lo
                 swap 3
                 eq
@@ -135,14 +143,10 @@ impl ast_types::DataType {
                     state.import_snippet(Box::new(tasm_lib::hashing::eq_digest::EqDigest));
                 triton_asm!(call { eq_digest })
             }
-            List(_, _) => todo!(),
-            Tuple(_) => todo!(),
-            Array(_) => todo!("Equality for arrays not yet implemented"),
+            Tuple(_) | Struct(_) | Enum(_) => self.compile_eq_code_on_stack(state),
+            List(_, _) | Array(_) | Boxed(_) => self.compile_eq_code_in_memory(state),
             Function(_) => todo!(),
-            Struct(_) => todo!(),
-            Boxed(_) => todo!("Comparison of MemPointer not supported yet"),
             Unresolved(name) => panic!("Cannot compare unresolved type {name}"),
-            Enum(_) => todo!("Equality for enums not yet implemented"),
         }
     }
 }
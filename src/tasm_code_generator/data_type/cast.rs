@@ -0,0 +1,101 @@
+use triton_vm::instruction::LabelledInstruction;
+use triton_vm::triton_asm;
+
+use crate::ast_types::DataType;
+use crate::type_checker;
+
+impl DataType {
+    /// Return the code for converting a value of this type to the given type with `as`.
+    /// The type checker only allows the casts handled here. As in Rust, narrowing casts
+    /// keep the least significant bits of the value. Unsigned integers are cast to a
+    /// `BFieldElement` by reducing them modulo p, and a `BFieldElement` is cast to an
+    /// unsigned integer through its canonical representative in [0, p).
+    /// BEFORE: _ [value]
+    /// AFTER:  _ [value as to_type]
+    pub(crate) fn cast_code(&self, to_type: &DataType) -> Vec<LabelledInstruction> {
+        use DataType::*;
+
+        // Allow identity-casting since we might need this to make the types
+        // agree with code compiled by rustc.
+        if self == to_type {
+            return triton_asm!();
+        }
+
+        // A field-less enum is represented by its discriminant, which is a `U32`
+        if let Enum(_) = self {
+            return U32.cast_code(to_type);
+        }
+
+        if type_checker::is_small_unsigned_integer_type(self)
+            || type_checker::is_small_unsigned_integer_type(to_type)
+        {
+            return self.small_unsigned_integer_cast_code(to_type);
+        }
+
+        if type_checker::is_signed_integer_type(self)
+            || type_checker::is_signed_integer_type(to_type)
+        {
+            return self.signed_integer_cast_code(to_type);
+        }
+
+        match (self, to_type) {
+            (Bool, U32) | (Bool, Bfe) | (U32, Bfe) => triton_asm!(),
+            (Bool | U32, U64) => triton_asm!(push 0 swap 1),
+            (Bool | U32, U128) => triton_asm!(
+                push 0
+                push 0
+                push 0
+                swap 3
+            ),
+            (U64, U32) => triton_asm!(swap 1 pop 1),
+            (U64, U128) => triton_asm!(
+                push 0
+                push 0
+                swap 3
+                swap 1
+                swap 2
+            ),
+            (U64, Bfe) => triton_asm!(
+                // _ hi lo
+                swap 1
+                push {1u64 << 32}
+                mul
+                add
+            ),
+            (U128, U32) => triton_asm!(swap 3 pop 3),
+            (U128, U64) => triton_asm!(
+                swap 2
+                pop 1
+                swap 2
+                pop 1
+            ),
+            (U128, Bfe) => triton_asm!(
+                // _ w3 w2 w1 w0
+                swap 3
+                push {1u64 << 32}
+                mul
+                swap 1
+                swap 2
+                add
+                // _ w0 w1 (w3 * 2^32 + w2)
+                push {1u64 << 32}
+                mul
+                add
+                push {1u64 << 32}
+                mul
+                add
+            ),
+            (Bfe, U32) => triton_asm!(
+                split
+                swap 1
+                pop 1
+            ),
+            (Bfe, U64) => triton_asm!(split),
+            (Bfe, U128) => triton_asm!(
+                split
+                {&U64.cast_code(&U128)}
+            ),
+            _ => unreachable!("Cannot cast from {self} to {to_type}"),
+        }
+    }
+}
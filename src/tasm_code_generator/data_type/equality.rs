@@ -0,0 +1,517 @@
+use itertools::Itertools;
+use triton_vm::instruction::LabelledInstruction;
+use triton_vm::triton_asm;
+use triton_vm::BFieldElement;
+
+use crate::ast_types;
+use crate::ast_types::DataType;
+use crate::tasm_code_generator::move_top_stack_value_to_memory;
+use crate::tasm_code_generator::CompilerState;
+
+impl DataType {
+    /// Return true if two values of this type are equal iff the words of their stack
+    /// representations are equal, i.e. if the stack representation holds no pointers.
+    fn is_word_comparable(&self) -> bool {
+        use ast_types::DataType::*;
+        match self {
+            Bool | U8 | U16 | U32 | U64 | U128 | I32 | I64 | Bfe | Xfe | Digest => true,
+            Tuple(tuple) => tuple.into_iter().all(|x| x.is_word_comparable()),
+            Struct(struct_type) => struct_type.field_types().all(|x| x.is_word_comparable()),
+            // The padding of enum variants is always zero
+            Enum(enum_type) => enum_type
+                .variants
+                .iter()
+                .all(|(_, data_type)| data_type.is_word_comparable()),
+            List(_, _) | Array(_) | Boxed(_) | VoidPointer | Function(_) | Unresolved(_) => false,
+        }
+    }
+
+    /// Return the code for comparing two tuples, structs, or enums on the stack. The
+    /// operands are moved to memory, such that values exceeding the reachable part of the
+    /// stack can be compared too. All comparisons of a type share the memory they move the
+    /// operands to.
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ (lhs == rhs)
+    pub(super) fn compile_eq_code_on_stack(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        let size = self.stack_size();
+        if size == 0 {
+            return triton_asm!(push 1);
+        }
+
+        let global_compiler_state = &mut state.global_compiler_state;
+        let snippet_state = &mut global_compiler_state.snippet_state;
+        let (lhs_pointer, rhs_pointer) = *global_compiler_state
+            .eq_scratch_areas
+            .entry(self.label_friendly_name())
+            .or_insert_with(|| {
+                (
+                    snippet_state.kmalloc(size as u32),
+                    snippet_state.kmalloc(size as u32),
+                )
+            });
+
+        [
+            move_top_stack_value_to_memory(Some(rhs_pointer), size),
+            move_top_stack_value_to_memory(Some(lhs_pointer), size),
+            self.compile_eq_code_for_moved_values(lhs_pointer.value(), rhs_pointer.value(), state),
+        ]
+        .concat()
+    }
+
+    /// Return the code for comparing two values of this type whose stack representations
+    /// were moved to memory, with the top word of the operands stored at `lhs` and `rhs`.
+    /// BEFORE: _
+    /// AFTER:  _ (lhs == rhs)
+    fn compile_eq_code_for_moved_values(
+        &self,
+        lhs: u64,
+        rhs: u64,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        use ast_types::DataType::*;
+        match self {
+            Tuple(_) | Struct(_) | Enum(_) if self.is_word_comparable() => {
+                let mut code = triton_asm!(push 1);
+                for i in 0..self.stack_size() as u64 {
+                    code.append(&mut triton_asm!(
+                        push {lhs + i}
+                        read_mem 1
+                        pop 1
+                        push {rhs + i}
+                        read_mem 1
+                        pop 1
+                        eq
+                        mul
+                    ));
+                }
+
+                code
+            }
+            Tuple(tuple) => {
+                Self::compile_eq_code_for_moved_fields(tuple.into_iter(), lhs, rhs, state)
+            }
+            Struct(struct_type) => {
+                Self::compile_eq_code_for_moved_fields(struct_type.field_types(), lhs, rhs, state)
+            }
+            Enum(enum_type) => {
+                // _
+                let mut code = triton_asm!(
+                    push {lhs}
+                    read_mem 1
+                    pop 1
+                    push {rhs}
+                    read_mem 1
+                    pop 1
+                    eq
+                    // _ (lhs_discriminant == rhs_discriminant)
+                );
+
+                // The data of the variants can only be compared if the discriminants match,
+                // as the data of another variant might contain pointers.
+                for (variant_name, variant_data_type) in enum_type.variants.iter() {
+                    let discriminant = enum_type.variant_discriminant(variant_name);
+                    let data_offset = 1 + enum_type.padding_size(variant_name) as u64;
+                    let data_eq_code = variant_data_type.compile_eq_code_for_moved_values(
+                        lhs + data_offset,
+                        rhs + data_offset,
+                        state,
+                    );
+                    let subroutine_label = format!(
+                        "{}_eq_variant_{variant_name}_{lhs}",
+                        self.label_friendly_name()
+                    );
+                    state.add_library_function(
+                        triton_asm!(
+                            {subroutine_label}:
+                                // _ 1
+                                pop 1
+                                {&data_eq_code}
+                                // _ (lhs_data == rhs_data)
+                                return
+                        )
+                        .try_into()
+                        .unwrap(),
+                    );
+
+                    code.append(&mut triton_asm!(
+                        // _ result
+                        dup 0
+                        push {lhs}
+                        read_mem 1
+                        pop 1
+                        push {discriminant}
+                        eq
+                        mul
+                        // _ result (result && lhs_discriminant == discriminant)
+
+                        skiz
+                        call {subroutine_label}
+                        // _ result'
+                    ));
+                }
+
+                code
+            }
+            _ => {
+                let size = self.stack_size();
+                [
+                    Self::copy_words_from_memory(Some(BFieldElement::new(lhs)), size),
+                    Self::copy_words_from_memory(Some(BFieldElement::new(rhs)), size),
+                    self.compile_eq_code(state),
+                ]
+                .concat()
+            }
+        }
+    }
+
+    /// Return the code for comparing the fields of two tuples or structs that were moved to
+    /// memory. The last field is on top of the stack, so it is stored first.
+    /// BEFORE: _
+    /// AFTER:  _ (lhs == rhs)
+    fn compile_eq_code_for_moved_fields<'a>(
+        field_types: impl Iterator<Item = &'a DataType>,
+        lhs: u64,
+        rhs: u64,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        let mut code = triton_asm!(push 1);
+        let mut offset = 0;
+        for field_type in field_types.collect_vec().into_iter().rev() {
+            code.append(&mut field_type.compile_eq_code_for_moved_values(
+                lhs + offset,
+                rhs + offset,
+                state,
+            ));
+            code.append(&mut triton_asm!(mul));
+            offset += field_type.stack_size() as u64;
+        }
+
+        code
+    }
+
+    /// Return the code for comparing two lists, arrays, or boxed values. The values in memory
+    /// are compared word by word, which is sound since their encodings are canonical.
+    /// BEFORE: _ *lhs *rhs
+    /// AFTER:  _ (lhs == rhs)
+    pub(super) fn compile_eq_code_in_memory(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        use ast_types::DataType::*;
+
+        // For these types, the value of the pointer is the same as the pointer to the value
+        if let Boxed(inner_type) = self {
+            if matches!(inner_type.as_ref(), List(_, _) | Array(_) | Boxed(_)) {
+                return inner_type.compile_eq_code(state);
+            }
+        }
+
+        let subroutine_label = format!("eq_{}", self.label_friendly_name());
+        if state.contains_subroutine(&subroutine_label) {
+            return triton_asm!(call { subroutine_label });
+        }
+
+        let eq_words = eq_words_in_memory(state);
+        let subroutine = match self {
+            List(element_type, list_type) => {
+                // Rejected by the type checker
+                let element_size = element_type.bfield_codec_length().unwrap_or_else(|| {
+                    unreachable!("cannot compare lists with elements of dynamic size: {self}")
+                });
+                let read_length = triton_asm!(read_mem 1 pop 1);
+                compare_sized_values(
+                    &subroutine_label,
+                    &read_length,
+                    list_type.metadata_size(),
+                    element_size,
+                    &eq_words,
+                    state,
+                )
+            }
+            Array(array_type) => {
+                let size = array_type.size_in_memory();
+                triton_asm!(
+                    {subroutine_label}:
+                        // _ *lhs *rhs
+                        push {size}
+                        call {eq_words}
+                        // _ (lhs == rhs)
+                        return
+                )
+            }
+            Boxed(inner_type) => match inner_type.bfield_codec_length() {
+                Some(size) => triton_asm!(
+                    {subroutine_label}:
+                        // _ *lhs *rhs
+                        push {size}
+                        call {eq_words}
+                        // _ (lhs == rhs)
+                        return
+                ),
+                None => {
+                    let encoding_size = inner_type.encoding_size_code(state);
+                    compare_sized_values(&subroutine_label, &encoding_size, 0, 1, &eq_words, state)
+                }
+            },
+            _ => unreachable!("{self} is not stored in memory"),
+        };
+        state.add_library_function(subroutine.try_into().unwrap());
+
+        triton_asm!(call { subroutine_label })
+    }
+
+    /// Return the code for getting the length of the encoding of a value in memory.
+    /// BEFORE: _ *value
+    /// AFTER:  _ size
+    fn encoding_size_code(&self, state: &mut CompilerState) -> Vec<LabelledInstruction> {
+        if let Some(size) = self.bfield_codec_length() {
+            return triton_asm!(pop 1 push { size });
+        }
+
+        match self {
+            DataType::Struct(struct_type) => {
+                let skip_fields = skip_encoded_fields(
+                    struct_type
+                        .field_ids_and_types_reversed()
+                        .map(|(_, field_type)| field_type),
+                );
+                triton_asm!(
+                    // _ *value
+                    dup 0
+                    {&skip_fields}
+                    // _ *value *next_value
+                    swap 1
+                    push -1
+                    mul
+                    add
+                    // _ size
+                )
+            }
+            DataType::Enum(enum_type) => {
+                let subroutine_label = format!("{}_encoding_size", self.label_friendly_name());
+                if state.contains_subroutine(&subroutine_label) {
+                    return triton_asm!(call { subroutine_label });
+                }
+
+                let mut skip_variant_data = triton_asm!();
+                for (variant_name, variant_data_type) in enum_type.variants.iter() {
+                    let discriminant = enum_type.variant_discriminant(variant_name);
+                    let variant_label = format!("{subroutine_label}_{variant_name}");
+                    let data_types = variant_data_type.as_tuple_type();
+                    let skip_fields = skip_encoded_fields(data_types.fields.iter().rev());
+                    state.add_library_function(
+                        triton_asm!(
+                            {variant_label}:
+                                // _ *discriminant *discriminant discriminant
+                                swap 1
+                                push 1
+                                add
+                                {&skip_fields}
+                                swap 1
+                                // _ *discriminant *next_value discriminant
+                                return
+                        )
+                        .try_into()
+                        .unwrap(),
+                    );
+
+                    skip_variant_data.append(&mut triton_asm!(
+                        dup 0
+                        push {discriminant}
+                        eq
+                        skiz
+                        call {variant_label}
+                    ));
+                }
+
+                state.add_library_function(
+                    triton_asm!(
+                        {subroutine_label}:
+                            // _ *discriminant
+                            dup 0
+                            dup 0
+                            read_mem 1
+                            pop 1
+                            // _ *discriminant *discriminant discriminant
+
+                            {&skip_variant_data}
+                            // _ *discriminant *next_value discriminant
+
+                            pop 1
+                            swap 1
+                            push -1
+                            mul
+                            add
+                            // _ size
+                            return
+                    )
+                    .try_into()
+                    .unwrap(),
+                );
+
+                triton_asm!(call { subroutine_label })
+            }
+            // Rejected by the type checker
+            _ => unreachable!("cannot compare boxed values of type {self}"),
+        }
+    }
+}
+
+/// Return the code for moving a pointer past a sequence of encoded fields. Fields of dynamic
+/// size are prefixed with their size.
+/// BEFORE: _ *first_field
+/// AFTER:  _ *next_value
+fn skip_encoded_fields<'a>(
+    field_types: impl Iterator<Item = &'a DataType>,
+) -> Vec<LabelledInstruction> {
+    field_types
+        .flat_map(|field_type| match field_type.bfield_codec_length() {
+            Some(static_size) => triton_asm!(push { static_size } add),
+            None => triton_asm!(
+                // _ *field_size
+                read_mem 1
+                // _ field_size (*field_size - 1)
+                add
+                push 2
+                add
+                // _ *next_field
+            ),
+        })
+        .collect()
+}
+
+/// Return a subroutine comparing two values in memory whose size is only known at runtime.
+/// `read_size` gets the size from the pointer to the value. The compared words start
+/// `metadata_size` words after the pointer, and there are `words_per_size` of them per size.
+/// BEFORE: _ *lhs *rhs
+/// AFTER:  _ (lhs == rhs)
+fn compare_sized_values(
+    subroutine_label: &str,
+    read_size: &[LabelledInstruction],
+    metadata_size: usize,
+    words_per_size: usize,
+    eq_words: &str,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    let compare_words_label = format!("{subroutine_label}_compare_words");
+    state.add_library_function(
+        triton_asm!(
+            {compare_words_label}:
+                // _ *lhs *rhs size 0
+                pop 1
+                dup 2
+                push {metadata_size}
+                add
+                dup 2
+                push {metadata_size}
+                add
+                dup 2
+                push {words_per_size}
+                mul
+                // _ *lhs *rhs size *lhs_words *rhs_words number_of_words
+
+                call {eq_words}
+                // _ *lhs *rhs size (lhs == rhs)
+                return
+        )
+        .try_into()
+        .unwrap(),
+    );
+
+    triton_asm!(
+        {subroutine_label}:
+            // _ *lhs *rhs
+            dup 1
+            {&read_size}
+            dup 1
+            {&read_size}
+            // _ *lhs *rhs lhs_size rhs_size
+
+            dup 1
+            eq
+            push 0
+            swap 1
+            // _ *lhs *rhs lhs_size 0 (lhs_size == rhs_size)
+
+            skiz
+            call {compare_words_label}
+            // _ *lhs *rhs lhs_size (lhs == rhs)
+
+            swap 3
+            pop 3
+            // _ (lhs == rhs)
+            return
+    )
+}
+
+/// Return the label of a subroutine comparing two sequences of words in memory.
+/// BEFORE: _ *lhs *rhs number_of_words
+/// AFTER:  _ (lhs == rhs)
+fn eq_words_in_memory(state: &mut CompilerState) -> String {
+    let subroutine_label = "eq_words_in_memory".to_owned();
+    if state.contains_subroutine(&subroutine_label) {
+        return subroutine_label;
+    }
+
+    let loop_label = format!("{subroutine_label}_loop");
+    state.add_library_function(
+        triton_asm!(
+            {loop_label}:
+                // _ *lhs *rhs remaining acc
+                dup 1
+                push 0
+                eq
+                skiz
+                return
+
+                dup 3
+                read_mem 1
+                pop 1
+                dup 3
+                read_mem 1
+                pop 1
+                eq
+                mul
+                // _ *lhs *rhs remaining acc'
+
+                swap 3
+                push 1
+                add
+                swap 3
+                swap 2
+                push 1
+                add
+                swap 2
+                swap 1
+                push -1
+                add
+                swap 1
+                // _ (*lhs + 1) (*rhs + 1) (remaining - 1) acc'
+
+                recurse
+        )
+        .try_into()
+        .unwrap(),
+    );
+    state.add_library_function(
+        triton_asm!(
+            {subroutine_label}:
+                // _ *lhs *rhs number_of_words
+                push 1
+                call {loop_label}
+                // _ *lhs *rhs 0 (lhs == rhs)
+
+                swap 3
+                pop 3
+                // _ (lhs == rhs)
+                return
+        )
+        .try_into()
+        .unwrap(),
+    );
+
+    subroutine_label
+}
@@ -0,0 +1,661 @@
+use triton_vm::instruction::LabelledInstruction;
+use triton_vm::triton_asm;
+use triton_vm::BFieldElement;
+
+use crate::ast_types::DataType;
+use crate::tasm_code_generator::CompilerState;
+
+/// The sign bit of the most significant word of a signed integer
+const SIGN_BIT: u64 = 1 << 31;
+
+/// Signed integers are stored as the two's complement of their value, in one `U32` word
+/// for `i32` and in two for `i64`, most significant word deepest in the stack. Arithmetic
+/// crashes the VM on overflow, like arithmetic on the unsigned types does.
+impl DataType {
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs + rhs]
+    pub(crate) fn signed_integer_add_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        match self {
+            DataType::I32 => triton_asm!(
+                // _ lhs rhs
+                push {SIGN_BIT}
+                xor
+                swap 1
+                push {SIGN_BIT}
+                xor
+                add
+                push {-(SIGN_BIT as i64)}
+                add
+                // _ (lhs + rhs + 2^31)
+                {&from_biased_i32()}
+            ),
+            DataType::I64 => {
+                let body = triton_asm!(
+                    // _ lhs_hi lhs_lo rhs_hi rhs_lo
+                    swap 1
+                    swap 2
+                    add
+                    split
+                    // _ lhs_hi rhs_hi carry lo
+                    swap 3
+                    push {SIGN_BIT}
+                    xor
+                    add
+                    swap 1
+                    push {SIGN_BIT}
+                    xor
+                    add
+                    push {-(SIGN_BIT as i64)}
+                    add
+                    // _ lo (lhs_hi + rhs_hi + carry + 2^31)
+                    {&from_biased_i32()}
+                    swap 1
+                    // _ hi lo
+                );
+                call_subroutine("tasm_langs_add_i64", body, state)
+            }
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs - rhs]
+    pub(crate) fn signed_integer_sub_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        match self {
+            DataType::I32 => triton_asm!(
+                // _ lhs rhs
+                push {SIGN_BIT}
+                xor
+                push -1
+                mul
+                swap 1
+                push {SIGN_BIT}
+                xor
+                add
+                push {SIGN_BIT}
+                add
+                // _ (lhs - rhs + 2^31)
+                {&from_biased_i32()}
+            ),
+            DataType::I64 => {
+                let body = triton_asm!(
+                    // _ lhs_hi lhs_lo rhs_hi rhs_lo
+                    push -1
+                    mul
+                    swap 1
+                    swap 2
+                    add
+                    push {1u64 << 32}
+                    add
+                    split
+                    // _ lhs_hi rhs_hi no_borrow lo
+                    swap 3
+                    push {SIGN_BIT}
+                    xor
+                    add
+                    swap 1
+                    push {SIGN_BIT}
+                    xor
+                    push -1
+                    mul
+                    add
+                    push {SIGN_BIT - 1}
+                    add
+                    // _ lo (lhs_hi - rhs_hi - 1 + no_borrow + 2^31)
+                    {&from_biased_i32()}
+                    swap 1
+                    // _ hi lo
+                );
+                call_subroutine("tasm_langs_sub_i64", body, state)
+            }
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs * rhs]
+    pub(crate) fn signed_integer_mul_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        match self {
+            // The product of two `i32`s is small enough to be calculated in the field
+            DataType::I32 => triton_asm!(
+                // _ lhs rhs
+                push {SIGN_BIT}
+                xor
+                push {-(SIGN_BIT as i64)}
+                add
+                swap 1
+                push {SIGN_BIT}
+                xor
+                push {-(SIGN_BIT as i64)}
+                add
+                mul
+                push {SIGN_BIT}
+                add
+                // _ (lhs * rhs + 2^31)
+                {&from_biased_i32()}
+            ),
+            DataType::I64 => {
+                let mul_u64 = state.import_snippet(Box::new(
+                    tasm_lib::arithmetic::u64::safe_mul_u64::SafeMulU64,
+                ));
+                let body = triton_asm!(
+                    // _ lhs_hi lhs_lo rhs_hi rhs_lo
+                    {&sign_and_magnitude_of_operands_i64()}
+                    // _ sign lhs_sign [|lhs|] [|rhs|]
+                    call {mul_u64}
+                    swap 2
+                    pop 1
+                    swap 2
+                    // _ [|product|] sign
+                    {&assert_magnitude_fits_i64(state)}
+                    {&conditional_negate_i64()}
+                    // _ [product]
+                );
+                call_subroutine("tasm_langs_mul_i64", body, state)
+            }
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs / rhs]
+    pub(crate) fn signed_integer_div_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        let div_mod = self.signed_integer_div_mod_code(state);
+        let pop_remainder = triton_asm!(pop { self.stack_size() });
+
+        [div_mod, pop_remainder].concat()
+    }
+
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs % rhs]
+    pub(crate) fn signed_integer_rem_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        let div_mod = self.signed_integer_div_mod_code(state);
+        let pop_quotient = match self {
+            DataType::I32 => triton_asm!(swap 1 pop 1),
+            DataType::I64 => triton_asm!(swap 2 pop 1 swap 2 pop 1),
+            _ => unreachable!("{self} is not a signed integer type"),
+        };
+
+        [div_mod, pop_quotient].concat()
+    }
+
+    /// Division rounds towards zero, and the remainder has the sign of the numerator, as
+    /// in Rust. The division is done on the magnitudes of the operands.
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs / rhs] [lhs % rhs]
+    fn signed_integer_div_mod_code(&self, state: &mut CompilerState) -> Vec<LabelledInstruction> {
+        match self {
+            DataType::I32 => {
+                let body = triton_asm!(
+                    // _ lhs rhs
+                    dup 0
+                    {&is_negative_i32()}
+                    swap 1
+                    dup 1
+                    {&magnitude_i32()}
+                    swap 2
+                    // _ |rhs| rhs_sign lhs
+                    dup 0
+                    {&is_negative_i32()}
+                    swap 1
+                    dup 1
+                    {&magnitude_i32()}
+                    // _ |rhs| rhs_sign lhs_sign |lhs|
+                    dup 3
+                    swap 1
+                    div_mod
+                    // _ |rhs| rhs_sign lhs_sign |quotient| |remainder|
+                    dup 2
+                    {&apply_sign_i32()}
+                    push {SIGN_BIT}
+                    add
+                    {&from_biased_i32()}
+                    swap 4
+                    pop 1
+                    // _ remainder rhs_sign lhs_sign |quotient|
+                    swap 2
+                    xor
+                    {&apply_sign_i32()}
+                    push {SIGN_BIT}
+                    add
+                    {&from_biased_i32()}
+                    swap 1
+                    // _ quotient remainder
+                );
+                call_subroutine("tasm_langs_div_mod_i32", body, state)
+            }
+            DataType::I64 => {
+                let div_mod_u64 = state
+                    .import_snippet(Box::new(tasm_lib::arithmetic::u64::div_mod_u64::DivModU64));
+                let body = triton_asm!(
+                    // _ lhs_hi lhs_lo rhs_hi rhs_lo
+                    {&sign_and_magnitude_of_operands_i64()}
+                    // _ sign lhs_sign [|lhs|] [|rhs|]
+                    call {div_mod_u64}
+                    // _ sign lhs_sign [|quotient|] [|remainder|]
+                    dup 4
+                    {&conditional_negate_i64()}
+                    swap 4
+                    pop 1
+                    swap 4
+                    // _ [remainder] [|quotient|] sign
+                    {&assert_magnitude_fits_i64(state)}
+                    {&conditional_negate_i64()}
+                    // _ [remainder] [quotient]
+                    swap 2
+                    swap 1
+                    swap 3
+                    swap 1
+                    // _ [quotient] [remainder]
+                );
+                call_subroutine("tasm_langs_div_mod_i64", body, state)
+            }
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+
+    /// BEFORE: _ [value]
+    /// AFTER:  _ [-value]
+    pub(crate) fn signed_integer_neg_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        match self {
+            DataType::I32 => triton_asm!(
+                // _ value
+                push {SIGN_BIT}
+                xor
+                push -1
+                mul
+                push {2 * SIGN_BIT}
+                add
+                // _ (-value + 2^31)
+                {&from_biased_i32()}
+            ),
+            DataType::I64 => {
+                let sub_code = self.signed_integer_sub_code(state);
+                triton_asm!(
+                    // _ value_hi value_lo
+                    push 0
+                    swap 2
+                    push 0
+                    swap 2
+                    // _ 0 0 value_hi value_lo
+                    {&sub_code}
+                )
+            }
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+
+    /// Arithmetic right shift, which fills the vacated bits with the sign bit. The value is
+    /// inverted before and after a logical shift if it is negative.
+    /// BEFORE: _ [value] shift_amount
+    /// AFTER:  _ [value >> shift_amount]
+    pub(crate) fn signed_integer_shr_code(
+        &self,
+        state: &mut CompilerState,
+    ) -> Vec<LabelledInstruction> {
+        match self {
+            DataType::I32 => {
+                let shr_u32 = state
+                    .import_snippet(Box::new(tasm_lib::arithmetic::u32::shiftright::Shiftright));
+                triton_asm!(
+                    // _ value shift_amount
+                    dup 1
+                    {&is_negative_i32()}
+                    push {u32::MAX as u64}
+                    mul
+                    // _ value shift_amount mask
+                    swap 2
+                    dup 2
+                    xor
+                    swap 1
+                    call {shr_u32}
+                    // _ mask ((value ^ mask) >> shift_amount)
+                    xor
+                )
+            }
+            DataType::I64 => {
+                let shr_u64 = state.import_snippet(Box::new(
+                    tasm_lib::arithmetic::u64::shift_right_u64::ShiftRightU64,
+                ));
+                triton_asm!(
+                    // _ value_hi value_lo shift_amount
+                    dup 2
+                    {&is_negative_i32()}
+                    push {u32::MAX as u64}
+                    mul
+                    // _ value_hi value_lo shift_amount mask
+                    swap 3
+                    dup 3
+                    xor
+                    swap 2
+                    dup 3
+                    xor
+                    swap 1
+                    call {shr_u64}
+                    // _ mask shifted_hi shifted_lo
+                    dup 2
+                    xor
+                    swap 2
+                    xor
+                    swap 1
+                    // _ hi lo
+                )
+            }
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+
+    /// Return the code for casting a value of this type to the given type, where one of the
+    /// types is a signed integer type. As in Rust, narrowing casts truncate the value, and
+    /// widening casts from signed integers extend the sign. A `BFieldElement` greater than
+    /// (p - 1)/2 represents the negative integer it is congruent to.
+    /// BEFORE: _ [value]
+    /// AFTER:  _ [value as to_type]
+    pub(crate) fn signed_integer_cast_code(&self, to_type: &DataType) -> Vec<LabelledInstruction> {
+        use DataType::*;
+        match (self, to_type) {
+            (I32, I32) | (I64, I64) | (I32, U32) | (U32, I32) | (I64, U64) | (U64, I64) => {
+                triton_asm!()
+            }
+            (Bool, I32) => triton_asm!(),
+            (Bool, I64) | (U32, I64) => triton_asm!(push 0 swap 1),
+            (I32, I64) | (I32, U64) => triton_asm!(
+                dup 0
+                {&is_negative_i32()}
+                push {u32::MAX as u64}
+                mul
+                swap 1
+            ),
+            (I32, U128) => triton_asm!(
+                dup 0
+                {&is_negative_i32()}
+                push {u32::MAX as u64}
+                mul
+                dup 0
+                dup 0
+                swap 3
+            ),
+            (I64, U128) => triton_asm!(
+                dup 1
+                {&is_negative_i32()}
+                push {u32::MAX as u64}
+                mul
+                dup 0
+                swap 3
+                swap 1
+                swap 2
+            ),
+            (I64, I32) | (I64, U32) | (U64, I32) => triton_asm!(swap 1 pop 1),
+            (U128, I32) => triton_asm!(swap 3 pop 3),
+            (U128, I64) => triton_asm!(
+                swap 2
+                pop 1
+                swap 2
+                pop 1
+            ),
+            (I32, Bfe) => triton_asm!(
+                push {SIGN_BIT}
+                xor
+                push {-(SIGN_BIT as i64)}
+                add
+            ),
+            (I64, Bfe) => triton_asm!(
+                // _ value_hi value_lo
+                swap 1
+                push {SIGN_BIT}
+                xor
+                push {-(SIGN_BIT as i64)}
+                add
+                push {1u64 << 32}
+                mul
+                add
+            ),
+            (Bfe, I64) => triton_asm!(
+                // Shift the value to [0, p) and subtract the shift as a `u64`, wrapping around
+                push {BFieldElement::MAX / 2}
+                add
+                split
+                push {SIGN_BIT}
+                add
+                split
+                // _ shifted_hi no_borrow lo
+                swap 2
+                add
+                push {SIGN_BIT}
+                add
+                split
+                swap 1
+                pop 1
+                swap 1
+                // _ hi lo
+            ),
+            (Bfe, I32) => {
+                let to_i64 = self.signed_integer_cast_code(&I64);
+                triton_asm!(
+                    {&to_i64}
+                    swap 1
+                    pop 1
+                )
+            }
+            _ => unreachable!("Cannot cast from {self} to {to_type}"),
+        }
+    }
+
+    /// Flip the sign bits of two signed integers, which maps the order of signed integers
+    /// onto the order of unsigned integers. This allows comparing them as unsigned integers.
+    /// BEFORE: _ [lhs] [rhs]
+    /// AFTER:  _ [lhs ^ sign_bit] [rhs ^ sign_bit]
+    pub(crate) fn flip_sign_bits_of_operands_code(&self) -> Vec<LabelledInstruction> {
+        match self {
+            DataType::I32 => triton_asm!(
+                push {SIGN_BIT}
+                xor
+                swap 1
+                push {SIGN_BIT}
+                xor
+                swap 1
+            ),
+            DataType::I64 => triton_asm!(
+                // _ lhs_hi lhs_lo rhs_hi rhs_lo
+                swap 1
+                push {SIGN_BIT}
+                xor
+                swap 1
+                swap 3
+                push {SIGN_BIT}
+                xor
+                swap 3
+            ),
+            _ => unreachable!("{self} is not a signed integer type"),
+        }
+    }
+}
+
+/// Return the code to test if an `i32`, or the most significant word of an `i64`, is negative
+/// BEFORE: _ value
+/// AFTER:  _ (value < 0)
+fn is_negative_i32() -> Vec<LabelledInstruction> {
+    triton_asm!(
+        push {SIGN_BIT - 1}
+        lt
+    )
+}
+
+/// Return the code to convert an `i32`, shifted by 2^31 and calculated in the field, to
+/// its two's complement. Crashes the VM if the value does not fit in an `i32`.
+/// BEFORE: _ (value + 2^31)
+/// AFTER:  _ value
+fn from_biased_i32() -> Vec<LabelledInstruction> {
+    triton_asm!(
+        split
+        swap 1
+        push 0
+        eq
+        assert
+        push {SIGN_BIT}
+        xor
+    )
+}
+
+/// BEFORE: _ value (value < 0)
+/// AFTER:  _ |value|
+fn magnitude_i32() -> Vec<LabelledInstruction> {
+    triton_asm!(
+        dup 1
+        push -2
+        mul
+        push {2 * SIGN_BIT}
+        add
+        mul
+        add
+    )
+}
+
+/// Return the code to negate a non-negative number calculated in the field, if the sign is set
+/// BEFORE: _ magnitude sign
+/// AFTER:  _ (magnitude * (1 - 2 * sign))
+fn apply_sign_i32() -> Vec<LabelledInstruction> {
+    triton_asm!(
+        push -2
+        mul
+        push 1
+        add
+        mul
+    )
+}
+
+/// Return the code to negate a 64-bit two's complement value, wrapping around, if the sign is
+/// set. The value is inverted and incremented.
+/// BEFORE: _ value_hi value_lo sign
+/// AFTER:  _ hi lo
+fn conditional_negate_i64() -> Vec<LabelledInstruction> {
+    triton_asm!(
+        dup 0
+        push {u32::MAX as u64}
+        mul
+        // _ value_hi value_lo sign mask
+        swap 2
+        dup 2
+        xor
+        add
+        split
+        // _ value_hi mask carry lo
+        swap 3
+        swap 1
+        swap 2
+        xor
+        add
+        split
+        swap 1
+        pop 1
+        // _ lo hi
+        swap 1
+    )
+}
+
+/// Return the code to crash the VM if the magnitude of an `i64` with the given sign is too big,
+/// i.e. if it exceeds 2^63 - 1 for non-negative values, or 2^63 for negative values.
+/// BEFORE: _ magnitude_hi magnitude_lo sign
+/// AFTER:  _ magnitude_hi magnitude_lo sign
+fn assert_magnitude_fits_i64(state: &mut CompilerState) -> Vec<LabelledInstruction> {
+    let sub_u64 = state.import_snippet(Box::new(tasm_lib::arithmetic::u64::sub_u64::SubU64));
+    triton_asm!(
+        dup 2
+        dup 2
+        dup 2
+        push {SIGN_BIT - 1}
+        add
+        push 1
+        dup 4
+        push -1
+        mul
+        add
+        push {u32::MAX as u64}
+        mul
+        // _ [magnitude] sign [magnitude] [2^63 - 1 + sign]
+        call {sub_u64}
+        pop 2
+    )
+}
+
+/// Return the code to replace two `i64`s by their magnitudes, below the sign of their product
+/// and the sign of the left-hand side.
+/// BEFORE: _ lhs_hi lhs_lo rhs_hi rhs_lo
+/// AFTER:  _ (lhs_sign ^ rhs_sign) lhs_sign [|lhs|] [|rhs|]
+fn sign_and_magnitude_of_operands_i64() -> Vec<LabelledInstruction> {
+    triton_asm!(
+        dup 1
+        {&is_negative_i32()}
+        dup 0
+        swap 5
+        swap 4
+        swap 3
+        swap 2
+        swap 1
+        // _ rhs_sign lhs_hi lhs_lo rhs_hi rhs_lo rhs_sign
+        {&conditional_negate_i64()}
+        dup 3
+        {&is_negative_i32()}
+        swap 2
+        swap 4
+        swap 1
+        swap 3
+        swap 1
+        swap 2
+        // _ rhs_sign [|rhs|] lhs_hi lhs_lo lhs_sign
+        dup 0
+        swap 6
+        dup 1
+        xor
+        swap 6
+        // _ sign [|rhs|] lhs_hi lhs_lo lhs_sign lhs_sign
+        swap 3
+        swap 2
+        swap 1
+        {&conditional_negate_i64()}
+        // _ sign [|rhs|] lhs_sign [|lhs|]
+        swap 4
+        swap 1
+        swap 3
+        swap 4
+        swap 2
+        swap 4
+    )
+}
+
+/// Return the code to call a subroutine with the given body, which is imported if it isn't
+/// already
+fn call_subroutine(
+    label: &str,
+    body: Vec<LabelledInstruction>,
+    state: &mut CompilerState,
+) -> Vec<LabelledInstruction> {
+    if !state.contains_subroutine(label) {
+        let subroutine = triton_asm!(
+            {label}:
+                {&body}
+                return
+        );
+        state.add_library_function(subroutine.try_into().unwrap());
+    }
+
+    triton_asm!(call { label })
+}
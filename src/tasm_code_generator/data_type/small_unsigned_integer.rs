@@ -0,0 +1,82 @@
+use triton_vm::instruction::LabelledInstruction;
+use triton_vm::triton_asm;
+
+use crate::ast_types::DataType;
+
+/// The small unsigned integers `u8` and `u16` are stored in one `U32` word. Operations
+/// that can exceed the range of the type are computed on that word and followed by a
+/// range check that crashes the VM on overflow, like arithmetic on the other unsigned
+/// types does.
+impl DataType {
+    /// The largest value of a small unsigned integer type
+    pub(crate) fn small_unsigned_integer_max(&self) -> u64 {
+        match self {
+            DataType::U8 => u8::MAX as u64,
+            DataType::U16 => u16::MAX as u64,
+            _ => unreachable!("{self} is not a small unsigned integer type"),
+        }
+    }
+
+    /// Crash the VM if the value on top of the stack is not in the range of this type.
+    /// BEFORE: _ value
+    /// AFTER:  _ value
+    pub(crate) fn small_unsigned_integer_range_check_code(&self) -> Vec<LabelledInstruction> {
+        triton_asm!(
+            push {self.small_unsigned_integer_max() + 1}
+            dup 1
+            lt
+            assert
+        )
+    }
+
+    /// Return the code for casting a value of this type to the given type, where one of the
+    /// types is a small unsigned integer type. As in Rust, narrowing casts keep the least
+    /// significant bits of the value. A `BFieldElement` is narrowed through its canonical
+    /// representative.
+    /// BEFORE: _ [value]
+    /// AFTER:  _ [value as to_type]
+    pub(crate) fn small_unsigned_integer_cast_code(
+        &self,
+        to_type: &DataType,
+    ) -> Vec<LabelledInstruction> {
+        use DataType::*;
+        match (self, to_type) {
+            (Bool | U8, U8 | U16) | (U16, U16) => triton_asm!(),
+            (U16 | U32 | I32, U8 | U16) => to_type.truncate_to_small_unsigned_integer_code(),
+            (U64 | I64, U8 | U16) => triton_asm!(
+                swap 1
+                pop 1
+                {&to_type.truncate_to_small_unsigned_integer_code()}
+            ),
+            (U128, U8 | U16) => triton_asm!(
+                swap 3
+                pop 3
+                {&to_type.truncate_to_small_unsigned_integer_code()}
+            ),
+            (Bfe, U8 | U16) => triton_asm!(
+                split
+                swap 1
+                pop 1
+                {&to_type.truncate_to_small_unsigned_integer_code()}
+            ),
+            (U8 | U16, U32 | I32 | Bfe) => triton_asm!(),
+            (U8 | U16, U64 | I64) => triton_asm!(push 0 swap 1),
+            (U8 | U16, U128) => triton_asm!(
+                push 0
+                push 0
+                push 0
+                swap 3
+            ),
+            _ => unreachable!("Cannot cast from {self} to {to_type}"),
+        }
+    }
+
+    /// BEFORE: _ value
+    /// AFTER:  _ (value mod 2^bits)
+    fn truncate_to_small_unsigned_integer_code(&self) -> Vec<LabelledInstruction> {
+        triton_asm!(
+            push {self.small_unsigned_integer_max()}
+            and
+        )
+    }
+}
@@ -0,0 +1,301 @@
+//! Lowering of loops that contain `break` or `continue` statements.
+//!
+//! A loop body is compiled into a subroutine that `recurse`s, and nested control flow in the
+//! body is compiled into subroutines of their own. Since a `break` or `continue` can thus be
+//! nested arbitrarily deep in subroutine calls, these statements are lowered to assignments of
+//! boolean flags that live on the stack. The loop condition checks the break flag, and all
+//! statements following a statement that can exit the current iteration are guarded by a
+//! check of the flags of the innermost loop. Since only `while` loops, `if` statements, and
+//! blocks are produced, the stack is cleaned up on every exit path by the code generation of
+//! these statements.
+
+use crate::ast;
+use crate::ast_types::DataType;
+use crate::type_checker::Typing;
+
+use super::CompilerState;
+
+type Stmt = ast::Stmt<Typing>;
+type Expr = ast::Expr<Typing>;
+
+/// The flags controlling the execution of an enclosing loop
+struct LoopFrame {
+    label: Option<String>,
+    break_flag: String,
+    continue_flag: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopExitKind {
+    Break,
+    Continue,
+}
+
+/// Return true iff the body of the loop contains a `break` or `continue` statement
+/// targeting the loop itself, or any loop enclosing it.
+pub(super) fn contains_loop_exits(block: &ast::BlockStmt<Typing>) -> bool {
+    !escaping_loop_exits(&block.stmts).is_empty()
+}
+
+/// Lower a `loop` to a `while` loop controlled by a break flag.
+pub(super) fn lower_loop_stmt(
+    loop_stmt: &ast::LoopStmt<Typing>,
+    state: &mut CompilerState,
+) -> Stmt {
+    lower_loop(&loop_stmt.label, None, &loop_stmt.block, &mut vec![], state)
+}
+
+/// Lower a `while` loop containing `break` or `continue` statements to a `while` loop
+/// controlled by a break flag.
+pub(super) fn lower_while_stmt(
+    while_stmt: &ast::WhileStmt<Typing>,
+    state: &mut CompilerState,
+) -> Stmt {
+    lower_loop(
+        &while_stmt.label,
+        Some(&while_stmt.condition),
+        &while_stmt.block,
+        &mut vec![],
+        state,
+    )
+}
+
+fn lower_loop(
+    label: &Option<String>,
+    condition: Option<&Expr>,
+    block: &ast::BlockStmt<Typing>,
+    frames: &mut Vec<LoopFrame>,
+    state: &mut CompilerState,
+) -> Stmt {
+    let targets_this_loop =
+        |exit_label: &Option<String>| exit_label.is_none() || exit_label == label;
+    let requires_continue_flag = escaping_loop_exits(&block.stmts)
+        .iter()
+        .any(|(kind, exit_label)| *kind == LoopExitKind::Continue && targets_this_loop(exit_label));
+
+    let break_flag = state.unique_label("loop_break_flag", None);
+    let continue_flag =
+        requires_continue_flag.then(|| state.unique_label("loop_continue_flag", None));
+    frames.push(LoopFrame {
+        label: label.to_owned(),
+        break_flag: break_flag.clone(),
+        continue_flag: continue_flag.clone(),
+    });
+    let body = lower_stmts(&block.stmts, frames, state);
+    frames.pop();
+
+    // The continue flag is declared inside of the loop body, such that it is reset in every
+    // iteration.
+    let mut iteration = vec![];
+    if let Some(continue_flag) = continue_flag {
+        iteration.push(declare_flag(&continue_flag));
+    }
+    match condition {
+        Some(condition) => iteration.push(Stmt::If(ast::IfStmt {
+            condition: condition.to_owned(),
+            then_branch: ast::BlockStmt { stmts: body },
+            else_branch: ast::BlockStmt {
+                stmts: vec![set_flag(&break_flag)],
+            },
+        })),
+        None => iteration.extend(body),
+    }
+
+    Stmt::Block(ast::BlockStmt {
+        stmts: vec![
+            declare_flag(&break_flag),
+            Stmt::While(ast::WhileStmt {
+                condition: not(flag(&break_flag)),
+                block: ast::BlockStmt { stmts: iteration },
+                label: None,
+            }),
+        ],
+    })
+}
+
+/// Lower a list of statements inside of a loop body. All statements following a statement
+/// that can exit the current iteration are only executed if the innermost loop's flags have
+/// not been set.
+fn lower_stmts(
+    stmts: &[Stmt],
+    frames: &mut Vec<LoopFrame>,
+    state: &mut CompilerState,
+) -> Vec<Stmt> {
+    let mut lowered = vec![];
+    for (i, stmt) in stmts.iter().enumerate() {
+        lowered.extend(lower_stmt(stmt, frames, state));
+
+        if escaping_loop_exits(std::slice::from_ref(stmt)).is_empty() {
+            continue;
+        }
+
+        let remaining = lower_stmts(&stmts[i + 1..], frames, state);
+        if !remaining.is_empty() {
+            let innermost = frames.last().unwrap();
+            let iteration_exited = match &innermost.continue_flag {
+                Some(continue_flag) => Expr::Binop(
+                    Box::new(flag(&innermost.break_flag)),
+                    ast::BinOp::Or,
+                    Box::new(flag(continue_flag)),
+                    Typing::KnownType(DataType::Bool),
+                ),
+                None => flag(&innermost.break_flag),
+            };
+            lowered.push(Stmt::If(ast::IfStmt {
+                condition: not(iteration_exited),
+                then_branch: ast::BlockStmt { stmts: remaining },
+                else_branch: ast::BlockStmt { stmts: vec![] },
+            }));
+        }
+
+        break;
+    }
+
+    lowered
+}
+
+fn lower_stmt(stmt: &Stmt, frames: &mut Vec<LoopFrame>, state: &mut CompilerState) -> Vec<Stmt> {
+    match stmt {
+        // A `break` exits all loops up to and including the targeted loop
+        Stmt::Break(label) => {
+            let target = target_frame(frames, label);
+            frames[target..]
+                .iter()
+                .map(|frame| set_flag(&frame.break_flag))
+                .collect()
+        }
+
+        // A `continue` exits all loops nested inside the targeted loop, and the current
+        // iteration of the targeted loop
+        Stmt::Continue(label) => {
+            let target = target_frame(frames, label);
+            let continue_flag = frames[target]
+                .continue_flag
+                .as_ref()
+                .expect("Targeted loop must have a continue flag");
+            let mut lowered = vec![set_flag(continue_flag)];
+            lowered.extend(
+                frames[target + 1..]
+                    .iter()
+                    .map(|frame| set_flag(&frame.break_flag)),
+            );
+            lowered
+        }
+        Stmt::Loop(loop_stmt) => vec![lower_loop(
+            &loop_stmt.label,
+            None,
+            &loop_stmt.block,
+            frames,
+            state,
+        )],
+        Stmt::While(while_stmt) if contains_loop_exits(&while_stmt.block) => vec![lower_loop(
+            &while_stmt.label,
+            Some(&while_stmt.condition),
+            &while_stmt.block,
+            frames,
+            state,
+        )],
+        Stmt::If(if_stmt) => vec![Stmt::If(ast::IfStmt {
+            condition: if_stmt.condition.to_owned(),
+            then_branch: lower_block(&if_stmt.then_branch, frames, state),
+            else_branch: lower_block(&if_stmt.else_branch, frames, state),
+        })],
+        Stmt::Block(block) => vec![Stmt::Block(lower_block(block, frames, state))],
+        Stmt::Match(match_stmt) => vec![Stmt::Match(ast::MatchStmt {
+            match_expression: match_stmt.match_expression.to_owned(),
+            arms: match_stmt
+                .arms
+                .iter()
+                .map(|arm| ast::MatchArm {
+                    match_condition: arm.match_condition.to_owned(),
+                    guard: arm.guard.to_owned(),
+                    body: lower_block(&arm.body, frames, state),
+                })
+                .collect(),
+        })],
+        _ => vec![stmt.to_owned()],
+    }
+}
+
+fn lower_block(
+    block: &ast::BlockStmt<Typing>,
+    frames: &mut Vec<LoopFrame>,
+    state: &mut CompilerState,
+) -> ast::BlockStmt<Typing> {
+    ast::BlockStmt {
+        stmts: lower_stmts(&block.stmts, frames, state),
+    }
+}
+
+/// Return the index of the frame of the loop targeted by a `break` or `continue`. The type
+/// checker rejects loop exits outside of loops and loop exits with undeclared labels, so the
+/// targeted loop always exists.
+fn target_frame(frames: &[LoopFrame], label: &Option<String>) -> usize {
+    let target = match label {
+        None => frames.len().checked_sub(1),
+        Some(_) => frames.iter().rposition(|frame| &frame.label == label),
+    };
+    target.unwrap_or_else(|| unreachable!("loop exit without a targeted loop: {label:?}"))
+}
+
+/// Return all `break` and `continue` statements in `stmts` that are not captured by a loop
+/// declared inside `stmts`.
+fn escaping_loop_exits(stmts: &[Stmt]) -> Vec<(LoopExitKind, Option<String>)> {
+    let mut exits = vec![];
+    for stmt in stmts {
+        match stmt {
+            Stmt::Break(label) => exits.push((LoopExitKind::Break, label.to_owned())),
+            Stmt::Continue(label) => exits.push((LoopExitKind::Continue, label.to_owned())),
+            Stmt::Loop(ast::LoopStmt { block, label })
+            | Stmt::While(ast::WhileStmt { block, label, .. }) => exits.extend(
+                escaping_loop_exits(&block.stmts)
+                    .into_iter()
+                    .filter(|(_, exit_label)| exit_label.is_some() && exit_label != label),
+            ),
+            Stmt::If(if_stmt) => {
+                exits.extend(escaping_loop_exits(&if_stmt.then_branch.stmts));
+                exits.extend(escaping_loop_exits(&if_stmt.else_branch.stmts));
+            }
+            Stmt::Block(block) => exits.extend(escaping_loop_exits(&block.stmts)),
+            Stmt::Match(match_stmt) => {
+                for arm in match_stmt.arms.iter() {
+                    exits.extend(escaping_loop_exits(&arm.body.stmts));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    exits
+}
+
+fn flag(name: &str) -> Expr {
+    Expr::Var(ast::Identifier::String(
+        name.to_owned(),
+        Typing::KnownType(DataType::Bool),
+    ))
+}
+
+fn not(expr: Expr) -> Expr {
+    Expr::Unary(
+        ast::UnaryOp::Not,
+        Box::new(expr),
+        Typing::KnownType(DataType::Bool),
+    )
+}
+
+fn declare_flag(name: &str) -> Stmt {
+    Stmt::Let(ast::LetStmt {
+        var_name: name.to_owned(),
+        mutable: true,
+        data_type: Some(DataType::Bool),
+        expr: Expr::Lit(ast::ExprLit::Bool(false)),
+    })
+}
+
+fn set_flag(name: &str) -> Stmt {
+    Stmt::Assign(ast::AssignStmt {
+        identifier: ast::Identifier::String(name.to_owned(), Typing::KnownType(DataType::Bool)),
+        expr: Expr::Lit(ast::ExprLit::Bool(true)),
+    })
+}
@@ -4,7 +4,6 @@ use triton_vm::instruction::LabelledInstruction;
 
 use crate::ast_types;
 use crate::custom_type_resolver::resolve_custom_types;
-use crate::extract_types_and_function;
 use crate::tasm_code_generator::compile_function;
 use crate::type_checker::annotate_fn_outer;
 use crate::StructsAndMethodsRustAst;
@@ -127,10 +126,13 @@ impl SourceFileLocation {
         syn::parse_file(&file).expect("Unable to parse rust code")
     }
 
-    fn read_file_from_disk(&self) -> String {
+    fn disk_path(&self) -> String {
         let end_of_path = self.end_of_file_path();
-        let path = format!("{MANIFEST_DIR}/{PROGRAMS_DIR}/{end_of_path}");
+        format!("{MANIFEST_DIR}/{PROGRAMS_DIR}/{end_of_path}")
+    }
 
+    fn read_file_from_disk(&self) -> String {
+        let path = self.disk_path();
         fs::read_to_string(&path).unwrap_or_else(|_| panic!("unable to read \"{path}\" from disk"))
     }
 
@@ -151,43 +153,23 @@ impl SourceFileLocation {
     }
 }
 
-fn parse_functions_and_types_inner(location: &SourceFileLocation) -> StructsAndMethodsRustAst {
-    let file = location.parse_file();
-    let (mut custom_types, dependencies) = extract_types_and_function(&file);
-
-    for dependency in dependencies {
-        let new_location = SourceFileLocation {
-            module_name: dependency,
-            ..location.to_owned()
-        };
-        let imported_custom_types = parse_functions_and_types_inner(&new_location);
-        custom_types.extend(imported_custom_types)
-    }
-
-    custom_types
-}
-
+/// Compile a program on disk through [`compile_to_instructions`][crate::compile_to_instructions],
+/// like any other program. Panics with the rendered diagnostics if the program does not compile.
 pub(crate) fn compile_for_test(
     location: &EntrypointLocation,
     list_type: ast_types::ListType,
 ) -> Vec<LabelledInstruction> {
-    get_standard_setup!(list_type, graft_config, libraries);
-
-    let entrypoint_fn = location.extract_entrypoint();
-    let rust_struct_asts = parse_functions_and_types_inner(&location.source_file_location);
-    let mut oil_ast = graft_config.graft_fn_decl(&entrypoint_fn);
-    let mut composite_types =
-        graft_config.graft_custom_types_methods_and_associated_functions(rust_struct_asts);
-    composite_types.checked_merge(graft_config.imported_custom_types);
-
-    resolve_custom_types(&mut oil_ast, &mut composite_types);
-
-    // type-check and annotate
-    annotate_fn_outer(&mut oil_ast, &mut composite_types, &libraries);
-
-    let tasm = compile_function(&oil_ast, &libraries, &composite_types);
-
-    tasm.compose()
+    let source_file_location = &location.source_file_location;
+    let SourceCodeProvider::Disk = source_file_location.provider else {
+        panic!("Only programs on disk can be compiled for tests");
+    };
+
+    crate::compile_to_instructions(
+        &source_file_location.disk_path(),
+        &location.entrypoint,
+        list_type,
+    )
+    .unwrap_or_else(|diagnostics| panic!("{diagnostics}"))
 }
 
 /// Produce a [`BasicSnippet`][basic_snippet] through compilation and string interpolation.
@@ -199,14 +181,18 @@ pub(crate) fn compile_to_basic_snippet(
     list_type: ast_types::ListType,
 ) -> String {
     get_standard_setup!(list_type, graft_config, libraries);
-    let mut oil_ast = graft_config.graft_fn_decl(&rust_ast);
     let mut composite_types =
         graft_config.graft_custom_types_methods_and_associated_functions(structs_and_methods);
+    let mut oil_ast = graft_config.graft_fn_decl(&rust_ast);
+    graft_config
+        .take_diagnostics()
+        .unwrap_or_else(|diagnostics| panic!("{diagnostics}"));
 
     resolve_custom_types(&mut oil_ast, &mut composite_types);
 
     // type-check and annotate
-    annotate_fn_outer(&mut oil_ast, &mut composite_types, &libraries);
+    annotate_fn_outer(&mut oil_ast, &mut composite_types, &libraries)
+        .unwrap_or_else(|diagnostics| panic!("{diagnostics}"));
 
     let tasm = compile_function(&oil_ast, &libraries, &composite_types);
 
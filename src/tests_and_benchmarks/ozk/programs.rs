@@ -1,7 +1,10 @@
 mod arithmetic;
 mod arrays;
 mod boxed;
+mod compile_errors;
 mod enums;
+mod loops;
+mod modules;
 mod option_types;
 mod other;
 mod project_euler;
@@ -2,3 +2,4 @@ mod bfe_add;
 mod dazefield_element_mul;
 mod mod_pow_u32;
 pub mod montyred;
+mod unsigned_integer_methods;
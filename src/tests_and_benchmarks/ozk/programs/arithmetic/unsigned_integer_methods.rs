@@ -0,0 +1,185 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn write_option_u32(value: Option<u32>) {
+    match value {
+        Some(inner) => {
+            tasm::tasm_io_write_to_stdout___bool(true);
+            tasm::tasm_io_write_to_stdout___u32(inner);
+        }
+        None => {
+            tasm::tasm_io_write_to_stdout___bool(false);
+        }
+    };
+
+    return;
+}
+
+fn write_option_u64(value: Option<u64>) {
+    match value {
+        Some(inner) => {
+            tasm::tasm_io_write_to_stdout___bool(true);
+            tasm::tasm_io_write_to_stdout___u64(inner);
+        }
+        None => {
+            tasm::tasm_io_write_to_stdout___bool(false);
+        }
+    };
+
+    return;
+}
+
+fn write_option_u128(value: Option<u128>) {
+    match value {
+        Some(inner) => {
+            tasm::tasm_io_write_to_stdout___bool(true);
+            tasm::tasm_io_write_to_stdout___u128(inner);
+        }
+        None => {
+            tasm::tasm_io_write_to_stdout___bool(false);
+        }
+    };
+
+    return;
+}
+
+fn main() {
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: u32 = tasm::tasm_io_read_stdin___u32();
+    write_option_u32(a.checked_add(b));
+    write_option_u32(a.checked_sub(b));
+    write_option_u32(a.checked_mul(b));
+    write_option_u32(a.checked_div(b));
+    tasm::tasm_io_write_to_stdout___u32(a.wrapping_add(b));
+    tasm::tasm_io_write_to_stdout___u32(a.wrapping_sub(b));
+    tasm::tasm_io_write_to_stdout___u32(a.wrapping_mul(b));
+    tasm::tasm_io_write_to_stdout___u32(a.saturating_add(b));
+    tasm::tasm_io_write_to_stdout___u32(a.saturating_sub(b));
+    tasm::tasm_io_write_to_stdout___u32(a.saturating_mul(b));
+    tasm::tasm_io_write_to_stdout___u32(a.min(b));
+    tasm::tasm_io_write_to_stdout___u32(a.max(b));
+    tasm::tasm_io_write_to_stdout___u32(a.trailing_zeros());
+    tasm::tasm_io_write_to_stdout___bool(b.is_power_of_two());
+    tasm::tasm_io_write_to_stdout___u32((a >> 1).next_power_of_two());
+    tasm::tasm_io_write_to_stdout___u32(a.max(1).ilog2());
+
+    let c: u64 = tasm::tasm_io_read_stdin___u64();
+    let d: u64 = tasm::tasm_io_read_stdin___u64();
+    write_option_u64(c.checked_add(d));
+    write_option_u64(c.checked_sub(d));
+    write_option_u64(c.checked_mul(d));
+    write_option_u64(c.checked_div(d));
+    tasm::tasm_io_write_to_stdout___u64(c.wrapping_add(d));
+    tasm::tasm_io_write_to_stdout___u64(c.wrapping_mul(d));
+    tasm::tasm_io_write_to_stdout___u64(c.saturating_add(d));
+    tasm::tasm_io_write_to_stdout___u64(c.saturating_sub(d));
+    tasm::tasm_io_write_to_stdout___u64(c.saturating_mul(d));
+    tasm::tasm_io_write_to_stdout___u64(c.min(d));
+    tasm::tasm_io_write_to_stdout___u64(c.max(d));
+    tasm::tasm_io_write_to_stdout___u32(c.trailing_zeros());
+    tasm::tasm_io_write_to_stdout___bool(d.is_power_of_two());
+    tasm::tasm_io_write_to_stdout___u64((c >> 1).next_power_of_two());
+    tasm::tasm_io_write_to_stdout___u32(c.max(1).ilog2());
+
+    let e: u128 = tasm::tasm_io_read_stdin___u128();
+    let f: u128 = tasm::tasm_io_read_stdin___u128();
+    write_option_u128(e.checked_add(f));
+    write_option_u128(e.checked_sub(f));
+    write_option_u128(e.checked_mul(f));
+    write_option_u128(e.checked_div(f));
+    tasm::tasm_io_write_to_stdout___u128(e.wrapping_add(f));
+    tasm::tasm_io_write_to_stdout___u128(e.wrapping_sub(f));
+    tasm::tasm_io_write_to_stdout___u128(e.wrapping_mul(f));
+    tasm::tasm_io_write_to_stdout___u128(e.saturating_add(f));
+    tasm::tasm_io_write_to_stdout___u128(e.saturating_sub(f));
+    tasm::tasm_io_write_to_stdout___u128(e.saturating_mul(f));
+    tasm::tasm_io_write_to_stdout___u128(e.min(f));
+    tasm::tasm_io_write_to_stdout___u128(e.max(f));
+    tasm::tasm_io_write_to_stdout___u32(e.trailing_zeros());
+    tasm::tasm_io_write_to_stdout___bool(f.is_power_of_two());
+    tasm::tasm_io_write_to_stdout___u128((e >> 1).next_power_of_two());
+    tasm::tasm_io_write_to_stdout___u32(e.max(1).ilog2());
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn unsigned_integer_methods_test() {
+        for (a, b, c, d, e, f) in [
+            (17u32, 5u32, 1u64 << 40, 12345u64, 1u128 << 100, 999u128),
+            (3, 0, 0, 0, 0, 0),
+            (5, 9, 7, 1 << 20, 7, 1 << 64),
+            (
+                u32::MAX,
+                1 << 31,
+                u64::MAX - 1,
+                u64::MAX,
+                u128::MAX - 4,
+                (1 << 127) + 3,
+            ),
+            (
+                1 << 20,
+                1 << 20,
+                (1 << 32) + 3,
+                (1 << 32) + 5,
+                (1 << 70) + 1,
+                (1 << 60) - 1,
+            ),
+        ] {
+            // Integers are read as `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [
+                a as u64,
+                b as u64,
+                c >> 32,
+                c & u32::MAX as u64,
+                d >> 32,
+                d & u32::MAX as u64,
+                (e >> 96) as u64,
+                (e >> 64) as u64 & u32::MAX as u64,
+                (e >> 32) as u64 & u32::MAX as u64,
+                e as u64 & u32::MAX as u64,
+                (f >> 96) as u64,
+                (f >> 64) as u64 & u32::MAX as u64,
+                (f >> 32) as u64 & u32::MAX as u64,
+                f as u64 & u32::MAX as u64,
+            ]
+            .into_iter()
+            .map(BFieldElement::new)
+            .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("arithmetic", "unsigned_integer_methods", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
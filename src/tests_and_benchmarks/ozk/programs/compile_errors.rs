@@ -0,0 +1,62 @@
+mod duplicate_definitions;
+mod duplicate_functions;
+mod invalid_cast;
+mod invalid_enum_variants;
+mod invalid_expressions;
+mod invalid_for_loops;
+mod invalid_generic_calls;
+mod invalid_items;
+mod invalid_loop_exits;
+mod invalid_traits;
+mod missing_type_annotations;
+mod multiple_errors;
+mod unsupported_generic_types;
+
+#[cfg(test)]
+mod test_helpers {
+    use std::fs;
+
+    use itertools::Itertools;
+
+    use crate::ast_types::ListType;
+    use crate::compile_error::Diagnostics;
+    use crate::compile_error::ErrorCode;
+
+    /// Compile a program that must not compile, which is written to a file of its own, named
+    /// after `name`, since tests run in parallel. Return the errors along with their rendering,
+    /// which shows the source lines of the errors while the file exists.
+    fn compile_erroneous_program(name: &str, program: &str) -> (Diagnostics, String) {
+        let file_name = format!("tasm_lang_{name}_{}.rs", std::process::id());
+        let source_file = std::env::temp_dir().join(file_name);
+        fs::write(&source_file, program).unwrap();
+
+        let result =
+            crate::compile_to_instructions(source_file.to_str().unwrap(), "main", ListType::Unsafe);
+        let rendered = result.as_ref().err().map(Diagnostics::render);
+        fs::remove_file(&source_file).unwrap();
+
+        (
+            result.expect_err("program must not compile"),
+            rendered.unwrap(),
+        )
+    }
+
+    /// Compile a program that must not compile, and assert that it is rejected with errors of
+    /// the given codes on the given lines, in the order in which they are reported. The
+    /// rendered errors are returned, such that the tests can check their messages.
+    pub(super) fn assert_compile_errors(
+        name: &str,
+        program: &str,
+        expected_codes_and_lines: &[(ErrorCode, usize)],
+    ) -> String {
+        let (diagnostics, rendered) = compile_erroneous_program(name, program);
+        let codes_and_lines = diagnostics
+            .errors
+            .iter()
+            .map(|error| (error.code, error.location.as_ref().unwrap().line))
+            .collect_vec();
+        assert_eq!(expected_codes_and_lines, codes_and_lines);
+
+        rendered
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn items_with_the_same_name_in_different_modules_are_reported_test() {
+        let program = "\
+mod a {
+    pub struct Point(u32);
+    pub const LIMIT: u32 = 10;
+}
+
+mod b {
+    pub struct Point(u64);
+    pub const LIMIT: u64 = 20;
+}
+
+fn main() {
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "duplicate_definitions",
+            program,
+            &[
+                (ErrorCode::DuplicateDefinition, 7),
+                (ErrorCode::DuplicateDefinition, 8),
+            ],
+        );
+        assert!(rendered.contains("error[E0015]: the name `Point` is defined multiple times"));
+        assert!(rendered.contains("error[E0015]: the name `LIMIT` is defined multiple times"));
+    }
+}
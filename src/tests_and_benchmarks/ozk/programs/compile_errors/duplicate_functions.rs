@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn free_functions_must_have_unique_names_test() {
+        let program = "\
+fn main() {
+    tasm::tasm_io_write_to_stdout___u32(helper());
+    return;
+}
+
+fn helper() -> u32 {
+    return 1;
+}
+
+fn helper() -> u32 {
+    return 2;
+}
+";
+        let rendered = assert_compile_errors(
+            "duplicate_functions",
+            program,
+            &[(ErrorCode::DuplicateFunction, 10)],
+        );
+        assert!(rendered.contains("error[E0008]: the function `helper` is defined multiple times"));
+    }
+}
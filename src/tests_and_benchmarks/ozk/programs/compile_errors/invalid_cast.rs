@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn impossible_cast_is_a_type_error_test() {
+        // `rustc` rejects this program as well, so it cannot be a module of this crate
+        let program = "\
+fn main() {
+    let digest: Digest = tasm::tasm_io_read_stdin___digest();
+    let value: u32 = digest as u32;
+    return;
+}
+";
+        let rendered = assert_compile_errors("invalid_cast", program, &[(ErrorCode::TypeError, 3)]);
+        assert!(rendered.contains("error[E0014]: cannot cast `Digest` as `u32`"));
+    }
+}
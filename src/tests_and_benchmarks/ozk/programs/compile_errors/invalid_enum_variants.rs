@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn invalid_variants_with_named_fields_are_type_errors_test() {
+        let program = "\
+struct Point {
+    x: u32,
+}
+
+enum Shape {
+    Circle { radius: u32 },
+    Square { side: u32 },
+}
+
+fn main() {
+    let p: Point = Point::Origin { x: 1 };
+    let s: Shape = Shape::Circle { diameter: 2 };
+    let t: Shape = Shape::Square { side: 3 };
+    match t {
+        Shape::Circle { diameter } => {
+            tasm::tasm_io_write_to_stdout___u32(diameter);
+        }
+        Shape::Square { side } => {
+            tasm::tasm_io_write_to_stdout___u32(side);
+        }
+    };
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "invalid_enum_variants",
+            program,
+            &[
+                (ErrorCode::TypeError, 11),
+                (ErrorCode::TypeError, 12),
+                (ErrorCode::TypeError, 14),
+            ],
+        );
+
+        for message in [
+            "expected enum, found struct `Point`",
+            "variant `Shape::Circle` has no field named `diameter`",
+        ] {
+            assert!(rendered.contains(message), "missing error: {message}");
+        }
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn type_errors_in_expressions_are_reported_at_their_statement_test() {
+        let program = "\
+enum Color {
+    Red,
+    Green,
+}
+
+fn nothing() {
+    return;
+}
+
+fn main() {
+    let a: u32 = 1;
+    let b: u64 = 2;
+    let c: u32 = a + b;
+    let d: u32 = e;
+    let f: u32 = g(a);
+    let h: u32 = nothing();
+    let color: Color = Color::Red;
+    match color {
+        Color::Red => {
+            return;
+        }
+    };
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "invalid_expressions",
+            program,
+            &[
+                (ErrorCode::TypeError, 13),
+                (ErrorCode::TypeError, 14),
+                (ErrorCode::TypeError, 15),
+                (ErrorCode::TypeError, 16),
+                (ErrorCode::TypeError, 18),
+            ],
+        );
+
+        for message in [
+            "mismatched types in the operands of `+`: expected `u32`, found `u64`",
+            "cannot find value `e` in this scope",
+            "cannot find function `g` in this scope",
+            "function `nothing` returns no value, so its call cannot be used as an expression",
+            "non-exhaustive patterns: `Color::Green` not covered",
+        ] {
+            assert!(rendered.contains(message), "missing error: {message}");
+        }
+        assert!(rendered.contains(".rs:13:5"));
+    }
+
+    #[test]
+    fn incomparable_operands_are_type_errors_test() {
+        let program = "\
+struct Point(u64, u64);
+
+fn main() {
+    let a: Point = Point(1, 2);
+    let b: Point = Point(3, 4);
+    let c: bool = a == b;
+    let d: Vec<Vec<u64>> = Vec::<Vec<u64>>::with_capacity(2);
+    let e: Vec<Vec<u64>> = Vec::<Vec<u64>>::with_capacity(2);
+    let f: bool = d != e;
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "incomparable_operands",
+            program,
+            &[(ErrorCode::TypeError, 6), (ErrorCode::TypeError, 9)],
+        );
+
+        for message in [
+            "binary operation `==` cannot be applied to type `Point`",
+            "binary operation `!=` is not supported for type `Vec<Vec<u64>>`",
+        ] {
+            assert!(rendered.contains(message), "missing error: {message}");
+        }
+    }
+
+    #[test]
+    fn unsupported_map_syntax_is_reported_test() {
+        let program = "\
+fn main() {
+    let v: Vec<u64> = Vec::<u64>::with_capacity(2);
+    let a: Vec<u64> = v.iter().map(|x| x + 1).collect_vec();
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "unsupported_map_syntax",
+            program,
+            &[(ErrorCode::UnsupportedSyntax, 3)],
+        );
+        assert!(rendered.contains(
+            "only `x.into_iter().map(<function or closure>).collect_vec()` is supported with `map`"
+        ));
+    }
+
+    #[test]
+    fn functions_not_taking_list_elements_cannot_map_lists_test() {
+        let program = "\
+fn main() {
+    let v: Vec<u64> = Vec::<u64>::with_capacity(2);
+    let a: Vec<u64> = v.into_iter().map(apply_to_one).collect_vec();
+    let b: Vec<bool> = v.into_iter().map(is_zero).collect_vec();
+    return;
+}
+
+fn apply_to_one(f: impl Fn(u64) -> u64) -> u64 {
+    return f(1);
+}
+
+fn is_zero(x: u32) -> bool {
+    return x == 0;
+}
+";
+        let rendered = assert_compile_errors(
+            "functions_not_taking_list_elements",
+            program,
+            &[(ErrorCode::TypeError, 3), (ErrorCode::TypeError, 4)],
+        );
+
+        for message in [
+            "cannot map a list with `apply_to_one`, which is generic or takes a function argument",
+            "expected a function taking `u64`, got `is_zero`",
+        ] {
+            assert!(rendered.contains(message), "missing error: {message}");
+        }
+    }
+
+    #[test]
+    fn out_of_range_literals_are_type_errors_test() {
+        let program = "\
+fn main() {
+    let a: i32 = 2147483648;
+    let b: i32 = -2147483648;
+    let c: i64 = -9223372036854775808;
+    let d: i64 = -9223372036854775809;
+    let e: u8 = 255;
+    let f: u8 = 256;
+    let g: u16 = 65536;
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "out_of_range_literals",
+            program,
+            &[
+                (ErrorCode::TypeError, 2),
+                (ErrorCode::TypeError, 5),
+                (ErrorCode::TypeError, 7),
+                (ErrorCode::TypeError, 8),
+            ],
+        );
+
+        for message in [
+            "literal out of range for `i32`: `2147483648` does not fit",
+            "literal out of range for `i64`: `9223372036854775809` does not fit",
+            "literal out of range for `u8`: `256` does not fit",
+            "literal out of range for `u16`: `65536` does not fit",
+        ] {
+            assert!(rendered.contains(message), "missing error: {message}");
+        }
+    }
+}
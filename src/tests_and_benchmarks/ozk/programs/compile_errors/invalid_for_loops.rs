@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn range_with_bounds_of_different_types_is_a_type_error_test() {
+        let program = "\
+fn main() {
+    let end: u64 = 10;
+    for i in 0u32..end {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "mismatched_range_bounds",
+            program,
+            &[(ErrorCode::TypeError, 3)],
+        );
+        assert!(rendered.contains("mismatched types in range bounds: expected `u32`, found `u64`"));
+    }
+
+    #[test]
+    fn range_of_bfes_is_a_type_error_test() {
+        let program = "\
+fn main() {
+    let start: BFieldElement = tasm::tasm_io_read_stdin___bfe();
+    let end: BFieldElement = tasm::tasm_io_read_stdin___bfe();
+    for i in start..end {
+        tasm::tasm_io_write_to_stdout___bfe(i);
+    }
+    return;
+}
+";
+        let rendered = assert_compile_errors("bfe_range", program, &[(ErrorCode::TypeError, 4)]);
+        assert!(rendered
+            .contains("ranges in for-loops must be of type `u32` or `u64`, found `BFieldElement`"));
+    }
+
+    #[test]
+    fn iterating_over_an_integer_is_a_type_error_test() {
+        let program = "\
+fn main() {
+    let a: u32 = 10;
+    for i in a {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "iterate_over_integer",
+            program,
+            &[(ErrorCode::TypeError, 3)],
+        );
+        assert!(rendered.contains("cannot iterate over value of type `u32`"));
+    }
+}
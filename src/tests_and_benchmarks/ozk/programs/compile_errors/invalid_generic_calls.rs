@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn wrong_number_of_type_arguments_test() {
+        let program = "\
+fn main() {
+    let a: u32 = largest::<u32, u32>(3, 4);
+    return;
+}
+
+fn largest<T: PartialOrd>(lhs: T, rhs: T) -> T {
+    if lhs > rhs {
+        return lhs;
+    }
+    return rhs;
+}
+";
+        let rendered = assert_compile_errors(
+            "wrong_number_of_type_arguments",
+            program,
+            &[(ErrorCode::TypeError, 2)],
+        );
+        assert!(rendered.contains("`largest` takes 1 type arguments but 2 were supplied"));
+    }
+
+    #[test]
+    fn generic_function_cannot_call_functions_local_to_the_caller_test() {
+        let program = "\
+fn main() {
+    let a: u32 = 4;
+    if a > 2 {
+        fn helper(x: u32) -> u32 {
+            return x;
+        }
+        let b: u32 = call_helper(a);
+    }
+    return;
+}
+
+fn call_helper<T>(x: T) -> T {
+    return helper(x);
+}
+";
+        let rendered = assert_compile_errors(
+            "generic_function_scope",
+            program,
+            &[(ErrorCode::TypeError, 13)],
+        );
+        assert!(rendered.contains("cannot find function `helper` in this scope"));
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn derive_attributes_and_receivers_are_checked_test() {
+        let program = "\
+#[derive(Clone, Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+struct Point(u32);
+
+#[derive = \"Copy\"]
+struct Size(u32);
+
+impl Point {
+    fn x(self: Box<Self>) -> u32 {
+        return self.0;
+    }
+}
+
+fn main() {
+    let a: Point = Point(1);
+    let b: Point = Point(2);
+    let same: bool = a == b;
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "invalid_items",
+            program,
+            &[
+                (ErrorCode::UnsupportedSyntax, 6),
+                (ErrorCode::UnsupportedSyntax, 10),
+            ],
+        );
+        assert!(rendered.contains("expected a list of traits, like `#[derive(Clone, Copy)]`"));
+        assert!(rendered.contains(
+            "only `self`, `mut self`, `&self` and `&mut self` are supported as receivers"
+        ));
+    }
+}
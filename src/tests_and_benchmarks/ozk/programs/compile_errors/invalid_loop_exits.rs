@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn break_and_continue_must_target_an_enclosing_loop_test() {
+        let program = "\
+fn main() {
+    let mut i: u32 = 0;
+    while i < 10 {
+        i += 1;
+        continue 'outer;
+    }
+    break;
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "invalid_loop_exits",
+            program,
+            &[
+                (ErrorCode::InvalidLoopExit, 5),
+                (ErrorCode::InvalidLoopExit, 7),
+            ],
+        );
+        assert!(rendered.contains("error[E0012]: use of undeclared label `'outer` in `continue`"));
+        assert!(rendered.contains("error[E0012]: `break` can only be used inside of a loop"));
+    }
+}
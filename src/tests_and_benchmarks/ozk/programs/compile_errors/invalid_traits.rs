@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn missing_trait_methods_are_type_errors_test() {
+        let program = "\
+trait Shape {
+    fn area(self) -> u64;
+    fn perimeter(self) -> u64;
+}
+
+struct Square(u64);
+
+impl Shape for Square {
+    fn area(self) -> u64 {
+        return self.0 * self.0;
+    }
+}
+
+fn main() {
+    let square: Square = Square(2);
+    let area: u64 = square.area();
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "missing_trait_methods",
+            program,
+            &[(ErrorCode::TypeError, 8)],
+        );
+        assert!(rendered
+            .contains("not all trait items implemented, missing: `perimeter` of trait `Shape`"));
+    }
+
+    #[test]
+    fn unsatisfied_trait_bounds_are_type_errors_test() {
+        let program = "\
+trait Shape {
+    fn area(self) -> u64;
+}
+
+#[derive(Clone, Copy)]
+struct Square(u64);
+
+impl Shape for Square {
+    fn area(self) -> u64 {
+        return self.0 * self.0;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Circle(u64);
+
+fn main() {
+    let a: u64 = total_area(Square(2), Square(3));
+    let b: u64 = total_area(Circle(2), Circle(3));
+    let c: u64 = total_area(2u64, 3u64);
+    let d: bool = largest(true, false);
+    return;
+}
+
+fn total_area<T: Shape>(first: T, second: T) -> u64 {
+    return first.area() + second.area();
+}
+
+fn largest<T: PartialOrd>(lhs: T, rhs: T) -> T {
+    if lhs > rhs {
+        return lhs;
+    }
+    return rhs;
+}
+";
+        let rendered = assert_compile_errors(
+            "unsatisfied_trait_bounds",
+            program,
+            &[
+                (ErrorCode::TypeError, 19),
+                (ErrorCode::TypeError, 20),
+                (ErrorCode::TypeError, 21),
+            ],
+        );
+
+        for message in [
+            "the trait bound `Circle: Shape` is not satisfied, as required by `total_area`",
+            "the trait bound `u64: Shape` is not satisfied, as required by `total_area`",
+            "the trait bound `bool: PartialOrd` is not satisfied, as required by `largest`",
+        ] {
+            assert!(rendered.contains(message), "missing error: {message}");
+        }
+    }
+
+    #[test]
+    fn unknown_trait_bounds_are_unsupported_test() {
+        let program = "\
+fn main() {
+    let a: u32 = identity(4);
+    return;
+}
+
+fn identity<T: std::fmt::Debug>(value: T) -> T {
+    return value;
+}
+";
+        let rendered = assert_compile_errors(
+            "unknown_trait_bounds",
+            program,
+            &[(ErrorCode::UnsupportedSyntax, 6)],
+        );
+        assert!(rendered.contains("trait bound `Debug` is not supported"));
+    }
+
+    #[test]
+    fn impls_of_unknown_traits_are_unsupported_test() {
+        let program = "\
+#[derive(Clone, Copy)]
+struct Point(u64, u64);
+
+impl std::ops::Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        return Point(self.0 + rhs.0, self.1 + rhs.1);
+    }
+}
+
+impl Default for Point {
+    fn default() -> Point {
+        return Point(0, 0);
+    }
+}
+
+fn main() {
+    let point: Point = Point(1, 2) + Point(3, 4);
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "impls_of_unknown_traits",
+            program,
+            &[(ErrorCode::UnsupportedSyntax, 12)],
+        );
+        assert!(rendered
+            .contains("trait `Default` is neither declared in the program nor an operator trait"));
+    }
+
+    #[test]
+    fn implementations_of_a_trait_with_different_type_arguments_are_rejected_test() {
+        let program = "\
+#[derive(Clone, Copy)]
+struct Point(u64, u64);
+
+impl std::ops::Add<Point> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        return Point(self.0 + rhs.0, self.1 + rhs.1);
+    }
+}
+
+impl std::ops::Add<u64> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: u64) -> Point {
+        return Point(self.0 + rhs, self.1 + rhs);
+    }
+}
+
+fn main() {
+    let point: Point = Point(1, 2) + Point(3, 4);
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "implementations_with_different_type_arguments",
+            program,
+            &[(ErrorCode::DuplicateDefinition, 15)],
+        );
+        assert!(rendered.contains("duplicate definitions with name `add` for `Point`"));
+    }
+}
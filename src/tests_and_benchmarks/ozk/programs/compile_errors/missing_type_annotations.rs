@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn field_of_binding_of_unknown_type_is_a_missing_type_annotation_test() {
+        let program = "\
+fn main() {
+    let pair = (4, 5);
+    let a: u32 = pair.0;
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "uninferred_field_access",
+            program,
+            &[(ErrorCode::MissingTypeAnnotation, 2)],
+        );
+        assert!(rendered.contains("error[E0003]: type annotations needed for `pair`"));
+    }
+
+    #[test]
+    fn errors_are_ordered_by_location_test() {
+        let program = "\
+fn main() {
+    let a = 4;
+    let b: u32 = true;
+    return;
+}
+";
+        assert_compile_errors(
+            "unused_uninferred_binding",
+            program,
+            &[
+                (ErrorCode::MissingTypeAnnotation, 2),
+                (ErrorCode::TypeError, 3),
+            ],
+        );
+    }
+}
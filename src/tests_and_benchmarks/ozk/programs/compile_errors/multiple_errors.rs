@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn all_errors_are_reported_with_their_location_test() {
+        let program = "\
+fn main() {
+    let a: u32;
+    a = 5;
+    println!(\"{a}\");
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "multiple_errors",
+            program,
+            &[
+                (ErrorCode::MissingInitializer, 2),
+                (ErrorCode::UnsupportedSyntax, 4),
+            ],
+        );
+        assert!(rendered.contains("error[E0005]: `a` must be initialized where it is declared"));
+        assert!(rendered.contains(".rs:2:5"));
+        assert!(rendered.contains("2 |     let a: u32;\n  |     ^^^^^^^^^^^"));
+        assert!(rendered.ends_with("error: aborting due to 2 previous errors"));
+    }
+}
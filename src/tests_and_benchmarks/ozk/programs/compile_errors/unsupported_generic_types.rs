@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test {
+    use crate::compile_error::ErrorCode;
+
+    use super::super::test_helpers::assert_compile_errors;
+
+    #[test]
+    fn recursive_generic_types_and_generic_types_of_type_parameters_are_rejected_test() {
+        let program = "\
+enum Tree<T> {
+    Leaf(T),
+    Node(Box<Tree<T>>, Box<Tree<T>>),
+}
+
+struct Pair<T> {
+    a: T,
+    b: T,
+}
+
+fn first<T>(pair: Pair<T>) -> T {
+    return pair.a;
+}
+
+fn main() {
+    let leaf: Tree<u32> = Tree::Leaf(4);
+    let a: u32 = first(Pair { a: 1, b: 2 });
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "unsupported_generic_types",
+            program,
+            &[
+                (ErrorCode::UnsupportedSyntax, 3),
+                (ErrorCode::UnsupportedSyntax, 3),
+                (ErrorCode::UnsupportedSyntax, 11),
+            ],
+        );
+        assert!(rendered.contains("recursive generic type `Tree` is not supported"));
+        assert!(rendered.contains(
+            "generic types cannot be instantiated with the type parameters of a generic function"
+        ));
+    }
+
+    #[test]
+    fn associated_constants_of_generic_types_are_rejected_test() {
+        let program = "\
+struct Wrapper<T> {
+    value: T,
+}
+
+impl<T> Wrapper<T> {
+    const LIMIT: u32 = 4;
+}
+
+fn main() {
+    let wrapper: Wrapper<u32> = Wrapper { value: 1 };
+    return;
+}
+";
+        let rendered = assert_compile_errors(
+            "associated_constants_of_generic_types",
+            program,
+            &[(ErrorCode::UnsupportedSyntax, 6)],
+        );
+        assert!(rendered.contains("associated constants of generic types are not supported"));
+    }
+}
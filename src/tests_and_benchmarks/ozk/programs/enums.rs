@@ -7,6 +7,8 @@ mod custom_struct_in_data;
 mod enum_with_non_copy_struct_data;
 mod enum_with_struct_data;
 mod enum_with_struct_with_two_vecs;
+mod match_expression;
+mod match_guards_and_nested_patterns;
 mod match_with_wildcard_bindings;
 mod move_boxed_enum_to_stack;
 mod rust_by_example_enums;
@@ -0,0 +1,114 @@
+use arbitrary::Arbitrary;
+use triton_vm::twenty_first::shared_math::bfield_codec::BFieldCodec;
+use triton_vm::BFieldElement;
+
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+enum Item {
+    Empty,
+    Single(u32),
+    Pair(u64, u32),
+}
+
+#[derive(Arbitrary, BFieldCodec, Clone, Debug)]
+enum BoxedItem {
+    A(BFieldElement, BFieldElement),
+    B(BFieldElement),
+    C,
+}
+
+fn main() {
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: u64 = tasm::tasm_io_read_stdin___u64();
+    write_item(Item::Empty);
+    write_item(Item::Single(a));
+    write_item(Item::Pair(b, a));
+
+    let category: u32 = match a {
+        0 => 100,
+        1..=9 => 200,
+        n => n + 1,
+    };
+    tasm::tasm_io_write_to_stdout___u32(category);
+
+    let boxed_item: Box<BoxedItem> =
+        BoxedItem::decode(&tasm::load_from_memory(BFieldElement::new(84))).unwrap();
+    let bfe_value: BFieldElement = match boxed_item.as_ref() {
+        BoxedItem::A(elem0, elem1) => *elem0 + *elem1,
+        BoxedItem::B(elem) => *elem,
+        BoxedItem::C => BFieldElement::new(7),
+    };
+    tasm::tasm_io_write_to_stdout___bfe(bfe_value);
+
+    return;
+}
+
+fn write_item(item: Item) {
+    let value: u64 = match item {
+        Item::Empty => 1,
+        Item::Single(x) => x as u64,
+        Item::Pair(hi, lo) => {
+            let sum: u64 = hi + lo as u64;
+            sum
+        }
+    };
+    tasm::tasm_io_write_to_stdout___u64(value);
+
+    let is_single: bool = match item {
+        Item::Single(_) => true,
+        _ => false,
+    };
+    tasm::tasm_io_write_to_stdout___bool(is_single);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use arbitrary::Unstructured;
+    use rand::random;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::execute_compiled_with_stack_and_ins_for_test;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::init_memory_from;
+
+    use super::*;
+
+    #[test]
+    fn match_expression_test() {
+        for a in [0, 5, 1 << 20] {
+            let rand: [u8; 100] = random();
+            let boxed_item = BoxedItem::arbitrary(&mut Unstructured::new(&rand)).unwrap();
+            let non_determinism = init_memory_from(&boxed_item, BFieldElement::new(84));
+
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [a, 1 << 10, 1 << 30]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+
+            // Run program on host machine
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location = EntrypointLocation::disk("enums", "match_expression", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                0,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
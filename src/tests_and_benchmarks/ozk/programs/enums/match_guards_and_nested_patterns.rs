@@ -0,0 +1,145 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+enum Inner {
+    Small(u32),
+    Large(u64),
+}
+
+#[derive(Clone, Copy)]
+enum Outer {
+    Empty,
+    Wrapped(Inner),
+    Pair { left: u32, right: Inner },
+}
+
+fn main() {
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: u64 = tasm::tasm_io_read_stdin___u64();
+
+    let some_a: Option<u32> = Some(a);
+    let some_zero: Option<u32> = Some(0);
+    let none: Option<u32> = None;
+    write_option(some_a);
+    write_option(some_zero);
+    write_option(none);
+
+    tasm::tasm_io_write_to_stdout___u64(outer_value(Outer::Empty));
+    tasm::tasm_io_write_to_stdout___u64(outer_value(Outer::Wrapped(Inner::Small(a))));
+    tasm::tasm_io_write_to_stdout___u64(outer_value(Outer::Wrapped(Inner::Large(b))));
+    tasm::tasm_io_write_to_stdout___u64(outer_value(Outer::Pair {
+        left: a,
+        right: Inner::Large(b),
+    }));
+    tasm::tasm_io_write_to_stdout___u64(outer_value(Outer::Pair {
+        left: a,
+        right: Inner::Small(a),
+    }));
+    tasm::tasm_io_write_to_stdout___u32(small_or_left(Outer::Wrapped(Inner::Small(a))));
+    tasm::tasm_io_write_to_stdout___u32(small_or_left(Outer::Pair {
+        left: a,
+        right: Inner::Large(b),
+    }));
+    tasm::tasm_io_write_to_stdout___u32(small_or_left(Outer::Wrapped(Inner::Large(b))));
+
+    return;
+}
+
+fn write_option(value: Option<u32>) {
+    match value {
+        Some(x) if x > 10 => {
+            tasm::tasm_io_write_to_stdout___u32(x);
+        }
+        Some(0) => {
+            tasm::tasm_io_write_to_stdout___u32(1000);
+        }
+        Some(_) => {
+            tasm::tasm_io_write_to_stdout___u32(2000);
+        }
+        None => {
+            tasm::tasm_io_write_to_stdout___u32(3000);
+        }
+    };
+
+    return;
+}
+
+fn outer_value(outer: Outer) -> u64 {
+    let value: u64 = match outer {
+        Outer::Empty => 0,
+        Outer::Wrapped(Inner::Small(x)) if x < 5 => x as u64,
+        Outer::Wrapped(Inner::Small(x)) => x as u64 + 100,
+        Outer::Wrapped(Inner::Large(y)) => y,
+        Outer::Pair {
+            right: Inner::Large(y),
+            ..
+        } => y + 1,
+        Outer::Pair {
+            left,
+            right: Inner::Small(_),
+        } => left as u64,
+    };
+
+    return value;
+}
+
+fn small_or_left(outer: Outer) -> u32 {
+    let value: u32 = match outer {
+        Outer::Wrapped(Inner::Small(x))
+        | Outer::Pair {
+            left: x,
+            right: Inner::Large(_),
+        } if x != 7 => x + 1,
+        _ => 0,
+    };
+
+    return value;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn match_guards_and_nested_patterns_test() {
+        for a in [3, 7, 20] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [a, 1, 1 << 20]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+
+            // Test function on host machine
+            let non_determinism = NonDeterminism::new(vec![]);
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Test function in Triton VM
+            let entrypoint_location = ozk_parsing::EntrypointLocation::disk(
+                "enums",
+                "match_guards_and_nested_patterns",
+                "main",
+            );
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                0,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,2 @@
+mod for_loops;
+mod loop_control;
@@ -0,0 +1,114 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    let n: u32 = tasm::tasm_io_read_stdin___u32();
+
+    let mut sum: u32 = 0;
+    for i in 0..n {
+        sum += i;
+    }
+    tasm::tasm_io_write_to_stdout___u32(sum);
+
+    for i in 1..=n {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+
+    for i in (0..n).rev() {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+
+    for i in (0..=n).rev() {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+
+    // Empty ranges
+    for i in n..n {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+    for i in (n + 1)..=n {
+        tasm::tasm_io_write_to_stdout___u32(i);
+    }
+
+    // Inclusive ranges ending at the maximum value may not overflow
+    let max: u32 = u32::MAX;
+    let mut count: u32 = 0;
+    for _ in max - 2..=max {
+        count += 1;
+    }
+    tasm::tasm_io_write_to_stdout___u32(count);
+
+    // Nested loops and `u64` ranges
+    let offset: u64 = 1099511627776;
+    let mut acc: u64 = 0;
+    for i in 0..n {
+        for j in offset..offset + 3 {
+            acc += j + i as u64;
+        }
+    }
+    tasm::tasm_io_write_to_stdout___u64(acc);
+
+    let mut list: Vec<u64> = Vec::<u64>::with_capacity(16);
+    for _ in 0..n {
+        list.push(tasm::tasm_io_read_stdin___u32() as u64);
+    }
+
+    let mut list_sum: u64 = 0;
+    for element in list.iter() {
+        list_sum += element;
+    }
+    tasm::tasm_io_write_to_stdout___u64(list_sum);
+
+    for element in list {
+        tasm::tasm_io_write_to_stdout___u64(element);
+    }
+
+    let array: Box<[u32; 4]> = {
+        let array: [u32; 4] = [100u32, 200u32, 300u32, 400u32];
+        Box::<[u32; 4]>::new(array)
+    };
+    let mut array_sum: u32 = 0;
+    for element in array.iter() {
+        array_sum += element;
+    }
+    tasm::tasm_io_write_to_stdout___u32(array_sum);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn for_loops_test() {
+        // Test function on host machine
+        let stdin: Vec<BFieldElement> = [5, 10, 20, 30, 40, 50]
+            .into_iter()
+            .map(BFieldElement::new)
+            .collect();
+        let non_determinism = NonDeterminism::new(vec![]);
+        let native_output =
+            rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+        // Test function in Triton VM
+        let entrypoint_location = EntrypointLocation::disk("loops", "for_loops", "main");
+        let parsed = entrypoint_location.extract_entrypoint();
+        let expected_stack_diff = 0;
+        let vm_output = execute_with_stack_and_ins_safe_lists(
+            &parsed,
+            vec![],
+            stdin,
+            non_determinism,
+            expected_stack_diff,
+        )
+        .unwrap();
+        assert_eq!(native_output, vm_output.output);
+    }
+}
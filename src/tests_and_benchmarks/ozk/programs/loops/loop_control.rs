@@ -0,0 +1,108 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    let n: u32 = tasm::tasm_io_read_stdin___u32();
+
+    // `loop` exited through `break`
+    let mut i: u32 = 0;
+    loop {
+        if i == n {
+            break;
+        }
+        tasm::tasm_io_write_to_stdout___u32(i);
+        i += 1;
+    }
+
+    // `while` with `continue`, skipping even numbers
+    let mut j: u32 = 0;
+    let mut odd_sum: u32 = 0;
+    while j < 2 * n {
+        j += 1;
+        if j % 2 == 0 {
+            continue;
+        }
+        odd_sum += j;
+    }
+    tasm::tasm_io_write_to_stdout___u32(odd_sum);
+
+    // `for` with `break` and `continue`
+    let mut acc: u64 = 0;
+    for k in 0..100 {
+        if k == 3 {
+            continue;
+        }
+        if k > n {
+            break;
+        }
+        acc += k as u64;
+    }
+    tasm::tasm_io_write_to_stdout___u64(acc);
+
+    // Labelled `break` and `continue` targeting an outer loop
+    let mut pairs: u32 = 0;
+    'outer: for a in 0..n {
+        let mut b: u32 = 0;
+        while b < n {
+            if b > a {
+                continue 'outer;
+            }
+            if a + b == 6 {
+                break 'outer;
+            }
+            pairs += 1;
+            b += 1;
+        }
+    }
+    tasm::tasm_io_write_to_stdout___u32(pairs);
+
+    // Inner loops with their own exits
+    let mut total: u32 = 0;
+    for x in 1..=n {
+        let mut y: u32 = 0;
+        loop {
+            y += 1;
+            if y >= x {
+                break;
+            }
+        }
+        total += y;
+    }
+    tasm::tasm_io_write_to_stdout___u32(total);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn loop_control_test() {
+        // Test function on host machine
+        let stdin = vec![BFieldElement::new(5)];
+        let non_determinism = NonDeterminism::new(vec![]);
+        let native_output =
+            rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+        // Test function in Triton VM
+        let entrypoint_location = EntrypointLocation::disk("loops", "loop_control", "main");
+        let parsed = entrypoint_location.extract_entrypoint();
+        let expected_stack_diff = 0;
+        let vm_output = execute_with_stack_and_ins_safe_lists(
+            &parsed,
+            vec![],
+            stdin,
+            non_determinism,
+            expected_stack_diff,
+        )
+        .unwrap();
+        assert_eq!(native_output, vm_output.output);
+    }
+}
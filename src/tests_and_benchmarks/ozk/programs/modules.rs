@@ -0,0 +1 @@
+mod multi_module_program;
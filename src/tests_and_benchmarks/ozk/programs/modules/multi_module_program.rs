@@ -0,0 +1,61 @@
+mod geometry;
+mod units;
+
+use self::geometry::Point;
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    let a: Point = Point {
+        x: tasm::tasm_io_read_stdin___u64(),
+        y: tasm::tasm_io_read_stdin___u64(),
+    };
+    let b: Point = Point {
+        x: tasm::tasm_io_read_stdin___u64(),
+        y: tasm::tasm_io_read_stdin___u64(),
+    };
+    tasm::tasm_io_write_to_stdout___u64(Point::manhattan_distance(a.x, a.y, b.x, b.y));
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::ast_types::ListType;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn multi_module_program_test() {
+        // Test function on host machine
+        let stdin: Vec<BFieldElement> = [0, 7, 0, 2, 0, 3, 0, 9]
+            .into_iter()
+            .map(BFieldElement::new)
+            .collect();
+        let non_determinism = NonDeterminism::new(vec![]);
+        let native_output =
+            rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+        // Test function in Triton VM, compiled through the public API
+        let root_file = format!(
+            "{}/src/tests_and_benchmarks/ozk/programs/modules/multi_module_program.rs",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let code = crate::compile_to_instructions(&root_file, "main", ListType::Unsafe)
+            .unwrap_or_else(|diagnostics| panic!("{diagnostics}"));
+        let expected_stack_diff = 0;
+        let vm_output = execute_compiled_with_stack_and_ins_for_test(
+            &code,
+            vec![],
+            stdin,
+            non_determinism,
+            expected_stack_diff,
+        )
+        .unwrap();
+        assert_eq!(native_output, vm_output.output);
+    }
+}
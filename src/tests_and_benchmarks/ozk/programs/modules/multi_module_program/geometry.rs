@@ -0,0 +1,15 @@
+use super::units::*;
+
+pub(super) struct Point {
+    pub(super) x: u64,
+    pub(super) y: u64,
+}
+
+impl Point {
+    pub(super) fn manhattan_distance(x0: u64, y0: u64, x1: u64, y1: u64) -> u64 {
+        let dx: u64 = if x0 < x1 { x1 - x0 } else { x0 - x1 };
+        let dy: u64 = if y0 < y1 { y1 - y0 } else { y0 - y1 };
+
+        return millimeters_from_centimeters(dx + dy);
+    }
+}
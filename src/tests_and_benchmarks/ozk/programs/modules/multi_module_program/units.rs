@@ -0,0 +1,3 @@
+pub(super) fn millimeters_from_centimeters(centimeters: u64) -> u64 {
+    return centimeters * 10;
+}
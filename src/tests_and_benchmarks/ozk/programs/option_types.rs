@@ -1,2 +1,3 @@
+mod if_let_and_while_let;
 mod is_some_is_none_unwrap;
 mod mutable_values;
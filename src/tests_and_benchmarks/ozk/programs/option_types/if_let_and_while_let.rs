@@ -0,0 +1,143 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+enum Message {
+    Quit,
+    Write(u32),
+    Move { x: u32, y: u32 },
+}
+
+#[derive(Clone, Copy)]
+struct Stack {
+    height: u32,
+}
+
+impl Stack {
+    fn pop(self) -> Option<u32> {
+        let popped: Option<u32> = if self.height == 0 {
+            None
+        } else {
+            Some(self.height - 1)
+        };
+
+        return popped;
+    }
+}
+
+fn main() {
+    let n: u32 = tasm::tasm_io_read_stdin___u32();
+
+    let mut list: Vec<u32> = Vec::<u32>::with_capacity(16);
+    for i in 0..n {
+        list.push(i * 3);
+    }
+
+    // Pop elements until the list is empty, skipping odd ones
+    while let Some(element) = list.pop() {
+        if element % 2 == 1 {
+            continue;
+        }
+        tasm::tasm_io_write_to_stdout___u32(element);
+    }
+    tasm::tasm_io_write_to_stdout___u32(list.len() as u32);
+
+    let mut countdown: Option<u32> = Some(n);
+    'countdown: while let Some(i) = countdown {
+        tasm::tasm_io_write_to_stdout___u32(i);
+        if i == 0 {
+            countdown = None;
+        } else {
+            countdown = Some(i - 1);
+        }
+        if i == 10 {
+            break 'countdown;
+        }
+    }
+
+    // A `pop` method of a custom type is called like any other method
+    let mut stack: Stack = Stack { height: n };
+    while let Some(height) = stack.pop() {
+        tasm::tasm_io_write_to_stdout___u32(height);
+        stack = Stack { height };
+    }
+
+    let mut maybe_n: Option<u32> = Some(n);
+    if n == 0 {
+        maybe_n = None;
+    }
+    if let Some(value) = maybe_n {
+        tasm::tasm_io_write_to_stdout___u32(value);
+    } else {
+        tasm::tasm_io_write_to_stdout___u32(1000);
+    }
+
+    write_message(Message::Quit);
+    write_message(Message::Write(n));
+    write_message(Message::Move { x: n, y: n + 1 });
+
+    return;
+}
+
+fn write_message(message: Message) {
+    if let Message::Write(value) = message {
+        tasm::tasm_io_write_to_stdout___u32(value);
+    }
+
+    if let Message::Quit = message {
+        tasm::tasm_io_write_to_stdout___u32(2000);
+    } else {
+        tasm::tasm_io_write_to_stdout___u32(3000);
+    }
+
+    let distance: u32 = if let Message::Move { x, y } = message {
+        x + y
+    } else {
+        0
+    };
+    tasm::tasm_io_write_to_stdout___u32(distance);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn if_let_and_while_let_test() {
+        for n in [0, 1, 5, 14] {
+            let stdin = vec![BFieldElement::new(n)];
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("option_types", "if_let_and_while_let", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
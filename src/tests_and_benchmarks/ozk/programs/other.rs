@@ -1,10 +1,24 @@
+mod casts;
+mod closures;
+mod composite_equality;
+mod constants_and_statics;
+mod free_functions;
+mod generic_functions;
 mod hash_varlen;
 mod import_type_declaration;
+mod let_type_inference;
+mod match_patterns;
 mod nested_tuples;
+mod operator_overloading;
 #[allow(dead_code)]
 mod removal_record_integrity_partial;
 mod returning_block_expr_u32;
+mod shadowing;
+mod short_circuit;
+mod signed_integers;
 mod simple_encode;
 mod simple_map_on_bfe;
 mod simple_struct;
+mod small_unsigned_integers;
+mod traits;
 mod value;
@@ -0,0 +1,97 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+fn main() {
+    let a: u64 = tasm::tasm_io_read_stdin___u64();
+    let b: u128 = tasm::tasm_io_read_stdin___u128();
+    let is_small: bool = a < 1000;
+
+    // Narrowing casts keep the least significant bits
+    tasm::tasm_io_write_to_stdout___u32(a as u32);
+    tasm::tasm_io_write_to_stdout___u64(b as u64);
+    tasm::tasm_io_write_to_stdout___u32(b as u32);
+    tasm::tasm_io_write_to_stdout___u64(a as u32 as u64);
+
+    // Widening casts
+    tasm::tasm_io_write_to_stdout___u128(a as u128);
+    tasm::tasm_io_write_to_stdout___u128(a as u32 as u128);
+    tasm::tasm_io_write_to_stdout___u64(a as u128 as u64);
+
+    tasm::tasm_io_write_to_stdout___u32(is_small as u32);
+    tasm::tasm_io_write_to_stdout___u64(is_small as u64);
+    tasm::tasm_io_write_to_stdout___u128(is_small as u128);
+
+    // Casting a field-less enum gives its discriminant
+    let heading: Direction = Direction::South;
+    tasm::tasm_io_write_to_stdout___u32(Direction::North as u32);
+    tasm::tasm_io_write_to_stdout___u32(Direction::West as u32);
+    tasm::tasm_io_write_to_stdout___u64(heading as u64);
+    tasm::tasm_io_write_to_stdout___u128(Direction::East as u128);
+    tasm::tasm_io_write_to_stdout___u64(a + Direction::West as u64);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn casts_test() {
+        for (a, b) in [
+            (5u64, 77u128),
+            ((1 << 40) + 7, (1 << 100) + (1 << 64) + 12345),
+            (u64::MAX - 3, u128::MAX),
+        ] {
+            // Integers are read as `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [
+                a >> 32,
+                a & u32::MAX as u64,
+                (b >> 96) as u64,
+                (b >> 64) as u64 & u32::MAX as u64,
+                (b >> 32) as u64 & u32::MAX as u64,
+                b as u64 & u32::MAX as u64,
+            ]
+            .into_iter()
+            .map(BFieldElement::new)
+            .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location = EntrypointLocation::disk("other", "casts", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
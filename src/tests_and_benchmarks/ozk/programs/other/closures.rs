@@ -0,0 +1,127 @@
+use itertools::Itertools;
+
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn double(x: u64) -> u64 {
+    return 2 * x;
+}
+
+fn apply_twice(f: impl Fn(u64) -> u64, x: u64) -> u64 {
+    return f(f(x));
+}
+
+fn sum_mapped<F: Fn(u64) -> u64>(v: &Vec<u64>, f: F) -> u64 {
+    let mut sum: u64 = 0;
+    let mut i: usize = 0;
+    while i < v.len() {
+        sum += f(v[i]);
+        i += 1;
+    }
+
+    return sum;
+}
+
+// Function arguments can be passed on, and called from closures
+fn apply_twice_and_increment(f: impl Fn(u64) -> u64, x: u64) -> u64 {
+    return apply_twice(|y| f(y) + 1, x);
+}
+
+fn map_all(v: Vec<u64>, f: impl Fn(u64) -> u64) -> Vec<u64> {
+    return v.into_iter().map(f).collect_vec();
+}
+
+fn consecutive_values(a: u64) -> Vec<u64> {
+    let mut values: Vec<u64> = Vec::<u64>::with_capacity(3);
+    values.push(a);
+    values.push(a + 1);
+    values.push(a + 2);
+
+    return values;
+}
+
+fn main() {
+    let a: u64 = tasm::tasm_io_read_stdin___u64();
+    let factor: u64 = tasm::tasm_io_read_stdin___u64();
+
+    let values: Vec<u64> = consecutive_values(a);
+
+    tasm::tasm_io_write_to_stdout___u64(apply_twice(double, a));
+    tasm::tasm_io_write_to_stdout___u64(apply_twice(|x| x * factor, a));
+    tasm::tasm_io_write_to_stdout___u64(apply_twice(|x: u64| -> u64 { x + 3 }, a));
+    tasm::tasm_io_write_to_stdout___u64(sum_mapped(&values, |x| x * factor + a));
+    tasm::tasm_io_write_to_stdout___u64(apply_twice_and_increment(|x| x * factor, a));
+
+    let squares: Vec<u64> = consecutive_values(a)
+        .into_iter()
+        .map(|x| x * x)
+        .collect_vec();
+    let scaled: Vec<u64> = consecutive_values(a)
+        .into_iter()
+        .map(|x| x * factor)
+        .collect_vec();
+    let shifted: Vec<u64> = map_all(values, |x| x + factor);
+
+    // The captured values may have the names that lowering the `map` call uses internally
+    let element: u64 = factor + 1;
+    let result: u64 = a + 2;
+    let offsets: Vec<u64> = consecutive_values(a)
+        .into_iter()
+        .map(|x| x * element + result)
+        .collect_vec();
+    let mut i: usize = 0;
+    while i < 3 {
+        tasm::tasm_io_write_to_stdout___u64(squares[i]);
+        tasm::tasm_io_write_to_stdout___u64(scaled[i]);
+        tasm::tasm_io_write_to_stdout___u64(shifted[i]);
+        tasm::tasm_io_write_to_stdout___u64(offsets[i]);
+        i += 1;
+    }
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn closures_test() {
+        for (a, factor) in [(3, 5), (1000, 7)] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [0, a, 0, factor]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location = EntrypointLocation::disk("other", "closures", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
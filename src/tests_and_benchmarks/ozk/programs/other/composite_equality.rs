@@ -0,0 +1,164 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy, PartialEq)]
+struct Point {
+    x: u64,
+    y: u64,
+    z: u64,
+    label: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Shape {
+    Empty,
+    Circle(u32),
+    Rectangle { width: u64, height: u64 },
+}
+
+#[derive(PartialEq)]
+struct Inventory {
+    id: u32,
+    items: Vec<u64>,
+}
+
+#[derive(PartialEq)]
+enum Payload {
+    Nothing,
+    Words(Vec<u64>),
+}
+
+fn main() {
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: u64 = tasm::tasm_io_read_stdin___u64();
+
+    // Tuples
+    tasm::tasm_io_write_to_stdout___bool((a, b) == (a, b));
+    tasm::tasm_io_write_to_stdout___bool((a, b) != (a + 1, b));
+
+    // Structs whose operands do not fit on the reachable part of the stack together
+    let p: Point = Point {
+        x: b,
+        y: 2,
+        z: 3,
+        label: a,
+    };
+    let q: Point = Point {
+        x: b,
+        y: 2,
+        z: 3,
+        label: 7,
+    };
+    tasm::tasm_io_write_to_stdout___bool(p == q);
+    tasm::tasm_io_write_to_stdout___bool(p != q);
+
+    // Enums with data
+    let circle: Shape = Shape::Circle(a);
+    let rectangle: Shape = Shape::Rectangle {
+        width: b,
+        height: 4,
+    };
+    tasm::tasm_io_write_to_stdout___bool(circle == Shape::Circle(7));
+    tasm::tasm_io_write_to_stdout___bool(circle == rectangle);
+    tasm::tasm_io_write_to_stdout___bool(circle != Shape::Empty);
+    tasm::tasm_io_write_to_stdout___bool(
+        rectangle
+            == Shape::Rectangle {
+                width: b,
+                height: 4,
+            },
+    );
+
+    // Lists and arrays
+    let list: Vec<u64> = numbers(a, b);
+    let same_list: Vec<u64> = numbers(a, b);
+    let longer_list: Vec<u64> = numbers(a + 1, b);
+    tasm::tasm_io_write_to_stdout___bool(list == same_list);
+    tasm::tasm_io_write_to_stdout___bool(list == longer_list);
+    let array: [u64; 3] = [b, 2, 3];
+    let other_array: [u64; 3] = [b, 2, a as u64];
+    tasm::tasm_io_write_to_stdout___bool(array == other_array);
+
+    // Structs and enums holding lists
+    let first: Inventory = Inventory {
+        id: a,
+        items: numbers(a, b),
+    };
+    let second: Inventory = Inventory {
+        id: a,
+        items: numbers(a, b),
+    };
+    let third: Inventory = Inventory {
+        id: a,
+        items: numbers(a, b + 1),
+    };
+    tasm::tasm_io_write_to_stdout___bool(first == second);
+    tasm::tasm_io_write_to_stdout___bool(first != third);
+    let words: Payload = Payload::Words(numbers(2, b));
+    let nothing: Payload = Payload::Nothing;
+    tasm::tasm_io_write_to_stdout___bool(words == Payload::Words(numbers(2, b)));
+    tasm::tasm_io_write_to_stdout___bool(words == nothing);
+
+    // Boxed values
+    let boxed_p: Box<Point> = Box::<Point>::new(p);
+    let boxed_q: Box<Point> = Box::<Point>::new(q);
+    tasm::tasm_io_write_to_stdout___bool(boxed_p == boxed_q);
+    tasm::tasm_io_write_to_stdout___bool(boxed_p == Box::<Point>::new(p));
+
+    return;
+}
+
+fn numbers(length: u32, start: u64) -> Vec<u64> {
+    let mut result: Vec<u64> = Vec::<u64>::with_capacity(16);
+    let mut i: u32 = 0;
+    while i < length {
+        result.push(start + i as u64);
+        i += 1;
+    }
+
+    return result;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn composite_equality_test() {
+        for a in [3, 7] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> =
+                [a, 0, 500].into_iter().map(BFieldElement::new).collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("other", "composite_equality", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
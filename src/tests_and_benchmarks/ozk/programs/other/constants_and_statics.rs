@@ -0,0 +1,121 @@
+use triton_vm::twenty_first::shared_math::x_field_element::XFieldElement;
+use triton_vm::BFieldElement;
+use triton_vm::Digest;
+
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+const LOG2_LENGTH: u32 = 2;
+const LENGTH: usize = 1 << LOG2_LENGTH;
+const OFFSET: u64 = 1 << 40;
+const POWERS_OF_TWO: [u64; LENGTH] = [1, 2, 4, 8];
+const GENERATOR: BFieldElement = BFieldElement::new(7);
+const CHALLENGE: XFieldElement = XFieldElement::new([
+    BFieldElement::new(1),
+    BFieldElement::new(2),
+    BFieldElement::new(3),
+]);
+const ROOT: Digest = Digest::new([
+    BFieldElement::new(10),
+    BFieldElement::new(11),
+    BFieldElement::new(12),
+    BFieldElement::new(13),
+    BFieldElement::new(14),
+]);
+
+static SCALE: u64 = 3;
+static TWIDDLE_FACTORS: [BFieldElement; LENGTH] = [
+    BFieldElement::new(1),
+    BFieldElement::new(18446462594437873665),
+    BFieldElement::new(18446744069414584320),
+    BFieldElement::new(281474976710656),
+];
+
+#[derive(Clone, Copy)]
+struct FriParameters {
+    log2_expansion_factor: u32,
+}
+
+impl FriParameters {
+    const NUM_COLLINEARITY_CHECKS: u32 = 3;
+
+    fn num_checked_values(self) -> u32 {
+        return Self::NUM_COLLINEARITY_CHECKS << self.log2_expansion_factor;
+    }
+}
+
+fn twiddle_factor(index: usize) -> BFieldElement {
+    return TWIDDLE_FACTORS[index];
+}
+
+fn main() {
+    let a: u64 = tasm::tasm_io_read_stdin___u64();
+    let index: u32 = tasm::tasm_io_read_stdin___u32();
+
+    tasm::tasm_io_write_to_stdout___u64(a + OFFSET);
+    tasm::tasm_io_write_to_stdout___u64(a * POWERS_OF_TWO[3]);
+    tasm::tasm_io_write_to_stdout___u64(SCALE * a);
+    tasm::tasm_io_write_to_stdout___bfe(GENERATOR * BFieldElement::new(a));
+    tasm::tasm_io_write_to_stdout___xfe(CHALLENGE);
+    tasm::tasm_io_write_to_stdout___digest(ROOT);
+
+    let mut i: usize = 0;
+    while i < LENGTH {
+        tasm::tasm_io_write_to_stdout___bfe(TWIDDLE_FACTORS[i]);
+        i += 1;
+    }
+    tasm::tasm_io_write_to_stdout___bfe(twiddle_factor(index as usize));
+
+    let parameters: FriParameters = FriParameters {
+        log2_expansion_factor: LOG2_LENGTH,
+    };
+    tasm::tasm_io_write_to_stdout___u32(FriParameters::NUM_COLLINEARITY_CHECKS);
+    tasm::tasm_io_write_to_stdout___u32(parameters.num_checked_values());
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn constants_and_statics_test() {
+        for (a, index) in [(5, 0), (1 << 33, 3)] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [a >> 32, a & u32::MAX as u64, index]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("other", "constants_and_statics", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
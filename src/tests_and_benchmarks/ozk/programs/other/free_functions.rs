@@ -0,0 +1,90 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    let n: u32 = tasm::tasm_io_read_stdin___u32();
+    tasm::tasm_io_write_to_stdout___u32(fibonacci(n));
+    tasm::tasm_io_write_to_stdout___bool(is_even(n));
+    tasm::tasm_io_write_to_stdout___bool(is_odd(n));
+    tasm::tasm_io_write_to_stdout___u64(sum_of_squares(n as u64));
+
+    return;
+}
+
+fn fibonacci(n: u32) -> u32 {
+    let result: u32 = if n < 2 {
+        n
+    } else {
+        fibonacci(n - 1) + fibonacci(n - 2)
+    };
+
+    return result;
+}
+
+// `is_even` and `is_odd` are mutually recursive
+fn is_even(n: u32) -> bool {
+    let result: bool = if n == 0 { true } else { is_odd(n - 1) };
+
+    return result;
+}
+
+fn is_odd(n: u32) -> bool {
+    let result: bool = if n == 0 { false } else { is_even(n - 1) };
+
+    return result;
+}
+
+fn sum_of_squares(n: u64) -> u64 {
+    let mut acc: u64 = 0;
+    let mut i: u64 = 1;
+    while i <= n {
+        acc += square(i);
+        i += 1;
+    }
+
+    return acc;
+}
+
+fn square(x: u64) -> u64 {
+    return x * x;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn free_functions_test() {
+        for n in [0, 1, 7, 10] {
+            // Test function on host machine
+            let stdin = vec![BFieldElement::new(n)];
+            let non_determinism = NonDeterminism::new(vec![]);
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Test function in Triton VM
+            let entrypoint_location =
+                ozk_parsing::EntrypointLocation::disk("other", "free_functions", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
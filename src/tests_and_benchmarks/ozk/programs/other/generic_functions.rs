@@ -0,0 +1,118 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: u64 = tasm::tasm_io_read_stdin___u64();
+
+    // The type parameter is inferred from the types of the arguments
+    tasm::tasm_io_write_to_stdout___u32(largest(a, 100));
+    tasm::tasm_io_write_to_stdout___u64(largest(b, 1000));
+    tasm::tasm_io_write_to_stdout___u32(largest_of_three(a, 7, 50));
+
+    // The type parameter is given explicitly
+    let c: u64 = largest::<u64>(b, a as u64);
+    tasm::tasm_io_write_to_stdout___u64(c);
+
+    let mut list: Vec<u64> = Vec::<u64>::with_capacity(16);
+    let mut i: u32 = 0;
+    while i < a {
+        list.push(b + i as u64);
+        i += 1;
+    }
+    tasm::tasm_io_write_to_stdout___u64(sum(&list, 0));
+
+    // Multiple type parameters
+    let (number, is_large): (u32, bool) = swap((a > 10, a));
+    tasm::tasm_io_write_to_stdout___u32(number);
+    tasm::tasm_io_write_to_stdout___bool(is_large);
+
+    // All type arguments are given explicitly, or some of them are left to be inferred
+    let (flag, larger): (bool, u64) = swap::<u64, bool>((b + 1, a < 10));
+    tasm::tasm_io_write_to_stdout___bool(flag);
+    tasm::tasm_io_write_to_stdout___u64(larger);
+    let (smaller, _): (u32, bool) = swap::<_, u32>((a > 5, a + 2));
+    tasm::tasm_io_write_to_stdout___u32(smaller);
+
+    // A type parameter bounded by a function trait is given by the function passed
+    tasm::tasm_io_write_to_stdout___u32(apply::<u32, _>(|x| x + 1, a));
+
+    return;
+}
+
+fn largest<T: PartialOrd + Copy>(lhs: T, rhs: T) -> T {
+    let mut result: T = rhs;
+    if lhs > rhs {
+        result = lhs;
+    }
+
+    return result;
+}
+
+// A generic function calling another generic function
+fn largest_of_three<T: PartialOrd + Copy>(a: T, b: T, c: T) -> T {
+    return largest(largest(a, b), c);
+}
+
+fn sum<T: Copy + std::ops::Add<Output = T>>(values: &Vec<T>, zero: T) -> T {
+    let mut acc: T = zero;
+    let mut i: usize = 0;
+    while i < values.len() {
+        acc = acc + values[i];
+        i += 1;
+    }
+
+    return acc;
+}
+
+fn swap<A, B>(pair: (A, B)) -> (B, A) {
+    return (pair.1, pair.0);
+}
+
+fn apply<T, F: Fn(T) -> T>(f: F, x: T) -> T {
+    return f(x);
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn generic_functions_test() {
+        for a in [3, 12] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> =
+                [a, 0, 500].into_iter().map(BFieldElement::new).collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("other", "generic_functions", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    // Types derived from the initializer
+    let a = tasm::tasm_io_read_stdin___u32();
+    let b = tasm::tasm_io_read_stdin___u64();
+    let sum = a + 7;
+    let is_large = sum > 10;
+    if is_large {
+        tasm::tasm_io_write_to_stdout___u32(sum);
+    }
+
+    // Number literals whose types are inferred from later uses
+    let c = 3;
+    let d = c * 2;
+    tasm::tasm_io_write_to_stdout___u64(b + d);
+
+    let mut i = 0;
+    let mut acc = 0;
+    while i < a {
+        acc += i as u64;
+        i += 1;
+    }
+    tasm::tasm_io_write_to_stdout___u64(acc);
+    tasm::tasm_io_write_to_stdout___u32(i);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn let_type_inference_test() {
+        // Test function on host machine
+        let stdin: Vec<BFieldElement> = [5, 0, 100].into_iter().map(BFieldElement::new).collect();
+        let non_determinism = NonDeterminism::new(vec![]);
+        let native_output =
+            rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+        // Test function in Triton VM
+        let entrypoint_location = EntrypointLocation::disk("other", "let_type_inference", "main");
+        let parsed = entrypoint_location.extract_entrypoint();
+        let expected_stack_diff = 0;
+        let vm_output = execute_with_stack_and_ins_safe_lists(
+            &parsed,
+            vec![],
+            stdin,
+            non_determinism,
+            expected_stack_diff,
+        )
+        .unwrap();
+        assert_eq!(native_output, vm_output.output);
+    }
+}
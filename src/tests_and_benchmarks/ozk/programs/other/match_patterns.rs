@@ -0,0 +1,158 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Point,
+    Circle { radius: u32 },
+    Rectangle { width: u32, height: u32 },
+}
+
+fn main() {
+    let n: u32 = tasm::tasm_io_read_stdin___u32();
+    let mut i: u32 = 0;
+    while i < n {
+        let x: u64 = tasm::tasm_io_read_stdin___u64();
+        classify(x);
+        i += 1;
+    }
+
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: bool = tasm::tasm_io_read_stdin___u32() == 1;
+    describe_pair(a, b);
+    describe_pair(0, !b);
+
+    let radius: u32 = tasm::tasm_io_read_stdin___u32();
+    let circle: Shape = Shape::Circle { radius };
+    let rectangle: Shape = Shape::Rectangle {
+        height: radius + 1,
+        width: 2,
+    };
+    write_dimensions(Shape::Point);
+    write_dimensions(circle);
+    write_dimensions(rectangle);
+
+    return;
+}
+
+fn classify(x: u64) {
+    match x {
+        0 => {
+            tasm::tasm_io_write_to_stdout___u32(100);
+        }
+        1 | 2 | 3 => {
+            tasm::tasm_io_write_to_stdout___u32(200);
+        }
+        4..=9 => {
+            tasm::tasm_io_write_to_stdout___u32(300);
+        }
+        10 | 20..=29 => {
+            tasm::tasm_io_write_to_stdout___u32(400);
+        }
+        n => {
+            tasm::tasm_io_write_to_stdout___u64(n);
+        }
+    };
+
+    return;
+}
+
+fn describe_pair(a: u32, b: bool) {
+    match (a, b) {
+        (0, true) => {
+            tasm::tasm_io_write_to_stdout___u32(1);
+        }
+        (0, false) => {
+            tasm::tasm_io_write_to_stdout___u32(2);
+        }
+        (1..=5, flag) => {
+            match flag {
+                true => {
+                    tasm::tasm_io_write_to_stdout___u32(3);
+                }
+                false => {
+                    tasm::tasm_io_write_to_stdout___u32(4);
+                }
+            };
+        }
+        (n, _) => {
+            tasm::tasm_io_write_to_stdout___u32(n);
+        }
+    };
+
+    return;
+}
+
+fn write_dimensions(shape: Shape) {
+    match shape {
+        Shape::Point => {
+            tasm::tasm_io_write_to_stdout___u32(0);
+        }
+        Shape::Circle { radius } => {
+            tasm::tasm_io_write_to_stdout___u32(radius);
+        }
+        Shape::Rectangle { width, height: h } => {
+            tasm::tasm_io_write_to_stdout___u32(width);
+            tasm::tasm_io_write_to_stdout___u32(h);
+        }
+    };
+
+    match shape {
+        Shape::Rectangle { height, .. } => {
+            tasm::tasm_io_write_to_stdout___u32(height);
+        }
+        _ => {
+            tasm::tasm_io_write_to_stdout___u32(0);
+        }
+    };
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn match_patterns_test() {
+        let values: [u64; 9] = [0, 2, 4, 9, 10, 11, 25, 30, 1 << 40];
+        for (a, b) in [(0, 1), (0, 0), (3, 1), (5, 0), (6, 1)] {
+            let mut stdin = vec![values.len() as u64];
+            for value in values {
+                // `u64`s are read as two `u32`s, most significant first
+                stdin.extend([value >> 32, value & u32::MAX as u64]);
+            }
+            stdin.extend([a, b, 14]);
+            let stdin: Vec<BFieldElement> = stdin.into_iter().map(BFieldElement::new).collect();
+
+            // Test function on host machine
+            let non_determinism = NonDeterminism::new(vec![]);
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Test function in Triton VM
+            let entrypoint_location =
+                ozk_parsing::EntrypointLocation::disk("other", "match_patterns", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use triton_vm::BFieldElement;
+
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        return Point {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        };
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Point) -> Point {
+        return Point {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        };
+    }
+}
+
+// The right-hand side does not have to be of the implementing type
+impl Mul<u64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: u64) -> Self::Output {
+        return Point {
+            x: self.x * factor,
+            y: self.y * factor,
+        };
+    }
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        return self.x == other.x && self.y == other.y;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Felt(BFieldElement);
+
+impl Mul for Felt {
+    type Output = Felt;
+
+    fn mul(self, rhs: Felt) -> Felt {
+        return Felt(self.0 * rhs.0);
+    }
+}
+
+impl Neg for Felt {
+    type Output = Felt;
+
+    fn neg(self) -> Self::Output {
+        return Felt(-self.0);
+    }
+}
+
+fn main() {
+    let a: u64 = tasm::tasm_io_read_stdin___u64();
+    let b: BFieldElement = tasm::tasm_io_read_stdin___bfe();
+
+    let p: Point = Point { x: a, y: 7 };
+    let q: Point = Point { x: 2, y: a + 1 };
+    let sum: Point = p + q;
+    tasm::tasm_io_write_to_stdout___u64(sum.x);
+    tasm::tasm_io_write_to_stdout___u64(sum.y);
+
+    // Overloaded operators can be nested and mixed with built-in ones
+    let r: Point = (p + q) * 3 - q;
+    tasm::tasm_io_write_to_stdout___u64(r.x);
+    tasm::tasm_io_write_to_stdout___u64(r.y);
+    tasm::tasm_io_write_to_stdout___bool(p == p);
+    tasm::tasm_io_write_to_stdout___bool(p == q);
+    tasm::tasm_io_write_to_stdout___bool(sum - q != p);
+
+    let mut acc: Point = Point { x: 0, y: 0 };
+    let mut i: u32 = 0;
+    while i < 4 {
+        acc = acc + p;
+        i += 1;
+    }
+    tasm::tasm_io_write_to_stdout___bool(acc == p * 4);
+
+    let felt: Felt = Felt(b);
+    let negated: Felt = -felt;
+    let squared: Felt = -negated * felt;
+    tasm::tasm_io_write_to_stdout___bfe(negated.0);
+    tasm::tasm_io_write_to_stdout___bfe(squared.0);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn operator_overloading_test() {
+        for a in [3, 12] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> = [0, a, 1 << 40]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("other", "operator_overloading", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    // Shadowing with a different type
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let a: u64 = a as u64 * 3;
+    tasm::tasm_io_write_to_stdout___u64(a);
+
+    // A binding declared in a block shadows the outer binding only inside of the block
+    let b: u32 = 10;
+    {
+        let b: bool = a > 20;
+        tasm::tasm_io_write_to_stdout___bool(b);
+        let c: u64 = a + 1;
+        tasm::tasm_io_write_to_stdout___u64(c);
+    }
+    tasm::tasm_io_write_to_stdout___u32(b);
+
+    // Shadowing inside of a loop body
+    let mut i: u32 = 0;
+    let mut acc: u64 = 0;
+    while i < 4 {
+        let i_squared: u32 = i * i;
+        let i_squared: u64 = i_squared as u64;
+        acc += i_squared;
+        i += 1;
+    }
+    tasm::tasm_io_write_to_stdout___u64(acc);
+    tasm::tasm_io_write_to_stdout___u32(i);
+
+    // Bindings whose types are inferred from their uses
+    let d = 2;
+    let d = d * 5;
+    tasm::tasm_io_write_to_stdout___u32(d);
+
+    // Match arms shadowing a binding of the enclosing scope
+    let value: Option<u32> = Some(b + 1);
+    let x: u64 = 1000;
+    match value {
+        Some(x) => {
+            tasm::tasm_io_write_to_stdout___u32(x);
+        }
+        None => {
+            tasm::tasm_io_write_to_stdout___u64(x);
+        }
+    };
+    tasm::tasm_io_write_to_stdout___u64(x);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn shadowing_test() {
+        for a in [1, 7] {
+            let stdin = vec![BFieldElement::new(a)];
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location = EntrypointLocation::disk("other", "shadowing", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+fn main() {
+    let n: u32 = tasm::tasm_io_read_stdin___u32();
+    let needle: u32 = tasm::tasm_io_read_stdin___u32();
+    let mut list: Vec<u32> = Vec::<u32>::with_capacity(16);
+    for _ in 0..n {
+        list.push(tasm::tasm_io_read_stdin___u32());
+    }
+
+    // The list is not indexed out of bounds when the needle is not found
+    let mut i: usize = 0;
+    while i < list.len() && list[i] != needle {
+        i += 1;
+    }
+    tasm::tasm_io_write_to_stdout___u32(i as u32);
+
+    // The right-hand side is only evaluated when the left-hand side does not determine the
+    // result
+    let a: bool = i == 0 && write_and_return(1, true);
+    let b: bool = i != 0 && write_and_return(2, false);
+    let c: bool = i == 0 || write_and_return(3, true);
+    let d: bool = i != 0 || write_and_return(4, false);
+    tasm::tasm_io_write_to_stdout___bool(a);
+    tasm::tasm_io_write_to_stdout___bool(b);
+    tasm::tasm_io_write_to_stdout___bool(c);
+    tasm::tasm_io_write_to_stdout___bool(d);
+
+    // Nested operators
+    let e: bool = (a || i < 2) && (b || c || write_and_return(5, true));
+    tasm::tasm_io_write_to_stdout___bool(e);
+
+    return;
+}
+
+fn write_and_return(marker: u32, value: bool) -> bool {
+    tasm::tasm_io_write_to_stdout___u32(marker);
+    return value;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn short_circuit_test() {
+        for needle in [7, 3, 100] {
+            // Test function on host machine
+            let stdin: Vec<BFieldElement> = [4, needle, 7, 9, 3, 1]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+            let non_determinism = NonDeterminism::new(vec![]);
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Test function in Triton VM. Safe lists crash on out-of-bounds accesses.
+            let entrypoint_location =
+                ozk_parsing::EntrypointLocation::disk("other", "short_circuit", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Safe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+const OFFSET: i32 = -7;
+
+fn distance(a: i64, b: i64) -> u64 {
+    let difference: i64 = if a < b { b - a } else { a - b };
+    return difference as u64;
+}
+
+fn main() {
+    let a: i32 = tasm::tasm_io_read_stdin___u32() as i32;
+    let b: i32 = tasm::tasm_io_read_stdin___u32() as i32;
+    let c: i64 = tasm::tasm_io_read_stdin___u64() as i64;
+
+    tasm::tasm_io_write_to_stdout___u32((a + b) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a - b) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a * b) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a / b) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a % b) as u32);
+    tasm::tasm_io_write_to_stdout___u32((-a) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a >> 3) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a << 2) as u32);
+    tasm::tasm_io_write_to_stdout___u32((a + OFFSET) as u32);
+    tasm::tasm_io_write_to_stdout___bool(a < b);
+    tasm::tasm_io_write_to_stdout___bool(a > b);
+    tasm::tasm_io_write_to_stdout___bool(a == -b);
+    tasm::tasm_io_write_to_stdout___bool(a < OFFSET);
+
+    let wide_a: i64 = a as i64;
+    let wide_b: i64 = b as i64;
+    tasm::tasm_io_write_to_stdout___u64((c + wide_a) as u64);
+    tasm::tasm_io_write_to_stdout___u64((c - wide_a) as u64);
+    tasm::tasm_io_write_to_stdout___u64((c * wide_b) as u64);
+    tasm::tasm_io_write_to_stdout___u64((c / wide_b) as u64);
+    tasm::tasm_io_write_to_stdout___u64((c % wide_b) as u64);
+    tasm::tasm_io_write_to_stdout___u64((-c) as u64);
+    tasm::tasm_io_write_to_stdout___u64((c >> 5) as u64);
+    tasm::tasm_io_write_to_stdout___bool(c < wide_a);
+    tasm::tasm_io_write_to_stdout___bool(c > wide_a);
+    tasm::tasm_io_write_to_stdout___u64(distance(c, wide_a));
+    tasm::tasm_io_write_to_stdout___u32(c as i32 as u32);
+    tasm::tasm_io_write_to_stdout___u64(a as u64);
+
+    let min: i64 = -9223372036854775808;
+    tasm::tasm_io_write_to_stdout___u64((min + 1) as u64);
+    tasm::tasm_io_write_to_stdout___u32(i32::MAX as u32);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn signed_integers_test() {
+        for (a, b, c) in [
+            (17i32, -5i32, -1234567890123i64),
+            (-100, 7, 1 << 40),
+            (-100, -3, 9876543210),
+        ] {
+            // Signed integers are read as their two's complement, and `u64`s are read as two
+            // `u32`s, most significant first
+            let c = c as u64;
+            let stdin: Vec<BFieldElement> = [
+                a as u32 as u64,
+                b as u32 as u64,
+                c >> 32,
+                c & u32::MAX as u64,
+            ]
+            .into_iter()
+            .map(BFieldElement::new)
+            .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location = EntrypointLocation::disk("other", "signed_integers", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
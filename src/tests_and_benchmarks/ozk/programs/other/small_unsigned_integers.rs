@@ -0,0 +1,112 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+const NIBBLE_MASK: u8 = 0x0f;
+
+fn low_nibble(byte: u8) -> u8 {
+    return byte & NIBBLE_MASK;
+}
+
+// Sum the four bytes of a `u32`, least significant first
+fn byte_sum(word: u32) -> u16 {
+    let mut sum: u16 = 0;
+    let mut i: u32 = 0;
+    while i < 4 {
+        let byte: u8 = (word >> (8 * i)) as u8;
+        sum += byte as u16;
+        i += 1;
+    }
+
+    return sum;
+}
+
+fn main() {
+    let a: u8 = tasm::tasm_io_read_stdin___u8();
+    let b: u8 = tasm::tasm_io_read_stdin___u8();
+    let c: u16 = tasm::tasm_io_read_stdin___u16();
+    let word: u32 = tasm::tasm_io_read_stdin___u32();
+
+    tasm::tasm_io_write_to_stdout___u8(a + b);
+    tasm::tasm_io_write_to_stdout___u8(a - b);
+    tasm::tasm_io_write_to_stdout___u8(b * 3);
+    tasm::tasm_io_write_to_stdout___u8(a / b);
+    tasm::tasm_io_write_to_stdout___u8(a % b);
+    tasm::tasm_io_write_to_stdout___u8(a ^ b);
+    tasm::tasm_io_write_to_stdout___u8(a | b);
+    tasm::tasm_io_write_to_stdout___u8(!a);
+    tasm::tasm_io_write_to_stdout___u8(a << 3);
+    tasm::tasm_io_write_to_stdout___u8(a >> 2);
+    tasm::tasm_io_write_to_stdout___u8(low_nibble(a));
+    tasm::tasm_io_write_to_stdout___bool(a < b);
+    tasm::tasm_io_write_to_stdout___bool(a > b);
+    tasm::tasm_io_write_to_stdout___bool(a == b);
+
+    tasm::tasm_io_write_to_stdout___u16(c + a as u16);
+    tasm::tasm_io_write_to_stdout___u16(c - 1000u16);
+    tasm::tasm_io_write_to_stdout___u16(c * 2);
+    tasm::tasm_io_write_to_stdout___u16(!c);
+    tasm::tasm_io_write_to_stdout___u16(c << 4);
+    tasm::tasm_io_write_to_stdout___u16(byte_sum(word));
+
+    tasm::tasm_io_write_to_stdout___u8(c as u8);
+    tasm::tasm_io_write_to_stdout___u8(word as u8);
+    tasm::tasm_io_write_to_stdout___u16(word as u16);
+    tasm::tasm_io_write_to_stdout___u32(c as u32);
+    tasm::tasm_io_write_to_stdout___u64(a as u64);
+    tasm::tasm_io_write_to_stdout___u8((word as u64 * 1000) as u8);
+    tasm::tasm_io_write_to_stdout___u32((a as i32 - 200) as u32);
+    tasm::tasm_io_write_to_stdout___u8(true as u8);
+    tasm::tasm_io_write_to_stdout___u8(u8::MAX - a);
+    tasm::tasm_io_write_to_stdout___u32(u16::BITS);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn small_unsigned_integers_test() {
+        for (a, b, c, word) in [
+            (100u8, 7u8, 1234u16, 0xdeadbeefu32),
+            (200, 55, 30000, 0x01020304),
+            (9, 9, 1000, 0),
+        ] {
+            let stdin: Vec<BFieldElement> = [a as u64, b as u64, c as u64, word as u64]
+                .into_iter()
+                .map(BFieldElement::new)
+                .collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("other", "small_unsigned_integers", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+trait Shape: Copy {
+    fn area(self) -> u64;
+
+    fn scaled_area(self, factor: u64) -> u64 {
+        return self.area() * factor;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Rectangle {
+    width: u64,
+    height: u64,
+}
+
+impl Rectangle {
+    fn square(side: u64) -> Self {
+        return Self {
+            width: side,
+            height: side,
+        };
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(self) -> u64 {
+        return self.width * self.height;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Square(u64);
+
+impl Shape for Square {
+    fn area(self) -> u64 {
+        return self.0 * self.0;
+    }
+
+    // Overrides the default method of the trait
+    fn scaled_area(self, factor: u64) -> u64 {
+        let side: u64 = self.0 * factor;
+        return side * side;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Doubled<T>(T);
+
+// An implementation for all instances of a generic type
+impl<T: Shape> Shape for Doubled<T> {
+    fn area(self) -> u64 {
+        return self.0.area() * 2;
+    }
+}
+
+fn main() {
+    let a: u64 = tasm::tasm_io_read_stdin___u64();
+
+    let rectangle: Rectangle = Rectangle {
+        width: a,
+        height: 3,
+    };
+    let square: Square = Square(a + 1);
+    tasm::tasm_io_write_to_stdout___u64(rectangle.area());
+    tasm::tasm_io_write_to_stdout___u64(rectangle.scaled_area(2));
+    tasm::tasm_io_write_to_stdout___u64(square.area());
+    tasm::tasm_io_write_to_stdout___u64(square.scaled_area(2));
+
+    // Trait bounds on generic functions
+    tasm::tasm_io_write_to_stdout___u64(total_area(rectangle, Rectangle::square(a)));
+    tasm::tasm_io_write_to_stdout___u64(total_area(square, square));
+
+    let doubled: Doubled<Square> = Doubled(square);
+    tasm::tasm_io_write_to_stdout___u64(doubled.area());
+    tasm::tasm_io_write_to_stdout___u64(doubled.scaled_area(3));
+
+    return;
+}
+
+fn total_area<T: Shape>(first: T, second: T) -> u64 {
+    return first.area() + second.area();
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn traits_test() {
+        for a in [2, 9] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin = vec![BFieldElement::new(0), BFieldElement::new(a)];
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location = EntrypointLocation::disk("other", "traits", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
@@ -1,4 +1,6 @@
 mod declaration_on_stack_with_list;
+mod destructuring_let;
+mod generic_structs_and_enums;
 mod nested_structs;
 mod simple_declaration_on_stack;
 mod simple_declaration_on_stack_spilled;
@@ -7,6 +9,7 @@ mod simple_struct;
 mod struct_with_array;
 mod struct_with_methods;
 mod struct_with_simple_methods;
+mod struct_with_small_unsigned_integers;
 mod struct_with_two_vecs_to_stack;
 mod struct_with_vecs;
 mod struct_with_xfe_and_xfes_to_stack;
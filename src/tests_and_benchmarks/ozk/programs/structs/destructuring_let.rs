@@ -0,0 +1,85 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: u32,
+    y: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Pair(u32, Point);
+
+fn main() {
+    let a: u64 = tasm::tasm_io_read_stdin___u64();
+    let b: u64 = tasm::tasm_io_read_stdin___u64();
+
+    let (q, r): (u64, u64) = div_mod(a, b);
+    tasm::tasm_io_write_to_stdout___u64(q);
+    tasm::tasm_io_write_to_stdout___u64(r);
+
+    let (mut count, (_, total)) = (1u32, (true, a + b));
+    count += 1;
+    tasm::tasm_io_write_to_stdout___u32(count);
+    tasm::tasm_io_write_to_stdout___u64(total);
+
+    let point: Point = Point { x: 7, y: b };
+    let Point { x, y } = point;
+    tasm::tasm_io_write_to_stdout___u32(x);
+    tasm::tasm_io_write_to_stdout___u64(y);
+
+    let Point { y: only_y, .. } = point;
+    tasm::tasm_io_write_to_stdout___u64(only_y);
+
+    let pair: Pair = Pair(3, point);
+    let Pair(first, Point { x: inner_x, .. }) = pair;
+    tasm::tasm_io_write_to_stdout___u32(first);
+    tasm::tasm_io_write_to_stdout___u32(inner_x);
+
+    return;
+}
+
+fn div_mod(numerator: u64, denominator: u64) -> (u64, u64) {
+    return (numerator / denominator, numerator % denominator);
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn destructuring_let_test() {
+        // `u64`s are read as two `u32`s, most significant first
+        let stdin: Vec<BFieldElement> = [1, 1000, 0, 17]
+            .into_iter()
+            .map(BFieldElement::new)
+            .collect();
+
+        // Run program on host machine
+        let non_determinism = NonDeterminism::default();
+        let native_output =
+            rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+        // Run test on Triton-VM
+        let entrypoint_location = EntrypointLocation::disk("structs", "destructuring_let", "main");
+        let test_program =
+            ozk_parsing::compile_for_test(&entrypoint_location, crate::ast_types::ListType::Unsafe);
+        let expected_stack_diff = 0;
+        let vm_output = execute_compiled_with_stack_and_ins_for_test(
+            &test_program,
+            vec![],
+            stdin,
+            non_determinism,
+            expected_stack_diff,
+        )
+        .unwrap();
+        assert_eq!(native_output, vm_output.output);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(Clone, Copy)]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+impl<T: Copy + PartialOrd> Pair<T> {
+    fn new(first: T, second: T) -> Pair<T> {
+        return Pair { first, second };
+    }
+
+    fn largest(self) -> T {
+        let mut result: T = self.second;
+        if self.first > self.second {
+            result = self.first;
+        }
+
+        return result;
+    }
+
+    fn swapped(self) -> Pair<T> {
+        return Pair::new(self.second, self.first);
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Labelled<K, V>(K, V);
+
+#[derive(Clone, Copy)]
+enum Shape<T> {
+    Empty,
+    Point(T),
+    Line { from: T, to: T },
+}
+
+fn main() {
+    let a: u32 = tasm::tasm_io_read_stdin___u32();
+    let b: u64 = tasm::tasm_io_read_stdin___u64();
+
+    // Instances of the same generic struct with different type arguments
+    let small: Pair<u32> = Pair::new(a, a * 2);
+    let large: Pair<u64> = Pair {
+        first: b,
+        second: 10,
+    };
+    tasm::tasm_io_write_to_stdout___u32(small.largest());
+    tasm::tasm_io_write_to_stdout___u64(large.largest());
+    let swapped: Pair<u64> = large.swapped();
+    tasm::tasm_io_write_to_stdout___u64(swapped.first);
+
+    let Pair { first, second } = small.swapped();
+    tasm::tasm_io_write_to_stdout___u32(first);
+    tasm::tasm_io_write_to_stdout___u32(second);
+
+    // Multiple type parameters
+    let labelled: Labelled<bool, u64> = Labelled(a > 5, b);
+    tasm::tasm_io_write_to_stdout___bool(labelled.0);
+    tasm::tasm_io_write_to_stdout___u64(labelled.1);
+
+    // Generic enums
+    let point: Shape<u32> = Shape::Point(a);
+    let line: Shape<u64> = Shape::Line { from: b, to: b + 1 };
+    let empty: Shape<u64> = Shape::Empty;
+    tasm::tasm_io_write_to_stdout___u32(point_or_zero(point));
+    tasm::tasm_io_write_to_stdout___u64(length(line));
+    tasm::tasm_io_write_to_stdout___u64(length(empty));
+
+    return;
+}
+
+fn point_or_zero(shape: Shape<u32>) -> u32 {
+    let mut result: u32 = 0;
+    if let Shape::Point(value) = shape {
+        result = value;
+    }
+
+    return result;
+}
+
+fn length(shape: Shape<u64>) -> u64 {
+    return match shape {
+        Shape::Empty => 0,
+        Shape::Point(_) => 1,
+        Shape::Line { from, to } => to - from + 1,
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use triton_vm::BFieldElement;
+    use triton_vm::NonDeterminism;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::*;
+
+    use super::*;
+
+    #[test]
+    fn generic_structs_and_enums_test() {
+        for a in [3, 12] {
+            // `u64`s are read as two `u32`s, most significant first
+            let stdin: Vec<BFieldElement> =
+                [a, 0, 500].into_iter().map(BFieldElement::new).collect();
+
+            // Run program on host machine
+            let non_determinism = NonDeterminism::default();
+            let native_output =
+                rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+
+            // Run test on Triton-VM
+            let entrypoint_location =
+                EntrypointLocation::disk("structs", "generic_structs_and_enums", "main");
+            let test_program = ozk_parsing::compile_for_test(
+                &entrypoint_location,
+                crate::ast_types::ListType::Unsafe,
+            );
+            let expected_stack_diff = 0;
+            let vm_output = execute_compiled_with_stack_and_ins_for_test(
+                &test_program,
+                vec![],
+                stdin,
+                non_determinism,
+                expected_stack_diff,
+            )
+            .unwrap();
+            assert_eq!(native_output, vm_output.output);
+        }
+    }
+}
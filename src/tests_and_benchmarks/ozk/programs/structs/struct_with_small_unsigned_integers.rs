@@ -0,0 +1,111 @@
+use arbitrary::Arbitrary;
+use tasm_lib::structure::tasm_object::TasmObject;
+use triton_vm::twenty_first::shared_math::bfield_codec::BFieldCodec;
+use triton_vm::BFieldElement;
+
+use crate::tests_and_benchmarks::ozk::rust_shadows as tasm;
+
+#[derive(TasmObject, BFieldCodec, Arbitrary)]
+struct SmallUnsignedIntegers {
+    pub a: u8,
+    pub b: u16,
+    pub c: u32,
+}
+
+fn main() {
+    let small_integers: Box<SmallUnsignedIntegers> =
+        SmallUnsignedIntegers::decode(&tasm::load_from_memory(BFieldElement::new(300))).unwrap();
+    let a: u8 = small_integers.a;
+    let b: u16 = small_integers.b;
+    tasm::tasm_io_write_to_stdout___u8(a);
+    tasm::tasm_io_write_to_stdout___u16(b);
+    tasm::tasm_io_write_to_stdout___u32(small_integers.c);
+
+    // Encode the fields again
+    let a_encoded: Vec<BFieldElement> = a.encode();
+    let b_encoded: Vec<BFieldElement> = b.encode();
+    tasm::tasm_io_write_to_stdout___u32(a_encoded.len() as u32);
+    tasm::tasm_io_write_to_stdout___bfe(a_encoded[0]);
+    tasm::tasm_io_write_to_stdout___u32(b_encoded.len() as u32);
+    tasm::tasm_io_write_to_stdout___bfe(b_encoded[0]);
+
+    // Decode values of the primitive types
+    let boxed_a: Box<u8> = u8::decode(&tasm::load_from_memory(BFieldElement::new(400))).unwrap();
+    let boxed_b: Box<u16> = u16::decode(&tasm::load_from_memory(BFieldElement::new(500))).unwrap();
+    tasm::tasm_io_write_to_stdout___u8(*boxed_a);
+    tasm::tasm_io_write_to_stdout___u16(*boxed_b);
+
+    return;
+}
+
+#[cfg(test)]
+mod test {
+    use arbitrary::Arbitrary;
+    use arbitrary::Unstructured;
+    use itertools::Itertools;
+    use rand::random;
+    use triton_vm::BFieldElement;
+
+    use crate::tests_and_benchmarks::ozk::ozk_parsing;
+    use crate::tests_and_benchmarks::ozk::ozk_parsing::EntrypointLocation;
+    use crate::tests_and_benchmarks::ozk::rust_shadows;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::execute_compiled_with_stack_and_ins_for_test;
+    use crate::tests_and_benchmarks::test_helpers::shared_test::init_memory_from;
+
+    use super::*;
+
+    #[test]
+    fn struct_with_small_unsigned_integers_test() {
+        let raw_data: [u8; 20] = random();
+        let mut unstructured = Unstructured::new(&raw_data);
+        let test_struct = SmallUnsignedIntegers::arbitrary(&mut unstructured).unwrap();
+        let a: u8 = random();
+        let b: u16 = random();
+        let mut non_determinism = init_memory_from(&test_struct, BFieldElement::new(300));
+        non_determinism
+            .ram
+            .extend(init_memory_from(&a, BFieldElement::new(400)).ram);
+        non_determinism
+            .ram
+            .extend(init_memory_from(&b, BFieldElement::new(500)).ram);
+        let stdin = vec![];
+        let expected_output = [
+            test_struct.a.encode(),
+            test_struct.b.encode(),
+            test_struct.c.encode(),
+            vec![BFieldElement::new(1)],
+            test_struct.a.encode(),
+            vec![BFieldElement::new(1)],
+            test_struct.b.encode(),
+            a.encode(),
+            b.encode(),
+        ]
+        .concat();
+
+        let native_output =
+            rust_shadows::wrap_main_with_io(&main)(stdin.clone(), non_determinism.clone());
+        assert_eq!(native_output, expected_output);
+
+        // Run test on Triton-VM
+        let entrypoint_location =
+            EntrypointLocation::disk("structs", "struct_with_small_unsigned_integers", "main");
+        let test_program =
+            ozk_parsing::compile_for_test(&entrypoint_location, crate::ast_types::ListType::Unsafe);
+        let vm_output = execute_compiled_with_stack_and_ins_for_test(
+            &test_program,
+            vec![],
+            stdin,
+            non_determinism,
+            0,
+        )
+        .unwrap();
+        if expected_output != vm_output.output {
+            panic!(
+                "expected_output:\n{}\ngot:\n{}\n. Code was:\n{}",
+                expected_output.iter().join(", "),
+                vm_output.output.iter().join(", "),
+                test_program.iter().join("\n")
+            );
+        }
+    }
+}
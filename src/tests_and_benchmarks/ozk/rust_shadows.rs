@@ -115,6 +115,28 @@ pub(super) fn tasm_arithmetic_u64_log_2_floor(val: u64) -> u32 {
     u64::BITS - val.leading_zeros() - 1
 }
 
+#[allow(non_snake_case)]
+pub(super) fn tasm_io_read_stdin___u8() -> u8 {
+    #[allow(clippy::unwrap_used)]
+    let val: u8 = PUB_INPUT
+        .with(|v| v.borrow_mut().pop().unwrap())
+        .value()
+        .try_into()
+        .unwrap();
+    val
+}
+
+#[allow(non_snake_case)]
+pub(super) fn tasm_io_read_stdin___u16() -> u16 {
+    #[allow(clippy::unwrap_used)]
+    let val: u16 = PUB_INPUT
+        .with(|v| v.borrow_mut().pop().unwrap())
+        .value()
+        .try_into()
+        .unwrap();
+    val
+}
+
 #[allow(non_snake_case)]
 pub(super) fn tasm_io_read_stdin___u32() -> u32 {
     #[allow(clippy::unwrap_used)]
@@ -192,6 +214,16 @@ pub(super) fn tasm_io_write_to_stdout___bool(x: bool) {
     PUB_OUTPUT.with(|v| v.borrow_mut().push(BFieldElement::new(x as u64)));
 }
 
+#[allow(non_snake_case)]
+pub(super) fn tasm_io_write_to_stdout___u8(x: u8) {
+    PUB_OUTPUT.with(|v| v.borrow_mut().push(BFieldElement::new(x as u64)));
+}
+
+#[allow(non_snake_case)]
+pub(super) fn tasm_io_write_to_stdout___u16(x: u16) {
+    PUB_OUTPUT.with(|v| v.borrow_mut().push(BFieldElement::new(x as u64)));
+}
+
 #[allow(non_snake_case)]
 pub(super) fn tasm_io_write_to_stdout___u32(x: u32) {
     PUB_OUTPUT.with(|v| v.borrow_mut().push(BFieldElement::new(x as u64)));
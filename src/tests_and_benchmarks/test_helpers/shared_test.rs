@@ -78,6 +78,9 @@ pub fn graft_check_compile_prop(
 ) -> Vec<LabelledInstruction> {
     get_standard_setup!(list_type, graft_config, libraries);
     let mut intermediate_language_ast = graft_config.graft_fn_decl(item_fn);
+    graft_config
+        .take_diagnostics()
+        .unwrap_or_else(|diagnostics| panic!("{diagnostics}"));
 
     // type-check and annotate. Doesn't handle structs and methods yet.
     let mut composite_types = CompositeTypes::default();
@@ -85,7 +88,8 @@ pub fn graft_check_compile_prop(
         &mut intermediate_language_ast,
         &mut composite_types,
         &libraries,
-    );
+    )
+    .unwrap_or_else(|diagnostics| panic!("{diagnostics}"));
 
     // compile
     let tasm = compile_function(&intermediate_language_ast, &libraries, &composite_types);
@@ -398,10 +402,10 @@ pub fn assert_list_equal(
     // assert elements agree on type
     if let Some(element_type) = element_type.clone() {
         for elem in expected_list.iter() {
-            type_checker::assert_type_equals(
-                &elem.get_type(),
-                &element_type,
-                "assert_list_equal test helper function",
+            assert_eq!(
+                element_type,
+                elem.get_type(),
+                "elements of expected list must all have the same type"
             );
         }
     }
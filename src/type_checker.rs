@@ -1,4 +1,6 @@
+use anyhow::anyhow;
 use anyhow::bail;
+use anyhow::ensure;
 use itertools::Itertools;
 use num::One;
 use std::collections::HashMap;
@@ -9,7 +11,13 @@ use triton_vm::BFieldElement;
 use crate::ast;
 use crate::ast::MethodCall;
 use crate::ast_types;
+use crate::compile_error::CompileError;
+use crate::compile_error::Diagnostics;
+use crate::compile_error::ErrorCode;
+use crate::compile_error::SourceLocation;
 use crate::composite_types::CompositeTypes;
+use crate::custom_type_resolver::enum_variant_constructor_call;
+use crate::custom_type_resolver::CustomTypeResolution;
 use crate::libraries;
 use crate::tasm_code_generator::SIZE_OF_ACCESSIBLE_STACK;
 
@@ -42,9 +50,13 @@ impl<T: GetType> GetType for ast::ExprLit<T> {
     fn get_type(&self) -> ast_types::DataType {
         match self {
             ast::ExprLit::Bool(_) => ast_types::DataType::Bool,
+            ast::ExprLit::U8(_) => ast_types::DataType::U8,
+            ast::ExprLit::U16(_) => ast_types::DataType::U16,
             ast::ExprLit::U32(_) => ast_types::DataType::U32,
             ast::ExprLit::U64(_) => ast_types::DataType::U64,
             ast::ExprLit::U128(_) => ast_types::DataType::U128,
+            ast::ExprLit::I32(_) => ast_types::DataType::I32,
+            ast::ExprLit::I64(_) => ast_types::DataType::I64,
             ast::ExprLit::Bfe(_) => ast_types::DataType::Bfe,
             ast::ExprLit::Xfe(_) => ast_types::DataType::Xfe,
             ast::ExprLit::Digest(_) => ast_types::DataType::Digest,
@@ -77,9 +89,13 @@ impl<T: GetType + std::fmt::Debug> GetType for ast::Expr<T> {
             ast::Expr::MethodCall(method_call) => method_call.get_type(),
             ast::Expr::Binop(_, _, _, t) => t.get_type(),
             ast::Expr::If(if_expr) => if_expr.get_type(),
+            ast::Expr::Match(match_expr) => match_expr.get_type(),
             ast::Expr::Cast(_expr, t) => t.to_owned(),
             ast::Expr::Unary(_, _, t) => t.get_type(),
             ast::Expr::ReturningBlock(ret_block) => ret_block.get_type(),
+            ast::Expr::Closure(_) => {
+                unreachable!("closures must be lifted to functions by the type checker")
+            }
         }
     }
 }
@@ -108,6 +124,12 @@ impl<T: GetType + std::fmt::Debug> GetType for ast::ExprIf<T> {
     }
 }
 
+impl<T: GetType + std::fmt::Debug> GetType for ast::MatchExpr<T> {
+    fn get_type(&self) -> ast_types::DataType {
+        self.arms[0].body.get_type()
+    }
+}
+
 impl<T: GetType> GetType for ast::Identifier<T> {
     fn get_type(&self) -> ast_types::DataType {
         match self {
@@ -146,6 +168,237 @@ pub struct CheckState<'a> {
 
     /// All non-atomic types that are in scope
     pub(crate) composite_types: &'a CompositeTypes,
+
+    /// The labels of the loops enclosing the statement that is being checked, innermost last.
+    /// Unlabelled loops are represented by `None`.
+    pub(crate) loop_labels: Vec<Option<String>>,
+
+    /// The errors found in the function that is being checked
+    pub(crate) diagnostics: Vec<CompileError>,
+
+    /// Bindings declared without a type annotation whose type could not be derived from
+    /// their initializer, like `let a = 4;`. Their type is inferred from the first use that
+    /// provides a type hint.
+    pub(crate) uninferred_bindings: HashMap<String, UninferredBinding>,
+
+    /// Bindings declared without a type annotation that are shadowed by a later declaration,
+    /// in the order in which they were shadowed. They are visible again once the scope of the
+    /// shadowing declaration is left.
+    pub(crate) shadowed_uninferred_bindings: Vec<(String, UninferredBinding)>,
+
+    /// The types of the `static` items of the program, by their names
+    pub(crate) statics: &'a HashMap<String, ast_types::DataType>,
+
+    /// The generic functions of the program and their instantiations
+    pub(crate) monomorphizations: &'a mut Monomorphizations,
+
+    /// The functions passed to the function arguments of the function that is being checked,
+    /// by the names of the function arguments
+    pub(crate) function_arguments: HashMap<String, FunctionValue>,
+
+    /// The variables of the functions enclosing the closure that is being checked, if any
+    pub(crate) enclosing_scope: Option<EnclosingScope>,
+
+    /// The location in the source code of the statement that is being checked, if known
+    pub(crate) stmt_location: Option<SourceLocation>,
+
+    /// The number of hidden bindings introduced by lowerings of the function that is being
+    /// checked, used for naming them
+    pub(crate) hidden_binding_count: usize,
+}
+
+/// The variables of the functions enclosing a closure, which the closure captures when it
+/// uses them
+#[derive(Debug, Default)]
+pub(crate) struct EnclosingScope {
+    /// The types of the variables, by their names. The type of a variable declared without a
+    /// type annotation might not be inferred yet.
+    bindings: HashMap<String, Option<ast_types::DataType>>,
+
+    /// The captured variables, in the order of their first use
+    captures: Vec<(String, ast_types::DataType)>,
+}
+
+/// The generic functions and the functions taking function arguments of a program, and their
+/// instantiations for the concrete types and functions they are called with. Shared by all
+/// functions of a program, such that each instantiation is only type checked and compiled
+/// once.
+#[derive(Debug, Default)]
+pub(crate) struct Monomorphizations {
+    generic_functions: HashMap<String, GenericFunction>,
+
+    /// The signatures of the instantiations, by the names of the instantiations
+    signatures: HashMap<String, ast::FnSignature>,
+
+    /// The type-checked instantiations and the functions that closures are lifted to, which
+    /// are compiled like any other function
+    functions: Vec<ast::Fn<Typing>>,
+
+    /// The number of closures lifted to functions so far, used for naming the functions
+    closure_count: usize,
+}
+
+/// A generic function or a function taking function arguments, along with the functions in
+/// scope where it is declared, which its instantiations are checked against
+#[derive(Clone, Debug)]
+struct GenericFunction {
+    function: ast::Fn<Typing>,
+    ftable: HashMap<String, Vec<ast::FnSignature>>,
+}
+
+/// A function passed to a function argument: a declared function, a closure lifted to a
+/// function, or a function argument of the calling function
+#[derive(Clone, Debug)]
+pub(crate) struct FunctionValue {
+    /// The name of the function that is called. It takes the captured values as arguments
+    /// before the arguments of the function type.
+    name: String,
+
+    /// The variables holding the values captured by a closure, and their types
+    captures: Vec<(String, ast_types::DataType)>,
+
+    function_type: ast_types::FunctionType,
+}
+
+/// The bindings visible where a scope is entered
+struct Scope {
+    vtable: HashMap<String, DataTypeAndMutability>,
+    shadowed_uninferred_binding_count: usize,
+}
+
+impl CheckState<'_> {
+    /// Report an error found in the statement that is being checked
+    fn report(&mut self, error: CompileError) {
+        let error = error.at_location(self.stmt_location.as_ref());
+        self.diagnostics.push(error);
+    }
+
+    /// If the closure that is being checked uses a variable of an enclosing function, capture
+    /// the variable, which makes it an immutable binding of the closure. The type of a
+    /// variable whose type is not yet inferred is inferred from the type hint.
+    fn capture_enclosing_binding(
+        &mut self,
+        name: &str,
+        hint: Option<&ast_types::DataType>,
+    ) -> anyhow::Result<()> {
+        let Some(enclosing_scope) = self.enclosing_scope.as_mut() else {
+            return Ok(());
+        };
+        if self.vtable.contains_key(name) {
+            return Ok(());
+        }
+
+        let captured_type = enclosing_scope
+            .captures
+            .iter()
+            .find(|(capture, _)| capture == name)
+            .map(|(_, data_type)| data_type.to_owned());
+        let data_type = match (captured_type, enclosing_scope.bindings.get(name)) {
+            (Some(data_type), _) => data_type,
+            (None, None) => return Ok(()),
+            (None, Some(data_type)) => {
+                let Some(data_type) = data_type.as_ref().or(hint) else {
+                    bail!("type annotations needed for `{name}`");
+                };
+                enclosing_scope
+                    .captures
+                    .push((name.to_owned(), data_type.to_owned()));
+                data_type.to_owned()
+            }
+        };
+        self.vtable.insert(
+            name.to_owned(),
+            DataTypeAndMutability::new(&data_type, false),
+        );
+
+        Ok(())
+    }
+
+    /// Return a name for a hidden binding introduced by a lowering, which is unique in the
+    /// function that is being checked, such that nested lowerings do not collide
+    fn hidden_binding_name(&mut self, prefix: &str) -> String {
+        let name = format!("_{prefix}_{}", self.hidden_binding_count);
+        self.hidden_binding_count += 1;
+        name
+    }
+
+    /// Enter the scope of a block, loop body, or match arm, whose declarations are only
+    /// visible inside of it
+    fn enter_scope(&self) -> Scope {
+        Scope {
+            vtable: self.vtable.clone(),
+            shadowed_uninferred_binding_count: self.shadowed_uninferred_bindings.len(),
+        }
+    }
+
+    /// Leave a scope. The bindings declared in it go out of scope, and the bindings they
+    /// shadowed are visible again.
+    fn leave_scope(&mut self, scope: Scope) {
+        self.vtable = scope.vtable;
+        let unshadowed_bindings = self
+            .shadowed_uninferred_bindings
+            .drain(scope.shadowed_uninferred_binding_count..)
+            .collect_vec();
+        self.uninferred_bindings.extend(unshadowed_bindings);
+    }
+
+    /// Declare a binding, which shadows any visible binding with the same name
+    fn declare_binding(&mut self, name: &str, binding: DataTypeAndMutability) {
+        self.shadow_uninferred_binding(name);
+        self.vtable.insert(name.to_owned(), binding);
+    }
+
+    /// Declare a binding whose type is inferred from its uses, which shadows any visible
+    /// binding with the same name
+    fn declare_uninferred_binding(&mut self, name: &str, mutable: bool) {
+        let binding = UninferredBinding {
+            mutable,
+            data_type: None,
+            shadows_uninferred_binding: self.shadow_uninferred_binding(name),
+            uses_without_type: vec![],
+        };
+        self.uninferred_bindings.insert(name.to_owned(), binding);
+    }
+
+    /// Hide the visible binding with the given name whose type is not yet inferred, if any.
+    /// Return whether there was such a binding.
+    fn shadow_uninferred_binding(&mut self, name: &str) -> bool {
+        let Some(binding) = self.uninferred_bindings.remove(name) else {
+            return false;
+        };
+        self.shadowed_uninferred_bindings
+            .push((name.to_owned(), binding));
+        true
+    }
+
+    /// Make the most recently shadowed binding with the given name visible again
+    fn unshadow_uninferred_binding(&mut self, name: &str) {
+        if let Some(binding) = self.take_shadowed_uninferred_binding(name) {
+            self.uninferred_bindings.insert(name.to_owned(), binding);
+        }
+    }
+
+    fn take_shadowed_uninferred_binding(&mut self, name: &str) -> Option<UninferredBinding> {
+        let index = self
+            .shadowed_uninferred_bindings
+            .iter()
+            .rposition(|(shadowed_name, _)| shadowed_name == name)?;
+        let (_, binding) = self.shadowed_uninferred_bindings.remove(index);
+        Some(binding)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct UninferredBinding {
+    pub mutable: bool,
+    pub data_type: Option<ast_types::DataType>,
+
+    /// Whether the declaration shadows another binding whose type is not yet inferred
+    pub shadows_uninferred_binding: bool,
+
+    /// The locations of the statements that use the binding before its type is known. The
+    /// errors of these uses are replaced by a single error at the declaration.
+    pub uses_without_type: Vec<Option<SourceLocation>>,
 }
 
 #[derive(Clone, Debug)]
@@ -178,7 +431,9 @@ fn annotate_method(
     composite_types: &CompositeTypes,
     libraries: &[Box<dyn libraries::Library>],
     ftable: HashMap<String, Vec<ast::FnSignature>>,
-) {
+    statics: &HashMap<String, ast_types::DataType>,
+    monomorphizations: &mut Monomorphizations,
+) -> Vec<CompileError> {
     // Initialize `CheckState`
     let vtable: HashMap<String, DataTypeAndMutability> =
         HashMap::with_capacity(method.signature.args.len());
@@ -189,51 +444,57 @@ fn annotate_method(
         vtable,
         ftable,
         composite_types,
+        loop_labels: vec![],
+        diagnostics: vec![],
+        uninferred_bindings: HashMap::default(),
+        shadowed_uninferred_bindings: vec![],
+        statics,
+        monomorphizations,
+        function_arguments: HashMap::default(),
+        enclosing_scope: None,
+        stmt_location: None,
+        hidden_binding_count: 0,
     };
 
     // Populate vtable with function arguments
     for arg in method.signature.args.iter() {
         match arg {
-            ast_types::AbstractArgument::FunctionArgument(_) => todo!(),
+            ast_types::AbstractArgument::FunctionArgument(_) => state
+                .diagnostics
+                .push(function_argument_error(&method.signature.name)),
             ast_types::AbstractArgument::ValueArgument(value_fn_arg) => {
                 let duplicate_fn_arg = state
                     .vtable
                     .insert(value_fn_arg.name.clone(), value_fn_arg.to_owned().into())
                     .is_some();
                 if duplicate_fn_arg {
-                    panic!("Duplicate function argument {}", value_fn_arg.name);
+                    state.diagnostics.push(duplicate_argument_error(
+                        &value_fn_arg.name,
+                        &method.signature.name,
+                    ));
                 }
             }
         }
     }
 
     // Verify that input arguments do not exceed 15 words
-    assert!(
-        method.signature.input_arguments_stack_size() < SIZE_OF_ACCESSIBLE_STACK,
-        "{}: Cannot handle method signatures with input size exceeding {} words",
-        method.signature.name,
-        SIZE_OF_ACCESSIBLE_STACK - 1
-    );
+    let input_arguments_stack_size = method.signature.input_arguments_stack_size();
+    if let Err(error) = check_arguments_stack_size(input_arguments_stack_size) {
+        state.diagnostics.push(error);
+    }
 
     if let ast::RoutineBody::Ast(stmts) = &mut method.body {
         // Verify that last statement of function exists, and that it is a `return` statement
-        let last_stmt = stmts.iter().last().unwrap_or_else(|| {
-            panic!(
-                "Method `{}`: Function cannot be emtpy.",
-                method.signature.name
-            )
-        });
-        assert!(
-            matches!(last_stmt, ast::Stmt::Return(_)),
-            "Method: `{}`: Last line of method must be a `return`",
-            method.signature.name
-        );
+        if let Err(error) = check_ends_with_return(stmts) {
+            state.diagnostics.push(error);
+        }
 
         // Type-annotate each statement in-place
-        stmts
-            .iter_mut()
-            .for_each(|stmt| annotate_stmt(stmt, &mut state, &method.signature));
+        declare_local_functions(stmts, &mut state.ftable, state.monomorphizations);
+        annotate_stmts(stmts, &mut state, &method.signature);
     }
+
+    state.diagnostics
 }
 
 fn annotate_fn_inner(
@@ -241,7 +502,10 @@ fn annotate_fn_inner(
     composite_types: &CompositeTypes,
     libraries: &[Box<dyn libraries::Library>],
     mut ftable: HashMap<String, Vec<ast::FnSignature>>,
-) {
+    statics: &HashMap<String, ast_types::DataType>,
+    monomorphizations: &mut Monomorphizations,
+    function_arguments: HashMap<String, FunctionValue>,
+) -> Vec<CompileError> {
     // Initialize `CheckState`
     let vtable: HashMap<String, DataTypeAndMutability> =
         HashMap::with_capacity(function.signature.args.len());
@@ -256,66 +520,122 @@ fn annotate_fn_inner(
         vtable,
         ftable,
         composite_types,
+        loop_labels: vec![],
+        diagnostics: vec![],
+        uninferred_bindings: HashMap::default(),
+        shadowed_uninferred_bindings: vec![],
+        statics,
+        monomorphizations,
+        function_arguments,
+        enclosing_scope: None,
+        stmt_location: None,
+        hidden_binding_count: 0,
     };
 
     // Populate vtable with function arguments
     for arg in function.signature.args.iter() {
         match arg {
-            ast_types::AbstractArgument::FunctionArgument(_) => todo!(),
+            // Free and local functions taking function arguments are only checked when
+            // instantiated, at which point their function arguments have been replaced by
+            // the values captured by the functions passed to them.
+            ast_types::AbstractArgument::FunctionArgument(_) => state
+                .diagnostics
+                .push(function_argument_error(&function.signature.name)),
             ast_types::AbstractArgument::ValueArgument(value_fn_arg) => {
                 let duplicate_fn_arg = state
                     .vtable
                     .insert(value_fn_arg.name.clone(), value_fn_arg.to_owned().into())
                     .is_some();
                 if duplicate_fn_arg {
-                    panic!(
-                        "Duplicate function argument {} in function \"{}\"",
-                        value_fn_arg.name, function.signature.name
-                    );
+                    state.diagnostics.push(duplicate_argument_error(
+                        &value_fn_arg.name,
+                        &function.signature.name,
+                    ));
                 }
             }
         }
     }
 
     // Verify that input arguments do not exceed 15 words
-    assert!(
-        state
-            .vtable
-            .values()
-            .map(|x| x.data_type.stack_size())
-            .sum::<usize>()
-            < SIZE_OF_ACCESSIBLE_STACK,
-        "Function `{}`: Cannot handle function signatures with input size exceeding {} words",
-        function.signature.name,
-        SIZE_OF_ACCESSIBLE_STACK - 1
-    );
+    let input_arguments_stack_size = state
+        .vtable
+        .values()
+        .map(|x| x.data_type.stack_size())
+        .sum::<usize>();
+    if let Err(error) = check_arguments_stack_size(input_arguments_stack_size) {
+        state.diagnostics.push(error);
+    }
 
     if let ast::RoutineBody::Ast(stmts) = &mut function.body {
         // Verify that last statement of function exists, and that it is a `return` statement
-        let last_stmt = stmts.iter().last().unwrap_or_else(|| {
-            panic!(
-                "Function `{}`: Function cannot be emtpy.",
-                function.signature.name
-            )
-        });
-        assert!(
-            matches!(last_stmt, ast::Stmt::Return(_)),
-            "Function `{}`: Last line of function must be a `return` statement.",
-            function.signature.name
-        );
+        if let Err(error) = check_ends_with_return(stmts) {
+            state.diagnostics.push(error);
+        }
 
         // Type-annotate each statement in-place
-        stmts
-            .iter_mut()
-            .for_each(|stmt| annotate_stmt(stmt, &mut state, &function.signature));
+        declare_local_functions(stmts, &mut state.ftable, state.monomorphizations);
+        annotate_stmts(stmts, &mut state, &function.signature);
+    }
+
+    state.diagnostics
+}
+
+fn duplicate_argument_error(argument_name: &str, function_name: &str) -> CompileError {
+    CompileError::new(
+        ErrorCode::DuplicateArgument,
+        format!(
+            "identifier `{argument_name}` is bound more than once in the arguments of \
+             `{function_name}`"
+        ),
+    )
+}
+
+fn function_argument_error(function_name: &str) -> CompileError {
+    CompileError::new(
+        ErrorCode::UnsupportedSyntax,
+        format!(
+            "`{function_name}` cannot take function arguments, only free functions and local \
+             functions can"
+        ),
+    )
+}
+
+fn check_arguments_stack_size(input_arguments_stack_size: usize) -> Result<(), CompileError> {
+    if input_arguments_stack_size >= SIZE_OF_ACCESSIBLE_STACK {
+        return Err(CompileError::new(
+            ErrorCode::ArgumentsTooLarge,
+            format!(
+                "cannot handle function signatures with input size exceeding {} words",
+                SIZE_OF_ACCESSIBLE_STACK - 1
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_ends_with_return(stmts: &[ast::Stmt<Typing>]) -> Result<(), CompileError> {
+    match stmts.last() {
+        Some(ast::Stmt::Return(_)) => Ok(()),
+        Some(_) => Err(CompileError::new(
+            ErrorCode::MissingReturn,
+            "last line of function must be a `return` statement",
+        )),
+        None => Err(CompileError::new(
+            ErrorCode::MissingReturn,
+            "function cannot be empty, it must end with a `return` statement",
+        )),
     }
 }
 
+/// Type-annotate a function along with all methods and associated functions of the custom
+/// types it uses. All errors found are returned, each tagged with the name of the function
+/// in which it was found. Methods and associated functions are named `<Type>::<name>`.
 pub(crate) fn annotate_fn_outer(
     function: &mut ast::Fn<Typing>,
     composite_types: &mut CompositeTypes,
     libraries: &[Box<dyn libraries::Library>],
-) {
+) -> Result<(), Diagnostics> {
     // Populate `ftable` with tuple constructors, allowing initialization of tuple structs
     // using `struct Foo(u32); let a = Foo(200);` as well as data-carrying enum-type
     // variants such as `Bar::A(200u32);`.
@@ -324,29 +644,305 @@ pub(crate) fn annotate_fn_outer(
     let ftable = composite_types.get_all_constructor_signatures();
 
     // Type annotate the function
-    let ftable_outer = ftable.clone();
-    annotate_fn_inner(function, composite_types, libraries, ftable);
+    let mut ftable_outer = ftable.clone();
+
+    // Methods and associated functions can call the free functions of the program, which
+    // are declared as local functions of the outer function. All functions can read the
+    // `static` items of the program, which are declared in the outer function.
+    let mut monomorphizations = Monomorphizations::default();
+    let mut statics = HashMap::default();
+    if let ast::RoutineBody::Ast(stmts) = &function.body {
+        declare_local_functions(stmts, &mut ftable_outer, &mut monomorphizations);
+        statics = declared_statics(stmts);
+    }
+    let function_name = function.signature.name.clone();
+    let mut errors = annotate_fn_inner(
+        function,
+        composite_types,
+        libraries,
+        ftable,
+        &statics,
+        &mut monomorphizations,
+        HashMap::default(),
+    )
+    .into_iter()
+    .map(|error| error.in_function(&function_name))
+    .collect_vec();
 
     // Type annotate all declared methods and associated functions
     let composite_type_copy = composite_types.clone();
-    composite_types.methods_mut().for_each(|method| {
-        annotate_method(
-            method,
-            &composite_type_copy,
-            libraries,
-            ftable_outer.clone(),
-        );
-    });
-    composite_types.associated_functions_mut().for_each(|func| {
-        annotate_fn_inner(func, &composite_type_copy, libraries, ftable_outer.clone());
-    });
+    for type_context in composite_types.type_contexts_mut() {
+        let type_name = type_context.composite_type.name().to_owned();
+        for method in type_context.methods.iter_mut() {
+            let method_name = format!("{type_name}::{}", method.signature.name);
+            let method_errors = annotate_method(
+                method,
+                &composite_type_copy,
+                libraries,
+                ftable_outer.clone(),
+                &statics,
+                &mut monomorphizations,
+            );
+            errors.extend(
+                method_errors
+                    .into_iter()
+                    .map(|error| error.in_function(&method_name)),
+            );
+        }
+        for func in type_context.associated_functions.iter_mut() {
+            let func_name = format!("{type_name}::{}", func.signature.name);
+            let func_errors = annotate_fn_inner(
+                func,
+                &composite_type_copy,
+                libraries,
+                ftable_outer.clone(),
+                &statics,
+                &mut monomorphizations,
+                HashMap::default(),
+            );
+            errors.extend(
+                func_errors
+                    .into_iter()
+                    .map(|error| error.in_function(&func_name)),
+            );
+        }
+    }
+
+    // The instantiations of generic functions are compiled as local functions of the outer
+    // function, like the free functions of the program.
+    if let ast::RoutineBody::Ast(stmts) = &mut function.body {
+        let instantiations = monomorphizations
+            .functions
+            .into_iter()
+            .map(ast::Stmt::FnDeclaration);
+        stmts.splice(0..0, instantiations);
+    }
+
+    Diagnostics::check(errors)
+}
+
+/// Type-annotate a list of statements in-place. An error in one statement does not prevent
+/// the following statements from being checked.
+fn annotate_stmts(
+    stmts: &mut [ast::Stmt<Typing>],
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) {
+    annotate_each_stmt(stmts, state, env_fn_signature);
+    annotate_inferred_let_stmts(stmts, state, env_fn_signature);
+}
+
+fn annotate_each_stmt(
+    stmts: &mut [ast::Stmt<Typing>],
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) {
+    let enclosing_location = state.stmt_location.clone();
+    let locations = stmt_locations(stmts);
+    for (stmt, location) in stmts.iter_mut().zip(locations) {
+        state.stmt_location = location.or_else(|| enclosing_location.clone());
+        if let Err(error) = annotate_stmt(stmt, state, env_fn_signature) {
+            state.report(error);
+        }
+    }
+    state.stmt_location = enclosing_location;
+}
+
+/// The location of each statement in a list of statements, as given by the preceding
+/// [`ast::Stmt::Location`], if any
+fn stmt_locations(stmts: &[ast::Stmt<Typing>]) -> Vec<Option<SourceLocation>> {
+    let mut location = None;
+    stmts
+        .iter()
+        .map(|stmt| {
+            if let ast::Stmt::Location(stmt_location) = stmt {
+                location = Some(stmt_location.to_owned());
+            }
+            location.clone()
+        })
+        .collect()
+}
+
+/// Annotate the declarations in a list of statements whose type was inferred from later
+/// uses. They are handled in reverse order, since an initializer like `a + 1` can determine
+/// the type of an earlier binding `a`.
+fn annotate_inferred_let_stmts(
+    stmts: &mut [ast::Stmt<Typing>],
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) {
+    let enclosing_location = state.stmt_location.clone();
+    let locations = stmt_locations(stmts);
+    for (stmt, location) in stmts.iter_mut().zip(locations).rev() {
+        if let ast::Stmt::Let(let_stmt) = stmt {
+            state.stmt_location = location.or_else(|| enclosing_location.clone());
+            if let Err(error) = annotate_inferred_let_stmt(let_stmt, state, env_fn_signature) {
+                state.report(error);
+            }
+        }
+    }
+    state.stmt_location = enclosing_location;
+}
+
+/// Annotate the initializer of a binding whose type was inferred from its uses
+fn annotate_inferred_let_stmt(
+    let_stmt: &mut ast::LetStmt<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Result<(), CompileError> {
+    let var_name = &let_stmt.var_name;
+    if let_stmt.data_type.is_some() {
+        return Ok(());
+    }
+    // A binding that is shadowed by a later declaration is found among the shadowed bindings.
+    // Since the declarations are handled in reverse order, it is the last one shadowed.
+    let Some(binding) = state
+        .uninferred_bindings
+        .remove(var_name)
+        .or_else(|| state.take_shadowed_uninferred_binding(var_name))
+    else {
+        return Ok(());
+    };
+
+    // The initializer refers to the binding that this declaration shadows
+    if binding.shadows_uninferred_binding {
+        state.unshadow_uninferred_binding(var_name);
+    }
+
+    let Some(data_type) = binding.data_type else {
+        let error = missing_type_annotation(var_name);
+        state.diagnostics.retain(|reported| {
+            reported.code != error.code
+                || reported.message != error.message
+                || !binding.uses_without_type.contains(&reported.location)
+        });
+        return Err(error);
+    };
+
+    let derived_type = derive_annotate_expr_type(
+        &mut let_stmt.expr,
+        Some(&data_type),
+        state,
+        env_fn_signature,
+    )
+    .map_err(type_error)?;
+    check_type_equals(
+        &derived_type,
+        &data_type,
+        &format!("let-statement of {var_name}"),
+    )?;
+    let_stmt.data_type = Some(data_type);
+
+    Ok(())
+}
+
+/// Return the type of a binding declared without a type annotation. If the type is not yet
+/// known, it is inferred from the type hint of the expression in which the binding is used.
+fn infer_binding_type(
+    var_name: &str,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+) -> Result<ast_types::DataType, CompileError> {
+    let binding = state.uninferred_bindings.get_mut(var_name).unwrap();
+    if binding.data_type.is_none() {
+        binding.data_type = hint.cloned();
+    }
+
+    match &binding.data_type {
+        Some(data_type) => Ok(data_type.to_owned()),
+        None => Err(missing_type_annotation_at_use(var_name, state)),
+    }
+}
+
+/// The error for a use of a binding whose type is not yet known. The use is recorded, such
+/// that the error can be replaced by the error at the declaration of the binding.
+fn missing_type_annotation_at_use(var_name: &str, state: &mut CheckState) -> CompileError {
+    let use_location = state.stmt_location.clone();
+    let binding = state.uninferred_bindings.get_mut(var_name).unwrap();
+    binding.uses_without_type.push(use_location);
+
+    missing_type_annotation(var_name)
+}
+
+fn missing_type_annotation(var_name: &str) -> CompileError {
+    CompileError::new(
+        ErrorCode::MissingTypeAnnotation,
+        format!("type annotations needed for `{var_name}`"),
+    )
+}
+
+/// Declare the bindings of a pattern in a destructuring `let` statement that destructures a
+/// value of type `data_type`. Only values on the stack can be destructured.
+fn bind_destructuring_pattern(
+    pattern: &ast::DestructuringPattern,
+    data_type: &ast_types::DataType,
+    state: &mut CheckState,
+) -> Result<(), CompileError> {
+    let mismatch = |expected: String| {
+        CompileError::new(
+            ErrorCode::TypeError,
+            format!(
+                "mismatched types in destructuring let-statement: expected {expected}, \
+                 found `{data_type}`"
+            ),
+        )
+    };
+
+    match pattern {
+        ast::DestructuringPattern::Wildcard => (),
+        ast::DestructuringPattern::Binding(ast::PatternMatchedBinding { name, mutable }) => {
+            state.declare_binding(name, DataTypeAndMutability::new(data_type, *mutable));
+        }
+        ast::DestructuringPattern::Tuple(elements) => match data_type {
+            ast_types::DataType::Tuple(tuple) if tuple.element_count() == elements.len() => {
+                for (element, element_type) in elements.iter().zip_eq(tuple.fields.iter()) {
+                    bind_destructuring_pattern(element, element_type, state)?;
+                }
+            }
+            _ => {
+                let expected = format!("a tuple with {} elements", elements.len());
+                return Err(mismatch(expected));
+            }
+        },
+        ast::DestructuringPattern::Struct {
+            struct_type: pattern_type,
+            fields,
+        } => {
+            let ast_types::DataType::Struct(struct_type) = data_type else {
+                return Err(mismatch(format!("`{pattern_type}`")));
+            };
+
+            // Patterns on instances of generic structs only name the generic struct
+            let is_generic_instance = matches!(
+                pattern_type,
+                ast_types::DataType::Unresolved(type_name) if *type_name == struct_type.name
+            );
+            if data_type != pattern_type && !is_generic_instance {
+                return Err(mismatch(format!("`{pattern_type}`")));
+            }
+            for (field_id, field_pattern) in fields.iter() {
+                let Some((_, field_type)) = struct_type
+                    .field_ids_and_types()
+                    .find(|(struct_field_id, _)| struct_field_id == field_id)
+                else {
+                    return Err(CompileError::new(
+                        ErrorCode::TypeError,
+                        format!("struct `{pattern_type}` has no field `{field_id}`"),
+                    ));
+                };
+                bind_destructuring_pattern(field_pattern, field_type, state)?;
+            }
+        }
+    }
+
+    Ok(())
 }
 
 fn annotate_stmt(
     stmt: &mut ast::Stmt<Typing>,
     state: &mut CheckState,
     env_fn_signature: &ast::FnSignature,
-) {
+) -> Result<(), CompileError> {
     match stmt {
         // `let a: u32 = 4;`
         ast::Stmt::Let(ast::LetStmt {
@@ -355,64 +951,128 @@ fn annotate_stmt(
             expr,
             mutable,
         }) => {
-            if state.vtable.contains_key(var_name) {
-                panic!("let-assign cannot shadow existing variable '{var_name}'!");
-            }
+            // `let a = 4;`
+            let Some(data_type) = data_type.as_ref() else {
+                match derive_annotate_expr_type(expr, None, state, env_fn_signature) {
+                    Ok(derived_type) => {
+                        state.declare_binding(
+                            var_name,
+                            DataTypeAndMutability::new(&derived_type, *mutable),
+                        );
+                        *data_type = Some(derived_type);
+                    }
+                    Err(_) => {
+                        state.declare_uninferred_binding(var_name, *mutable);
+                    }
+                }
+                return Ok(());
+            };
 
             let let_expr_hint: Option<&ast_types::DataType> = Some(data_type);
             let derived_type =
-                derive_annotate_expr_type(expr, let_expr_hint, state, env_fn_signature).unwrap();
-            assert_type_equals(
-                &derived_type,
+                derive_annotate_expr_type(expr, let_expr_hint, state, env_fn_signature);
+
+            // The binding is declared even if its initializer is ill-typed, such that the
+            // statements using it can still be checked.
+            state.declare_binding(var_name, DataTypeAndMutability::new(data_type, *mutable));
+            check_type_equals(
+                &derived_type.map_err(type_error)?,
                 data_type,
                 &format!("let-statement of {var_name}"),
-            );
-            state.vtable.insert(
-                var_name.clone(),
-                DataTypeAndMutability::new(data_type, *mutable),
-            );
+            )?;
+        }
+
+        // `let (a, b): (u32, u64) = (4, 5);`
+        ast::Stmt::DestructuringLet(ast::DestructuringLetStmt {
+            pattern,
+            data_type,
+            expr,
+        }) => {
+            let derived_type =
+                derive_annotate_expr_type(expr, data_type.as_ref(), state, env_fn_signature);
+            let Some(data_type) = data_type.as_ref() else {
+                let derived_type = derived_type.map_err(type_error)?;
+                bind_destructuring_pattern(pattern, &derived_type, state)?;
+                *data_type = Some(derived_type);
+                return Ok(());
+            };
+
+            // The bindings are declared even if the initializer is ill-typed, such that the
+            // statements using them can still be checked.
+            bind_destructuring_pattern(pattern, data_type, state)?;
+            check_type_equals(
+                &derived_type.map_err(type_error)?,
+                data_type,
+                "destructuring let-statement",
+            )?;
         }
 
         // `a = 4;`, where `a` is declared as `mut`
         ast::Stmt::Assign(ast::AssignStmt { identifier, expr }) => {
+            // The type of a binding that is not yet inferred is inferred from the assigned value
+            if let ast::Identifier::String(var_name, _) = identifier {
+                let is_uninferred = matches!(
+                    state.uninferred_bindings.get(var_name),
+                    Some(UninferredBinding {
+                        data_type: None,
+                        ..
+                    })
+                );
+                if is_uninferred {
+                    let Ok(expr_type) =
+                        derive_annotate_expr_type(expr, None, state, env_fn_signature)
+                    else {
+                        return Err(missing_type_annotation_at_use(var_name, state));
+                    };
+                    state
+                        .uninferred_bindings
+                        .get_mut(var_name)
+                        .unwrap()
+                        .data_type = Some(expr_type);
+                }
+            }
+
             let (identifier_type, mutable, new_expr) =
-                annotate_identifier_type(identifier, None, state, env_fn_signature);
-            assert!(
-                new_expr.is_none(),
-                "Cannot rewrite expression outside of atomic variable expression"
-            );
+                annotate_identifier_type(identifier, None, state, env_fn_signature)
+                    .map_err(type_error)?;
+            if new_expr.is_some() {
+                return Err(CompileError::new(
+                    ErrorCode::TypeError,
+                    format!("cannot assign to enum variant `{identifier}`"),
+                ));
+            }
             let assign_expr_hint = &identifier_type;
             let expr_type =
                 derive_annotate_expr_type(expr, Some(assign_expr_hint), state, env_fn_signature)
-                    .unwrap();
+                    .map_err(type_error)?;
 
             // Only allow assignment if binding was declared as mutable
-            assert_type_equals(&identifier_type, &expr_type, "assign-statement");
-            assert!(
-                mutable,
-                "Cannot re-assign non-mutable binding: {identifier}"
-            )
+            check_type_equals(&identifier_type, &expr_type, "assign-statement")?;
+            if !mutable {
+                return Err(CompileError::new(
+                    ErrorCode::ImmutableAssignment,
+                    format!("cannot re-assign non-mutable binding `{identifier}`"),
+                ));
+            }
         }
 
         ast::Stmt::Return(opt_expr) => match (opt_expr, &env_fn_signature.output) {
-            (None, ast_types::DataType::Tuple(tys)) => assert_eq!(
-                0,
-                tys.element_count(),
-                "Return without value; expect {} to return nothing.",
-                env_fn_signature.name
-            ),
+            (None, output) if output.is_unit() => (),
             (None, _) => {
-                panic!(
-                    "Return without value; expect function {} to return {}",
-                    env_fn_signature.name, env_fn_signature.output
-                )
+                return Err(CompileError::new(
+                    ErrorCode::TypeError,
+                    format!(
+                        "return without value; expected function `{}` to return `{}`",
+                        env_fn_signature.name, env_fn_signature.output
+                    ),
+                ))
             }
             (Some(ret_expr), _) => {
                 let hint = &env_fn_signature.output;
                 let expr_ret_type =
                     derive_annotate_expr_type(ret_expr, Some(hint), state, env_fn_signature)
-                        .unwrap();
-                assert_type_equals(&env_fn_signature.output, &expr_ret_type, "return stmt");
+                        .map_err(type_error)?;
+                check_type_equals(&expr_ret_type, &env_fn_signature.output, "return stmt")?;
             }
         },
 
@@ -420,55 +1080,85 @@ fn annotate_stmt(
             name,
             args,
             annot,
-            type_parameter,
+            type_arguments,
             arg_evaluation_order,
         }) => {
+            resolve_function_argument_call(name, args, state);
+
             // Attempt to annotate all arguments before getting the function signature
-            for arg in args.iter_mut() {
-                // It's OK if this fails, as a later invocation of the argument expressions
-                // might pass.
-                let _ = derive_annotate_expr_type(arg, None, state, env_fn_signature);
-            }
+            let arg_types = args
+                .iter_mut()
+                .map(|arg| {
+                    // It's OK if this fails, as a later invocation of the argument expressions
+                    // might pass.
+                    derive_annotate_expr_type(arg, None, state, env_fn_signature).ok()
+                })
+                .collect_vec();
 
+            monomorphize_fn_call(
+                name,
+                type_arguments,
+                args,
+                &arg_types,
+                None,
+                state,
+                env_fn_signature,
+            )
+            .map_err(type_error)?;
             let callees_fn_signature =
-                get_fn_signature(name, state, type_parameter, args, env_fn_signature, None);
-            assert!(
-                callees_fn_signature.output.is_unit(),
-                "Function call '{name}' at statement-level must return the unit type."
-            );
+                get_fn_signature(name, state, type_arguments, args, None).map_err(type_error)?;
+            if !callees_fn_signature.output.is_unit() {
+                return Err(CompileError::new(
+                    ErrorCode::TypeError,
+                    format!("function call `{name}` at statement-level must return the unit type"),
+                ));
+            }
 
-            derive_annotate_fn_call_args(&callees_fn_signature, args, state);
+            derive_annotate_fn_call_args(&callees_fn_signature, args, state).map_err(type_error)?;
 
             *arg_evaluation_order = callees_fn_signature.arg_evaluation_order;
             *annot = Typing::KnownType(callees_fn_signature.output);
         }
 
         ast::Stmt::MethodCall(method_call) => {
-            // Attempt to annotate all arguments before getting the function signature
-            for arg in method_call.args.iter_mut() {
-                // It's OK if this fails, as a later invocation of the argument expressions
-                // might pass.
-                let _ = derive_annotate_expr_type(arg, None, state, env_fn_signature);
+            // Attempt to annotate all arguments before getting the method signature. Only the
+            // receiver must be annotated here; it's OK if annotating the other arguments fails,
+            // as a later invocation of the argument expressions might pass.
+            for (i, arg) in method_call.args.iter_mut().enumerate() {
+                let arg_type = derive_annotate_expr_type(arg, None, state, env_fn_signature);
+                if i == 0 {
+                    arg_type.map_err(type_error)?;
+                }
             }
 
             let receiver_type = method_call.args[0].get_type();
 
             let callees_method_signature: ast::FnSignature =
-                get_method_signature(state, method_call, env_fn_signature);
-            assert!(
-                callees_method_signature.output.is_unit(),
-                "Method call {receiver_type}.'{}' at statement-level must return the unit type.",
-                method_call.method_name
-            );
+                get_method_signature(state, method_call).map_err(type_error)?;
+            if !callees_method_signature.output.is_unit() {
+                return Err(CompileError::new(
+                    ErrorCode::TypeError,
+                    format!(
+                        "method call `{receiver_type}.{}` at statement-level must return the \
+                         unit type",
+                        method_call.method_name
+                    ),
+                ));
+            }
 
             // TODO: Check that receiver_type corresponds to method's FnSignature
 
-            derive_annotate_fn_call_args(&callees_method_signature, &mut method_call.args, state);
+            derive_annotate_fn_call_args(&callees_method_signature, &mut method_call.args, state)
+                .map_err(type_error)?;
 
             method_call.annot = Typing::KnownType(callees_method_signature.output)
         }
 
-        ast::Stmt::While(ast::WhileStmt { condition, block }) => {
+        ast::Stmt::While(ast::WhileStmt {
+            condition,
+            block,
+            label,
+        }) => {
             let condition_hint = ast_types::DataType::Bool;
             let condition_type = derive_annotate_expr_type(
                 condition,
@@ -476,13 +1166,24 @@ fn annotate_stmt(
                 state,
                 env_fn_signature,
             )
-            .unwrap();
-            assert_type_equals(
+            .map_err(type_error)?;
+            check_type_equals(
                 &condition_type,
                 &ast_types::DataType::Bool,
                 "while-condition",
-            );
-            annotate_block_stmt(block, env_fn_signature, state);
+            )?;
+            annotate_loop_body(block, label, env_fn_signature, state);
+        }
+
+        ast::Stmt::Loop(ast::LoopStmt { block, label }) => {
+            annotate_loop_body(block, label, env_fn_signature, state);
+        }
+
+        ast::Stmt::Break(label) => check_loop_label_in_scope("break", label, state)?,
+        ast::Stmt::Continue(label) => check_loop_label_in_scope("continue", label, state)?,
+
+        ast::Stmt::For(for_stmt) => {
+            *stmt = lower_for_stmt(for_stmt, state, env_fn_signature)?;
         }
 
         ast::Stmt::If(ast::IfStmt {
@@ -497,170 +1198,2280 @@ fn annotate_stmt(
                 state,
                 env_fn_signature,
             )
-            .unwrap();
-            assert_type_equals(&condition_type, &ast_types::DataType::Bool, "if-condition");
+            .map_err(type_error)?;
+            check_type_equals(&condition_type, &ast_types::DataType::Bool, "if-condition")?;
 
             annotate_block_stmt(then_branch, env_fn_signature, state);
             annotate_block_stmt(else_branch, env_fn_signature, state);
         }
 
-        ast::Stmt::Match(ast::MatchStmt {
-            arms,
-            match_expression,
-        }) => {
-            // Verify that match-expression returns an enum-type
-            let match_expression_type =
-                derive_annotate_expr_type(match_expression, None, state, env_fn_signature).unwrap();
-            let (enum_type, is_boxed) = match match_expression_type {
-                ast_types::DataType::Enum(enum_type) => (enum_type, false),
-                ast_types::DataType::Boxed(inner) => match *inner.to_owned() {
-                    ast_types::DataType::Enum(enum_type) => (enum_type, true),
-                    other => panic!("`match` statements are only supported on enum types. For now. Got {other}")
-                },
-                _ => panic!("`match` statements are only supported on enum types. For now. Got {match_expression_type}")
+        ast::Stmt::Match(match_stmt) => {
+            if let Some(vector_type) =
+                popped_vector_type(&mut match_stmt.match_expression, state, env_fn_signature)
+            {
+                *stmt = lower_popped_vector_match_stmt(
+                    match_stmt,
+                    vector_type,
+                    state,
+                    env_fn_signature,
+                )?;
+                return Ok(());
+            }
+
+            let match_expression_type = derive_annotate_expr_type(
+                &mut match_stmt.match_expression,
+                None,
+                state,
+                env_fn_signature,
+            );
+            let arms = match_stmt
+                .arms
+                .iter()
+                .map(|arm| (&arm.match_condition, &arm.guard));
+            if is_lowered_match(match_expression_type.as_ref().ok(), arms) {
+                let match_expression_type = match_expression_type.map_err(type_error)?;
+                *stmt =
+                    lower_match_stmt(match_stmt, match_expression_type, state, env_fn_signature)?;
+            } else {
+                let match_expression_type = match_expression_type.map_err(type_error)?;
+                annotate_enum_match_stmt(
+                    match_stmt,
+                    &match_expression_type,
+                    state,
+                    env_fn_signature,
+                )?;
+            }
+        }
+
+        ast::Stmt::Block(block_stmt) => {
+            annotate_block_stmt(block_stmt, env_fn_signature, state);
+        }
+        ast::Stmt::Assert(assert_expr) => {
+            let expr_type = derive_annotate_expr_type(
+                &mut assert_expr.expression,
+                Some(&ast_types::DataType::Bool),
+                state,
+                env_fn_signature,
+            )
+            .map_err(type_error)?;
+            check_type_equals(&expr_type, &ast_types::DataType::Bool, "assert expression")?;
+        }
+        ast::Stmt::Panic(_) | ast::Stmt::Location(_) => (),
+        ast::Stmt::Static(ast::StaticStmt {
+            name,
+            data_type,
+            expr,
+        }) => {
+            let expr_type =
+                derive_annotate_expr_type(expr, Some(data_type), state, env_fn_signature)
+                    .map_err(type_error)?;
+            check_type_equals(&expr_type, data_type, &format!("static item `{name}`"))?;
+        }
+        // Generic functions and functions taking function arguments are type checked when
+        // they are instantiated
+        ast::Stmt::FnDeclaration(function) if function.is_instantiated_per_call() => (),
+        ast::Stmt::FnDeclaration(function) => {
+            // A local function can see all functions available in the outer scope.
+            let function_errors = annotate_fn_inner(
+                function,
+                state.composite_types,
+                state.libraries,
+                state.ftable.clone(),
+                state.statics,
+                state.monomorphizations,
+                HashMap::default(),
+            );
+            state.diagnostics.extend(
+                function_errors
+                    .into_iter()
+                    .map(|error| error.in_function(&function.signature.name)),
+            );
+            state.ftable.insert(
+                function.signature.name.clone(),
+                vec![function.signature.clone()],
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn annotate_block_stmt(
+    block: &mut ast::BlockStmt<Typing>,
+    fn_signature: &ast::FnSignature,
+    state: &mut CheckState,
+) {
+    let block_scope = state.enter_scope();
+    declare_local_functions(&block.stmts, &mut state.ftable, state.monomorphizations);
+    annotate_stmts(&mut block.stmts, state, fn_signature);
+    state.leave_scope(block_scope);
+}
+
+/// Make all functions declared in a list of statements callable from anywhere in that list,
+/// such that local functions can call functions declared after them, and such that they can
+/// be mutually recursive.
+fn declare_local_functions(
+    stmts: &[ast::Stmt<Typing>],
+    ftable: &mut HashMap<String, Vec<ast::FnSignature>>,
+    monomorphizations: &mut Monomorphizations,
+) {
+    let local_functions = stmts.iter().filter_map(|stmt| match stmt {
+        ast::Stmt::FnDeclaration(function) => Some(function),
+        _ => None,
+    });
+    let (generic_functions, functions): (Vec<_>, Vec<_>) =
+        local_functions.partition(|function| function.is_instantiated_per_call());
+    for function in functions {
+        ftable.insert(
+            function.signature.name.clone(),
+            vec![function.signature.clone()],
+        );
+    }
+    for function in generic_functions {
+        monomorphizations.generic_functions.insert(
+            function.signature.name.clone(),
+            GenericFunction {
+                function: function.clone(),
+                ftable: ftable.clone(),
+            },
+        );
+    }
+}
+
+/// Return the types of the `static` items declared in a list of statements
+fn declared_statics(stmts: &[ast::Stmt<Typing>]) -> HashMap<String, ast_types::DataType> {
+    stmts
+        .iter()
+        .filter_map(|stmt| match stmt {
+            ast::Stmt::Static(static_stmt) => {
+                Some((static_stmt.name.clone(), static_stmt.data_type.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn annotate_loop_body(
+    block: &mut ast::BlockStmt<Typing>,
+    label: &Option<String>,
+    fn_signature: &ast::FnSignature,
+    state: &mut CheckState,
+) {
+    state.loop_labels.push(label.to_owned());
+    annotate_block_stmt(block, fn_signature, state);
+    state.loop_labels.pop();
+}
+
+/// Verify that a `break` or `continue` statement refers to an enclosing loop
+fn check_loop_label_in_scope(
+    keyword: &str,
+    label: &Option<String>,
+    state: &CheckState,
+) -> Result<(), CompileError> {
+    if state.loop_labels.is_empty() {
+        return Err(CompileError::new(
+            ErrorCode::InvalidLoopExit,
+            format!("`{keyword}` can only be used inside of a loop"),
+        ));
+    }
+    if let Some(label) = label {
+        if !state.loop_labels.contains(&Some(label.to_owned())) {
+            return Err(CompileError::new(
+                ErrorCode::InvalidLoopExit,
+                format!("use of undeclared label `'{label}` in `{keyword}`"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lower a `for` loop to a block statement containing a `while` loop, and annotate it. The
+/// state of the iteration is kept in hidden bindings, and the loop variable is bound at the
+/// beginning of each iteration, after which the iteration state is advanced, and then the loop
+/// body is executed.
+fn lower_for_stmt(
+    for_stmt: &ast::ForStmt<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Result<ast::Stmt<Typing>, CompileError> {
+    use ast::BinOp::*;
+
+    let ast::ForStmt {
+        loop_variable,
+        iterable,
+        block,
+        label,
+    } = for_stmt.to_owned();
+
+    let prefix = state.hidden_binding_name("for_loop");
+    let name = |suffix: &str| format!("{prefix}_{suffix}");
+    let var =
+        |suffix: &str| ast::Expr::Var(ast::Identifier::String(name(suffix), Typing::default()));
+    let declare = |suffix: &str, data_type: &ast_types::DataType, mutable, expr| {
+        ast::Stmt::Let(ast::LetStmt {
+            var_name: name(suffix),
+            mutable,
+            data_type: Some(data_type.to_owned()),
+            expr,
+        })
+    };
+    let assign = |suffix: &str, expr| {
+        ast::Stmt::Assign(ast::AssignStmt {
+            identifier: ast::Identifier::String(name(suffix), Typing::default()),
+            expr,
+        })
+    };
+    let binop =
+        |lhs, binop, rhs| ast::Expr::Binop(Box::new(lhs), binop, Box::new(rhs), Typing::default());
+    let not = |expr| ast::Expr::Unary(ast::UnaryOp::Not, Box::new(expr), Typing::default());
+    let one = || ast::Expr::Lit(ast::ExprLit::GenericNum(1, Typing::default()));
+    let bind_loop_variable = |data_type: &ast_types::DataType, expr: ast::Expr<Typing>| {
+        loop_variable
+            .iter()
+            .map(|binding| {
+                ast::Stmt::Let(ast::LetStmt {
+                    var_name: binding.name.to_owned(),
+                    mutable: binding.mutable,
+                    data_type: Some(data_type.to_owned()),
+                    expr: expr.clone(),
+                })
+            })
+            .collect_vec()
+    };
+    let while_loop = |condition, iteration_stmts: Vec<ast::Stmt<Typing>>| {
+        ast::Stmt::While(ast::WhileStmt {
+            condition,
+            block: ast::BlockStmt {
+                stmts: [iteration_stmts, vec![ast::Stmt::Block(block.clone())]].concat(),
+            },
+            label: label.clone(),
+        })
+    };
+
+    // The bounds of a range and the iterated collection are annotated where the loop is
+    // lowered, so the declarations of the hidden bindings holding them are not annotated again.
+    let (annotated_declarations, stmts) = match iterable {
+        ast::ForIterable::Range(ast::RangeExpr {
+            mut start,
+            mut end,
+            inclusive,
+            reversed,
+        }) => {
+            let index_type = annotate_range_bounds(&mut start, &mut end, state, env_fn_signature)?;
+            let bool_type = ast_types::DataType::Bool;
+            match (inclusive, reversed) {
+                // `for i in a..b`
+                (false, false) => (
+                    vec![
+                        declare("index", &index_type, true, start),
+                        declare("end", &index_type, false, end),
+                    ],
+                    vec![while_loop(
+                        binop(var("index"), Lt, var("end")),
+                        [
+                            bind_loop_variable(&index_type, var("index")),
+                            vec![assign("index", binop(var("index"), Add, one()))],
+                        ]
+                        .concat(),
+                    )],
+                ),
+
+                // `for i in a..=b`. The index is only incremented when the last iteration has
+                // not been reached, to avoid overflows when `b` is the maximum value of its type.
+                (true, false) => (
+                    vec![
+                        declare("index", &index_type, true, start),
+                        declare("end", &index_type, false, end),
+                    ],
+                    vec![
+                        declare(
+                            "done",
+                            &bool_type,
+                            true,
+                            binop(var("index"), Gt, var("end")),
+                        ),
+                        while_loop(
+                            not(var("done")),
+                            [
+                                bind_loop_variable(&index_type, var("index")),
+                                vec![
+                                    assign("done", binop(var("index"), Eq, var("end"))),
+                                    ast::Stmt::If(ast::IfStmt {
+                                        condition: not(var("done")),
+                                        then_branch: ast::BlockStmt {
+                                            stmts: vec![assign(
+                                                "index",
+                                                binop(var("index"), Add, one()),
+                                            )],
+                                        },
+                                        else_branch: ast::BlockStmt { stmts: vec![] },
+                                    }),
+                                ],
+                            ]
+                            .concat(),
+                        ),
+                    ],
+                ),
+
+                // `for i in (a..b).rev()`
+                (false, true) => (
+                    vec![
+                        declare("start", &index_type, false, start),
+                        declare("index", &index_type, true, end),
+                    ],
+                    vec![while_loop(
+                        binop(var("start"), Lt, var("index")),
+                        [
+                            vec![assign("index", binop(var("index"), Sub, one()))],
+                            bind_loop_variable(&index_type, var("index")),
+                        ]
+                        .concat(),
+                    )],
+                ),
+
+                // `for i in (a..=b).rev()`. The index is only decremented when the last
+                // iteration has not been reached, to avoid underflows when `a` is zero.
+                (true, true) => (
+                    vec![
+                        declare("start", &index_type, false, start),
+                        declare("index", &index_type, true, end),
+                    ],
+                    vec![
+                        declare(
+                            "done",
+                            &bool_type,
+                            true,
+                            binop(var("index"), Lt, var("start")),
+                        ),
+                        while_loop(
+                            not(var("done")),
+                            [
+                                bind_loop_variable(&index_type, var("index")),
+                                vec![
+                                    assign("done", binop(var("index"), Eq, var("start"))),
+                                    ast::Stmt::If(ast::IfStmt {
+                                        condition: not(var("done")),
+                                        then_branch: ast::BlockStmt {
+                                            stmts: vec![assign(
+                                                "index",
+                                                binop(var("index"), Sub, one()),
+                                            )],
+                                        },
+                                        else_branch: ast::BlockStmt { stmts: vec![] },
+                                    }),
+                                ],
+                            ]
+                            .concat(),
+                        ),
+                    ],
+                ),
+            }
+        }
+
+        // `for x in v`, `for x in v.iter()`
+        ast::ForIterable::Collection(mut collection) => {
+            let collection_type =
+                derive_annotate_expr_type(&mut collection, None, state, env_fn_signature)
+                    .map_err(type_error)?;
+            let (element_type, array_length) =
+                for_loop_element_type_and_array_length(&collection_type)?;
+            let length = match array_length {
+                Some(length) => ast::Expr::Lit(ast::ExprLit::U32(length as u32)),
+                None => ast::Expr::MethodCall(ast::MethodCall {
+                    method_name: "len".to_owned(),
+                    args: vec![var("collection")],
+                    annot: Typing::default(),
+                    associated_type: None,
+                }),
             };
+            let element = ast::Expr::Var(ast::Identifier::Index(
+                Box::new(ast::Identifier::String(
+                    name("collection"),
+                    Typing::default(),
+                )),
+                Box::new(ast::IndexExpr::Dynamic(var("index"))),
+                Typing::default(),
+            ));
+
+            let index_type = ast_types::DataType::U32;
+            (
+                vec![declare("collection", &collection_type, false, collection)],
+                vec![
+                    declare("length", &index_type, false, length),
+                    declare(
+                        "index",
+                        &index_type,
+                        true,
+                        ast::Expr::Lit(ast::ExprLit::U32(0)),
+                    ),
+                    while_loop(
+                        binop(var("index"), Lt, var("length")),
+                        [
+                            bind_loop_variable(&element_type, element),
+                            vec![assign("index", binop(var("index"), Add, one()))],
+                        ]
+                        .concat(),
+                    ),
+                ],
+            )
+        }
+    };
 
-            let mut variants_encountered: HashSet<String> = HashSet::default();
-            let arm_count = arms.len();
-            let mut contains_wildcard_arm = false;
-            for (i, arm) in arms.iter_mut().enumerate() {
-                match &arm.match_condition {
-                    ast::MatchCondition::CatchAll => {
-                        assert_eq!(
-                            i,
-                            arm_count - 1,
-                            "When using wildcard in match statement, wildcard must be used in last match arm. \
-                            Match expression was for type {}",
-                            enum_type.name
-                        );
-                        contains_wildcard_arm = true;
+    Ok(annotate_lowered_stmts(
+        annotated_declarations,
+        stmts,
+        state,
+        env_fn_signature,
+    ))
+}
 
-                        annotate_block_stmt(&mut arm.body, env_fn_signature, state);
-                    }
-                    ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
-                        type_name,
-                        variant_name,
-                        data_bindings,
-                    }) => {
-                        assert!(
-                            variants_encountered.insert(variant_name.clone()),
-                            "Repeated variant name {} in match statement on {} encounter",
-                            variant_name,
+/// Annotate the statements that a statement is lowered to, in a scope of their own, and return
+/// the block statement containing them. The leading declarations of hidden bindings hold
+/// expressions of the lowered statement that have already been annotated, so only their
+/// bindings are declared.
+fn annotate_lowered_stmts(
+    annotated_declarations: Vec<ast::Stmt<Typing>>,
+    mut stmts: Vec<ast::Stmt<Typing>>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> ast::Stmt<Typing> {
+    let scope = state.enter_scope();
+    declare_hidden_bindings(&annotated_declarations, state);
+    annotate_stmts(&mut stmts, state, env_fn_signature);
+    state.leave_scope(scope);
+
+    ast::Stmt::Block(ast::BlockStmt {
+        stmts: [annotated_declarations, stmts].concat(),
+    })
+}
+
+/// Declare the bindings of declarations of hidden bindings, which are declared with their type
+fn declare_hidden_bindings(declarations: &[ast::Stmt<Typing>], state: &mut CheckState) {
+    for declaration in declarations {
+        let ast::Stmt::Let(ast::LetStmt {
+            var_name,
+            data_type: Some(data_type),
+            mutable,
+            ..
+        }) = declaration
+        else {
+            unreachable!("hidden bindings are declared with their type");
+        };
+        state.declare_binding(var_name, DataTypeAndMutability::new(data_type, *mutable));
+    }
+}
+
+/// Return true if a match must be lowered by [`lower_match_stmt`] or [`lower_match_expr`] before
+/// it is type checked, which is the case for matches on values that are not enums and for
+/// matches with guards, nested patterns, or arms that bind the matched enum value as a whole.
+/// If the type of the matched value cannot be derived, the match is treated as a match on an
+/// enum, which reports the error.
+fn is_lowered_match<'a>(
+    match_expression_type: Option<&ast_types::DataType>,
+    arms: impl Iterator<
+        Item = (
+            &'a ast::MatchCondition<Typing>,
+            &'a Option<ast::Expr<Typing>>,
+        ),
+    >,
+) -> bool {
+    let arms = arms.collect_vec();
+    let has_guard_or_nested_pattern = arms.iter().any(|(match_condition, guard)| {
+        guard.is_some() || matches!(match_condition, ast::MatchCondition::Pattern(_))
+    });
+    if has_guard_or_nested_pattern {
+        return true;
+    }
+
+    let Some(match_expression_type) = match_expression_type else {
+        return false;
+    };
+    if !matches!(match_expression_type.unbox(), ast_types::DataType::Enum(_)) {
+        return true;
+    }
+
+    arms.iter()
+        .any(|(match_condition, _)| match match_condition {
+            ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+                type_name: None,
+                variant_name,
+                data_bindings,
+                named_data_bindings,
+            }) => {
+                data_bindings.is_empty()
+                    && named_data_bindings.is_empty()
+                    && !is_variant_name(match_expression_type, variant_name)
+            }
+            _ => false,
+        })
+}
+
+/// Annotate a match statement on an enum that is handled by the code generator, whose matched
+/// value has been annotated
+fn annotate_enum_match_stmt(
+    match_stmt: &mut ast::MatchStmt<Typing>,
+    match_expression_type: &ast_types::DataType,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Result<(), CompileError> {
+    let arms = &mut match_stmt.arms;
+    let (enum_type, is_boxed) = matched_enum_type(match_expression_type)?;
+
+    let mut variants_encountered: HashSet<String> = HashSet::default();
+    let arm_count = arms.len();
+    for (i, arm) in arms.iter_mut().enumerate() {
+        let arm_scope = state.enter_scope();
+        let arm_binding = bind_enum_match_arm(
+            &mut arm.match_condition,
+            i == arm_count - 1,
+            &enum_type,
+            is_boxed,
+            &mut variants_encountered,
+            state,
+        );
+        if arm_binding.is_ok() {
+            annotate_block_stmt(&mut arm.body, env_fn_signature, state);
+        }
+        state.leave_scope(arm_scope);
+        arm_binding?;
+    }
+
+    let contains_wildcard_arm = arms
+        .iter()
+        .any(|arm| matches!(arm.match_condition, ast::MatchCondition::CatchAll));
+    check_enum_match_exhaustive(&enum_type, &variants_encountered, contains_wildcard_arm)?;
+
+    Ok(())
+}
+
+/// Annotate a match expression on an enum that is handled by the code generator, whose matched
+/// value has been annotated, and return its type
+fn derive_annotate_enum_match_expr(
+    match_expr: &mut ast::MatchExpr<Typing>,
+    match_expression_type: &ast_types::DataType,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<ast_types::DataType> {
+    let arms = &mut match_expr.arms;
+    let (enum_type, is_boxed) = matched_enum_type(match_expression_type)?;
+
+    let mut variants_encountered: HashSet<String> = HashSet::default();
+    let mut arm_types = vec![];
+    let arm_count = arms.len();
+    for (i, arm) in arms.iter_mut().enumerate() {
+        let arm_scope = state.enter_scope();
+        let arm_type = bind_enum_match_arm(
+            &mut arm.match_condition,
+            i == arm_count - 1,
+            &enum_type,
+            is_boxed,
+            &mut variants_encountered,
+            state,
+        )
+        .map_err(anyhow::Error::from)
+        .and_then(|()| {
+            derive_annotate_returning_block_expr(&mut arm.body, hint, state, env_fn_signature)
+        });
+        state.leave_scope(arm_scope);
+        arm_types.push(arm_type?);
+    }
+
+    let contains_wildcard_arm = arms
+        .iter()
+        .any(|arm| matches!(arm.match_condition, ast::MatchCondition::CatchAll));
+    check_enum_match_exhaustive(&enum_type, &variants_encountered, contains_wildcard_arm)?;
+
+    let Some(first_arm_type) = arm_types.first().cloned() else {
+        bail!("`match` expressions must have at least one arm");
+    };
+    let mut arm_types = arms
+        .iter()
+        .zip_eq(arm_types)
+        .filter(|(arm, _)| !is_diverging_match_arm(&arm.body))
+        .map(|(_, arm_type)| arm_type);
+    let match_expr_type = arm_types.next().unwrap_or(first_arm_type);
+    for arm_type in arm_types {
+        check_type_equals(&arm_type, &match_expr_type, "`match` arms")?;
+    }
+
+    Ok(match_expr_type)
+}
+
+/// Return the enum type of the value matched against in a match statement or expression, and
+/// whether that value is boxed
+fn matched_enum_type(
+    match_expression_type: &ast_types::DataType,
+) -> Result<(Box<ast_types::EnumType>, bool), CompileError> {
+    match match_expression_type {
+        ast_types::DataType::Enum(enum_type) => Ok((enum_type.to_owned(), false)),
+        ast_types::DataType::Boxed(inner) if matches!(**inner, ast_types::DataType::Enum(_)) => {
+            let (enum_type, _) = matched_enum_type(inner)?;
+            Ok((enum_type, true))
+        }
+        _ => Err(CompileError::new(
+            ErrorCode::TypeError,
+            format!(
+                "`match` is only supported on enums and on the values that the arms match \
+                 with patterns, not on values of type `{match_expression_type}`"
+            ),
+        )),
+    }
+}
+
+/// Check the pattern of an arm of a match on an enum, and declare the bindings of the pattern
+/// in the scope of the arm
+fn bind_enum_match_arm(
+    match_condition: &mut ast::MatchCondition<Typing>,
+    is_last_arm: bool,
+    enum_type: &ast_types::EnumType,
+    is_boxed: bool,
+    variants_encountered: &mut HashSet<String>,
+    state: &mut CheckState,
+) -> Result<(), CompileError> {
+    if let ast::MatchCondition::EnumVariant(enum_variant_selector) = match_condition {
+        resolve_named_data_bindings(enum_variant_selector, enum_type)?;
+    }
+
+    match match_condition {
+        ast::MatchCondition::CatchAll => {
+            if !is_last_arm {
+                return Err(match_arm_error(
+                    "the wildcard pattern `_` must be used in the last arm of a `match`",
+                ));
+            }
+
+            Ok(())
+        }
+        ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+            type_name,
+            variant_name,
+            data_bindings,
+            named_data_bindings: _,
+        }) => {
+            match type_name {
+                Some(enum_type_name) if *enum_type_name != enum_type.name => {
+                    return Err(match_arm_error(format!(
+                        "mismatched types in `match` arm: expected `{}`, found `{enum_type_name}`",
+                        enum_type.name
+                    )));
+                }
+                None if !enum_type.is_prelude => {
+                    return Err(match_arm_error(format!(
+                        "the variant `{variant_name}` must be given as `{}::{variant_name}`",
+                        enum_type.name
+                    )));
+                }
+                _ => (),
+            };
+            if !enum_type.has_variant_of_name(variant_name) {
+                return Err(match_arm_error(format!(
+                    "no variant named `{variant_name}` in enum `{}`",
+                    enum_type.name
+                )));
+            }
+            if !variants_encountered.insert(variant_name.clone()) {
+                return Err(match_arm_error(format!(
+                    "the variant `{variant_name}` is matched by several arms"
+                )));
+            }
+
+            let variant_data_tuple = enum_type.variant_data_type(variant_name).as_tuple_type();
+            if !data_bindings.is_empty()
+                && variant_data_tuple.element_count() != data_bindings.len()
+            {
+                return Err(match_arm_error(format!(
+                    "the variant `{variant_name}` has {} fields, but the pattern has {}",
+                    variant_data_tuple.element_count(),
+                    data_bindings.len()
+                )));
+            }
+            if !data_bindings.iter().map(|x| &x.name).all_unique() {
+                return Err(match_arm_error(
+                    "identifiers are bound more than once in the same pattern",
+                ));
+            }
+
+            data_bindings.iter().enumerate().for_each(|(i, x)| {
+                let new_binding_type = if is_boxed {
+                    ast_types::DataType::Boxed(Box::new(variant_data_tuple.fields[i].to_owned()))
+                } else {
+                    variant_data_tuple.fields[i].to_owned()
+                };
+
+                state.declare_binding(
+                    &x.name,
+                    DataTypeAndMutability::new(&new_binding_type, x.mutable),
+                );
+            });
+
+            Ok(())
+        }
+        ast::MatchCondition::Pattern(_) => Err(match_arm_error(format!(
+            "only variants of `{}` and `_` can be matched against a value of that type",
+            enum_type.name
+        ))),
+    }
+}
+
+/// Verify that all variants of an enum are covered by the arms of a match, *or* that the
+/// match has a wildcard arm
+fn check_enum_match_exhaustive(
+    enum_type: &ast_types::EnumType,
+    variants_encountered: &HashSet<String>,
+    contains_wildcard_arm: bool,
+) -> Result<(), CompileError> {
+    if variants_encountered.len() == enum_type.variants.len() || contains_wildcard_arm {
+        return Ok(());
+    }
+
+    let missing_variants = enum_type
+        .variants
+        .iter()
+        .map(|(variant_name, _)| variant_name)
+        .filter(|variant_name| !variants_encountered.contains(*variant_name))
+        .map(|variant_name| format!("`{}::{variant_name}`", enum_type.name))
+        .join(", ");
+    Err(match_arm_error(format!(
+        "non-exhaustive patterns: {missing_variants} not covered"
+    )))
+}
+
+/// Return true if the body of an arm of a match expression panics before its value is
+/// returned, like `_ => { panic!(); }`. Such an arm takes the type of the other arms.
+fn is_diverging_match_arm(body: &ast::ReturningBlock<Typing>) -> bool {
+    matches!(body.stmts.last(), Some(ast::Stmt::Panic(_)))
+        && matches!(&body.return_expr, ast::Expr::Tuple(elements) if elements.is_empty())
+}
+
+fn match_arm_error(message: impl Into<String>) -> CompileError {
+    CompileError::new(ErrorCode::TypeError, message)
+}
+
+/// Lower a match statement that is not handled by the code generator, see
+/// [`is_lowered_match`], to a block statement. The matched value is kept in a hidden binding,
+/// and the arm that is taken is decided by `if` statements and by matches on enum variants
+/// without guards or nested patterns. The body of each arm is then lowered once, see
+/// [`MatchLowering::dispatch`]. The lowered match is annotated, apart from the matched value,
+/// which has been annotated already.
+fn lower_match_stmt(
+    match_stmt: &ast::MatchStmt<Typing>,
+    subject_type: ast_types::DataType,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Result<ast::Stmt<Typing>, CompileError> {
+    let ast::MatchStmt {
+        match_expression,
+        arms,
+    } = match_stmt.to_owned();
+    let (match_conditions, bodies): (Vec<_>, Vec<_>) = arms
+        .into_iter()
+        .map(|arm| ((arm.match_condition, arm.guard), arm.body))
+        .unzip();
+    let (subject_declaration, mut lowering) =
+        MatchLowering::new(match_expression, subject_type, match_conditions, state)?;
+    let arm_declaration = lowering.arm_declaration()?;
+    let dispatch = lowering.dispatch(bodies)?;
+
+    Ok(annotate_lowered_stmts(
+        vec![subject_declaration],
+        vec![arm_declaration, ast::Stmt::Block(dispatch)],
+        state,
+        env_fn_signature,
+    ))
+}
+
+/// Lower a match expression that is not handled by the code generator to a returning block,
+/// like [`lower_match_stmt`] does for match statements, and return it along with its type.
+fn lower_match_expr(
+    match_expr: &ast::MatchExpr<Typing>,
+    subject_type: ast_types::DataType,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<(ast::Expr<Typing>, ast_types::DataType)> {
+    let ast::MatchExpr {
+        match_expression,
+        arms,
+    } = match_expr.to_owned();
+    let (match_conditions, bodies): (Vec<_>, Vec<_>) = arms
+        .into_iter()
+        .map(|arm| ((arm.match_condition, arm.guard), arm.body))
+        .unzip();
+    let (subject_declaration, mut lowering) =
+        MatchLowering::new(*match_expression, subject_type, match_conditions, state)?;
+    let mut lowered_match = ast::ReturningBlock {
+        stmts: vec![lowering.arm_declaration()?],
+        return_expr: ast::Expr::ReturningBlock(Box::new(lowering.dispatch(bodies)?)),
+    };
+
+    let scope = state.enter_scope();
+    declare_hidden_bindings(std::slice::from_ref(&subject_declaration), state);
+    let match_expr_type =
+        derive_annotate_returning_block_expr(&mut lowered_match, hint, state, env_fn_signature);
+    state.leave_scope(scope);
+
+    let lowered_match_expr = ast::Expr::ReturningBlock(Box::new(ast::ReturningBlock {
+        stmts: vec![subject_declaration],
+        return_expr: ast::Expr::ReturningBlock(Box::new(lowered_match)),
+    }));
+    Ok((lowered_match_expr, match_expr_type?))
+}
+
+/// If the value matched against is the result of `pop` on a vector, like in
+/// `while let Some(x) = v.pop()`, return the type of the vector, which is annotated. Such
+/// matches are lowered by [`lower_popped_vector_match_stmt`] and
+/// [`lower_popped_vector_match_expr`], as `pop` on a vector returns its last element.
+fn popped_vector_type(
+    match_expression: &mut ast::Expr<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Option<ast_types::DataType> {
+    let ast::Expr::MethodCall(ast::MethodCall {
+        method_name, args, ..
+    }) = match_expression
+    else {
+        return None;
+    };
+    if method_name != "pop" || args.len() != 1 {
+        return None;
+    }
+
+    let receiver_type = derive_annotate_expr_type(&mut args[0], None, state, env_fn_signature);
+    receiver_type
+        .ok()
+        .filter(|receiver_type| matches!(receiver_type, ast_types::DataType::List(_, _)))
+}
+
+/// Return the call of `pop` on a vector that is unwrapped, like `v.pop().unwrap()`, as `pop`
+/// on a vector returns its last element, and fails if the vector is empty
+fn unwrapped_vector_pop(
+    expr: &mut ast::Expr<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Option<ast::Expr<Typing>> {
+    let ast::Expr::MethodCall(ast::MethodCall {
+        method_name, args, ..
+    }) = expr
+    else {
+        return None;
+    };
+    let [ast::Expr::MethodCall(pop_call)] = args.as_mut_slice() else {
+        return None;
+    };
+    if method_name != "unwrap" || pop_call.method_name != "pop" || pop_call.args.len() != 1 {
+        return None;
+    }
+
+    let receiver_type =
+        derive_annotate_expr_type(&mut pop_call.args[0], None, state, env_fn_signature);
+    matches!(receiver_type, Ok(ast_types::DataType::List(_, _)))
+        .then(|| ast::Expr::MethodCall(pop_call.to_owned()))
+}
+
+/// Lower a match statement on the result of `pop` on a vector, see [`popped_vector_type`], to
+/// a block statement, and annotate it. The vector is kept in a hidden binding, and the arms
+/// that match `Some` are checked against the popped element if the vector is not empty, and
+/// the arms that match `None` are taken otherwise.
+fn lower_popped_vector_match_stmt(
+    match_stmt: &ast::MatchStmt<Typing>,
+    vector_type: ast_types::DataType,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Result<ast::Stmt<Typing>, CompileError> {
+    let ast::MatchStmt {
+        match_expression,
+        arms,
+    } = match_stmt.to_owned();
+    let arms = arms
+        .into_iter()
+        .map(|arm| (arm.match_condition, arm.guard, arm.body))
+        .collect_vec();
+    let (vector_declaration, lowered_match) =
+        lower_popped_vector_match(match_expression, vector_type, arms, state)?;
+
+    Ok(annotate_lowered_stmts(
+        vec![vector_declaration],
+        lowered_match.stmts,
+        state,
+        env_fn_signature,
+    ))
+}
+
+/// Lower a match expression on the result of `pop` on a vector to a returning block, like
+/// [`lower_popped_vector_match_stmt`] does for match statements, and return it along with its
+/// type.
+fn lower_popped_vector_match_expr(
+    match_expr: &ast::MatchExpr<Typing>,
+    vector_type: ast_types::DataType,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<(ast::Expr<Typing>, ast_types::DataType)> {
+    let ast::MatchExpr {
+        match_expression,
+        arms,
+    } = match_expr.to_owned();
+    let arms = arms
+        .into_iter()
+        .map(|arm| (arm.match_condition, arm.guard, arm.body))
+        .collect_vec();
+    let (vector_declaration, mut lowered_match) =
+        lower_popped_vector_match(*match_expression, vector_type, arms, state)?;
+
+    let scope = state.enter_scope();
+    declare_hidden_bindings(std::slice::from_ref(&vector_declaration), state);
+    let match_expr_type =
+        derive_annotate_returning_block_expr(&mut lowered_match, hint, state, env_fn_signature);
+    state.leave_scope(scope);
+
+    let lowered_match_expr = ast::Expr::ReturningBlock(Box::new(ast::ReturningBlock {
+        stmts: vec![vector_declaration],
+        return_expr: ast::Expr::ReturningBlock(Box::new(lowered_match)),
+    }));
+    Ok((lowered_match_expr, match_expr_type?))
+}
+
+/// Return the declaration of the hidden binding holding the vector that is popped, along with
+/// the lowering of the match on the result of `pop`. An arm whose pattern matches both `Some`
+/// and `None`, like `_`, appears in both branches of the lowering.
+fn lower_popped_vector_match<B: LoweredArmBody>(
+    match_expression: ast::Expr<Typing>,
+    vector_type: ast_types::DataType,
+    arms: Vec<(ast::MatchCondition<Typing>, Option<ast::Expr<Typing>>, B)>,
+    state: &mut CheckState,
+) -> Result<(ast::Stmt<Typing>, B), CompileError> {
+    let ast::Expr::MethodCall(ast::MethodCall { mut args, .. }) = match_expression else {
+        unreachable!("the value matched against is a call of `pop`, see `popped_vector_type`");
+    };
+    let ast_types::DataType::List(element_type, _) = &vector_type else {
+        unreachable!("`pop` is called on a vector, see `popped_vector_type`");
+    };
+
+    let vector_name = state.hidden_binding_name("pop");
+    let vector = ast::Expr::Var(ast::Identifier::String(
+        vector_name.clone(),
+        Typing::default(),
+    ));
+    let element_name = format!("{vector_name}_element");
+    let element_declaration = ast::Stmt::Let(ast::LetStmt {
+        var_name: element_name.clone(),
+        mutable: false,
+        data_type: Some(*element_type.to_owned()),
+        expr: ast::Expr::MethodCall(ast::MethodCall {
+            method_name: "pop".to_owned(),
+            args: vec![vector.clone()],
+            annot: Typing::default(),
+            associated_type: None,
+        }),
+    });
+
+    let mut some_arms = vec![];
+    let mut none_arms = vec![];
+    for (match_condition, guard, body) in arms {
+        let (some_pattern, matches_none) =
+            split_option_pattern(match_condition_pattern(match_condition))?;
+        if let Some(some_pattern) = some_pattern {
+            some_arms.push((
+                ast::MatchCondition::Pattern(Box::new(some_pattern)),
+                guard.clone(),
+                body.clone(),
+            ));
+        }
+        if matches_none {
+            none_arms.push((guard, body));
+        }
+    }
+
+    let Some(none_arm) = none_arms.iter().position(|(guard, _)| guard.is_none()) else {
+        return Err(match_arm_error(
+            "`match` on the result of `pop` must have an arm that matches `None`",
+        ));
+    };
+    let none_branch = none_arms[..=none_arm].iter().rev().skip(1).fold(
+        none_arms[none_arm].1.clone(),
+        |else_branch, (guard, body)| {
+            let guard = guard.clone().expect("only the last arm has no guard");
+            B::branch(guard, body.clone(), else_branch)
+        },
+    );
+    let some_branch = B::matching(
+        ast::Expr::Var(ast::Identifier::String(element_name, Typing::default())),
+        some_arms,
+    )
+    .with_bindings(vec![element_declaration]);
+
+    let vector_length = ast::Expr::MethodCall(ast::MethodCall {
+        method_name: "len".to_owned(),
+        args: vec![vector],
+        annot: Typing::default(),
+        associated_type: None,
+    });
+    let is_not_empty = ast::Expr::Binop(
+        Box::new(vector_length),
+        ast::BinOp::Neq,
+        Box::new(ast::Expr::Lit(ast::ExprLit::U32(0))),
+        Typing::default(),
+    );
+    let vector_declaration = ast::Stmt::Let(ast::LetStmt {
+        var_name: vector_name,
+        mutable: false,
+        data_type: Some(vector_type),
+        expr: args.remove(0),
+    });
+
+    Ok((
+        vector_declaration,
+        B::branch(is_not_empty, some_branch, none_branch),
+    ))
+}
+
+/// Split a pattern on an `Option` into the pattern on the value of `Some` that it matches, if
+/// any, and whether it matches `None`
+fn split_option_pattern(
+    pattern: ast::Pattern<Typing>,
+) -> Result<(Option<ast::Pattern<Typing>>, bool), CompileError> {
+    let is_option =
+        |type_name: &Option<String>| type_name.as_deref().unwrap_or("Option") == "Option";
+    match pattern {
+        ast::Pattern::Wildcard => Ok((Some(ast::Pattern::Wildcard), true)),
+        ast::Pattern::Binding(ast::PatternMatchedBinding {
+            name,
+            mutable: false,
+        }) if name == "None" => Ok((None, true)),
+        ast::Pattern::EnumVariant(ast::EnumVariantPattern {
+            type_name,
+            variant_name,
+            fields,
+            named_fields,
+        }) if is_option(&type_name) && named_fields.is_empty() => {
+            match (variant_name.as_str(), <[_; 1]>::try_from(fields)) {
+                ("Some", Ok([field])) => Ok((Some(field), false)),
+                ("None", Err(fields)) if fields.is_empty() => Ok((None, true)),
+                _ => Err(match_arm_error(format!(
+                    "cannot match the result of `pop` against variant `{variant_name}`"
+                ))),
+            }
+        }
+        ast::Pattern::Or(alternatives) => {
+            let mut some_patterns = vec![];
+            let mut matches_none = false;
+            for alternative in alternatives {
+                let (some_pattern, alternative_matches_none) = split_option_pattern(alternative)?;
+                some_patterns.extend(some_pattern);
+                matches_none |= alternative_matches_none;
+            }
+            let some_pattern = match some_patterns.len() {
+                0 => None,
+                1 => some_patterns.pop(),
+                _ => Some(ast::Pattern::Or(some_patterns)),
+            };
+            Ok((some_pattern, matches_none))
+        }
+        _ => Err(match_arm_error(
+            "the result of `pop` can only be matched against `Some` and `None` patterns",
+        )),
+    }
+}
+
+/// A pattern that remains to be checked against the matched value or a part of it
+#[derive(Debug, Clone)]
+struct PatternTest {
+    subject: ast::Identifier<Typing>,
+    subject_type: ast_types::DataType,
+    pattern: ast::Pattern<Typing>,
+}
+
+/// An arm of a match that is being lowered. Its tests are patterns that are not irrefutable,
+/// as bindings are moved to `bindings`, and tuple patterns are split into their elements.
+#[derive(Debug, Clone)]
+struct MatchRow {
+    tests: Vec<PatternTest>,
+    bindings: Vec<ast::Stmt<Typing>>,
+    guard: Option<ast::Expr<Typing>>,
+    arm: usize,
+}
+
+/// How a lowered match decides which arm is taken
+#[derive(Debug, Clone)]
+enum MatchDecision {
+    /// Take an arm, or, if its guard is not satisfied, continue with another decision
+    Arm {
+        arm: usize,
+        bindings: Vec<ast::Stmt<Typing>>,
+        guard: Option<(ast::Expr<Typing>, Box<MatchDecision>)>,
+    },
+    If {
+        condition: ast::Expr<Typing>,
+        then_branch: Box<MatchDecision>,
+        else_branch: Box<MatchDecision>,
+    },
+
+    /// Match on the variants of an enum, binding the data of each variant
+    Switch {
+        subject: ast::Identifier<Typing>,
+        arms: Vec<(ast::MatchCondition<Typing>, MatchDecision)>,
+    },
+
+    /// No arm matches, which the exhaustiveness of matches in Rust rules out
+    Unreachable,
+}
+
+/// Lowers the arms of a match to a [`MatchDecision`] by checking the patterns of the first
+/// arm that may match, one at a time. Enum variants are checked by matches on all variants
+/// that appear in the remaining arms, such that the data of a variant can be matched against
+/// nested patterns.
+struct MatchLowering {
+    subject: ast::Identifier<Typing>,
+    rows: Vec<MatchRow>,
+
+    /// The number of hidden bindings that the data of enum variants is bound to
+    hidden_binding_count: usize,
+}
+
+impl MatchLowering {
+    /// Return the declaration of the hidden binding holding the matched value, which has been
+    /// annotated, along with the lowering of the match.
+    fn new(
+        match_expression: ast::Expr<Typing>,
+        subject_type: ast_types::DataType,
+        arms: Vec<(ast::MatchCondition<Typing>, Option<ast::Expr<Typing>>)>,
+        state: &mut CheckState,
+    ) -> Result<(ast::Stmt<Typing>, Self), CompileError> {
+        if arms.is_empty() {
+            return Err(match_arm_error("`match` must have at least one arm"));
+        }
+
+        let subject_name = state.hidden_binding_name("match");
+        let subject_declaration = ast::Stmt::Let(ast::LetStmt {
+            var_name: subject_name.clone(),
+            mutable: false,
+            data_type: Some(subject_type.clone()),
+            expr: match_expression,
+        });
+        let subject = ast::Identifier::String(subject_name, Typing::default());
+
+        let mut rows = vec![];
+        for (arm, (match_condition, guard)) in arms.into_iter().enumerate() {
+            let test = PatternTest {
+                subject: subject.clone(),
+                subject_type: subject_type.clone(),
+                pattern: match_condition_pattern(match_condition),
+            };
+            let row = MatchRow {
+                tests: vec![test],
+                bindings: vec![],
+                guard,
+                arm,
+            };
+            rows.extend(expand_match_row(row)?);
+        }
+        let lowering = Self {
+            subject,
+            rows,
+            hidden_binding_count: 0,
+        };
+
+        Ok((subject_declaration, lowering))
+    }
+
+    /// Return the declaration of the hidden binding holding the index of the arm that is
+    /// taken. Guards are checked here, with the bindings of their arm's pattern in scope.
+    fn arm_declaration(&mut self) -> Result<ast::Stmt<Typing>, CompileError> {
+        let decision = self.decide(self.rows.clone(), false)?;
+        let arm_index = decision.lower(&mut |arm, _| ast::ReturningBlock {
+            stmts: vec![],
+            return_expr: arm_index_literal(arm),
+        });
+
+        Ok(ast::Stmt::Let(ast::LetStmt {
+            var_name: self.arm_name(),
+            mutable: false,
+            data_type: Some(ast_types::DataType::U32),
+            expr: ast::Expr::ReturningBlock(Box::new(arm_index)),
+        }))
+    }
+
+    /// Lower the bodies of the arms to a chain of `if`s on the index of the arm that is taken,
+    /// such that each body appears once in the lowered match. The bindings of an arm's
+    /// pattern are declared before its body, from the matched value.
+    fn dispatch<B: LoweredArmBody>(&mut self, bodies: Vec<B>) -> Result<B, CompileError> {
+        let mut arm_bodies = vec![];
+        for (arm, body) in bodies.into_iter().enumerate() {
+            arm_bodies.push(self.bind_arm(arm, body)?);
+        }
+
+        // The last arm is taken if no other arm is
+        let last_arm_body = arm_bodies
+            .pop()
+            .expect("lowered matches have at least one arm");
+        let arm = ast::Expr::Var(ast::Identifier::String(self.arm_name(), Typing::default()));
+        let dispatch = arm_bodies.into_iter().enumerate().rev().fold(
+            last_arm_body,
+            |else_branch, (i, arm_body)| {
+                let condition = ast::Expr::Binop(
+                    Box::new(arm.clone()),
+                    ast::BinOp::Eq,
+                    Box::new(arm_index_literal(i)),
+                    Typing::default(),
+                );
+                B::branch(condition, arm_body, else_branch)
+            },
+        );
+
+        Ok(dispatch)
+    }
+
+    /// Declare the bindings of an arm's pattern before its body. As the arm is known to match
+    /// the value, the data of enum variants is bound by matches on the variants of the arm's
+    /// pattern only. If the pattern has alternatives that bind from different places, the
+    /// bindings are first collected in a hidden binding, such that the body appears once.
+    fn bind_arm<B: LoweredArmBody>(&mut self, arm: usize, body: B) -> Result<B, CompileError> {
+        let rows = self
+            .rows
+            .iter()
+            .filter(|row| row.arm == arm)
+            .map(|row| MatchRow {
+                guard: None,
+                ..row.to_owned()
+            })
+            .collect_vec();
+        let decision = self.decide(rows, true)?;
+
+        let mut leaf_bindings = vec![];
+        decision.for_each_arm(&mut |bindings| leaf_bindings.push(bindings.to_owned()));
+        if leaf_bindings.iter().all(|bindings| bindings.is_empty()) {
+            return Ok(body);
+        }
+        if leaf_bindings.len() == 1 {
+            return Ok(decision.lower(&mut |_, bindings| body.clone().with_bindings(bindings)));
+        }
+
+        let declared_bindings = |bindings: &[ast::Stmt<Typing>]| {
+            bindings
+                .iter()
+                .filter_map(|binding| match binding {
+                    ast::Stmt::Let(let_stmt) => Some(let_stmt.to_owned()),
+                    _ => None,
+                })
+                .sorted_by(|a, b| a.var_name.cmp(&b.var_name))
+                .collect_vec()
+        };
+        let bindings = declared_bindings(&leaf_bindings[0]);
+        let names = bindings.iter().map(|x| &x.var_name).collect_vec();
+        for other_bindings in leaf_bindings.iter().skip(1) {
+            let other_bindings = declared_bindings(other_bindings);
+            let other_names = other_bindings.iter().map(|x| &x.var_name).collect_vec();
+            if let Some(name) = names
+                .iter()
+                .chain(other_names.iter())
+                .find(|name| !names.contains(name) || !other_names.contains(name))
+            {
+                return Err(match_arm_error(format!(
+                    "variable `{name}` is not bound in all patterns"
+                )));
+            }
+        }
+
+        // The values of the bindings are a tuple, unless there is only one of them
+        let values_name = format!("{}_{arm}", self.arm_name());
+        let values = ast::Identifier::String(values_name.clone(), Typing::default());
+        let binding_vars = bindings
+            .iter()
+            .map(|binding| {
+                ast::Expr::Var(ast::Identifier::String(
+                    binding.var_name.to_owned(),
+                    Typing::default(),
+                ))
+            })
+            .collect_vec();
+        let binding_types = bindings
+            .iter()
+            .map(|binding| binding.data_type.to_owned().unwrap())
+            .collect_vec();
+        let (values_expr, values_type) = match bindings.len() {
+            1 => (binding_vars[0].to_owned(), binding_types[0].to_owned()),
+            _ => (
+                ast::Expr::Tuple(binding_vars),
+                ast_types::DataType::Tuple(binding_types.into()),
+            ),
+        };
+        let values_block = decision.lower(&mut |_, bindings| ast::ReturningBlock {
+            stmts: bindings,
+            return_expr: values_expr.clone(),
+        });
+
+        let mut declarations = vec![ast::Stmt::Let(ast::LetStmt {
+            var_name: values_name,
+            mutable: false,
+            data_type: Some(values_type),
+            expr: ast::Expr::ReturningBlock(Box::new(values_block)),
+        })];
+        let is_single_value = bindings.len() == 1;
+        for (i, binding) in bindings.into_iter().enumerate() {
+            let value = match is_single_value {
+                true => values.clone(),
+                false => ast::Identifier::Field(
+                    Box::new(values.clone()),
+                    ast_types::FieldId::UnnamedField(i),
+                    Typing::default(),
+                ),
+            };
+            declarations.push(ast::Stmt::Let(ast::LetStmt {
+                expr: ast::Expr::Var(value),
+                ..binding
+            }));
+        }
+
+        Ok(body.with_bindings(declarations))
+    }
+
+    fn decide(
+        &mut self,
+        rows: Vec<MatchRow>,
+        known_to_match: bool,
+    ) -> Result<MatchDecision, CompileError> {
+        let Some(first_row) = rows.first() else {
+            return Ok(MatchDecision::Unreachable);
+        };
+
+        let Some(first_test) = first_row.tests.first() else {
+            let guard = match first_row.guard.clone() {
+                Some(guard) => match self.decide(rows[1..].to_vec(), known_to_match)? {
+                    // The guard of the last arm that can match is always satisfied
+                    MatchDecision::Unreachable => None,
+                    otherwise => Some((guard, Box::new(otherwise))),
+                },
+                None => None,
+            };
+            return Ok(MatchDecision::Arm {
+                arm: first_row.arm,
+                bindings: first_row.bindings.clone(),
+                guard,
+            });
+        };
+
+        if matches!(first_test.pattern, ast::Pattern::EnumVariant(_)) {
+            let subject = first_test.subject.clone();
+            let subject_type = first_test.subject_type.clone();
+            return self.switch(subject, &subject_type, rows, known_to_match);
+        }
+
+        // Check the patterns on values that are not enums, up to the first enum variant, in
+        // one condition
+        let condition_count = first_row
+            .tests
+            .iter()
+            .take_while(|test| !matches!(test.pattern, ast::Pattern::EnumVariant(_)))
+            .count();
+        let mut conditions = vec![];
+        for test in first_row.tests[..condition_count].iter() {
+            let (condition, _) = lower_pattern(&test.pattern, &test.subject, &test.subject_type)?;
+            conditions.extend(condition);
+        }
+
+        let mut then_rows = rows.clone();
+        then_rows[0].tests.drain(..condition_count);
+        let then_branch = self.decide(then_rows, known_to_match)?;
+        let Some(condition) = conditions.into_iter().reduce(|acc, condition| {
+            ast::Expr::Binop(
+                Box::new(acc),
+                ast::BinOp::And,
+                Box::new(condition),
+                Typing::default(),
+            )
+        }) else {
+            return Ok(then_branch);
+        };
+
+        let else_branch = match self.decide(rows[1..].to_vec(), known_to_match)? {
+            // The patterns of the last arm that can match always match
+            MatchDecision::Unreachable => return Ok(then_branch),
+            else_branch => else_branch,
+        };
+
+        Ok(MatchDecision::If {
+            condition,
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+        })
+    }
+
+    /// Match on the variants of the enum `subject` that appear in the rows. The data of each
+    /// variant is bound to hidden bindings, which are matched against the nested patterns of
+    /// the rows on that variant. Rows that do not check `subject` are kept for all variants.
+    /// If the rows are known to match, the variants that do not appear in them are unreachable.
+    fn switch(
+        &mut self,
+        subject: ast::Identifier<Typing>,
+        subject_type: &ast_types::DataType,
+        rows: Vec<MatchRow>,
+        known_to_match: bool,
+    ) -> Result<MatchDecision, CompileError> {
+        if !matches!(subject_type.unbox(), ast_types::DataType::Enum(_)) {
+            return Err(CompileError::new(
+                ErrorCode::TypeError,
+                format!("cannot match an enum variant against a value of type `{subject_type}`"),
+            ));
+        }
+        let (enum_type, is_boxed) = matched_enum_type(subject_type)?;
+        let test_of_subject = |row: &MatchRow| row.tests.iter().position(|x| x.subject == subject);
+
+        let mut arms = vec![];
+        for (variant_name, _) in enum_type.variants.iter() {
+            let variant_is_checked = rows.iter().any(|row| {
+                let Some(i) = test_of_subject(row) else {
+                    return false;
+                };
+                matches!(
+                    &row.tests[i].pattern,
+                    ast::Pattern::EnumVariant(pattern) if &pattern.variant_name == variant_name
+                )
+            });
+            if !variant_is_checked {
+                continue;
+            }
+
+            let field_types = enum_type
+                .variant_data_type(variant_name)
+                .as_tuple_type()
+                .fields
+                .into_iter()
+                .map(|field_type| match is_boxed {
+                    true => ast_types::DataType::Boxed(Box::new(field_type)),
+                    false => field_type,
+                })
+                .collect_vec();
+            let field_subjects = field_types
+                .iter()
+                .map(|_| self.hidden_binding())
+                .collect_vec();
+
+            let mut variant_rows = vec![];
+            for row in rows.iter() {
+                let Some(i) = test_of_subject(row) else {
+                    variant_rows.push(row.to_owned());
+                    continue;
+                };
+                let ast::Pattern::EnumVariant(pattern) = &row.tests[i].pattern else {
+                    return Err(CompileError::new(
+                        ErrorCode::TypeError,
+                        format!(
+                            "only variants of `{}` and `_` can be matched against a value \
+                             of that type",
                             enum_type.name
-                        );
+                        ),
+                    ));
+                };
+                if &pattern.variant_name != variant_name {
+                    continue;
+                }
 
-                        match type_name {
-                            Some(enum_type_name) => {
-                                assert_eq!(
-                                    &enum_type.name,
-                                    enum_type_name,
-                                    "Match conditions on type {} must all be of same type. Got bad type: {enum_type_name}", enum_type.name);
-                            }
-                            None => {
-                                assert!(enum_type.is_prelude, "Only enums specified in prelude may use only the variant name in a match arm");
-                            }
-                        };
+                let field_patterns =
+                    enum_variant_field_patterns(pattern, &enum_type, field_types.len())?;
+                let mut tests = field_subjects
+                    .iter()
+                    .zip_eq(field_types.iter())
+                    .zip_eq(field_patterns)
+                    .map(|((subject, subject_type), pattern)| PatternTest {
+                        subject: subject.to_owned(),
+                        subject_type: subject_type.to_owned(),
+                        pattern,
+                    })
+                    .collect_vec();
+                tests.extend(
+                    row.tests
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != i)
+                        .map(|(_, test)| test.to_owned()),
+                );
+                variant_rows.extend(expand_match_row(MatchRow {
+                    tests,
+                    ..row.to_owned()
+                })?);
+            }
+
+            let data_bindings = field_subjects
+                .iter()
+                .map(|field_subject| ast::PatternMatchedBinding {
+                    name: field_subject.to_string(),
+                    mutable: false,
+                })
+                .collect();
+            let enum_variant_selector = ast::EnumVariantSelector {
+                type_name: Some(enum_type.name.clone()),
+                variant_name: variant_name.to_owned(),
+                data_bindings,
+                named_data_bindings: vec![],
+            };
+            arms.push((
+                ast::MatchCondition::EnumVariant(enum_variant_selector),
+                self.decide(variant_rows, known_to_match)?,
+            ));
+        }
+
+        let other_rows = rows
+            .into_iter()
+            .filter(|row| test_of_subject(row).is_none())
+            .collect_vec();
+        if arms.len() < enum_type.variants.len() && (known_to_match || !other_rows.is_empty()) {
+            let other_variants = self.decide(other_rows, known_to_match)?;
+            arms.push((ast::MatchCondition::CatchAll, other_variants));
+        }
+
+        Ok(MatchDecision::Switch { subject, arms })
+    }
+
+    fn hidden_binding(&mut self) -> ast::Identifier<Typing> {
+        let name = format!("{}_{}", self.subject, self.hidden_binding_count);
+        self.hidden_binding_count += 1;
+        ast::Identifier::String(name, Typing::default())
+    }
+
+    /// The name of the hidden binding holding the index of the arm that is taken
+    fn arm_name(&self) -> String {
+        format!("{}_arm", self.subject)
+    }
+}
+
+impl MatchDecision {
+    /// Lower the decision, where `arm_body` returns the body of an arm that is taken, given
+    /// the bindings of the arm's pattern.
+    fn lower<B: LoweredArmBody>(
+        self,
+        arm_body: &mut impl FnMut(usize, Vec<ast::Stmt<Typing>>) -> B,
+    ) -> B {
+        match self {
+            MatchDecision::Arm {
+                arm,
+                bindings,
+                guard: Some((guard, otherwise)),
+            } => {
+                let condition = guard_condition(bindings.clone(), guard);
+                let then_branch = arm_body(arm, bindings);
+                B::branch(condition, then_branch, otherwise.lower(arm_body))
+            }
+            MatchDecision::Arm {
+                arm,
+                bindings,
+                guard: None,
+            } => arm_body(arm, bindings),
+            MatchDecision::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let then_branch = then_branch.lower(arm_body);
+                B::branch(condition, then_branch, else_branch.lower(arm_body))
+            }
+            MatchDecision::Switch { subject, arms } => {
+                let arms = arms
+                    .into_iter()
+                    .map(|(match_condition, decision)| (match_condition, decision.lower(arm_body)))
+                    .collect();
+                B::switch(subject, arms)
+            }
+            MatchDecision::Unreachable => B::unreachable(),
+        }
+    }
+
+    /// Call `f` with the bindings of each arm that the decision can take
+    fn for_each_arm(&self, f: &mut impl FnMut(&[ast::Stmt<Typing>])) {
+        match self {
+            MatchDecision::Arm {
+                bindings, guard, ..
+            } => {
+                f(bindings);
+                if let Some((_, otherwise)) = guard {
+                    otherwise.for_each_arm(f);
+                }
+            }
+            MatchDecision::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                then_branch.for_each_arm(f);
+                else_branch.for_each_arm(f);
+            }
+            MatchDecision::Switch { arms, .. } => {
+                arms.iter()
+                    .for_each(|(_, decision)| decision.for_each_arm(f));
+            }
+            MatchDecision::Unreachable => (),
+        }
+    }
+}
+
+/// The body of an arm of a lowered match, which is a block statement for match statements and
+/// a returning block for match expressions
+trait LoweredArmBody: Clone {
+    fn with_bindings(self, bindings: Vec<ast::Stmt<Typing>>) -> Self;
+
+    fn branch(condition: ast::Expr<Typing>, then_branch: Self, else_branch: Self) -> Self;
+
+    fn switch(
+        subject: ast::Identifier<Typing>,
+        arms: Vec<(ast::MatchCondition<Typing>, Self)>,
+    ) -> Self {
+        let arms = arms
+            .into_iter()
+            .map(|(match_condition, body)| (match_condition, None, body))
+            .collect();
+        Self::matching(ast::Expr::Var(subject), arms)
+    }
+
+    /// A match on a value whose arms may have guards
+    fn matching(
+        match_expression: ast::Expr<Typing>,
+        arms: Vec<(ast::MatchCondition<Typing>, Option<ast::Expr<Typing>>, Self)>,
+    ) -> Self;
+
+    /// The body of an arm of a match on enum variants that the matched value never has
+    fn unreachable() -> Self;
+}
+
+impl LoweredArmBody for ast::BlockStmt<Typing> {
+    fn with_bindings(self, bindings: Vec<ast::Stmt<Typing>>) -> Self {
+        ast::BlockStmt {
+            stmts: [bindings, self.stmts].concat(),
+        }
+    }
+
+    fn branch(condition: ast::Expr<Typing>, then_branch: Self, else_branch: Self) -> Self {
+        ast::BlockStmt {
+            stmts: vec![ast::Stmt::If(ast::IfStmt {
+                condition,
+                then_branch,
+                else_branch,
+            })],
+        }
+    }
+
+    fn matching(
+        match_expression: ast::Expr<Typing>,
+        arms: Vec<(ast::MatchCondition<Typing>, Option<ast::Expr<Typing>>, Self)>,
+    ) -> Self {
+        let match_stmt = ast::MatchStmt {
+            match_expression,
+            arms: arms
+                .into_iter()
+                .map(|(match_condition, guard, body)| ast::MatchArm {
+                    match_condition,
+                    guard,
+                    body,
+                })
+                .collect(),
+        };
+        ast::BlockStmt {
+            stmts: vec![ast::Stmt::Match(match_stmt)],
+        }
+    }
+
+    fn unreachable() -> Self {
+        ast::BlockStmt {
+            stmts: vec![ast::Stmt::Panic(ast::PanicStmt)],
+        }
+    }
+}
+
+impl LoweredArmBody for ast::ReturningBlock<Typing> {
+    fn with_bindings(self, bindings: Vec<ast::Stmt<Typing>>) -> Self {
+        ast::ReturningBlock {
+            stmts: [bindings, self.stmts].concat(),
+            return_expr: self.return_expr,
+        }
+    }
+
+    fn branch(condition: ast::Expr<Typing>, then_branch: Self, else_branch: Self) -> Self {
+        ast::ReturningBlock {
+            stmts: vec![],
+            return_expr: ast::Expr::If(ast::ExprIf {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            }),
+        }
+    }
+
+    fn matching(
+        match_expression: ast::Expr<Typing>,
+        arms: Vec<(ast::MatchCondition<Typing>, Option<ast::Expr<Typing>>, Self)>,
+    ) -> Self {
+        let match_expr = ast::MatchExpr {
+            match_expression: Box::new(match_expression),
+            arms: arms
+                .into_iter()
+                .map(|(match_condition, guard, body)| ast::MatchExprArm {
+                    match_condition,
+                    guard,
+                    body,
+                })
+                .collect(),
+        };
+        ast::ReturningBlock {
+            stmts: vec![],
+            return_expr: ast::Expr::Match(match_expr),
+        }
+    }
+
+    /// A block that panics, which takes the type of the other arms of the match, see
+    /// [`is_diverging_match_arm`]
+    fn unreachable() -> Self {
+        ast::ReturningBlock {
+            stmts: vec![ast::Stmt::Panic(ast::PanicStmt)],
+            return_expr: ast::Expr::Tuple(vec![]),
+        }
+    }
+}
+
+fn arm_index_literal(arm: usize) -> ast::Expr<Typing> {
+    ast::Expr::Lit(ast::ExprLit::U32(arm as u32))
+}
+
+/// The condition of a guard, which can refer to the bindings of the arm's pattern
+fn guard_condition(
+    bindings: Vec<ast::Stmt<Typing>>,
+    guard: ast::Expr<Typing>,
+) -> ast::Expr<Typing> {
+    match bindings.is_empty() {
+        true => guard,
+        false => ast::Expr::ReturningBlock(Box::new(ast::ReturningBlock {
+            stmts: bindings,
+            return_expr: guard,
+        })),
+    }
+}
+
+/// Return the pattern of a match arm. A single identifier, like `a` in
+/// `match x { a => { ... } }`, is a binding, unless it is the name of a variant of the
+/// matched enum, like `None`.
+fn match_condition_pattern(match_condition: ast::MatchCondition<Typing>) -> ast::Pattern<Typing> {
+    let binding = |binding: ast::PatternMatchedBinding| ast::Pattern::Binding(binding);
+    match match_condition {
+        ast::MatchCondition::CatchAll => ast::Pattern::Wildcard,
+        ast::MatchCondition::Pattern(pattern) => *pattern,
+        ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+            type_name: None,
+            variant_name,
+            data_bindings,
+            named_data_bindings,
+        }) if data_bindings.is_empty() && named_data_bindings.is_empty() => {
+            binding(ast::PatternMatchedBinding {
+                name: variant_name,
+                mutable: false,
+            })
+        }
+        ast::MatchCondition::EnumVariant(ast::EnumVariantSelector {
+            type_name,
+            variant_name,
+            data_bindings,
+            named_data_bindings,
+        }) => ast::Pattern::EnumVariant(ast::EnumVariantPattern {
+            type_name,
+            variant_name,
+            fields: data_bindings.into_iter().map(binding).collect(),
+            named_fields: named_data_bindings
+                .into_iter()
+                .map(|(field_name, x)| (field_name, binding(x)))
+                .collect(),
+        }),
+    }
+}
+
+/// Move the bindings of a row's patterns to `let` statements, and split tuple patterns into
+/// patterns on their elements. Or-patterns that contain bindings or enum variants are expanded
+/// into one row per alternative.
+fn expand_match_row(row: MatchRow) -> Result<Vec<MatchRow>, CompileError> {
+    let MatchRow {
+        tests: mut pending_tests,
+        mut bindings,
+        guard,
+        arm,
+    } = row;
+    pending_tests.reverse();
+
+    let mut tests = vec![];
+    while let Some(test) = pending_tests.pop() {
+        let PatternTest {
+            subject,
+            subject_type,
+            pattern,
+        } = test;
+        match pattern {
+            ast::Pattern::Wildcard => (),
+            ast::Pattern::Binding(binding)
+                if !binding.mutable && is_variant_name(&subject_type, &binding.name) =>
+            {
+                let pattern = ast::Pattern::EnumVariant(ast::EnumVariantPattern {
+                    type_name: None,
+                    variant_name: binding.name,
+                    fields: vec![],
+                    named_fields: vec![],
+                });
+                tests.push(PatternTest {
+                    subject,
+                    subject_type,
+                    pattern,
+                });
+            }
+            ast::Pattern::Binding(binding) => bindings.push(ast::Stmt::Let(ast::LetStmt {
+                var_name: binding.name,
+                mutable: binding.mutable,
+                data_type: Some(subject_type),
+                expr: ast::Expr::Var(subject),
+            })),
+            ast::Pattern::Tuple(elements) => {
+                let ast_types::DataType::Tuple(tuple_type) = &subject_type else {
+                    return Err(CompileError::new(
+                        ErrorCode::TypeError,
+                        format!(
+                            "cannot match a tuple pattern against a value of type `{subject_type}`"
+                        ),
+                    ));
+                };
+                if tuple_type.element_count() != elements.len() {
+                    return Err(CompileError::new(
+                        ErrorCode::TypeError,
+                        format!(
+                            "cannot match a tuple pattern with {} elements against a value of \
+                             type `{subject_type}`",
+                            elements.len()
+                        ),
+                    ));
+                }
+
+                let element_tests = elements
+                    .into_iter()
+                    .zip(tuple_type.fields.iter())
+                    .enumerate()
+                    .map(|(i, (element, element_type))| PatternTest {
+                        subject: ast::Identifier::Field(
+                            Box::new(subject.clone()),
+                            ast_types::FieldId::UnnamedField(i),
+                            Typing::default(),
+                        ),
+                        subject_type: element_type.to_owned(),
+                        pattern: element,
+                    });
+                pending_tests.extend(element_tests.rev());
+            }
+            ast::Pattern::Or(alternatives)
+                if !is_condition_pattern(&subject_type, &alternatives) =>
+            {
+                let mut rows = vec![];
+                for alternative in alternatives {
+                    let alternative_test = PatternTest {
+                        subject: subject.clone(),
+                        subject_type: subject_type.clone(),
+                        pattern: alternative,
+                    };
+                    let alternative_tests = tests
+                        .iter()
+                        .cloned()
+                        .chain([alternative_test])
+                        .chain(pending_tests.iter().rev().cloned())
+                        .collect();
+                    rows.extend(expand_match_row(MatchRow {
+                        tests: alternative_tests,
+                        bindings: bindings.clone(),
+                        guard: guard.clone(),
+                        arm,
+                    })?);
+                }
+
+                return Ok(rows);
+            }
+            pattern => tests.push(PatternTest {
+                subject,
+                subject_type,
+                pattern,
+            }),
+        }
+    }
+
+    Ok(vec![MatchRow {
+        tests,
+        bindings,
+        guard,
+        arm,
+    }])
+}
+
+/// Return true if the alternatives of an or-pattern can be checked by one condition, which is
+/// the case if they contain neither bindings nor enum variants.
+fn is_condition_pattern(
+    subject_type: &ast_types::DataType,
+    alternatives: &[ast::Pattern<Typing>],
+) -> bool {
+    alternatives.iter().all(|pattern| match pattern {
+        ast::Pattern::Wildcard | ast::Pattern::Literal(_) | ast::Pattern::Range(_) => true,
+        ast::Pattern::Binding(_) | ast::Pattern::EnumVariant(_) => false,
+        ast::Pattern::Or(alternatives) => is_condition_pattern(subject_type, alternatives),
+        ast::Pattern::Tuple(elements) => match subject_type {
+            ast_types::DataType::Tuple(tuple_type) => elements
+                .iter()
+                .zip(tuple_type.fields.iter())
+                .all(|(element, element_type)| {
+                    is_condition_pattern(element_type, std::slice::from_ref(element))
+                }),
+            _ => false,
+        },
+    })
+}
+
+/// Return true if `name` is the name of a variant of the enum `data_type`
+fn is_variant_name(data_type: &ast_types::DataType, name: &str) -> bool {
+    match data_type.unbox() {
+        ast_types::DataType::Enum(enum_type) => enum_type
+            .variants
+            .iter()
+            .any(|(variant_name, _)| variant_name == name),
+        _ => false,
+    }
+}
+
+/// Return the patterns that the data of an enum variant is matched against, in declaration
+/// order. Data that is not mentioned in the pattern is matched by `_`.
+fn enum_variant_field_patterns(
+    pattern: &ast::EnumVariantPattern<Typing>,
+    enum_type: &ast_types::EnumType,
+    field_count: usize,
+) -> Result<Vec<ast::Pattern<Typing>>, CompileError> {
+    let ast::EnumVariantPattern {
+        type_name,
+        variant_name,
+        fields,
+        named_fields,
+    } = pattern;
+    let error = |message: String| Err(CompileError::new(ErrorCode::TypeError, message));
+
+    if let Some(type_name) = type_name {
+        if type_name != &enum_type.name {
+            return error(format!(
+                "cannot match a variant of `{type_name}` against a value of type `{}`",
+                enum_type.name
+            ));
+        }
+    }
+
+    if !named_fields.is_empty() {
+        let Some(field_names) = enum_type.variant_field_names(variant_name) else {
+            return error(format!(
+                "variant `{variant_name}` of `{}` does not have named fields",
+                enum_type.name
+            ));
+        };
+        let mut field_patterns = vec![ast::Pattern::Wildcard; field_count];
+        for (field_name, field_pattern) in named_fields {
+            let Some(i) = field_names.iter().position(|x| x == field_name) else {
+                return error(format!(
+                    "variant `{variant_name}` of `{}` does not have a field named `{field_name}`",
+                    enum_type.name
+                ));
+            };
+            field_patterns[i] = field_pattern.to_owned();
+        }
+
+        return Ok(field_patterns);
+    }
+
+    match fields.len() {
+        0 => Ok(vec![ast::Pattern::Wildcard; field_count]),
+        n if n == field_count => Ok(fields.to_owned()),
+        n => error(format!(
+            "variant `{variant_name}` of `{}` has {field_count} fields, but the pattern has {n}",
+            enum_type.name
+        )),
+    }
+}
+
+/// The condition under which a value matches a pattern, or `None` if the pattern matches any
+/// value, along with the `let` statements declaring the pattern's bindings.
+type LoweredPattern = (Option<ast::Expr<Typing>>, Vec<ast::Stmt<Typing>>);
+
+/// Lower a pattern matched against the value of `subject`.
+fn lower_pattern(
+    pattern: &ast::Pattern<Typing>,
+    subject: &ast::Identifier<Typing>,
+    subject_type: &ast_types::DataType,
+) -> Result<LoweredPattern, CompileError> {
+    use ast::BinOp::*;
+
+    let binop =
+        |lhs, binop, rhs| ast::Expr::Binop(Box::new(lhs), binop, Box::new(rhs), Typing::default());
+    let not = |expr| ast::Expr::Unary(ast::UnaryOp::Not, Box::new(expr), Typing::default());
+    let value = || match subject_type {
+        ast_types::DataType::Boxed(_) => ast::Expr::Unary(
+            ast::UnaryOp::Deref,
+            Box::new(ast::Expr::Var(subject.to_owned())),
+            Typing::default(),
+        ),
+        _ => ast::Expr::Var(subject.to_owned()),
+    };
+
+    let lowered_pattern = match pattern {
+        ast::Pattern::Wildcard => (None, vec![]),
+        ast::Pattern::Binding(binding) => {
+            let binding = ast::Stmt::Let(ast::LetStmt {
+                var_name: binding.name.to_owned(),
+                mutable: binding.mutable,
+                data_type: Some(subject_type.to_owned()),
+                expr: ast::Expr::Var(subject.to_owned()),
+            });
+            (None, vec![binding])
+        }
+        ast::Pattern::Literal(literal) => (Some(binop(value(), Eq, literal.to_owned())), vec![]),
+        ast::Pattern::Range(ast::RangePattern {
+            start,
+            end,
+            inclusive,
+        }) => {
+            let above_start = not(binop(value(), Lt, start.to_owned()));
+            let below_end = match inclusive {
+                true => not(binop(value(), Gt, end.to_owned())),
+                false => binop(value(), Lt, end.to_owned()),
+            };
+            (Some(binop(above_start, And, below_end)), vec![])
+        }
+        ast::Pattern::Or(alternatives) => {
+            let mut conditions = vec![];
+            for alternative in alternatives {
+                let (condition, bindings) = lower_pattern(alternative, subject, subject_type)?;
+                if !bindings.is_empty() {
+                    return Err(CompileError::new(
+                        ErrorCode::UnsupportedSyntax,
+                        "bindings in or-patterns are not supported",
+                    ));
+                }
+                let Some(condition) = condition else {
+                    return Ok((None, vec![]));
+                };
+                conditions.push(condition);
+            }
+
+            let condition = conditions
+                .into_iter()
+                .reduce(|acc, condition| binop(acc, Or, condition));
+            (condition, vec![])
+        }
+        ast::Pattern::Tuple(elements) => {
+            let ast_types::DataType::Tuple(tuple_type) = subject_type else {
+                return Err(CompileError::new(
+                    ErrorCode::TypeError,
+                    format!(
+                        "cannot match a tuple pattern against a value of type `{subject_type}`"
+                    ),
+                ));
+            };
+            if tuple_type.element_count() != elements.len() {
+                return Err(CompileError::new(
+                    ErrorCode::TypeError,
+                    format!(
+                        "cannot match a tuple pattern with {} elements against a value of type \
+                         `{subject_type}`",
+                        elements.len()
+                    ),
+                ));
+            }
+
+            let mut conditions = vec![];
+            let mut bindings = vec![];
+            for (i, (element, element_type)) in elements.iter().zip(&tuple_type.fields).enumerate()
+            {
+                let element_subject = ast::Identifier::Field(
+                    Box::new(subject.to_owned()),
+                    ast_types::FieldId::UnnamedField(i),
+                    Typing::default(),
+                );
+                let (condition, element_bindings) =
+                    lower_pattern(element, &element_subject, element_type)?;
+                conditions.extend(condition);
+                bindings.extend(element_bindings);
+            }
+
+            let condition = conditions
+                .into_iter()
+                .reduce(|acc, condition| binop(acc, And, condition));
+            (condition, bindings)
+        }
+        ast::Pattern::EnumVariant(pattern) => {
+            return Err(CompileError::new(
+                ErrorCode::TypeError,
+                format!(
+                    "cannot match enum variant `{}` against a value of type `{subject_type}`",
+                    pattern.variant_name
+                ),
+            ));
+        }
+    };
+
+    Ok(lowered_pattern)
+}
+
+/// Convert the bindings of a pattern on an enum variant with named fields, like
+/// `Foo::Bar { b, .. }`, to bindings of the variant's data in declaration order. Fields
+/// that are not bound in the pattern are bound to hidden names.
+fn resolve_named_data_bindings(
+    enum_variant_selector: &mut ast::EnumVariantSelector,
+    enum_type: &ast_types::EnumType,
+) -> Result<(), CompileError> {
+    let named_data_bindings = std::mem::take(&mut enum_variant_selector.named_data_bindings);
+    if named_data_bindings.is_empty() {
+        return Ok(());
+    }
+
+    let variant_name = &enum_variant_selector.variant_name;
+    let Some(field_names) = enum_type.variant_field_names(variant_name) else {
+        return Err(match_arm_error(format!(
+            "variant `{}::{variant_name}` has no named fields",
+            enum_type.name
+        )));
+    };
+    for (field_name, _) in named_data_bindings.iter() {
+        if !field_names.contains(field_name) {
+            return Err(match_arm_error(format!(
+                "variant `{}::{variant_name}` has no field named `{field_name}`",
+                enum_type.name
+            )));
+        }
+    }
+
+    enum_variant_selector.data_bindings = field_names
+        .iter()
+        .map(|field_name| {
+            named_data_bindings
+                .iter()
+                .find(|(bound_field_name, _)| bound_field_name == field_name)
+                .map(|(_, binding)| binding.to_owned())
+                .unwrap_or_else(|| ast::PatternMatchedBinding {
+                    name: format!("_{variant_name}_{field_name}"),
+                    mutable: false,
+                })
+        })
+        .collect();
+
+    Ok(())
+}
+
+/// Annotate the bounds of a range in a for-loop, and return the type of the range. An integer
+/// literal bound takes the type of the other bound, and a range between two integer literals
+/// is a range of `u32`s.
+fn annotate_range_bounds(
+    start: &mut ast::Expr<Typing>,
+    end: &mut ast::Expr<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> Result<ast_types::DataType, CompileError> {
+    let start_type = derive_annotate_expr_type(start, None, state, env_fn_signature).ok();
+    let end_type = derive_annotate_expr_type(end, start_type.as_ref(), state, env_fn_signature);
+    let range_type = match (start_type, end_type) {
+        (Some(start_type), end_type) => {
+            check_type_equals(&end_type.map_err(type_error)?, &start_type, "range bounds")?;
+            start_type
+        }
+        (None, Ok(end_type)) => {
+            let start_type =
+                derive_annotate_expr_type(start, Some(&end_type), state, env_fn_signature)
+                    .map_err(type_error)?;
+            check_type_equals(&end_type, &start_type, "range bounds")?;
+            end_type
+        }
+        (None, Err(_)) => {
+            let range_type = ast_types::DataType::U32;
+            for bound in [start, end] {
+                derive_annotate_expr_type(bound, Some(&range_type), state, env_fn_signature)
+                    .map_err(type_error)?;
+            }
+            range_type
+        }
+    };
 
-                        let variant_data_tuple =
-                            enum_type.variant_data_type(variant_name).as_tuple_type();
-                        assert!(data_bindings.is_empty() || variant_data_tuple.element_count() == data_bindings.len(), "Number of bindings must match number of elements in variant data tuple");
-                        assert!(
-                            data_bindings.iter().map(|x| &x.name).all_unique(),
-                            "Name repetition in pattern matching not allowed"
-                        );
+    if !matches!(
+        range_type,
+        ast_types::DataType::U32 | ast_types::DataType::U64
+    ) {
+        return Err(CompileError::new(
+            ErrorCode::TypeError,
+            format!("ranges in for-loops must be of type `u32` or `u64`, found `{range_type}`"),
+        ));
+    }
 
-                        data_bindings.iter().enumerate().for_each(|(i, x)| {
-                            let new_binding_type = if is_boxed {
-                                ast_types::DataType::Boxed(Box::new(
-                                    variant_data_tuple.fields[i].to_owned(),
-                                ))
-                            } else {
-                                variant_data_tuple.fields[i].to_owned()
-                            };
-
-                            state.vtable.insert(
-                                x.name.to_owned(),
-                                DataTypeAndMutability::new(&new_binding_type, x.mutable),
-                            );
-                        });
+    Ok(range_type)
+}
 
-                        annotate_block_stmt(&mut arm.body, env_fn_signature, state);
+/// Return the type of the elements that a for-loop over a value of the given type binds,
+/// and the length of the iterated collection if it is statically known.
+fn for_loop_element_type_and_array_length(
+    collection_type: &ast_types::DataType,
+) -> Result<(ast_types::DataType, Option<usize>), CompileError> {
+    let cannot_iterate = |data_type: &ast_types::DataType| {
+        CompileError::new(
+            ErrorCode::TypeError,
+            format!("cannot iterate over value of type `{data_type}`"),
+        )
+    };
 
-                        // Remove bindings set in match-arm after body's type check
-                        data_bindings.iter().for_each(|x| {
-                            state.vtable.remove(&x.name);
-                        });
-                    }
+    match collection_type {
+        ast_types::DataType::List(element_type, _) => Ok((*element_type.to_owned(), None)),
+        ast_types::DataType::Boxed(inner_type) => {
+            let (element_type, array_length) = match inner_type.as_ref() {
+                ast_types::DataType::List(element_type, _) => (element_type, None),
+                ast_types::DataType::Array(array_type) => {
+                    (&array_type.element_type, Some(array_type.length))
                 }
-            }
+                other => return Err(cannot_iterate(other)),
+            };
 
-            // Verify that all cases where covered, *or* a wildcard was encountered.
-            assert!(
-                variants_encountered.len() == enum_type.variants.len() || contains_wildcard_arm,
-                "All cases must be covered for match-expression for {}. Missing variants: {}.",
-                enum_type.name,
-                enum_type
-                    .variants
-                    .iter()
-                    .map(|x| &x.0)
-                    .filter(|x| !variants_encountered.contains(*x))
-                    .join(",")
-            );
-        }
+            // Like when indexing into a boxed sequence, elements that are not copy stay boxed
+            let element_type = if element_type.is_copy() {
+                *element_type.to_owned()
+            } else {
+                ast_types::DataType::Boxed(element_type.to_owned())
+            };
 
-        ast::Stmt::Block(block_stmt) => {
-            annotate_block_stmt(block_stmt, env_fn_signature, state);
-        }
-        ast::Stmt::Assert(assert_expr) => {
-            let expr_type = derive_annotate_expr_type(
-                &mut assert_expr.expression,
-                Some(&ast_types::DataType::Bool),
-                state,
-                env_fn_signature,
-            )
-            .unwrap();
-            assert_type_equals(&expr_type, &ast_types::DataType::Bool, "assert expression");
-        }
-        ast::Stmt::Panic(_) => (),
-        ast::Stmt::FnDeclaration(function) => {
-            // A local function can see all functions available in the outer scope.
-            annotate_fn_inner(
-                function,
-                state.composite_types,
-                state.libraries,
-                state.ftable.clone(),
-            );
-            state.ftable.insert(
-                function.signature.name.clone(),
-                vec![function.signature.clone()],
-            );
+            Ok((element_type, array_length))
         }
+        ast_types::DataType::Array(_) => Err(CompileError::new(
+            ErrorCode::TypeError,
+            "cannot iterate over array that is not boxed, as arrays only support dynamic \
+             indexing when boxed",
+        )),
+        other => Err(cannot_iterate(other)),
     }
 }
 
-fn annotate_block_stmt(
-    block: &mut ast::BlockStmt<Typing>,
-    fn_signature: &ast::FnSignature,
-    state: &mut CheckState,
-) {
-    let vtable_before = state.vtable.clone();
-    block
-        .stmts
-        .iter_mut()
-        .for_each(|stmt| annotate_stmt(stmt, state, fn_signature));
-    state.vtable = vtable_before;
-}
-
-pub fn assert_type_equals(
+/// Return a type error if the derived type of a value differs from the type it must have
+fn check_type_equals(
     derived_type: &ast_types::DataType,
     data_type: &ast_types::DataType,
     context: &str,
-) {
+) -> Result<(), CompileError> {
     if derived_type != data_type {
-        panic!(
-            "Type mismatch between type\n'{data_type:?}'\n and derived type\n'{derived_type:?}'\n for {context}",
-        );
+        return Err(CompileError::new(
+            ErrorCode::TypeError,
+            format!(
+                "mismatched types in {context}: expected `{data_type}`, found `{derived_type}`"
+            ),
+        ));
     }
+
+    Ok(())
+}
+
+/// The error for a number literal that does not fit in the type it is inferred as
+fn literal_out_of_range(n: u128, data_type: &ast_types::DataType) -> anyhow::Error {
+    anyhow!("literal out of range for `{data_type}`: `{n}` does not fit")
+}
+
+/// Convert an error found while deriving the type of an expression to a compile error. Errors
+/// that already are compile errors keep their code.
+fn type_error(error: anyhow::Error) -> CompileError {
+    error
+        .downcast::<CompileError>()
+        .unwrap_or_else(|error| CompileError::new(ErrorCode::TypeError, error.to_string()))
 }
 
 /// Set type and return (type, mutable, new_expr) tuple. `new_expr` indicates that the
@@ -670,48 +3481,71 @@ pub(crate) fn annotate_identifier_type(
     hint: Option<&ast_types::DataType>,
     state: &mut CheckState,
     fn_signature: &ast::FnSignature,
-) -> (ast_types::DataType, bool, Option<ast::Expr<Typing>>) {
+) -> anyhow::Result<(ast_types::DataType, bool, Option<ast::Expr<Typing>>)> {
     match identifier {
         // x
         ast::Identifier::String(var_name, var_type) => {
+            if let Some(binding) = state.uninferred_bindings.get(var_name) {
+                let mutable = binding.mutable;
+                let data_type = infer_binding_type(var_name, hint, state)?;
+                *var_type = Typing::KnownType(data_type.clone());
+                return Ok((data_type, mutable, None));
+            }
+
+            if let Some(function_value) = state.function_arguments.get(var_name) {
+                // Identifier is a function argument
+                let data_type =
+                    ast_types::DataType::Function(Box::new(function_value.function_type.clone()));
+                *var_type = Typing::KnownType(data_type.clone());
+                return Ok((data_type, false, None));
+            }
+
+            if let Some(data_type) = state.statics.get(var_name) {
+                // Identifier is a `static` item, which cannot be shadowed
+                *var_type = Typing::KnownType(data_type.clone());
+                return Ok((data_type.to_owned(), false, None));
+            }
+
+            state.capture_enclosing_binding(var_name, hint)?;
             match state.vtable.get(var_name) {
                 Some(found_type) => {
                     // Identifier is a declared variable
                     *var_type = Typing::KnownType(found_type.data_type.clone());
-                    (found_type.data_type.clone(), found_type.mutable, None)
+                    let found_type = found_type.to_owned();
+                    Ok((found_type.data_type, found_type.mutable, None))
                 }
                 None => match state.ftable.get(var_name) {
                     // Identifier is a declared function
                     Some(functions) => {
-                        assert!(
+                        ensure!(
                             functions.len().is_one(),
-                            "Duplicate function name: {var_name}"
+                            "cannot use `{var_name}` as a value, as several functions have that name"
                         );
                         let function = functions[0].clone();
                         *var_type = Typing::KnownType(function.clone().into());
                         let function_datatype: ast_types::DataType = function.into();
-                        (function_datatype, false, None)
+                        Ok((function_datatype, false, None))
                     }
                     None => {
                         // Identifier is variant type declared in `prelude` without associated data, like `None`.
                         // Here, the identifier must be rewritten to an `EnumDeclaration` by the caller.
-                        let type_hint = match hint {
-                        Some(ty) => ty,
-                        None => panic!("type of variable or identifier \"{var_name}\" could not be resolved"),
-                    };
-                        let prelude_type = state
-                            .composite_types
-                            .prelude_variant_match(var_name, type_hint).expect("type of variable or identifier \"{var_name}\" could not be resolved");
+                        let Some(prelude_type) = hint.and_then(|type_hint| {
+                            state
+                                .composite_types
+                                .prelude_variant_match(var_name, type_hint)
+                        }) else {
+                            bail!("cannot find value `{var_name}` in this scope");
+                        };
                         *var_type = Typing::KnownType(prelude_type.clone().into());
 
-                        (
+                        Ok((
                             prelude_type.clone().into(),
                             false,
                             Some(ast::Expr::EnumDeclaration(ast::EnumDeclaration {
                                 enum_type: prelude_type.into(),
                                 variant_name: var_name.to_owned(),
                             })),
-                        )
+                        ))
                     }
                 },
             }
@@ -722,24 +3556,24 @@ pub(crate) fn annotate_identifier_type(
             let index_hint = ast_types::DataType::U32;
             let index_type = match index_expr.as_mut() {
                 ast::IndexExpr::Dynamic(index_expr) => {
-                    derive_annotate_expr_type(index_expr, Some(&index_hint), state, fn_signature)
-                        .unwrap()
+                    derive_annotate_expr_type(index_expr, Some(&index_hint), state, fn_signature)?
                 }
                 ast::IndexExpr::Static(_) => ast_types::DataType::U32,
             };
-            if !is_index_type(&index_type) {
-                panic!("Cannot index list with type '{index_type}'");
-            }
+            ensure!(
+                is_index_type(&index_type),
+                "cannot index into a list with a value of type `{index_type}`"
+            );
 
             // Only `Vec<T>` and `[T; n]` can be indexed, so if type is e.g. `MemPointer(Vec<T>)`, then the
             // type of `a` need to be forced to `Vec<T>`.
             // TODO: It's possible that the type of `list_identifier` needs to be forced to. But to
             // do that, this function probably needs the expression, and not just the identifier.
             let (maybe_list_type, mutable, rewrite_expr) =
-                annotate_identifier_type(list_identifier, None, state, fn_signature);
-            assert!(
+                annotate_identifier_type(list_identifier, None, state, fn_signature)?;
+            ensure!(
                 rewrite_expr.is_none(),
-                "Cannot rewrite expression outside of atomic variable expression, like `a`."
+                "cannot index into enum variant `{list_identifier}`"
             );
             let mut forced_sequence_type = maybe_list_type.clone();
             let element_type = loop {
@@ -747,27 +3581,38 @@ pub(crate) fn annotate_identifier_type(
                     break elem_ty;
                 } else if let ast_types::DataType::Array(array_type) = &forced_sequence_type {
                     // If iterator type is array and *not* boxed, then index *must* be statically
-                    // known. Also ensure that this value is not out-of-bounds.
+                    // known. Also ensure that this value is not out-of-bounds. The arrays of
+                    // `static` items live in static memory, so they are indexed like boxed
+                    // arrays.
+                    let is_static = matches!(
+                        list_identifier.as_ref(),
+                        ast::Identifier::String(name, _) if state.statics.contains_key(name)
+                    );
                     let statically_known_index = match index_expr.as_ref() {
                         ast::IndexExpr::Dynamic(index_expr) => {
-                            assert!(maybe_list_type.is_boxed(), "Array must be boxed for dynamically indices to work. Index expr was: {index_expr}");
+                            ensure!(
+                                maybe_list_type.is_boxed() || is_static,
+                                "cannot index into array that is not boxed with non-constant \
+                                 index `{index_expr}`, as arrays only support dynamic indexing \
+                                 when boxed"
+                            );
                             None
                         }
                         ast::IndexExpr::Static(index) => Some(index),
                     };
                     if let Some(x) = statically_known_index {
-                        assert!(
+                        ensure!(
                             *x < array_type.length,
-                            "Index for array out-of-range. Index was {x}, length is {}",
+                            "index out of bounds: the length is {} but the index is {x}",
                             array_type.length
-                        )
+                        );
                     }
 
                     break &array_type.element_type;
                 } else if let ast_types::DataType::Boxed(inner_type) = forced_sequence_type {
                     forced_sequence_type = *inner_type.to_owned();
                 } else {
-                    panic!("Cannot index into {list_identifier} of type {maybe_list_type}");
+                    bail!("cannot index into a value of type `{maybe_list_type}`");
                 }
             };
 
@@ -783,43 +3628,889 @@ pub(crate) fn annotate_identifier_type(
                 *element_type.to_owned()
             };
 
-            list_identifier.force_type(&forced_sequence_type);
-            *known_type = Typing::KnownType(element_type.clone());
+            list_identifier.force_type(&forced_sequence_type);
+            *known_type = Typing::KnownType(element_type.clone());
+
+            Ok((element_type, mutable, None))
+        }
+
+        // x.foo
+        ast::Identifier::Field(ident, field_id, annot) => {
+            let (receiver_type, mutable, rewrite_expr) =
+                annotate_identifier_type(ident, None, state, fn_signature)?;
+            ensure!(
+                rewrite_expr.is_none(),
+                "no field `{field_id}` on enum variant `{ident}`"
+            );
+            // Only structs have fields, so receiver_type must be a struct, or a pointer to a struct.
+            let data_type = receiver_type.field_access_returned_type(field_id)?;
+            *annot = Typing::KnownType(data_type.clone());
+
+            Ok((data_type, mutable, None))
+        }
+    }
+}
+
+/// If a function call calls a generic function or a function taking function arguments,
+/// instantiate the function for the concrete types and functions of the call, and make the
+/// call call the instantiation. The concrete types are inferred from the types of the
+/// arguments, which have been annotated where known, and from the expected type of the
+/// result, unless given explicitly, like in `sum::<u64>(v)`. The arguments passed to
+/// function arguments are replaced by the values captured by the passed functions.
+fn monomorphize_fn_call(
+    name: &mut String,
+    explicit_type_arguments: &[Option<ast_types::DataType>],
+    args: &mut Vec<ast::Expr<Typing>>,
+    arg_types: &[Option<ast_types::DataType>],
+    output_type_hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<()> {
+    let Some(GenericFunction {
+        function: generic_function,
+        ftable,
+    }) = state
+        .monomorphizations
+        .generic_functions
+        .get(name.as_str())
+        .cloned()
+    else {
+        return Ok(());
+    };
+    ensure!(
+        generic_function.signature.args.len() == args.len(),
+        "`{name}` takes {} arguments but {} were supplied",
+        generic_function.signature.args.len(),
+        args.len()
+    );
+
+    let mut type_arguments = HashMap::default();
+    if !explicit_type_arguments.is_empty() {
+        let declared_type_parameters = &generic_function.declared_type_parameters;
+        ensure!(
+            declared_type_parameters.len() == explicit_type_arguments.len(),
+            "`{name}` takes {} type arguments but {} were supplied",
+            declared_type_parameters.len(),
+            explicit_type_arguments.len()
+        );
+        for (parameter, argument) in declared_type_parameters.iter().zip(explicit_type_arguments) {
+            match (parameter, argument) {
+                (Some(parameter), Some(argument)) => {
+                    type_arguments.insert(parameter.to_owned(), argument.to_owned());
+                }
+                (None, Some(argument)) => bail!(
+                    "cannot give the type `{argument}` to a function type parameter of \
+                     `{name}`, its type is that of the function passed, use `_` in its place"
+                ),
+                (_, None) => (),
+            }
+        }
+    }
+    for (parameter, arg_type) in generic_function.signature.args.iter().zip(arg_types) {
+        let (ast_types::AbstractArgument::ValueArgument(parameter), Some(arg_type)) =
+            (parameter, arg_type)
+        else {
+            continue;
+        };
+        bind_type_parameters(
+            &parameter.data_type,
+            arg_type,
+            &generic_function.type_parameters,
+            &mut type_arguments,
+        );
+    }
+    if let Some(output_type) = output_type_hint {
+        bind_type_parameters(
+            &generic_function.signature.output,
+            output_type,
+            &generic_function.type_parameters,
+            &mut type_arguments,
+        );
+    }
+
+    // The functions passed to function arguments are found once the types of the value
+    // arguments are known, since closures get the types of their arguments from them
+    let mut function_values = HashMap::default();
+    for (parameter, arg) in generic_function.signature.args.iter().zip(args.iter_mut()) {
+        let ast_types::AbstractArgument::FunctionArgument(parameter) = parameter else {
+            continue;
+        };
+        let declared_type = &parameter.function_type;
+        let expected_type = |data_type| {
+            instantiate_declared_type(
+                data_type,
+                &generic_function.type_parameters,
+                &type_arguments,
+                state.composite_types,
+            )
+        };
+        let expected_input = expected_type(&declared_type.input_argument);
+        let expected_output = expected_type(&declared_type.output);
+        let function_value = function_value(
+            arg,
+            expected_input.as_ref(),
+            expected_output.as_ref(),
+            state,
+            env_fn_signature,
+        )?;
+        bind_type_parameters(
+            &ast_types::DataType::Function(Box::new(declared_type.to_owned())),
+            &ast_types::DataType::Function(Box::new(function_value.function_type.clone())),
+            &generic_function.type_parameters,
+            &mut type_arguments,
+        );
+        function_values.insert(parameter.abstract_name.clone(), function_value);
+    }
+
+    let mut concrete_types = vec![];
+    for parameter_name in generic_function.type_parameters.iter() {
+        let Some(concrete_type) = type_arguments.get(parameter_name) else {
+            bail!(
+                "cannot infer type parameter `{parameter_name}` of `{name}`, \
+                 specify it like `{name}::<{parameter_name}>(...)`"
+            );
+        };
+        concrete_types.push(concrete_type.to_owned());
+    }
+    for (parameter_name, trait_name) in generic_function.trait_bounds.iter() {
+        let concrete_type = &type_arguments[parameter_name];
+        ensure!(
+            implements_trait(concrete_type, trait_name, state.composite_types),
+            "the trait bound `{concrete_type}: {trait_name}` is not satisfied, as required by \
+             `{name}`"
+        );
+    }
+
+    // The types and functions are part of the name, such that the instantiations get
+    // different labels
+    let mut instantiation_name_parts = concrete_types
+        .iter()
+        .map(|concrete_type| concrete_type.label_friendly_name())
+        .collect_vec();
+    let mut passed_functions = vec![];
+    for arg in generic_function.signature.args.iter() {
+        if let ast_types::AbstractArgument::FunctionArgument(parameter) = arg {
+            let function_value = function_values[&parameter.abstract_name].clone();
+            instantiation_name_parts.push(function_value.name.clone());
+            passed_functions.push(Some(function_value));
+        } else {
+            passed_functions.push(None);
+        }
+    }
+    let instantiation_name = format!("{name}___{}", instantiation_name_parts.join("___"));
+    if !state
+        .monomorphizations
+        .signatures
+        .contains_key(&instantiation_name)
+    {
+        instantiate_generic_function(
+            generic_function,
+            ftable,
+            &instantiation_name,
+            type_arguments,
+            function_values,
+            state,
+        );
+    }
+
+    // The instantiation takes the captured values in place of the function arguments
+    for (position, function_value) in passed_functions.into_iter().enumerate().rev() {
+        if let Some(function_value) = function_value {
+            args.splice(position..=position, function_value.capture_exprs());
+        }
+    }
+
+    let signature = state.monomorphizations.signatures[&instantiation_name].clone();
+    state
+        .ftable
+        .insert(instantiation_name.clone(), vec![signature]);
+    *name = instantiation_name;
+
+    Ok(())
+}
+
+impl FunctionValue {
+    /// Return the expressions that evaluate to the values captured by the function
+    fn capture_exprs(&self) -> Vec<ast::Expr<Typing>> {
+        self.captures
+            .iter()
+            .map(|(capture, _)| {
+                ast::Expr::Var(ast::Identifier::String(
+                    capture.to_owned(),
+                    Typing::default(),
+                ))
+            })
+            .collect_vec()
+    }
+}
+
+/// If a function call calls a function argument, like `f(x)`, make it call the function
+/// passed to the function argument, with the values captured by that function as leading
+/// arguments
+fn resolve_function_argument_call(
+    name: &mut String,
+    args: &mut Vec<ast::Expr<Typing>>,
+    state: &mut CheckState,
+) {
+    let Some(function_value) = state.function_arguments.get(name.as_str()) else {
+        return;
+    };
+
+    args.splice(0..0, function_value.capture_exprs());
+    *name = function_value.name.clone();
+}
+
+/// Return the function that is passed to a function argument: a declared function, a
+/// closure, or a function argument of the function that is being checked. A closure is
+/// lifted to a function. The types of the arguments and of the result of the function are
+/// expected to be the given ones, where known.
+fn function_value(
+    arg: &ast::Expr<Typing>,
+    expected_input: Option<&ast_types::DataType>,
+    expected_output: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<FunctionValue> {
+    let function_value = match arg {
+        ast::Expr::Closure(closure) => lift_closure(
+            closure,
+            expected_input,
+            expected_output,
+            state,
+            env_fn_signature,
+        )?,
+        ast::Expr::Var(ast::Identifier::String(function_name, _)) => {
+            if let Some(function_value) = state.function_arguments.get(function_name) {
+                function_value.to_owned()
+            } else {
+                let Some([signature]) = state.ftable.get(function_name).map(|x| x.as_slice())
+                else {
+                    bail!("cannot find function `{function_name}`");
+                };
+                let data_type: ast_types::DataType = signature.to_owned().into();
+                let ast_types::DataType::Function(function_type) = data_type else {
+                    unreachable!()
+                };
+                FunctionValue {
+                    name: function_name.to_owned(),
+                    captures: vec![],
+                    function_type: *function_type,
+                }
+            }
+        }
+        _ => bail!("only functions and closures can be passed to function arguments, got {arg}"),
+    };
+
+    let function_type = &function_value.function_type;
+    if let Some(expected_input) = expected_input {
+        ensure!(
+            function_type.input_argument == *expected_input,
+            "expected a function taking `{expected_input}`, got `{}`",
+            function_value.name
+        );
+    }
+    if let Some(expected_output) = expected_output {
+        ensure!(
+            function_type.output == *expected_output,
+            "expected a function returning `{expected_output}`, got `{}`",
+            function_value.name
+        );
+    }
+
+    Ok(function_value)
+}
+
+/// Lift a closure to a function, which takes the values captured by the closure as
+/// arguments before the arguments of the closure, and type check the function. The types of
+/// the arguments of the closure must be annotated or expected.
+fn lift_closure(
+    closure: &ast::Closure<Typing>,
+    expected_input: Option<&ast_types::DataType>,
+    expected_output: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<FunctionValue> {
+    // Closures with several arguments take them as a tuple
+    let expected_arg_types = match (expected_input, closure.args.len()) {
+        (None, arg_count) => vec![None; arg_count],
+        (Some(expected_input), 1) => vec![Some(expected_input)],
+        (Some(ast_types::DataType::Tuple(tuple)), arg_count) if tuple.fields.len() == arg_count => {
+            tuple.fields.iter().map(Some).collect_vec()
+        }
+        (Some(expected_input), arg_count) => {
+            bail!("expected a closure taking `{expected_input}`, got one taking {arg_count} arguments")
+        }
+    };
+    let mut args = vec![];
+    for ((arg_name, annotated_type), expected_type) in
+        closure.args.iter().zip_eq(expected_arg_types)
+    {
+        let data_type = match (annotated_type.as_ref(), expected_type) {
+            (Some(annotated_type), Some(expected_type)) => {
+                ensure!(
+                    annotated_type == expected_type,
+                    "expected closure argument `{arg_name}` of type `{expected_type}`, got \
+                     `{annotated_type}`"
+                );
+                annotated_type
+            }
+            (Some(data_type), None) | (None, Some(data_type)) => data_type,
+            (None, None) => bail!("type annotations needed for closure argument `{arg_name}`"),
+        };
+        args.push(ast_types::AbstractValueArg {
+            name: arg_name.to_owned(),
+            data_type: data_type.to_owned(),
+            mutable: false,
+        });
+    }
+
+    let closure_name = format!(
+        "{}___closure_{}",
+        env_fn_signature.name, state.monomorphizations.closure_count
+    );
+    state.monomorphizations.closure_count += 1;
+
+    // The body is checked once, as the body of the function. The variables of the enclosing
+    // functions are captured when the body uses them.
+    let mut bindings = state
+        .enclosing_scope
+        .as_ref()
+        .map(|enclosing_scope| enclosing_scope.bindings.clone())
+        .unwrap_or_default();
+    bindings.extend(
+        state
+            .vtable
+            .iter()
+            .map(|(name, binding)| (name.to_owned(), Some(binding.data_type.to_owned()))),
+    );
+    bindings.extend(
+        state
+            .uninferred_bindings
+            .iter()
+            .map(|(name, binding)| (name.to_owned(), binding.data_type.to_owned())),
+    );
+    let output_hint = closure.output.as_ref().or(expected_output);
+    let mut signature = ast::FnSignature {
+        name: closure_name.clone(),
+        args: args
+            .iter()
+            .cloned()
+            .map(ast_types::AbstractArgument::ValueArgument)
+            .collect_vec(),
+        output: output_hint
+            .cloned()
+            .unwrap_or_else(ast_types::DataType::unit),
+        arg_evaluation_order: Default::default(),
+    };
+    let mut closure_state = CheckState {
+        libraries: state.libraries,
+        vtable: args
+            .iter()
+            .map(|arg| (arg.name.to_owned(), arg.to_owned().into()))
+            .collect(),
+        ftable: state.ftable.clone(),
+        composite_types: state.composite_types,
+        loop_labels: vec![],
+        diagnostics: vec![],
+        uninferred_bindings: HashMap::default(),
+        shadowed_uninferred_bindings: vec![],
+        statics: state.statics,
+        monomorphizations: state.monomorphizations,
+        function_arguments: state.function_arguments.clone(),
+        enclosing_scope: Some(EnclosingScope {
+            bindings,
+            captures: vec![],
+        }),
+        stmt_location: state.stmt_location.clone(),
+        hidden_binding_count: 0,
+    };
+    let mut body = closure.body.as_ref().to_owned();
+    let body_type =
+        derive_annotate_expr_type(&mut body, output_hint, &mut closure_state, &signature);
+    let CheckState {
+        diagnostics,
+        enclosing_scope,
+        ..
+    } = closure_state;
+    for error in diagnostics {
+        state.report(error);
+    }
+    let body_type = body_type?;
+    let output = match &closure.output {
+        Some(output) => {
+            check_type_equals(&body_type, output, "the body of the closure")?;
+            output.to_owned()
+        }
+        None => body_type,
+    };
+
+    let captures = enclosing_scope.unwrap().captures;
+    let input_argument = match args.len() {
+        1 => args[0].data_type.to_owned(),
+        _ => ast_types::DataType::Tuple(
+            args.iter()
+                .map(|arg| arg.data_type.clone())
+                .collect_vec()
+                .into(),
+        ),
+    };
+    let capture_args = captures.iter().map(|(name, data_type)| {
+        ast_types::AbstractArgument::ValueArgument(ast_types::AbstractValueArg {
+            name: name.to_owned(),
+            data_type: data_type.to_owned(),
+            mutable: false,
+        })
+    });
+    signature.args = capture_args.chain(signature.args).collect_vec();
+    signature.output = output.clone();
+    if let Err(error) = check_arguments_stack_size(signature.input_arguments_stack_size()) {
+        state.report(error);
+    }
+
+    let function = ast::Fn {
+        signature,
+        body: ast::RoutineBody::Ast(vec![ast::Stmt::Return(Some(body))]),
+        type_parameters: vec![],
+        declared_type_parameters: vec![],
+        trait_bounds: vec![],
+    };
+    state
+        .ftable
+        .insert(closure_name.clone(), vec![function.signature.clone()]);
+    state.monomorphizations.functions.push(function);
+
+    Ok(FunctionValue {
+        name: closure_name,
+        captures,
+        function_type: ast_types::FunctionType {
+            input_argument,
+            output,
+        },
+    })
+}
+
+/// If a list is mapped with a function, a closure, or a function argument, like in
+/// `v.into_iter().map(|x| x * two).collect_vec()`, check that the function takes the elements
+/// of the list, and lift the closure to a function. If the function captures values, return
+/// a call to a function that maps the list by calling the function for each element, since
+/// the values cannot be passed through the `map` snippet.
+fn lower_map_call(
+    expr: &mut ast::Expr<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<Option<ast::Expr<Typing>>> {
+    let ast::Expr::MethodCall(method_call) = expr else {
+        return Ok(None);
+    };
+    let is_function_value = match method_call.args.get(1) {
+        Some(ast::Expr::Closure(_)) => true,
+        Some(ast::Expr::Var(ast::Identifier::String(name, _))) => {
+            let is_variable = state.vtable.contains_key(name)
+                || state.uninferred_bindings.contains_key(name)
+                || state.statics.contains_key(name)
+                || state
+                    .enclosing_scope
+                    .as_ref()
+                    .is_some_and(|enclosing_scope| enclosing_scope.bindings.contains_key(name));
+            !is_variable
+        }
+        _ => false,
+    };
+    if method_call.method_name != "map" || method_call.args.len() != 2 || !is_function_value {
+        return Ok(None);
+    }
+
+    let receiver_type =
+        derive_annotate_expr_type(&mut method_call.args[0], None, state, env_fn_signature)?;
+    let ast_types::DataType::List(element_type, list_type) = &receiver_type else {
+        bail!("only lists can be mapped, got `{receiver_type}`");
+    };
+    if let ast::Expr::Var(ast::Identifier::String(name, _)) = &method_call.args[1] {
+        ensure!(
+            !state.monomorphizations.generic_functions.contains_key(name),
+            "cannot map a list with `{name}`, which is generic or takes a function argument"
+        );
+    }
+    let function_value = function_value(
+        &method_call.args[1],
+        Some(element_type.as_ref()),
+        None,
+        state,
+        env_fn_signature,
+    )?;
+    if function_value.captures.is_empty() {
+        method_call.args[1] = ast::Expr::Var(ast::Identifier::String(
+            function_value.name,
+            Typing::default(),
+        ));
+        return Ok(None);
+    }
+
+    let map_function_name = format!("{}___map", function_value.name);
+    if !state
+        .monomorphizations
+        .signatures
+        .contains_key(&map_function_name)
+    {
+        let var = |name: &str| {
+            ast::Expr::Var(ast::Identifier::String(name.to_owned(), Typing::default()))
+        };
+
+        // The bindings of the function have hidden names, such that they cannot collide with
+        // the names of the captured values
+        let n = state.monomorphizations.closure_count;
+        let list = format!("_map_list_{n}");
+        let result = format!("_map_result_{n}");
+        let element = format!("_map_element_{n}");
+        let output_type = ast_types::DataType::List(
+            Box::new(function_value.function_type.output.clone()),
+            *list_type,
+        );
+        let capture_args = function_value.captures.iter().map(|(capture, data_type)| {
+            ast_types::AbstractValueArg {
+                name: capture.to_owned(),
+                data_type: data_type.to_owned(),
+                mutable: false,
+            }
+        });
+        let list_arg = ast_types::AbstractValueArg {
+            name: list.clone(),
+            data_type: receiver_type.clone(),
+            mutable: false,
+        };
+
+        // let mut result: Vec<U> = Vec::<U>::with_capacity(list.len());
+        // for element in list {
+        //     result.push(f(<captures>, element));
+        // }
+        // return result;
+        let declare_result = ast::Stmt::Let(ast::LetStmt {
+            var_name: result.clone(),
+            mutable: true,
+            data_type: Some(output_type.clone()),
+            expr: ast::Expr::FnCall(ast::FnCall {
+                name: "Vec::with_capacity".to_owned(),
+                args: vec![ast::Expr::MethodCall(ast::MethodCall {
+                    method_name: "len".to_owned(),
+                    args: vec![var(&list)],
+                    annot: Typing::default(),
+                    associated_type: None,
+                })],
+                type_arguments: vec![Some(function_value.function_type.output.clone())],
+                arg_evaluation_order: Default::default(),
+                annot: Typing::default(),
+            }),
+        });
+        let push_mapped_element = ast::Stmt::MethodCall(ast::MethodCall {
+            method_name: "push".to_owned(),
+            args: vec![
+                var(&result),
+                ast::Expr::FnCall(ast::FnCall {
+                    name: function_value.name.clone(),
+                    args: [function_value.capture_exprs(), vec![var(&element)]].concat(),
+                    type_arguments: vec![],
+                    arg_evaluation_order: Default::default(),
+                    annot: Typing::default(),
+                }),
+            ],
+            annot: Typing::default(),
+            associated_type: None,
+        });
+        let map_elements = ast::Stmt::For(ast::ForStmt {
+            loop_variable: Some(ast::PatternMatchedBinding {
+                name: element,
+                mutable: false,
+            }),
+            iterable: ast::ForIterable::Collection(var(&list)),
+            block: ast::BlockStmt {
+                stmts: vec![push_mapped_element],
+            },
+            label: None,
+        });
+        let mut function = ast::Fn {
+            signature: ast::FnSignature {
+                name: map_function_name.clone(),
+                args: capture_args
+                    .chain([list_arg])
+                    .map(ast_types::AbstractArgument::ValueArgument)
+                    .collect_vec(),
+                output: output_type,
+                arg_evaluation_order: Default::default(),
+            },
+            body: ast::RoutineBody::Ast(vec![
+                declare_result,
+                map_elements,
+                ast::Stmt::Return(Some(var(&result))),
+            ]),
+            type_parameters: vec![],
+            declared_type_parameters: vec![],
+            trait_bounds: vec![],
+        };
+        state
+            .monomorphizations
+            .signatures
+            .insert(map_function_name.clone(), function.signature.clone());
+        let function_errors = annotate_fn_inner(
+            &mut function,
+            state.composite_types,
+            state.libraries,
+            state.ftable.clone(),
+            state.statics,
+            state.monomorphizations,
+            HashMap::default(),
+        );
+        state.diagnostics.extend(function_errors);
+        state.monomorphizations.functions.push(function);
+    }
+
+    let signature = state.monomorphizations.signatures[&map_function_name].clone();
+    state
+        .ftable
+        .insert(map_function_name.clone(), vec![signature]);
+    let list = method_call.args[0].to_owned();
+    Ok(Some(ast::Expr::FnCall(ast::FnCall {
+        name: map_function_name,
+        args: [function_value.capture_exprs(), vec![list]].concat(),
+        type_arguments: vec![],
+        arg_evaluation_order: Default::default(),
+        annot: Typing::default(),
+    })))
+}
+
+/// Return true if a concrete type implements a trait that a type parameter is bounded by.
+/// The primitive types implement the traits of the operators they support, and custom
+/// types implement the traits they derive or have an `impl Trait for Type` block for.
+fn implements_trait(
+    data_type: &ast_types::DataType,
+    trait_name: &str,
+    composite_types: &CompositeTypes,
+) -> bool {
+    let has_trait_impl = || {
+        composite_types
+            .get_by_type(data_type)
+            .is_some_and(|type_ctx| type_ctx.traits.iter().any(|name| name == trait_name))
+    };
+    match trait_name {
+        "Copy" => data_type.is_copy(),
+        "Clone" => true,
+        "PartialEq" | "Eq" => data_type.is_partial_eq() || has_trait_impl(),
+        "PartialOrd" | "Ord" => is_integer_type(data_type),
+        "Add" | "Sub" | "Mul" | "Div" => is_arithmetic_type(data_type) || has_trait_impl(),
+        "Neg" => is_negatable_type(data_type) || has_trait_impl(),
+        "Not" => type_compatible_with_not(data_type) || has_trait_impl(),
+        "Rem" | "BitAnd" | "BitOr" | "BitXor" | "Shl" | "Shr" => {
+            is_integer_type(data_type) || has_trait_impl()
+        }
+        _ => has_trait_impl(),
+    }
+}
+
+/// Return a type declared by a generic function, with the concrete types of its type
+/// parameters substituted. Returns `None` if the type contains a type parameter whose
+/// concrete type is not yet known.
+fn instantiate_declared_type(
+    declared_type: &ast_types::DataType,
+    type_parameters: &[String],
+    type_arguments: &HashMap<String, ast_types::DataType>,
+    composite_types: &CompositeTypes,
+) -> Option<ast_types::DataType> {
+    let contains_unknown_type_parameter = type_parameters.iter().any(|type_parameter| {
+        !type_arguments.contains_key(type_parameter)
+            && contains_type_parameter(declared_type, type_parameter)
+    });
+    if contains_unknown_type_parameter {
+        return None;
+    }
+
+    let mut data_type = declared_type.to_owned();
+    data_type.resolve_custom_types(&composite_types.with_type_arguments(type_arguments.clone()));
+    Some(data_type)
+}
+
+fn contains_type_parameter(data_type: &ast_types::DataType, type_parameter: &str) -> bool {
+    use ast_types::DataType::*;
+    match data_type {
+        Unresolved(name) => name == type_parameter,
+        List(inner, _) | Boxed(inner) => contains_type_parameter(inner, type_parameter),
+        Array(array_type) => contains_type_parameter(&array_type.element_type, type_parameter),
+        Tuple(tuple) => tuple
+            .fields
+            .iter()
+            .any(|field| contains_type_parameter(field, type_parameter)),
+        Function(function_type) => {
+            contains_type_parameter(&function_type.input_argument, type_parameter)
+                || contains_type_parameter(&function_type.output, type_parameter)
+        }
+        _ => false,
+    }
+}
+
+/// Find the concrete types of the type parameters occurring in the declared type of an
+/// argument or a result of a generic function, by comparing it with the actual type. Type
+/// parameters whose type is already known are left as they are; a conflicting type is
+/// reported when the call is type checked against the instantiation.
+fn bind_type_parameters(
+    declared_type: &ast_types::DataType,
+    actual_type: &ast_types::DataType,
+    type_parameters: &[String],
+    type_arguments: &mut HashMap<String, ast_types::DataType>,
+) {
+    use ast_types::DataType::*;
+    match (declared_type, actual_type) {
+        (Unresolved(name), _) if type_parameters.contains(name) => {
+            type_arguments
+                .entry(name.to_owned())
+                .or_insert_with(|| actual_type.to_owned());
+        }
+        (List(declared_element, _), List(actual_element, _))
+        | (Boxed(declared_element), Boxed(actual_element)) => bind_type_parameters(
+            declared_element,
+            actual_element,
+            type_parameters,
+            type_arguments,
+        ),
+        (Array(declared_array), Array(actual_array)) => bind_type_parameters(
+            &declared_array.element_type,
+            &actual_array.element_type,
+            type_parameters,
+            type_arguments,
+        ),
+        (Tuple(declared_tuple), Tuple(actual_tuple)) => {
+            for (declared_element, actual_element) in
+                declared_tuple.fields.iter().zip(actual_tuple.fields.iter())
+            {
+                bind_type_parameters(
+                    declared_element,
+                    actual_element,
+                    type_parameters,
+                    type_arguments,
+                );
+            }
+        }
+        (Function(declared_function), Function(actual_function)) => {
+            bind_type_parameters(
+                &declared_function.input_argument,
+                &actual_function.input_argument,
+                type_parameters,
+                type_arguments,
+            );
+            bind_type_parameters(
+                &declared_function.output,
+                &actual_function.output,
+                type_parameters,
+                type_arguments,
+            );
+        }
+        _ => (),
+    }
+}
 
-            (element_type, mutable, None)
+/// Instantiate a generic function or a function taking function arguments for concrete types
+/// and functions, and type check the instantiation. Errors are reported in the instantiated
+/// function.
+fn instantiate_generic_function(
+    mut function: ast::Fn<Typing>,
+    mut ftable: HashMap<String, Vec<ast::FnSignature>>,
+    instantiation_name: &str,
+    type_arguments: HashMap<String, ast_types::DataType>,
+    function_values: HashMap<String, FunctionValue>,
+    state: &mut CheckState,
+) {
+    // The function is checked in the scope where it is declared, in which the functions
+    // passed to it from the call site can be called as well
+    for function_value in function_values.values() {
+        if let Some(signatures) = state.ftable.get(&function_value.name) {
+            ftable.insert(function_value.name.clone(), signatures.clone());
         }
+    }
 
-        // x.foo
-        ast::Identifier::Field(ident, field_id, annot) => {
-            let (receiver_type, mutable, rewrite_expr) =
-                annotate_identifier_type(ident, None, state, fn_signature);
-            assert!(
-                rewrite_expr.is_none(),
-                "Cannot rewrite expression outside of atomic variable expression"
-            );
-            // Only structs have fields, so receiver_type must be a struct, or a pointer to a struct.
-            let data_type = receiver_type.field_access_returned_type(field_id);
-            *annot = Typing::KnownType(data_type.clone());
+    let generic_name = function.signature.name.clone();
+    let composite_types = state.composite_types.with_type_arguments(type_arguments);
+    function.type_parameters.clear();
+    function.declared_type_parameters.clear();
+    function.trait_bounds.clear();
+    function.resolve_custom_types(&composite_types);
+    function.signature.name = instantiation_name.to_owned();
+
+    // A function argument is replaced by arguments holding the values captured by the
+    // function passed to it, which are named after the function argument
+    let mut function_arguments = HashMap::default();
+    let mut args = vec![];
+    for arg in std::mem::take(&mut function.signature.args) {
+        let ast_types::AbstractArgument::FunctionArgument(function_arg) = arg else {
+            args.push(arg);
+            continue;
+        };
+        let mut function_value = function_values[&function_arg.abstract_name].clone();
+        for (capture, data_type) in function_value.captures.iter_mut() {
+            *capture = format!("{}___{capture}", function_arg.abstract_name);
+            args.push(ast_types::AbstractArgument::ValueArgument(
+                ast_types::AbstractValueArg {
+                    name: capture.to_owned(),
+                    data_type: data_type.to_owned(),
+                    mutable: false,
+                },
+            ));
+        }
+        function_arguments.insert(function_arg.abstract_name, function_value);
+    }
+    function.signature.args = args;
+
+    // The signature is known before the body is checked, such that the instantiation can
+    // call itself
+    state
+        .monomorphizations
+        .signatures
+        .insert(instantiation_name.to_owned(), function.signature.clone());
+    let function_errors = annotate_fn_inner(
+        &mut function,
+        state.composite_types,
+        state.libraries,
+        ftable,
+        state.statics,
+        state.monomorphizations,
+        function_arguments,
+    );
+    state.diagnostics.extend(
+        function_errors
+            .into_iter()
+            .map(|error| error.in_function(&generic_name)),
+    );
+    state.monomorphizations.functions.push(function);
+}
 
-            (data_type, mutable, None)
+/// Return the instance of the generic struct or enum `type_name` that is expected by the type
+/// hint. Expressions like `Pair { a, b }` only name the generic type.
+fn generic_type_instance_from_hint(
+    type_name: &str,
+    hint: Option<&ast_types::DataType>,
+) -> anyhow::Result<ast_types::DataType> {
+    match hint {
+        Some(instance @ ast_types::DataType::Struct(struct_type))
+            if struct_type.name == type_name =>
+        {
+            Ok(instance.to_owned())
         }
+        Some(instance @ ast_types::DataType::Enum(enum_type)) if enum_type.name == type_name => {
+            Ok(instance.to_owned())
+        }
+        _ => bail!("type annotations needed: cannot infer the type arguments of `{type_name}`"),
     }
 }
 
 fn get_fn_signature(
     name: &str,
     state: &CheckState,
-    type_parameter: &Option<ast_types::DataType>,
+    type_arguments: &[Option<ast_types::DataType>],
     args: &[ast::Expr<Typing>],
-    env_fn_signature: &ast::FnSignature,
     output_type_hint: Option<&ast_types::DataType>,
-) -> ast::FnSignature {
+) -> anyhow::Result<ast::FnSignature> {
     match state.ftable.get(name) {
         None => (),
         Some(fn_signatures) => {
             match fn_signatures.len() {
                 0 => unreachable!(),
-                1 => return fn_signatures[0].clone(),
+                1 => return Ok(fn_signatures[0].clone()),
                 _ => {
                     // find the 1st function that matches the types and matches the output
                     // type *if* a type hint is provided.
@@ -829,17 +4520,16 @@ fn get_fn_signature(
                             match output_type_hint {
                                 Some(some_hint) => {
                                     if sig.output == *some_hint {
-                                        return sig.to_owned();
+                                        return Ok(sig.to_owned());
                                     }
                                 }
-                                None => return sig.to_owned(),
+                                None => return Ok(sig.to_owned()),
                             }
                         }
                     }
 
-                    println!("args[0].get_type: {:#?}", args[0].get_type());
-                    todo!("\nname: {name}\ntype_parameter: {type_parameter:#?}")
-                    // <-- We need to inspect types for this case
+                    let arg_types = types.iter().map(|t| format!("`{t}`")).join(", ");
+                    bail!("no function `{name}` takes arguments of types ({arg_types})");
                 }
             }
         }
@@ -847,27 +4537,32 @@ fn get_fn_signature(
 
     match state
         .composite_types
-        .get_associated_function_signature(name)
+        .get_associated_function_signature(name, args, output_type_hint)
     {
         None => (),
-        Some(afunc) => return afunc.to_owned(),
+        Some(afunc) => return Ok(afunc.to_owned()),
     };
 
     // Function from libraries are in scope
     for lib in state.libraries.iter() {
         if let Some(fn_name) = lib.get_function_name(name) {
-            return lib.function_name_to_signature(&fn_name, type_parameter.to_owned(), args);
+            let mut type_parameters = type_arguments.iter().flatten();
+            let type_parameter = type_parameters.next().cloned();
+            ensure!(
+                type_parameters.next().is_none(),
+                "`{name}` takes at most one type argument"
+            );
+            return Ok(lib.function_name_to_signature(&fn_name, type_parameter, args));
         }
     }
 
-    panic!("Function call in {} Don't know what type of value '{name}' returns! Type parameter was: {type_parameter:?}. ftable is:\n{}", env_fn_signature.name, state.ftable.keys().join("\n"))
+    bail!("cannot find function `{name}` in this scope")
 }
 
 fn get_method_signature(
     state: &CheckState,
     method_call: &mut MethodCall<Typing>,
-    env_fn_signature: &ast::FnSignature,
-) -> ast::FnSignature {
+) -> anyhow::Result<ast::FnSignature> {
     // Implemented following the description from: https://stackoverflow.com/a/28552082/2574407
     // TODO: Handle automatic dereferencing and referencing of MemPointer types
     let original_receiver_type = method_call.args[0].get_type();
@@ -887,7 +4582,7 @@ fn get_method_signature(
                             var.force_type(&forced_type);
                         }
 
-                        return method.signature.to_owned();
+                        return Ok(method.signature.to_owned());
                     }
 
                     let auto_boxed_forced_type =
@@ -898,7 +4593,7 @@ fn get_method_signature(
                         //     var.force_type(&auto_boxed_forced_type);
                         // }
 
-                        return method.signature.to_owned();
+                        return Ok(method.signature.to_owned());
                     }
                 }
             }
@@ -913,12 +4608,12 @@ fn get_method_signature(
                     var.force_type(&forced_type);
                 }
 
-                return lib.method_name_to_signature(
+                return Ok(lib.method_name_to_signature(
                     &method_name,
                     &forced_type,
                     &method_call.args,
                     state,
-                );
+                ));
             }
         }
 
@@ -930,27 +4625,21 @@ fn get_method_signature(
         }
     }
 
-    let declared_types = state.composite_types.all_composite_type_names();
-    let declared_method_names = state.composite_types.all_method_names();
-    panic!(
-        "Method call in '{}' Don't know what type of value '{}' returns!
-         Receiver type was: {original_receiver_type:?}
-         \n\nDeclared methods are:\n{declared_method_names}\n\n Declared types are:\n{declared_types}\n\n",
-        env_fn_signature.name, method_call.method_name,
-    );
+    bail!(
+        "no method named `{}` found for value of type `{original_receiver_type}`",
+        method_call.method_name
+    )
 }
 
 fn derive_annotate_fn_call_args(
     callees_fn_signature: &ast::FnSignature,
     args: &mut [ast::Expr<Typing>],
     state: &mut CheckState,
-) {
+) -> anyhow::Result<()> {
     let fn_name = &callees_fn_signature.name;
-    assert_eq!(
-        callees_fn_signature.args.len(),
-        args.len(),
-        "Wrong number of arguments in function call to '{}'; expected {} arguments, got {}.",
-        fn_name,
+    ensure!(
+        callees_fn_signature.args.len() == args.len(),
+        "`{fn_name}` takes {} arguments but {} arguments were supplied",
         callees_fn_signature.args.len(),
         args.len(),
     );
@@ -964,14 +4653,14 @@ fn derive_annotate_fn_call_args(
                 // arg_expr must evaluate to a FnCall here
 
                 let arg_hint = Some(&abstract_function.function_type.input_argument);
-                derive_annotate_expr_type(arg_expr, arg_hint, state, callees_fn_signature).unwrap()
+                derive_annotate_expr_type(arg_expr, arg_hint, state, callees_fn_signature)
             }
             ast_types::AbstractArgument::ValueArgument(abstract_value) => {
                 let arg_hint = Some(&abstract_value.data_type);
-                derive_annotate_expr_type(arg_expr, arg_hint, state, callees_fn_signature).unwrap()
+                derive_annotate_expr_type(arg_expr, arg_hint, state, callees_fn_signature)
             }
         })
-        .collect();
+        .try_collect()?;
 
     // Compare list of concrete arguments with function signature, i.e. expected arguments
     for (arg_pos, (fn_arg, expr_type)) in callees_fn_signature
@@ -984,24 +4673,195 @@ fn derive_annotate_fn_call_args(
             ast_types::AbstractArgument::FunctionArgument(ast_types::AbstractFunctionArg {
                 abstract_name: _,
                 function_type,
-            }) => assert_type_equals(
-                &ast_types::DataType::Function(Box::new(function_type.to_owned())),
+            }) => check_type_equals(
                 expr_type,
-                "Function argument",
-            ),
+                &ast_types::DataType::Function(Box::new(function_type.to_owned())),
+                &format!("argument {} of `{fn_name}`", arg_pos + 1),
+            )?,
             ast_types::AbstractArgument::ValueArgument(ast_types::AbstractValueArg {
-                name: arg_name,
+                name: _,
                 data_type: arg_type,
                 mutable: _mutable,
-            }) => {
-                let arg_pos = arg_pos + 1;
-                assert_eq!(
-                arg_type, expr_type,
-                "Wrong type of function argument {arg_pos} function call '{arg_name}' in '{fn_name}'\n \nexpected type \"{arg_type}\", but got type  \"{expr_type}\"\n\n",
-            );
+            }) => check_type_equals(
+                expr_type,
+                arg_type,
+                &format!("argument {} of `{fn_name}`", arg_pos + 1),
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Annotate the operands of a binary operator whose operands have the same type. If the
+/// type of the left-hand side cannot be derived on its own, like for number literals, it
+/// is derived from the type of the right-hand side.
+fn derive_annotate_operand_types(
+    lhs_expr: &mut ast::Expr<Typing>,
+    rhs_expr: &mut ast::Expr<Typing>,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<(ast_types::DataType, ast_types::DataType)> {
+    let maybe_lhs_type = derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature);
+    derive_annotate_rhs_operand_type(
+        maybe_lhs_type,
+        lhs_expr,
+        rhs_expr,
+        hint,
+        state,
+        env_fn_signature,
+    )
+}
+
+/// Annotate the right-hand side of a binary operator whose left-hand side has been
+/// annotated, or whose type could not be derived on its own
+fn derive_annotate_rhs_operand_type(
+    maybe_lhs_type: anyhow::Result<ast_types::DataType>,
+    lhs_expr: &mut ast::Expr<Typing>,
+    rhs_expr: &mut ast::Expr<Typing>,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<(ast_types::DataType, ast_types::DataType)> {
+    let rhs_hint = match maybe_lhs_type {
+        Ok(ref ty) => Some(ty),
+        Err(_) => hint,
+    };
+    let rhs_type = derive_annotate_expr_type(rhs_expr, rhs_hint, state, env_fn_signature)?;
+    let lhs_type = match maybe_lhs_type {
+        Ok(ty) => ty,
+        Err(_) => derive_annotate_expr_type(lhs_expr, Some(&rhs_type), state, env_fn_signature)?,
+    };
+
+    Ok((lhs_type, rhs_type))
+}
+
+/// The types of the operands of an operator that custom types can overload, or the call of
+/// the method overloading the operator
+enum OperandTypes {
+    Primitive(ast_types::DataType, ast_types::DataType),
+    Overloaded(ast::Expr<Typing>, ast_types::DataType),
+}
+
+/// Annotate the operands of `+`, `-`, `*`, `==`, or `!=`. If the left-hand side is of a
+/// custom type with a method implementing the operator trait, the application becomes a call
+/// to the method, e.g. `a + b` becomes `a.add(b)`. Since only `eq` is required by
+/// `PartialEq`, `a != b` becomes `!a.eq(b)`. Each operand is annotated once.
+fn derive_annotate_overloadable_operand_types(
+    method_name: &str,
+    negated: bool,
+    lhs_expr: &mut ast::Expr<Typing>,
+    rhs_expr: &mut ast::Expr<Typing>,
+    hint: Option<&ast_types::DataType>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<OperandTypes> {
+    let maybe_lhs_type = derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature);
+    if let Ok(lhs_type) = &maybe_lhs_type {
+        if has_operator_method(lhs_type, method_name, state) {
+            let mut method_call = ast::MethodCall {
+                method_name: method_name.to_owned(),
+                args: vec![lhs_expr.to_owned(), rhs_expr.to_owned()],
+                annot: Typing::default(),
+                associated_type: None,
+            };
+            let output_type =
+                annotate_operator_method_call(&mut method_call, state, env_fn_signature)?;
+            let method_call = ast::Expr::MethodCall(method_call);
+            if !negated {
+                return Ok(OperandTypes::Overloaded(method_call, output_type));
             }
+
+            check_type_equals(&output_type, &ast_types::DataType::Bool, "`eq`")?;
+            let negated_method_call = ast::Expr::Unary(
+                ast::UnaryOp::Not,
+                Box::new(method_call),
+                Typing::KnownType(ast_types::DataType::Bool),
+            );
+            return Ok(OperandTypes::Overloaded(negated_method_call, output_type));
         }
     }
+
+    let (lhs_type, rhs_type) = derive_annotate_rhs_operand_type(
+        maybe_lhs_type,
+        lhs_expr,
+        rhs_expr,
+        hint,
+        state,
+        env_fn_signature,
+    )?;
+    Ok(OperandTypes::Primitive(lhs_type, rhs_type))
+}
+
+/// Return true if the operand type is a custom type with a method implementing an operator
+/// trait. Types deriving `PartialEq` are compared structurally.
+fn has_operator_method(
+    operand_type: &ast_types::DataType,
+    method_name: &str,
+    state: &CheckState,
+) -> bool {
+    matches!(
+        operand_type,
+        ast_types::DataType::Struct(_) | ast_types::DataType::Enum(_)
+    ) && state
+        .composite_types
+        .get_by_type(operand_type)
+        .is_some_and(|type_ctx| type_ctx.get_method(method_name).is_some())
+}
+
+/// Annotate the call of a method implementing an operator trait, whose receiver has been
+/// annotated. Returns the type of the result.
+fn annotate_operator_method_call(
+    method_call: &mut ast::MethodCall<Typing>,
+    state: &mut CheckState,
+    env_fn_signature: &ast::FnSignature,
+) -> anyhow::Result<ast_types::DataType> {
+    let signature = get_method_signature(state, method_call)?;
+    let method_name = &method_call.method_name;
+    ensure!(
+        signature.args.len() == method_call.args.len(),
+        "`{method_name}` must take {} arguments to implement an operator",
+        method_call.args.len()
+    );
+    for (arg, parameter) in method_call.args.iter_mut().zip(&signature.args).skip(1) {
+        let ast_types::AbstractArgument::ValueArgument(parameter) = parameter else {
+            bail!("`{method_name}` cannot take a function argument to implement an operator");
+        };
+        let arg_type =
+            derive_annotate_expr_type(arg, Some(&parameter.data_type), state, env_fn_signature)?;
+        check_type_equals(
+            &arg_type,
+            &parameter.data_type,
+            &format!("the argument of `{method_name}`"),
+        )?;
+    }
+    method_call.annot = Typing::KnownType(signature.output.clone());
+
+    Ok(signature.output)
+}
+
+/// Return the literal that a negated integer literal evaluates to, if the literal is
+/// inferred as a signed integer type. This allows writing `-2147483648` as an `i32`,
+/// even though `2147483648` is not an `i32`.
+fn negative_integer_literal(
+    expr: &ast::Expr<Typing>,
+    hint: Option<&ast_types::DataType>,
+) -> Option<ast::Expr<Typing>> {
+    let ast::Expr::Unary(ast::UnaryOp::Neg, inner_expr, _) = expr else {
+        return None;
+    };
+    let ast::Expr::Lit(ast::ExprLit::GenericNum(n, _)) = inner_expr.as_ref() else {
+        return None;
+    };
+    let value = -i128::try_from(*n).ok()?;
+    let literal = match hint? {
+        ast_types::DataType::I32 => ast::ExprLit::I32(value.try_into().ok()?),
+        ast_types::DataType::I64 => ast::ExprLit::I64(value.try_into().ok()?),
+        _ => return None,
+    };
+
+    Some(ast::Expr::Lit(literal))
 }
 
 /// Annotate an expression. This function can return error, if the type information
@@ -1013,11 +4873,25 @@ fn derive_annotate_expr_type(
     state: &mut CheckState,
     env_fn_signature: &ast::FnSignature,
 ) -> anyhow::Result<ast_types::DataType> {
+    if let Some(map_call) = lower_map_call(expr, state, env_fn_signature)? {
+        *expr = map_call;
+    }
+    if let Some(pop_call) = unwrapped_vector_pop(expr, state, env_fn_signature) {
+        *expr = pop_call;
+    }
+    if let Some(literal) = negative_integer_literal(expr, hint) {
+        *expr = literal;
+    }
+
     let res = match expr {
         ast::Expr::Lit(ast::ExprLit::Bool(_)) => Ok(ast_types::DataType::Bool),
+        ast::Expr::Lit(ast::ExprLit::U8(_)) => Ok(ast_types::DataType::U8),
+        ast::Expr::Lit(ast::ExprLit::U16(_)) => Ok(ast_types::DataType::U16),
         ast::Expr::Lit(ast::ExprLit::U32(_)) => Ok(ast_types::DataType::U32),
         ast::Expr::Lit(ast::ExprLit::U64(_)) => Ok(ast_types::DataType::U64),
         ast::Expr::Lit(ast::ExprLit::U128(_)) => Ok(ast_types::DataType::U128),
+        ast::Expr::Lit(ast::ExprLit::I32(_)) => Ok(ast_types::DataType::I32),
+        ast::Expr::Lit(ast::ExprLit::I64(_)) => Ok(ast_types::DataType::I64),
         ast::Expr::Lit(ast::ExprLit::Bfe(_)) => Ok(ast_types::DataType::Bfe),
         ast::Expr::Lit(ast::ExprLit::Xfe(_)) => Ok(ast_types::DataType::Xfe),
         ast::Expr::Lit(ast::ExprLit::Digest(_)) => Ok(ast_types::DataType::Digest),
@@ -1034,45 +4908,71 @@ fn derive_annotate_expr_type(
             use ast_types::DataType::*;
 
             match hint {
+                Some(&U8) => {
+                    let n: u8 = (*n).try_into().map_err(|_| literal_out_of_range(*n, &U8))?;
+                    *expr = ast::Expr::Lit(ast::ExprLit::U8(n));
+                    Ok(U8)
+                }
+                Some(&U16) => {
+                    let n: u16 = (*n).try_into().map_err(|_| literal_out_of_range(*n, &U16))?;
+                    *expr = ast::Expr::Lit(ast::ExprLit::U16(n));
+                    Ok(U16)
+                }
                 Some(&U32) => {
-                    let err = format!("Cannot infer number literal {n} as u32");
-                    let n: u32 = (*n).try_into().expect(&err);
+                    let n: u32 = (*n).try_into().map_err(|_| literal_out_of_range(*n, &U32))?;
                     *expr = ast::Expr::Lit(ast::ExprLit::U32(n));
                     Ok(U32)
                 }
                 Some(&U64) => {
-                    *expr =
-                        ast::Expr::Lit(ast::ExprLit::U64(TryInto::<u64>::try_into(*n).unwrap()));
+                    let n: u64 = (*n).try_into().map_err(|_| literal_out_of_range(*n, &U64))?;
+                    *expr = ast::Expr::Lit(ast::ExprLit::U64(n));
                     Ok(U64)
                 }
                 Some(&U128) => {
-                    *expr =
-                        ast::Expr::Lit(ast::ExprLit::U128(TryInto::<u128>::try_into(*n).unwrap()));
+                    *expr = ast::Expr::Lit(ast::ExprLit::U128(*n));
                     Ok(U128)
                 }
+                Some(&I32) => {
+                    let n: i32 = (*n).try_into().map_err(|_| literal_out_of_range(*n, &I32))?;
+                    *expr = ast::Expr::Lit(ast::ExprLit::I32(n));
+                    Ok(I32)
+                }
+                Some(&I64) => {
+                    let n: i64 = (*n).try_into().map_err(|_| literal_out_of_range(*n, &I64))?;
+                    *expr = ast::Expr::Lit(ast::ExprLit::I64(n));
+                    Ok(I64)
+                }
                 Some(&Bfe) => {
-                    assert!(*n <= BFieldElement::MAX as u128);
-                    let n = BFieldElement::new(TryInto::<u64>::try_into(*n).unwrap());
+                    ensure!(*n <= BFieldElement::MAX as u128, literal_out_of_range(*n, &Bfe));
+                    let n = BFieldElement::new(*n as u64);
                     *expr = ast::Expr::Lit(ast::ExprLit::Bfe(n));
                     Ok(Bfe)
                 }
                 Some(&Xfe) => {
-                    assert!(*n <= BFieldElement::MAX as u128);
-                    let n = BFieldElement::new(TryInto::<u64>::try_into(*n).unwrap());
+                    ensure!(*n <= BFieldElement::MAX as u128, literal_out_of_range(*n, &Xfe));
+                    let n = BFieldElement::new(*n as u64);
                     let n = XFieldElement::new_const(n);
                     *expr = ast::Expr::Lit(ast::ExprLit::Xfe(n));
                     Ok(Xfe)
                 }
-                Some(hint) => panic!("GenericNum does not infer as type hint {hint}"),
+                Some(hint) => bail!("mismatched types: expected `{hint}`, found integer"),
                 None => bail!("GenericNum does not infer in context with no type hint. Missing type hint for: {}", expr),
             }
         }
 
+        ast::Expr::Var(ast::Identifier::String(var_name, var_type))
+            if state.uninferred_bindings.contains_key(var_name) =>
+        {
+            let data_type = infer_binding_type(var_name, hint, state)?;
+            *var_type = Typing::KnownType(data_type.clone());
+            Ok(data_type)
+        }
+
         ast::Expr::Var(identifier) => match identifier.resolved() {
             Some(ty) => Ok(ty.to_owned()),
             None => {
                 let (identifier_type, _mutable, new_expr) =
-                    annotate_identifier_type(identifier, hint, state, env_fn_signature);
+                    annotate_identifier_type(identifier, hint, state, env_fn_signature)?;
                 if let Some(new_expr) = new_expr {
                     *expr = new_expr;
                 }
@@ -1111,17 +5011,20 @@ fn derive_annotate_expr_type(
                             state,
                             env_fn_signature,
                         )?;
-                        assert!(Some(expr_type.to_owned()) == hint || hint.is_none(), "All expressions in array declaration must evaluate to the same type. Got {} and {}.", hint.unwrap(), expr_type);
+                        if let Some(element_type) = &hint {
+                            check_type_equals(&expr_type, element_type, "array elements")?;
+                        }
                         hint = Some(expr_type);
                     }
 
                     elem_expressions.len()
                 }
             };
+            let Some(element_type) = hint else {
+                bail!("type annotations needed for empty array");
+            };
             let derived_type = ast_types::DataType::Array(ast_types::ArrayType {
-                element_type: Box::new(
-                    hint.expect("Cannot derive type for empty array").to_owned(),
-                ),
+                element_type: Box::new(element_type),
                 length,
             });
             *array_type = Typing::KnownType(derived_type.clone());
@@ -1129,50 +5032,62 @@ fn derive_annotate_expr_type(
         }
 
         ast::Expr::Struct(struct_expr) => {
-            let struct_type =
-                if let ast_types::DataType::Struct(struct_type) = &struct_expr.struct_type {
-                    struct_type.to_owned()
-                } else {
-                    panic!("Type in struct declaration must be Struct type");
-                };
-            let struct_type =
-                if let ast_types::StructVariant::NamedFields(named_fields) = &struct_type.variant {
-                    named_fields.to_owned()
-                } else {
-                    panic!("Type in struct declaration must be struct with named fields");
-                };
+            if let Err(error) = enum_variant_constructor_call(struct_expr, state.composite_types) {
+                bail!(error);
+            }
+            if let ast_types::DataType::Unresolved(type_name) = &struct_expr.struct_type {
+                struct_expr.struct_type = generic_type_instance_from_hint(type_name, hint)?;
+            }
+            let struct_type = match &struct_expr.struct_type {
+                ast_types::DataType::Struct(ast_types::StructType {
+                    variant: ast_types::StructVariant::NamedFields(named_fields),
+                    ..
+                }) => named_fields.to_owned(),
+                other => bail!("`{other}` is not a struct with named fields"),
+            };
 
             // Perform type-checking of each field, ensure all fields are defined
             let mut remaining_fields = struct_type.fields.clone();
             for (field_name, expr) in struct_expr.field_names_and_values.iter_mut() {
+                ensure!(
+                    remaining_fields.iter().any(|(name, _)| name == field_name),
+                    match struct_type
+                        .fields
+                        .iter()
+                        .any(|(name, _)| name == field_name)
+                    {
+                        true => format!("field `{field_name}` specified more than once"),
+                        false => format!(
+                            "struct `{}` has no field named `{field_name}`",
+                            struct_type.name
+                        ),
+                    }
+                );
                 let field_id = ast_types::FieldId::NamedField(field_name.to_owned());
                 let expected_field_type = struct_expr
                     .struct_type
-                    .field_access_returned_type(&field_id);
+                    .field_access_returned_type(&field_id)?;
                 let derived_field_type = derive_annotate_expr_type(
                     expr,
                     Some(&expected_field_type),
                     state,
                     env_fn_signature,
-                )
-                .expect("Must know result type here");
-                assert_type_equals(
+                )?;
+                check_type_equals(
                     &derived_field_type,
                     &expected_field_type,
-                    &format!("Struct initialization of {}", struct_expr.struct_type),
-                );
-
-                let m = remaining_fields
-                    .iter()
-                    .filter(|(name, _)| name == field_name)
-                    .collect_vec();
-                assert!(m.len().is_one());
+                    &format!("field `{field_name}` of `{}`", struct_type.name),
+                )?;
                 remaining_fields.retain(|(name, _)| name != field_name);
             }
 
-            assert!(
+            ensure!(
                 remaining_fields.is_empty(),
-                "Missing declarations of fields {remaining_fields:?} for struct {}",
+                "missing fields {} in initializer of `{}`",
+                remaining_fields
+                    .iter()
+                    .map(|(name, _)| format!("`{name}`"))
+                    .join(", "),
                 struct_type.name
             );
 
@@ -1189,9 +5104,10 @@ fn derive_annotate_expr_type(
                         .position(|(name, _)| name == field_name)
                         .unwrap_or(usize::MAX)
                 });
-            assert_eq!(
-                original_order, struct_expr.field_names_and_values,
-                "{}: Fields in declaration must match that in type definition in order to allow left-to-right evaluation",
+            ensure!(
+                original_order == struct_expr.field_names_and_values,
+                "the fields in the initializer of `{}` must be given in the order in which they \
+                 are declared, to allow left-to-right evaluation",
                 struct_type.name
             );
 
@@ -1199,46 +5115,69 @@ fn derive_annotate_expr_type(
         }
 
         ast::Expr::EnumDeclaration(enum_decl) => {
+            if let ast_types::DataType::Unresolved(type_name) = &enum_decl.enum_type {
+                enum_decl.enum_type = generic_type_instance_from_hint(type_name, hint)?;
+            }
+
             // 1. Verify that `variant_name` exists in `enum_type`
-            let enum_type = enum_decl.enum_type.as_enum_type();
-            assert!(
+            let ast_types::DataType::Enum(enum_type) = &enum_decl.enum_type else {
+                bail!("`{}` is not an enum", enum_decl.enum_type);
+            };
+            ensure!(
                 enum_type.has_variant_of_name(&enum_decl.variant_name),
-                "Variant name {} not known for enum type {}",
+                "no variant named `{}` in enum `{}`",
                 enum_decl.variant_name,
                 enum_type.name
             );
 
             let variant_data_type = enum_type.variant_data_type(&enum_decl.variant_name);
-            assert!(
+            ensure!(
                 variant_data_type.is_unit(),
-                "Declarations of data-carrying enum variants must contain data"
+                "the data of variant `{}::{}` must be given",
+                enum_type.name,
+                enum_decl.variant_name
             );
 
             Ok(enum_decl.enum_type.clone())
         }
 
         ast::Expr::FnCall(fn_call) => {
+            resolve_function_argument_call(&mut fn_call.name, &mut fn_call.args, state);
+
             // Attempt to annotate all arguments before getting the function signature
-            for arg in fn_call.args.iter_mut() {
-                // It's OK if this fails, as a later invocation of the argument expressions
-                // might pass.
-                let _ = derive_annotate_expr_type(arg, None, state, env_fn_signature);
-            }
+            let arg_types = fn_call
+                .args
+                .iter_mut()
+                .map(|arg| {
+                    // It's OK if this fails, as a later invocation of the argument expressions
+                    // might pass.
+                    derive_annotate_expr_type(arg, None, state, env_fn_signature).ok()
+                })
+                .collect_vec();
 
+            monomorphize_fn_call(
+                &mut fn_call.name,
+                &fn_call.type_arguments,
+                &mut fn_call.args,
+                &arg_types,
+                hint,
+                state,
+                env_fn_signature,
+            )?;
             let callees_fn_signature = get_fn_signature(
                 &fn_call.name,
                 state,
-                &fn_call.type_parameter,
+                &fn_call.type_arguments,
                 &fn_call.args,
-                env_fn_signature,
                 hint,
-            );
-            assert!(
+            )?;
+            ensure!(
                 !callees_fn_signature.output.is_unit(),
-                "Function calls in expressions cannot return the unit type"
+                "function `{}` returns no value, so its call cannot be used as an expression",
+                fn_call.name
             );
 
-            derive_annotate_fn_call_args(&callees_fn_signature, &mut fn_call.args, state);
+            derive_annotate_fn_call_args(&callees_fn_signature, &mut fn_call.args, state)?;
             fn_call.annot = Typing::KnownType(callees_fn_signature.output.clone());
             fn_call.arg_evaluation_order = callees_fn_signature.arg_evaluation_order;
 
@@ -1246,25 +5185,28 @@ fn derive_annotate_expr_type(
         }
 
         ast::Expr::MethodCall(method_call) => {
-            // Attempt to annotate all arguments before getting the function signature
-            for arg in method_call.args.iter_mut() {
-                // It's OK if this fails, as a later invocation of the argument expressions
-                // might pass.
-                let _ = derive_annotate_expr_type(arg, None, state, env_fn_signature);
+            // Attempt to annotate all arguments before getting the method signature. Only the
+            // receiver must be annotated here; it's OK if annotating the other arguments fails,
+            // as a later invocation of the argument expressions might pass.
+            for (i, arg) in method_call.args.iter_mut().enumerate() {
+                let arg_type = derive_annotate_expr_type(arg, None, state, env_fn_signature);
+                if i == 0 {
+                    arg_type?;
+                }
             }
 
             let callees_method_signature: ast::FnSignature =
-                get_method_signature(state, method_call, env_fn_signature);
-            assert!(
+                get_method_signature(state, method_call)?;
+            ensure!(
                 !callees_method_signature.output.is_unit(),
-                "Method call {} in expressions cannot return the unit type",
+                "method `{}` returns no value, so its call cannot be used as an expression",
                 method_call.method_name
             );
 
             // We don't need to check receiver type here since that is done by
             // `derive_annotate_fn_call_args` below.
 
-            derive_annotate_fn_call_args(&callees_method_signature, &mut method_call.args, state);
+            derive_annotate_fn_call_args(&callees_method_signature, &mut method_call.args, state)?;
 
             method_call.annot = Typing::KnownType(callees_method_signature.output.clone());
 
@@ -1275,36 +5217,46 @@ fn derive_annotate_expr_type(
             use ast::UnaryOp::*;
             let rhs_type = derive_annotate_expr_type(rhs_expr, hint, state, env_fn_signature)?;
             match unaryop {
+                Neg if has_operator_method(&rhs_type, "neg", state) => {
+                    let mut method_call = ast::MethodCall {
+                        method_name: "neg".to_owned(),
+                        args: vec![rhs_expr.as_ref().to_owned()],
+                        annot: Typing::default(),
+                        associated_type: None,
+                    };
+                    let output_type =
+                        annotate_operator_method_call(&mut method_call, state, env_fn_signature)?;
+                    *expr = ast::Expr::MethodCall(method_call);
+                    Ok(output_type)
+                }
                 Neg => {
-                    assert!(
+                    ensure!(
                         is_negatable_type(&rhs_type),
-                        "Cannot negate type '{rhs_type}'",
+                        "cannot apply unary operator `-` to type `{rhs_type}`"
                     );
                     *unaryop_type = Typing::KnownType(rhs_type.clone());
                     Ok(rhs_type)
                 }
                 Not => {
-                    assert!(
+                    ensure!(
                         type_compatible_with_not(&rhs_type),
-                        "Cannot negate type '{rhs_type}'",
+                        "cannot apply unary operator `!` to type `{rhs_type}`"
                     );
                     *unaryop_type = Typing::KnownType(rhs_type.clone());
                     Ok(rhs_type)
                 }
-                Deref => {
-                    match rhs_type {
-                        ast_types::DataType::Boxed(inner_inner_type) => {
-                            *unaryop_type = Typing::KnownType(*inner_inner_type.clone());
-                            Ok(*inner_inner_type)
-                        },
-                        _ =>  panic!("Cannot dereference type of `{rhs_type}` as this expression has:\n{rhs_expr:#?}")
+                Deref => match rhs_type {
+                    ast_types::DataType::Boxed(inner_inner_type) => {
+                        *unaryop_type = Typing::KnownType(*inner_inner_type.clone());
+                        Ok(*inner_inner_type)
                     }
-                }
+                    _ => bail!("type `{rhs_type}` cannot be dereferenced"),
+                },
                 Ref(_mutable) => {
                     let resulting_type = ast_types::DataType::Boxed(Box::new(rhs_type));
                     *unaryop_type = Typing::KnownType(resulting_type.clone());
                     Ok(resulting_type)
-                },
+                }
             }
         }
 
@@ -1313,31 +5265,27 @@ fn derive_annotate_expr_type(
             match binop {
                 // Overloaded for all arithmetic types.
                 Add => {
-                    let maybe_lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature);
-                    let rhs_hint = match hint {
-                        Some(hint) => Some(hint),
-                        None => match maybe_lhs_type {
-                            Ok(ref ty) => Some(ty),
-                            Err(_) => None,
-                        },
-                    };
-                    let rhs_type =
-                        derive_annotate_expr_type(rhs_expr, rhs_hint, state, env_fn_signature)?;
-                    let lhs_type = match maybe_lhs_type {
-                        Ok(ty) => ty,
-                        Err(_) => derive_annotate_expr_type(
-                            lhs_expr,
-                            Some(&rhs_type),
-                            state,
-                            env_fn_signature,
-                        )?,
+                    let operand_types = derive_annotate_overloadable_operand_types(
+                        "add",
+                        false,
+                        lhs_expr,
+                        rhs_expr,
+                        hint,
+                        state,
+                        env_fn_signature,
+                    )?;
+                    let (lhs_type, rhs_type) = match operand_types {
+                        OperandTypes::Primitive(lhs_type, rhs_type) => (lhs_type, rhs_type),
+                        OperandTypes::Overloaded(method_call, output_type) => {
+                            *expr = method_call;
+                            return Ok(output_type);
+                        }
                     };
 
-                    assert_type_equals(&lhs_type, &rhs_type, "add-expr");
-                    assert!(
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `+`")?;
+                    ensure!(
                         is_arithmetic_type(&lhs_type),
-                        "Cannot add non-arithmetic type '{lhs_type}'",
+                        "binary operation `+` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
@@ -1351,67 +5299,72 @@ fn derive_annotate_expr_type(
                     let rhs_type =
                         derive_annotate_expr_type(rhs_expr, bool_hint, state, env_fn_signature)?;
 
-                    assert_type_equals(&lhs_type, &ast_types::DataType::Bool, "and-expr lhs");
-                    assert_type_equals(&rhs_type, &ast_types::DataType::Bool, "and-expr rhs");
+                    check_type_equals(
+                        &lhs_type,
+                        &ast_types::DataType::Bool,
+                        "the operands of `&&`",
+                    )?;
+                    check_type_equals(
+                        &rhs_type,
+                        &ast_types::DataType::Bool,
+                        "the operands of `&&`",
+                    )?;
                     *binop_type = Typing::KnownType(ast_types::DataType::Bool);
                     Ok(ast_types::DataType::Bool)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 BitAnd => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let (lhs_type, rhs_type) = derive_annotate_operand_types(
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
 
-                    assert_type_equals(&lhs_type, &rhs_type, "bitwise-and-expr");
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot bitwise-and type '{lhs_type}' (not u32-based)",
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `&`")?;
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `&` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 BitXor => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let (lhs_type, rhs_type) = derive_annotate_operand_types(
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
 
-                    assert_type_equals(&lhs_type, &rhs_type, "bitwise-xor-expr");
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot bitwise-xor type '{lhs_type}' (not u32-based)"
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `^`")?;
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `^` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 BitOr => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let (lhs_type, rhs_type) = derive_annotate_operand_types(
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
 
-                    assert_type_equals(&lhs_type, &rhs_type, "bitwise-or-expr");
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot bitwise-or type '{lhs_type}' (not u32-based)"
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `|`")?;
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `|` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
@@ -1419,19 +5372,18 @@ fn derive_annotate_expr_type(
 
                 // Overloaded for all arithmetic types.
                 Div => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let (lhs_type, rhs_type) = derive_annotate_operand_types(
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
 
-                    assert_type_equals(&lhs_type, &rhs_type, "div-expr");
-                    assert!(
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `/`")?;
+                    ensure!(
                         is_arithmetic_type(&lhs_type),
-                        "Cannot divide non-arithmetic type '{lhs_type}'"
+                        "binary operation `/` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
@@ -1441,50 +5393,45 @@ fn derive_annotate_expr_type(
                 Eq => {
                     // Cannot provide parent `hint` (since it's Bool)
                     let no_hint = None;
-                    let maybe_lhs_type =
-                        derive_annotate_expr_type(lhs_expr, no_hint, state, env_fn_signature);
-                    let rhs_hint = match maybe_lhs_type {
-                        Ok(ref ty) => Some(ty),
-                        Err(_) => None,
-                    };
-                    let rhs_type =
-                        derive_annotate_expr_type(rhs_expr, rhs_hint, state, env_fn_signature)?;
-                    let lhs_type = match maybe_lhs_type {
-                        Ok(ty) => ty,
-                        Err(_) => derive_annotate_expr_type(
-                            lhs_expr,
-                            Some(&rhs_type),
-                            state,
-                            env_fn_signature,
-                        )?,
+                    let operand_types = derive_annotate_overloadable_operand_types(
+                        "eq",
+                        false,
+                        lhs_expr,
+                        rhs_expr,
+                        no_hint,
+                        state,
+                        env_fn_signature,
+                    )?;
+                    let (lhs_type, rhs_type) = match operand_types {
+                        OperandTypes::Primitive(lhs_type, rhs_type) => (lhs_type, rhs_type),
+                        OperandTypes::Overloaded(method_call, output_type) => {
+                            *expr = method_call;
+                            return Ok(output_type);
+                        }
                     };
 
-                    assert_type_equals(&lhs_type, &rhs_type, "eq-expr");
-                    assert!(
-                        is_primitive_type(&lhs_type),
-                        "Cannot compare non-primitive type '{lhs_type}' for equality"
-                    );
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `==`")?;
+                    check_comparable(&lhs_type, "==")?;
                     *binop_type = Typing::KnownType(ast_types::DataType::Bool);
                     Ok(ast_types::DataType::Bool)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 Lt | Gt => {
                     // FIXME: Cannot provide parent `hint` (since it's Bool)
                     let no_hint = None;
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, no_hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let (lhs_type, rhs_type) = derive_annotate_operand_types(
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        no_hint,
                         state,
                         env_fn_signature,
                     )?;
 
-                    assert_type_equals(&lhs_type, &rhs_type, "lt-expr");
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot compare type '{lhs_type}' with less-than (not u32-based)"
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `<`")?;
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `<` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(ast_types::DataType::Bool);
                     Ok(ast_types::DataType::Bool)
@@ -1492,14 +5439,22 @@ fn derive_annotate_expr_type(
 
                 // Overloaded for all primitive types.
                 Mul => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let operand_types = derive_annotate_overloadable_operand_types(
+                        "mul",
+                        false,
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
+                    let (lhs_type, rhs_type) = match operand_types {
+                        OperandTypes::Primitive(lhs_type, rhs_type) => (lhs_type, rhs_type),
+                        OperandTypes::Overloaded(method_call, output_type) => {
+                            *expr = method_call;
+                            return Ok(output_type);
+                        }
+                    };
 
                     // We are allowed to multiply an XFieldElement with a BFieldElement, but we
                     // don't currently support the mirrored expression.
@@ -1508,10 +5463,10 @@ fn derive_annotate_expr_type(
                         *binop_type = Typing::KnownType(ast_types::DataType::Xfe);
                         Ok(ast_types::DataType::Xfe)
                     } else {
-                        assert_type_equals(&lhs_type, &rhs_type, "mul-expr");
-                        assert!(
+                        check_type_equals(&rhs_type, &lhs_type, "the operands of `*`")?;
+                        ensure!(
                             is_arithmetic_type(&lhs_type),
-                            "Cannot multiply non-arithmetic type '{lhs_type}'"
+                            "binary operation `*` cannot be applied to type `{lhs_type}`"
                         );
                         *binop_type = Typing::KnownType(lhs_type.clone());
                         Ok(lhs_type)
@@ -1522,20 +5477,25 @@ fn derive_annotate_expr_type(
                 Neq => {
                     // FIXME: Cannot provide parent `hint` (since it's Bool)
                     let no_hint = None;
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, no_hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let operand_types = derive_annotate_overloadable_operand_types(
+                        "eq",
+                        true,
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        no_hint,
                         state,
                         env_fn_signature,
                     )?;
+                    let (lhs_type, rhs_type) = match operand_types {
+                        OperandTypes::Primitive(lhs_type, rhs_type) => (lhs_type, rhs_type),
+                        OperandTypes::Overloaded(method_call, output_type) => {
+                            *expr = method_call;
+                            return Ok(output_type);
+                        }
+                    };
 
-                    assert_type_equals(&lhs_type, &rhs_type, "neq-expr");
-                    assert!(
-                        is_primitive_type(&lhs_type),
-                        "Cannot compare type '{lhs_type}' with not-equal (not primitive)"
-                    );
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `!=`")?;
+                    check_comparable(&lhs_type, "!=")?;
                     *binop_type = Typing::KnownType(ast_types::DataType::Bool);
                     Ok(ast_types::DataType::Bool)
                 }
@@ -1547,85 +5507,108 @@ fn derive_annotate_expr_type(
                     let rhs_type =
                         derive_annotate_expr_type(rhs_expr, hint, state, env_fn_signature)?;
 
-                    assert_type_equals(&lhs_type, &ast_types::DataType::Bool, "or-expr lhs");
-                    assert_type_equals(&rhs_type, &ast_types::DataType::Bool, "or-expr rhs");
+                    check_type_equals(
+                        &lhs_type,
+                        &ast_types::DataType::Bool,
+                        "the operands of `||`",
+                    )?;
+                    check_type_equals(
+                        &rhs_type,
+                        &ast_types::DataType::Bool,
+                        "the operands of `||`",
+                    )?;
                     *binop_type = Typing::KnownType(ast_types::DataType::Bool);
                     Ok(ast_types::DataType::Bool)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 Rem => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let (lhs_type, rhs_type) = derive_annotate_operand_types(
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
 
-                    assert_type_equals(&lhs_type, &rhs_type, "rem-expr");
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot find remainder for type '{lhs_type}' (not u32-based)"
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `%`")?;
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `%` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 Shl => {
                     let lhs_type =
                         derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
 
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot shift-left for type '{lhs_type}' (not u32-based)"
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `<<` cannot be applied to type `{lhs_type}`"
                     );
 
                     let rhs_hint = Some(&ast_types::DataType::U32);
                     let rhs_type =
                         derive_annotate_expr_type(rhs_expr, rhs_hint, state, env_fn_signature)?;
 
-                    assert_type_equals(&rhs_type, &ast_types::DataType::U32, "shl-rhs-expr");
+                    check_type_equals(
+                        &rhs_type,
+                        &ast_types::DataType::U32,
+                        "the shift amount of `<<`",
+                    )?;
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
                 }
 
-                // Restricted to U32-based types. (Triton VM limitation)
+                // Restricted to integer types. (Triton VM limitation)
                 Shr => {
                     let lhs_type =
                         derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
 
-                    assert!(
-                        is_u32_based_type(&lhs_type),
-                        "Cannot shift-right for type '{lhs_type}' (not u32-based)"
+                    ensure!(
+                        is_integer_type(&lhs_type),
+                        "binary operation `>>` cannot be applied to type `{lhs_type}`"
                     );
 
                     let rhs_hint = Some(&ast_types::DataType::U32);
                     let rhs_type =
                         derive_annotate_expr_type(rhs_expr, rhs_hint, state, env_fn_signature)?;
 
-                    assert_type_equals(&rhs_type, &ast_types::DataType::U32, "shr-rhs-expr");
+                    check_type_equals(
+                        &rhs_type,
+                        &ast_types::DataType::U32,
+                        "the shift amount of `>>`",
+                    )?;
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
                 }
 
                 // Overloaded for all arithmetic types.
                 Sub => {
-                    let lhs_type =
-                        derive_annotate_expr_type(lhs_expr, hint, state, env_fn_signature)?;
-                    let rhs_type = derive_annotate_expr_type(
+                    let operand_types = derive_annotate_overloadable_operand_types(
+                        "sub",
+                        false,
+                        lhs_expr,
                         rhs_expr,
-                        Some(&lhs_type),
+                        hint,
                         state,
                         env_fn_signature,
                     )?;
+                    let (lhs_type, rhs_type) = match operand_types {
+                        OperandTypes::Primitive(lhs_type, rhs_type) => (lhs_type, rhs_type),
+                        OperandTypes::Overloaded(method_call, output_type) => {
+                            *expr = method_call;
+                            return Ok(output_type);
+                        }
+                    };
 
-                    assert_type_equals(&lhs_type, &rhs_type, "sub-expr");
-                    assert!(
+                    check_type_equals(&rhs_type, &lhs_type, "the operands of `-`")?;
+                    ensure!(
                         is_arithmetic_type(&lhs_type),
-                        "Cannot subtract non-arithmetic type '{lhs_type}'"
+                        "binary operation `-` cannot be applied to type `{lhs_type}`"
                     );
                     *binop_type = Typing::KnownType(lhs_type.clone());
                     Ok(lhs_type)
@@ -1645,34 +5628,85 @@ fn derive_annotate_expr_type(
                 state,
                 env_fn_signature,
             )?;
-            assert_type_equals(
+            check_type_equals(
                 &condition_type,
                 &ast_types::DataType::Bool,
-                "if-condition-expr",
-            );
+                "`if` condition",
+            )?;
 
             let then_type =
-                derive_annotate_returning_block_expr(then_branch, hint, state, env_fn_signature);
-            let else_type =
-                derive_annotate_returning_block_expr(else_branch, hint, state, env_fn_signature);
+                derive_annotate_returning_block_expr(then_branch, hint, state, env_fn_signature)?;
+            let else_hint = hint.unwrap_or(&then_type);
+            let else_type = derive_annotate_returning_block_expr(
+                else_branch,
+                Some(else_hint),
+                state,
+                env_fn_signature,
+            )?;
 
-            assert_type_equals(&then_type, &else_type, "if-then-else-expr");
+            check_type_equals(&else_type, &then_type, "`if` and `else` branches")?;
 
             Ok(then_type)
         }
-        ast::Expr::ReturningBlock(ret_block) => Ok(derive_annotate_returning_block_expr(
-            ret_block,
-            hint,
-            state,
-            env_fn_signature,
-        )),
+        ast::Expr::Match(match_expr) => {
+            if let Some(vector_type) =
+                popped_vector_type(&mut match_expr.match_expression, state, env_fn_signature)
+            {
+                let (lowered_match_expr, match_expr_type) = lower_popped_vector_match_expr(
+                    match_expr,
+                    vector_type,
+                    hint,
+                    state,
+                    env_fn_signature,
+                )?;
+                *expr = lowered_match_expr;
+                return Ok(match_expr_type);
+            }
+
+            let match_expression_type = derive_annotate_expr_type(
+                &mut match_expr.match_expression,
+                None,
+                state,
+                env_fn_signature,
+            );
+            let arms = match_expr
+                .arms
+                .iter()
+                .map(|arm| (&arm.match_condition, &arm.guard));
+            if is_lowered_match(match_expression_type.as_ref().ok(), arms) {
+                let (lowered_match_expr, match_expr_type) = lower_match_expr(
+                    match_expr,
+                    match_expression_type?,
+                    hint,
+                    state,
+                    env_fn_signature,
+                )?;
+                *expr = lowered_match_expr;
+                Ok(match_expr_type)
+            } else {
+                derive_annotate_enum_match_expr(
+                    match_expr,
+                    &match_expression_type?,
+                    hint,
+                    state,
+                    env_fn_signature,
+                )
+            }
+        }
+        ast::Expr::ReturningBlock(ret_block) => {
+            derive_annotate_returning_block_expr(ret_block, hint, state, env_fn_signature)
+        }
+
+        // Closures are lifted to functions where they are passed to a function argument
+        ast::Expr::Closure(_) => {
+            bail!("closures can only be passed to function arguments or to `map`")
+        }
 
         ast::Expr::Cast(expr, to_type) => {
             let from_type = derive_annotate_expr_type(expr, None, state, env_fn_signature)?;
-            let valid_cast = is_u32_based_type(&from_type) && is_u32_based_type(to_type)
-                || from_type == ast_types::DataType::Bool && is_arithmetic_type(to_type);
-
-            assert!(valid_cast, "Cannot cast from {from_type} to {to_type}");
+            if !is_valid_cast(&from_type, to_type) {
+                bail!("cannot cast `{from_type}` as `{to_type}`");
+            }
 
             Ok(to_type.to_owned())
         }
@@ -1686,19 +5720,43 @@ fn derive_annotate_returning_block_expr(
     hint: Option<&ast_types::DataType>,
     state: &mut CheckState,
     env_fn_signature: &ast::FnSignature,
-) -> ast_types::DataType {
-    let vtable_before = state.vtable.clone();
-    ret_block
-        .stmts
-        .iter_mut()
-        .for_each(|stmt| annotate_stmt(stmt, state, env_fn_signature));
+) -> anyhow::Result<ast_types::DataType> {
+    let block_scope = state.enter_scope();
+    annotate_each_stmt(&mut ret_block.stmts, state, env_fn_signature);
     let ret_type =
-        derive_annotate_expr_type(&mut ret_block.return_expr, hint, state, env_fn_signature)
-            .unwrap();
-    state.vtable = vtable_before.clone();
+        derive_annotate_expr_type(&mut ret_block.return_expr, hint, state, env_fn_signature);
+    annotate_inferred_let_stmts(&mut ret_block.stmts, state, env_fn_signature);
+    state.leave_scope(block_scope);
     ret_type
 }
 
+/// Return true if a value of type `from_type` can be converted to `to_type` with `as`.
+///
+/// Integers, `bool`s, and the discriminants of field-less enums can be cast to any integer
+/// type, and integers can be cast to and from `BFieldElement`s.
+pub(crate) fn is_valid_cast(
+    from_type: &ast_types::DataType,
+    to_type: &ast_types::DataType,
+) -> bool {
+    use ast_types::DataType::*;
+    let is_integer_or_bfe =
+        |data_type: &ast_types::DataType| is_integer_type(data_type) || *data_type == Bfe;
+    match from_type {
+        _ if from_type == to_type => is_integer_or_bfe(from_type) || *from_type == Bool,
+        Bool => is_integer_or_bfe(to_type),
+        Bfe => is_integer_type(to_type),
+        Enum(enum_type) => {
+            let is_field_less = !enum_type.variants.is_empty()
+                && enum_type
+                    .variants
+                    .iter()
+                    .all(|(_, data_type)| data_type.is_unit());
+            is_field_less && is_integer_type(to_type)
+        }
+        _ => is_integer_type(from_type) && is_integer_or_bfe(to_type),
+    }
+}
+
 /// A type that can be used as address in `read_mem` and `write_mem` calls.
 ///
 /// Since memory addresses are essentially `BFieldElement`s, only types
@@ -1712,22 +5770,69 @@ pub(crate) fn is_index_type(data_type: &ast_types::DataType) -> bool {
 ///
 /// Note that not all operators work for all arithmetic types.
 ///
-/// E.g. the bitwise operators only work for `is_u32_based_type()`.
+/// E.g. the bitwise operators only work for `is_integer_type()`.
 pub(crate) fn is_arithmetic_type(data_type: &ast_types::DataType) -> bool {
     use ast_types::DataType::*;
-    matches!(data_type, U32 | U64 | U128 | Bfe | Xfe)
+    matches!(
+        data_type,
+        U8 | U16 | U32 | U64 | U128 | I32 | I64 | Bfe | Xfe
+    )
+}
+
+/// Check that values of a type can be compared with `==` and `!=`. Values in memory are
+/// compared word by word, which requires the size of their encodings: the elements of lists
+/// must be of static size, and boxed values of dynamic size must be structs or enums.
+fn check_comparable(data_type: &ast_types::DataType, operator: &str) -> anyhow::Result<()> {
+    use ast_types::DataType::*;
+    ensure!(
+        data_type.is_partial_eq(),
+        "binary operation `{operator}` cannot be applied to type `{data_type}`"
+    );
+
+    let is_comparable_in_memory = match data_type {
+        List(element_type, _) => element_type.bfield_codec_length().is_some(),
+        Boxed(inner_type) => {
+            matches!(
+                inner_type.as_ref(),
+                Struct(_) | Enum(_) | List(_, _) | Array(_) | Boxed(_)
+            ) || inner_type.bfield_codec_length().is_some()
+        }
+        _ => true,
+    };
+    ensure!(
+        is_comparable_in_memory,
+        "binary operation `{operator}` is not supported for type `{data_type}`"
+    );
+
+    match data_type {
+        Tuple(tuple) => tuple
+            .into_iter()
+            .try_for_each(|field_type| check_comparable(field_type, operator)),
+        Struct(struct_type) => struct_type
+            .field_types()
+            .try_for_each(|field_type| check_comparable(field_type, operator)),
+        Enum(enum_type) => enum_type
+            .variants
+            .iter()
+            .try_for_each(|(_, data_type)| check_comparable(data_type, operator)),
+        Boxed(inner_type) => match inner_type.as_ref() {
+            List(_, _) | Array(_) | Boxed(_) => check_comparable(inner_type, operator),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
 }
 
 /// A type from which expressions such as `-value` can be formed
 pub(crate) fn is_negatable_type(data_type: &ast_types::DataType) -> bool {
     use ast_types::DataType::*;
-    matches!(data_type, Bfe | Xfe)
+    matches!(data_type, I32 | I64 | Bfe | Xfe)
 }
 
 /// A type from which expressions such as `!value` can be formed
 pub(crate) fn type_compatible_with_not(data_type: &ast_types::DataType) -> bool {
     use ast_types::DataType::*;
-    matches!(data_type, Bool | U32 | U64)
+    matches!(data_type, Bool | U8 | U16 | U32 | U64 | I32 | I64)
 }
 
 /// A type that is implemented in terms of `U32` values.
@@ -1738,8 +5843,24 @@ pub(crate) fn is_u32_based_type(data_type: &ast_types::DataType) -> bool {
     matches!(data_type, U32 | U64 | U128)
 }
 
-/// A non-composite fixed-length type.
-pub(crate) fn is_primitive_type(data_type: &ast_types::DataType) -> bool {
+/// An unsigned integer type narrower than `U32`. Its values are stored in a single `U32`
+/// value that is range checked after every operation that could overflow.
+pub(crate) fn is_small_unsigned_integer_type(data_type: &ast_types::DataType) -> bool {
+    use ast_types::DataType::*;
+    matches!(data_type, U8 | U16)
+}
+
+/// A signed integer type. Its values are stored as their two's complement in `U32` values.
+pub(crate) fn is_signed_integer_type(data_type: &ast_types::DataType) -> bool {
     use ast_types::DataType::*;
-    matches!(data_type, Bool | U32 | U64 | U128 | Bfe | Xfe | Digest)
+    matches!(data_type, I32 | I64)
+}
+
+/// A type on which the bitwise, comparison, and shift operators are defined.
+///
+/// E.g. `U8`, `U32` and `I64`.
+pub(crate) fn is_integer_type(data_type: &ast_types::DataType) -> bool {
+    is_small_unsigned_integer_type(data_type)
+        || is_u32_based_type(data_type)
+        || is_signed_integer_type(data_type)
 }